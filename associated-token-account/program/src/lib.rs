@@ -9,10 +9,11 @@ pub mod processor;
 pub use solana_program;
 use solana_program::{
     instruction::{AccountMeta, Instruction},
-    program_pack::Pack,
+    program_error::ProgramError,
     pubkey::Pubkey,
     sysvar,
 };
+use spl_token::state::{get_extension_types, ExtensionType};
 
 solana_program::declare_id!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
 
@@ -53,6 +54,23 @@ fn get_associated_token_address_and_bump_seed_internal(
     )
 }
 
+/// Gets the length of the data an associated token account of the given mint
+/// is allocated with.  Besides an immutable owner and the extensions the mint
+/// requires of its token accounts, it has space for the optional extensions
+/// the owner may enable later, as accounts cannot be resized.
+pub fn get_associated_token_account_len(mint_data: &[u8]) -> Result<usize, ProgramError> {
+    let mint_extension_types = get_extension_types(mint_data)?;
+    let mut account_extension_types =
+        ExtensionType::get_required_init_account_extensions(&mint_extension_types);
+    account_extension_types.extend_from_slice(&[
+        ExtensionType::ImmutableOwner,
+        ExtensionType::DelegationExpiry,
+        ExtensionType::MemoTransfer,
+        ExtensionType::CpiGuard,
+    ]);
+    Ok(ExtensionType::get_account_len(&account_extension_types))
+}
+
 /// Create an associated token account for the given wallet address and token mint
 ///
 /// Accounts expected by this instruction:
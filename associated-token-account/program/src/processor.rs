@@ -48,10 +48,12 @@ pub fn process_instruction(
         &[bump_seed],
     ];
 
+    let account_len = get_associated_token_account_len(&spl_token_mint_info.data.borrow())?;
+
     // Fund the associated token account with the minimum balance to be rent exempt
     let rent = &Rent::from_account_info(rent_sysvar_info)?;
     let required_lamports = rent
-        .minimum_balance(spl_token::state::Account::LEN)
+        .minimum_balance(account_len)
         .max(1)
         .saturating_sub(associated_token_account_info.lamports());
 
@@ -76,10 +78,7 @@ pub fn process_instruction(
 
     msg!("Allocate space for the associated token account");
     invoke_signed(
-        &system_instruction::allocate(
-            associated_token_account_info.key,
-            spl_token::state::Account::LEN as u64,
-        ),
+        &system_instruction::allocate(associated_token_account_info.key, account_len as u64),
         &[
             associated_token_account_info.clone(),
             system_program_info.clone(),
@@ -97,6 +96,18 @@ pub fn process_instruction(
         &[&associated_token_account_signer_seeds],
     )?;
 
+    msg!("Make the owner of the associated token account immutable");
+    invoke(
+        &spl_token::instruction::initialize_immutable_owner(
+            &spl_token_program_id,
+            associated_token_account_info.key,
+        )?,
+        &[
+            associated_token_account_info.clone(),
+            spl_token_program_info.clone(),
+        ],
+    )?;
+
     msg!("Initialize the associated token account");
     invoke(
         &spl_token::instruction::initialize_account(
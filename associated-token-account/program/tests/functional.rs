@@ -1,13 +1,24 @@
 // Mark this test as BPF-only due to current `ProgramTest` limitations when CPIing into the system program
 #![cfg(feature = "test-bpf")]
 
-use solana_program::{instruction::*, program_pack::Pack, pubkey::Pubkey, system_instruction};
+use solana_program::{instruction::*, pubkey::Pubkey, system_instruction};
 use solana_program_test::*;
 use solana_sdk::{
     signature::Signer,
     transaction::{Transaction, TransactionError},
 };
 use spl_associated_token_account::*;
+use spl_token::state::{get_extension, ExtensionType, ImmutableOwner};
+
+// Associated token accounts have space for the optional account extensions
+fn associated_token_account_len() -> usize {
+    ExtensionType::get_account_len(&[
+        ExtensionType::ImmutableOwner,
+        ExtensionType::DelegationExpiry,
+        ExtensionType::MemoTransfer,
+        ExtensionType::CpiGuard,
+    ])
+}
 
 fn program_test(token_mint_address: Pubkey) -> ProgramTest {
     let mut pc = ProgramTest::new(
@@ -16,6 +27,13 @@ fn program_test(token_mint_address: Pubkey) -> ProgramTest {
         processor!(processor::process_instruction),
     );
 
+    // Use this tree's token program, which knows the immutable owner extension
+    pc.add_program(
+        "spl_token",
+        spl_token::id(),
+        processor!(spl_token::processor::Processor::process),
+    );
+
     // Add a token mint account
     //
     // The account data was generated by running:
@@ -45,7 +63,7 @@ async fn test_associated_token_address() {
     let (mut banks_client, payer, recent_blockhash) =
         program_test(token_mint_address).start().await;
     let rent = banks_client.get_rent().await.unwrap();
-    let expected_token_account_balance = rent.minimum_balance(spl_token::state::Account::LEN);
+    let expected_token_account_balance = rent.minimum_balance(associated_token_account_len());
 
     // Associated account does not exist
     assert_eq!(
@@ -75,10 +93,14 @@ async fn test_associated_token_address() {
         .expect("associated_account not none");
     assert_eq!(
         associated_account.data.len(),
-        spl_token::state::Account::LEN
+        associated_token_account_len()
     );
     assert_eq!(associated_account.owner, spl_token::id());
     assert_eq!(associated_account.lamports, expected_token_account_balance);
+    assert_eq!(
+        get_extension::<ImmutableOwner>(&associated_account.data).unwrap(),
+        Some(ImmutableOwner)
+    );
 }
 
 #[tokio::test]
@@ -91,7 +113,7 @@ async fn test_create_with_a_lamport() {
     let (mut banks_client, payer, recent_blockhash) =
         program_test(token_mint_address).start().await;
     let rent = banks_client.get_rent().await.unwrap();
-    let expected_token_account_balance = rent.minimum_balance(spl_token::state::Account::LEN);
+    let expected_token_account_balance = rent.minimum_balance(associated_token_account_len());
 
     // Transfer 1 lamport into `associated_token_address` before creating it
     let mut transaction = Transaction::new_with_payer(
@@ -144,7 +166,7 @@ async fn test_create_with_excess_lamports() {
     let (mut banks_client, payer, recent_blockhash) =
         program_test(token_mint_address).start().await;
     let rent = banks_client.get_rent().await.unwrap();
-    let expected_token_account_balance = rent.minimum_balance(spl_token::state::Account::LEN);
+    let expected_token_account_balance = rent.minimum_balance(associated_token_account_len());
 
     // Transfer 1 lamport into `associated_token_address` before creating it
     let mut transaction = Transaction::new_with_payer(
@@ -20,6 +20,7 @@ cargo +"$rust_stable" test-bpf -- --nocapture
 # Build/test all host crates
 cargo +"$rust_stable" build
 cargo +"$rust_stable" test -- --nocapture
+cargo +"$rust_stable" test --manifest-path=token/program/Cargo.toml --features parser -- --nocapture
 
 # Run test-client sanity check
 cargo +"$rust_stable" run --manifest-path=utils/test-client/Cargo.toml
@@ -35,12 +36,10 @@ SWAP_PROGRAM_OWNER_FEE_ADDRESS="HfoTxFR1Tm6kGmWgYWD6J7YHVy1UwqSULUGVLXkJqaKN" \
     --bpf-out-dir target/deploy-production
 mv target/deploy-production/spl_token_swap.so target/deploy/spl_token_swap_production.so
 
-#  # Check generated C headers
-#  cargo run --manifest-path=utils/cgen/Cargo.toml
-#
-#  git diff --exit-code token/program/inc/token.h
-#  cc token/program/inc/token.h -o target/token.gch
-#  git diff --exit-code token-swap/program/inc/token-swap.h
-#  cc token-swap/program/inc/token-swap.h -o target/token-swap.gch
+# Check that the C headers compile.  They are regenerated with ./cbindgen.sh,
+# whose output depends on the solana-program version resolved without
+# Cargo.lock, so they are not compared against a fresh generation here
+cc token/program/inc/token.h -o target/token.gch
+cc token-swap/program/inc/token-swap.h -o target/token-swap.gch
 
 exit 0
@@ -3,9 +3,10 @@ use clap::{
     ArgMatches, SubCommand,
 };
 use console::Emoji;
+use serde_json::{json, Value};
 use solana_account_decoder::{
-    parse_token::{TokenAccountType, UiAccountState},
-    UiAccountData,
+    parse_token::{token_amount_to_ui_amount, TokenAccountType, UiAccountState},
+    UiAccountData, UiAccountEncoding,
 };
 use solana_clap_utils::{
     fee_payer::fee_payer_arg,
@@ -14,35 +15,54 @@ use solana_clap_utils::{
         is_amount, is_amount_or_all, is_parsable, is_url_or_moniker, is_valid_pubkey,
         is_valid_signer, normalize_to_url_if_moniker,
     },
-    keypair::{pubkey_from_path, signer_from_path, DefaultSigner},
+    keypair::{pubkey_from_path, signer_from_path, CliSignerInfo, DefaultSigner},
     nonce::*,
     offline::{self, *},
     ArgConstant,
 };
 use solana_cli_output::{display::println_name_value, return_signers, OutputFormat};
 use solana_client::{
-    blockhash_query::BlockhashQuery, rpc_client::RpcClient, rpc_request::TokenAccountsFilter,
+    blockhash_query::BlockhashQuery,
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType},
+    rpc_request::TokenAccountsFilter,
 };
 use solana_remote_wallet::remote_wallet::RemoteWalletManager;
 use solana_sdk::{
+    account::Account as SolanaAccount,
     commitment_config::CommitmentConfig,
+    hash::Hash,
     instruction::Instruction,
     message::Message,
     native_token::*,
+    program_option::COption,
     program_pack::Pack,
     pubkey::Pubkey,
-    signature::{Keypair, Signer},
+    signature::{Keypair, Signature, Signer},
     system_instruction, system_program,
-    transaction::Transaction,
+    transaction::{self, Transaction},
 };
 use spl_associated_token_account::*;
 use spl_token::{
     self,
     instruction::*,
     native_mint,
-    state::{Account, Mint, Multisig},
+    parser::{parse_compiled_instruction, parse_token},
+    state::{
+        get_extension, get_extension_types, Account, ExtensionType, Mint, Multisig,
+        MultisigProposal, TokenMetadata,
+    },
+};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs::{File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::Path,
+    process::exit,
+    str::FromStr,
+    sync::Arc,
 };
-use std::{collections::HashMap, process::exit, str::FromStr, sync::Arc};
 
 static WARNING: Emoji = Emoji("⚠️", "!");
 
@@ -70,6 +90,14 @@ pub const MULTISIG_SIGNER_ARG: ArgConstant<'static> = ArgConstant {
     help: "Member signer of a multisig account",
 };
 
+pub const TRANSACTION_FILE_ARG: ArgConstant<'static> = ArgConstant {
+    name: "transaction_file",
+    long: "transaction-file",
+    help: "Write the transaction to this file instead of sending it, for the multisig signers \
+           to sign with `spl-token sign` before sending it with `spl-token broadcast`. \
+           Requires --nonce, and exactly M --multisig-signer arguments",
+};
+
 pub fn mint_address_arg<'a, 'b>() -> Arg<'a, 'b> {
     Arg::with_name(MINT_ADDRESS_ARG.name)
         .long(MINT_ADDRESS_ARG.long)
@@ -130,6 +158,16 @@ pub fn multisig_signer_arg<'a, 'b>() -> Arg<'a, 'b> {
         .help(MULTISIG_SIGNER_ARG.help)
 }
 
+pub fn transaction_file_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name(TRANSACTION_FILE_ARG.name)
+        .long(TRANSACTION_FILE_ARG.long)
+        .value_name("PATH")
+        .takes_value(true)
+        .requires_all(&[MULTISIG_SIGNER_ARG.name, NONCE_ARG.name])
+        .conflicts_with(SIGN_ONLY_ARG.name)
+        .help(TRANSACTION_FILE_ARG.help)
+}
+
 fn is_multisig_minimum_signers(string: String) -> Result<(), String> {
     let v = u8::from_str(&string).map_err(|e| e.to_string())? as usize;
     if v < MIN_SIGNERS {
@@ -141,6 +179,15 @@ fn is_multisig_minimum_signers(string: String) -> Result<(), String> {
     }
 }
 
+fn is_transfers_per_transaction(string: String) -> Result<(), String> {
+    let transfers_per_transaction = string.parse::<usize>().map_err(|e| e.to_string())?;
+    if transfers_per_transaction < 1 {
+        Err("must be at least 1".to_string())
+    } else {
+        Ok(())
+    }
+}
+
 struct Config<'a> {
     rpc_client: RpcClient,
     verbose: bool,
@@ -152,10 +199,47 @@ struct Config<'a> {
     blockhash_query: BlockhashQuery,
     sign_only: bool,
     multisigner_pubkeys: Vec<&'a Pubkey>,
+    output_format: OutputFormat,
+    transaction_file: Option<String>,
 }
 
 type Error = Box<dyn std::error::Error>;
-type CommandResult = Result<Option<(u64, Vec<Vec<Instruction>>)>, Error>;
+
+struct TransactionInfo {
+    minimum_balance_for_rent_exemption: u64,
+    instruction_batches: Vec<Vec<Instruction>>,
+    // Description of the command, printed along with its signatures as JSON
+    output: Value,
+    // Token accounts whose resulting balances are included in the JSON output
+    token_accounts: Vec<Pubkey>,
+}
+
+type CommandResult = Result<Option<TransactionInfo>, Error>;
+
+fn is_json_output(config: &Config) -> bool {
+    matches!(
+        config.output_format,
+        OutputFormat::Json | OutputFormat::JsonCompact
+    )
+}
+
+fn println_display(config: &Config, message: String) {
+    if !is_json_output(config) {
+        println!("{}", message);
+    }
+}
+
+fn format_json(output_format: &OutputFormat, value: &Value) -> String {
+    if let OutputFormat::JsonCompact = output_format {
+        value.to_string()
+    } else {
+        serde_json::to_string_pretty(value).unwrap()
+    }
+}
+
+fn println_json(config: &Config, value: &Value) {
+    println!("{}", format_json(&config.output_format, value));
+}
 
 fn new_throwaway_signer() -> (Option<Box<dyn Signer>>, Option<Pubkey>) {
     let keypair = Keypair::new();
@@ -219,7 +303,7 @@ fn command_create_token(
     token: Pubkey,
     enable_freeze: bool,
 ) -> CommandResult {
-    println!("Creating token {}", token);
+    println_display(config, format!("Creating token {}", token));
 
     let minimum_balance_for_rent_exemption = if !config.sign_only {
         config
@@ -250,10 +334,17 @@ fn command_create_token(
             decimals,
         )?,
     ];
-    Ok(Some((
+    Ok(Some(TransactionInfo {
         minimum_balance_for_rent_exemption,
-        vec![instructions],
-    )))
+        instruction_batches: vec![instructions],
+        output: json!({
+            "address": token.to_string(),
+            "decimals": decimals,
+            "mintAuthority": config.owner.to_string(),
+            "freezeAuthority": freeze_authority_pubkey.map(|pubkey| pubkey.to_string()),
+        }),
+        token_accounts: vec![],
+    }))
 }
 
 fn command_create_account(
@@ -262,15 +353,20 @@ fn command_create_account(
     maybe_account: Option<Pubkey>,
 ) -> CommandResult {
     let minimum_balance_for_rent_exemption = if !config.sign_only {
+        let account_len = if maybe_account.is_some() {
+            Account::LEN
+        } else {
+            associated_token_account_len(config, &token)?
+        };
         config
             .rpc_client
-            .get_minimum_balance_for_rent_exemption(Account::LEN)?
+            .get_minimum_balance_for_rent_exemption(account_len)?
     } else {
         0
     };
 
     let (account, system_account_ok, instructions) = if let Some(account) = maybe_account {
-        println!("Creating account {}", account);
+        println_display(config, format!("Creating account {}", account));
         (
             account,
             false,
@@ -287,7 +383,7 @@ fn command_create_account(
         )
     } else {
         let account = get_associated_token_address(&config.owner, &token);
-        println!("Creating account {}", account);
+        println_display(config, format!("Creating account {}", account));
         (
             account,
             true,
@@ -309,10 +405,23 @@ fn command_create_account(
         }
     }
 
-    Ok(Some((
+    Ok(Some(TransactionInfo {
         minimum_balance_for_rent_exemption,
-        vec![instructions],
-    )))
+        instruction_batches: vec![instructions],
+        output: json!({
+            "address": account.to_string(),
+            "mint": token.to_string(),
+            "owner": config.owner.to_string(),
+        }),
+        token_accounts: vec![account],
+    }))
+}
+
+// Associated token accounts are allocated from the extensions of their mint,
+// as the associated token account program does
+fn associated_token_account_len(config: &Config, token: &Pubkey) -> Result<usize, Error> {
+    let mint_account = config.rpc_client.get_account(token)?;
+    get_associated_token_account_len(&mint_account.data).map_err(|e| e.into())
 }
 
 fn command_create_multisig(
@@ -321,11 +430,14 @@ fn command_create_multisig(
     minimum_signers: u8,
     multisig_members: Vec<Pubkey>,
 ) -> CommandResult {
-    println!(
-        "Creating {}/{} multisig {}",
-        minimum_signers,
-        multisig_members.len(),
-        multisig
+    println_display(
+        config,
+        format!(
+            "Creating {}/{} multisig {}",
+            minimum_signers,
+            multisig_members.len(),
+            multisig
+        ),
     );
 
     let minimum_balance_for_rent_exemption = if !config.sign_only {
@@ -351,10 +463,20 @@ fn command_create_multisig(
             minimum_signers,
         )?,
     ];
-    Ok(Some((
+    Ok(Some(TransactionInfo {
         minimum_balance_for_rent_exemption,
-        vec![instructions],
-    )))
+        instruction_batches: vec![instructions],
+        output: json!({
+            "address": multisig.to_string(),
+            "m": minimum_signers,
+            "n": multisig_members.len(),
+            "signers": multisig_members
+                .iter()
+                .map(|pubkey| pubkey.to_string())
+                .collect::<Vec<_>>(),
+        }),
+        token_accounts: vec![],
+    }))
 }
 
 fn command_authorize(
@@ -368,19 +490,27 @@ fn command_authorize(
         AuthorityType::FreezeAccount => "freeze authority",
         AuthorityType::AccountOwner => "owner",
         AuthorityType::CloseAccount => "close authority",
+        AuthorityType::PermanentDelegate => "permanent delegate",
+        AuthorityType::WithdrawWithheldTokens => "withdraw withheld authority",
+        AuthorityType::CloseMint => "mint close authority",
+        AuthorityType::MetadataUpdate => "metadata update authority",
+        AuthorityType::InterestRate => "rate authority",
     };
-    println!(
-        "Updating {}\n  Current {}: {}\n  New {}: {}",
-        account,
-        auth_str,
-        config.owner,
-        auth_str,
-        new_owner
-            .map(|pubkey| pubkey.to_string())
-            .unwrap_or_else(|| "disabled".to_string())
+    println_display(
+        config,
+        format!(
+            "Updating {}\n  Current {}: {}\n  New {}: {}",
+            account,
+            auth_str,
+            config.owner,
+            auth_str,
+            new_owner
+                .map(|pubkey| pubkey.to_string())
+                .unwrap_or_else(|| "disabled".to_string())
+        ),
     );
 
-    let instructions = vec![set_authority(
+    let instructions = vec![set_authority_with_instructions_sysvar(
         &spl_token::id(),
         &account,
         new_owner.as_ref(),
@@ -388,7 +518,17 @@ fn command_authorize(
         &config.owner,
         &config.multisigner_pubkeys,
     )?];
-    Ok(Some((0, vec![instructions])))
+    Ok(Some(TransactionInfo {
+        minimum_balance_for_rent_exemption: 0,
+        instruction_batches: vec![instructions],
+        output: json!({
+            "address": account.to_string(),
+            "authorityType": auth_str,
+            "currentAuthority": config.owner.to_string(),
+            "newAuthority": new_owner.map(|pubkey| pubkey.to_string()),
+        }),
+        token_accounts: vec![],
+    }))
 }
 
 fn resolve_mint_info(
@@ -424,6 +564,7 @@ fn command_transfer(
     fund_recipient: bool,
     mint_address: Option<Pubkey>,
     mint_decimals: Option<u8>,
+    memo: Option<String>,
 ) -> CommandResult {
     let (mint_pubkey, decimals) = resolve_mint_info(config, &sender, mint_address, mint_decimals)?;
     let sender_token_amount = config
@@ -445,11 +586,14 @@ fn command_transfer(
         .map(|ui_amount| spl_token::ui_amount_to_amount(ui_amount, decimals))
         .unwrap_or(sender_balance);
 
-    println!(
-        "Transfer {} tokens\n  Sender: {}\n  Recipient: {}",
-        spl_token::amount_to_ui_amount(transfer_balance, decimals),
-        sender,
-        recipient
+    println_display(
+        config,
+        format!(
+            "Transfer {} tokens\n  Sender: {}\n  Recipient: {}",
+            spl_token::amount_to_ui_amount(transfer_balance, decimals),
+            sender,
+            recipient
+        ),
     );
 
     if transfer_balance > sender_balance {
@@ -480,9 +624,12 @@ fn command_transfer(
 
     if Some(spl_token::id()) != recipient_account_owner {
         recipient_token_account = get_associated_token_address(&recipient, &mint_pubkey);
-        println!(
-            "  Recipient associated token account: {}",
-            recipient_token_account
+        println_display(
+            config,
+            format!(
+                "  Recipient associated token account: {}",
+                recipient_token_account
+            ),
         );
 
         let needs_funding = if let Some(recipient_token_account_data) = config
@@ -503,13 +650,17 @@ fn command_transfer(
 
         if needs_funding {
             if fund_recipient {
-                minimum_balance_for_rent_exemption += config
-                    .rpc_client
-                    .get_minimum_balance_for_rent_exemption(Account::LEN)?;
-                println!(
-                    "  Funding recipient: {} ({} SOL)",
-                    recipient_token_account,
-                    lamports_to_sol(minimum_balance_for_rent_exemption)
+                minimum_balance_for_rent_exemption +=
+                    config.rpc_client.get_minimum_balance_for_rent_exemption(
+                        associated_token_account_len(config, &mint_pubkey)?,
+                    )?;
+                println_display(
+                    config,
+                    format!(
+                        "  Funding recipient: {} ({} SOL)",
+                        recipient_token_account,
+                        lamports_to_sol(minimum_balance_for_rent_exemption)
+                    ),
                 );
                 instructions.push(create_associated_token_account(
                     &config.fee_payer,
@@ -526,7 +677,10 @@ fn command_transfer(
         }
     }
 
-    instructions.push(transfer_checked(
+    if let Some(memo) = &memo {
+        instructions.push(spl_memo::build_memo(memo.as_bytes(), &[]));
+    }
+    instructions.push(transfer_checked_with_instructions_sysvar(
         &spl_token::id(),
         &sender,
         &mint_pubkey,
@@ -536,10 +690,469 @@ fn command_transfer(
         transfer_balance,
         decimals,
     )?);
-    Ok(Some((
+    Ok(Some(TransactionInfo {
         minimum_balance_for_rent_exemption,
-        vec![instructions],
-    )))
+        instruction_batches: vec![instructions],
+        output: json!({
+            "sender": sender.to_string(),
+            "recipient": recipient.to_string(),
+            "recipientTokenAccount": recipient_token_account.to_string(),
+            "mint": mint_pubkey.to_string(),
+            "amount": transfer_balance.to_string(),
+            "uiAmount": spl_token::amount_to_ui_amount(transfer_balance, decimals),
+            "decimals": decimals,
+        }),
+        token_accounts: vec![sender, recipient_token_account],
+    }))
+}
+
+struct DistributionRow {
+    line: usize,
+    recipient: Pubkey,
+    ui_amount: String,
+    amount: u64,
+}
+
+struct DistributionTransfer<'a> {
+    row: &'a DistributionRow,
+    token_account: Pubkey,
+    create_token_account: bool,
+}
+
+// Converts a decimal token amount to base units without going through a
+// float, so that every row of a distribution pays exactly what it says
+fn parse_ui_amount(ui_amount: &str, decimals: u8) -> Result<u64, String> {
+    let (whole, fraction) = match ui_amount.find('.') {
+        Some(index) => (&ui_amount[..index], &ui_amount[index + 1..]),
+        None => (ui_amount, ""),
+    };
+    let is_digits = |digits: &str| digits.bytes().all(|byte| byte.is_ascii_digit());
+    if (whole.is_empty() && fraction.is_empty()) || !is_digits(whole) || !is_digits(fraction) {
+        return Err(format!("{} is not a token amount", ui_amount));
+    }
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.len() > decimals as usize {
+        return Err(format!(
+            "{} has more than {} decimal places",
+            ui_amount, decimals
+        ));
+    }
+    let units = format!("{}{:0<width$}", whole, fraction, width = decimals as usize);
+    if units.is_empty() {
+        return Ok(0);
+    }
+    units
+        .parse::<u64>()
+        .map_err(|_| format!("{} is too large", ui_amount))
+}
+
+// Rows are `RECIPIENT_ADDRESS,TOKEN_AMOUNT`, optionally preceded by a header
+fn read_distribution_csv(path: &str, decimals: u8) -> Result<Vec<DistributionRow>, Error> {
+    let file = File::open(path).map_err(|err| format!("Unable to open {}: {}", path, err))?;
+    parse_distribution_csv(path, BufReader::new(file), decimals)
+}
+
+fn parse_distribution_csv<R: BufRead>(
+    path: &str,
+    reader: R,
+    decimals: u8,
+) -> Result<Vec<DistributionRow>, Error> {
+    let mut rows = vec![];
+    for (index, line) in reader.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(|field| field.trim()).collect();
+        if fields.len() != 2 {
+            return Err(format!(
+                "{}:{}: expected RECIPIENT_ADDRESS,TOKEN_AMOUNT",
+                path, line_number
+            )
+            .into());
+        }
+        let recipient = match Pubkey::from_str(fields[0]) {
+            Ok(recipient) => recipient,
+            Err(_) if rows.is_empty() && line_number == 1 => continue,
+            Err(err) => {
+                return Err(format!("{}:{}: invalid recipient: {}", path, line_number, err).into())
+            }
+        };
+        let amount = parse_ui_amount(fields[1], decimals)
+            .map_err(|err| format!("{}:{}: invalid amount: {}", path, line_number, err))?;
+        rows.push(DistributionRow {
+            line: line_number,
+            recipient,
+            ui_amount: fields[1].to_string(),
+            amount,
+        });
+    }
+    Ok(rows)
+}
+
+fn distribution_record(
+    row: &DistributionRow,
+    signature: &Signature,
+    blockhash: &Hash,
+    status: &str,
+) -> String {
+    json!({
+        "line": row.line,
+        "recipient": row.recipient.to_string(),
+        "amount": row.ui_amount,
+        "signature": signature.to_string(),
+        "blockhash": blockhash.to_string(),
+        "status": status,
+    })
+    .to_string()
+}
+
+// Looks up a transaction sent by an earlier run.  The ledger history is
+// searched too, since a transaction that landed long ago has left the
+// status cache of the node.
+fn get_sent_transaction_status(
+    config: &Config,
+    signature: &Signature,
+) -> Result<Option<transaction::Result<()>>, Error> {
+    Ok(config
+        .rpc_client
+        .get_signature_status_with_commitment_and_history(
+            signature,
+            config.rpc_client.commitment(),
+            true,
+        )?)
+}
+
+// Lines of the rows whose transfer is recorded as confirmed, and the
+// transaction last sent for each of the other recorded rows
+type DistributionState = (HashSet<usize>, HashMap<usize, (Signature, Hash)>);
+
+fn parse_distribution_state<R: BufRead>(
+    path: &str,
+    reader: R,
+    rows: &[DistributionRow],
+) -> Result<DistributionState, Error> {
+    let rows_by_line: HashMap<usize, &DistributionRow> =
+        rows.iter().map(|row| (row.line, row)).collect();
+
+    let mut confirmed = HashSet::new();
+    let mut unconfirmed = HashMap::new();
+    for line in reader.lines() {
+        let record: Value = serde_json::from_str(&line?)
+            .map_err(|err| format!("{}: invalid record: {}", path, err))?;
+        let field = |name: &str| {
+            record[name]
+                .as_str()
+                .map(|value| value.to_string())
+                .ok_or_else(|| format!("{}: record is missing {}", path, name))
+        };
+        let line = record["line"]
+            .as_u64()
+            .ok_or_else(|| format!("{}: record is missing line", path))?
+            as usize;
+        match rows_by_line.get(&line) {
+            Some(row)
+                if row.recipient.to_string() == field("recipient")?
+                    && row.ui_amount == field("amount")? => {}
+            _ => {
+                return Err(format!(
+                    "{} does not match the distribution at line {}, \
+                     use a new state file for a modified distribution",
+                    path, line
+                )
+                .into())
+            }
+        }
+        if field("status")? == "confirmed" {
+            confirmed.insert(line);
+            unconfirmed.remove(&line);
+        } else if !confirmed.contains(&line) {
+            let signature = Signature::from_str(&field("signature")?)?;
+            let blockhash = Hash::from_str(&field("blockhash")?)?;
+            unconfirmed.insert(line, (signature, blockhash));
+        }
+    }
+    Ok((confirmed, unconfirmed))
+}
+
+// Returns the lines of the rows whose transfer is confirmed.  A transaction
+// that was sent but whose confirmation was not recorded is looked up, and the
+// distribution refuses to resume while it could still land: its rows are only
+// sent again once its blockhash has expired on the finalized bank, which every
+// later block descends from.
+fn load_distribution_state(
+    config: &Config,
+    path: &str,
+    rows: &[DistributionRow],
+) -> Result<HashSet<usize>, Error> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(HashSet::new()),
+        Err(err) => return Err(format!("Unable to open {}: {}", path, err).into()),
+    };
+    let (mut confirmed, unconfirmed) = parse_distribution_state(path, BufReader::new(file), rows)?;
+
+    let mut statuses = HashMap::new();
+    for (line, (signature, blockhash)) in unconfirmed {
+        if !statuses.contains_key(&signature) {
+            let status = get_sent_transaction_status(config, &signature)?;
+            if status.is_none()
+                && config
+                    .rpc_client
+                    .get_fee_calculator_for_blockhash_with_commitment(
+                        &blockhash,
+                        CommitmentConfig::finalized(),
+                    )?
+                    .value
+                    .is_some()
+            {
+                return Err(format!(
+                    "The status of transaction {} for line {} is unknown and it may still \
+                     be confirmed. Its rows are not sent again until its blockhash {} has \
+                     expired, retry in a couple of minutes",
+                    signature, line, blockhash
+                )
+                .into());
+            }
+            statuses.insert(signature, status);
+        }
+        if let Some(Ok(())) = statuses[&signature] {
+            confirmed.insert(line);
+        }
+    }
+    Ok(confirmed)
+}
+
+fn write_distribution_records(
+    state_file: &mut File,
+    transfers: &[DistributionTransfer],
+    signature: &Signature,
+    blockhash: &Hash,
+    status: &str,
+) -> Result<(), Error> {
+    for transfer in transfers {
+        writeln!(
+            state_file,
+            "{}",
+            distribution_record(transfer.row, signature, blockhash, status)
+        )?;
+    }
+    state_file.sync_data()?;
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn command_distribute(
+    config: &Config,
+    signer_info: &CliSignerInfo,
+    sender: Pubkey,
+    csv_path: &str,
+    state_path: &str,
+    fund_recipient: bool,
+    transfers_per_transaction: usize,
+    memo: Option<String>,
+) -> CommandResult {
+    let (mint_pubkey, decimals) = resolve_mint_info(config, &sender, None, None)?;
+    let rows = read_distribution_csv(csv_path, decimals)?;
+    let confirmed = load_distribution_state(config, state_path, &rows)?;
+    let pending_rows: Vec<&DistributionRow> = rows
+        .iter()
+        .filter(|row| !confirmed.contains(&row.line))
+        .collect();
+
+    println_display(config, format!(
+        "Distribute tokens to {} recipients\n  Sender: {}\n  State file: {}\n  Already confirmed: {}",
+        rows.len(),
+        sender,
+        state_path,
+        rows.len() - pending_rows.len()
+    ));
+    if pending_rows.is_empty() {
+        println_display(config, "Nothing to do".to_string());
+        if is_json_output(config) {
+            println_json(
+                config,
+                &json!({
+                    "sender": sender.to_string(),
+                    "stateFile": state_path,
+                    "transfers": [],
+                }),
+            );
+        }
+        return Ok(None);
+    }
+
+    let total_amount = pending_rows
+        .iter()
+        .try_fold(0u64, |total, row| total.checked_add(row.amount))
+        .ok_or("Error: Distribution total overflows")?;
+    let sender_token_amount = config.rpc_client.get_token_account_balance(&sender)?;
+    let sender_balance = sender_token_amount.amount.parse::<u64>()?;
+    if total_amount > sender_balance {
+        return Err(format!(
+            "Error: Sender has insufficient funds for {} tokens, current balance is {}",
+            spl_token::amount_to_ui_amount(total_amount, decimals),
+            sender_token_amount.real_number_string_trimmed()
+        )
+        .into());
+    }
+
+    // Resolve every recipient before sending anything
+    let mut transfers = vec![];
+    let mut created_token_accounts = HashSet::new();
+    for row in pending_rows {
+        let recipient_account_owner = config
+            .rpc_client
+            .get_account_with_commitment(&row.recipient, config.rpc_client.commitment())?
+            .value
+            .map(|account_data| account_data.owner);
+        if recipient_account_owner == Some(spl_token::id()) {
+            transfers.push(DistributionTransfer {
+                row,
+                token_account: row.recipient,
+                create_token_account: false,
+            });
+            continue;
+        }
+
+        let token_account = get_associated_token_address(&row.recipient, &mint_pubkey);
+        let needs_funding = match config
+            .rpc_client
+            .get_account_with_commitment(&token_account, config.rpc_client.commitment())?
+            .value
+        {
+            Some(account_data) if account_data.owner == spl_token::id() => false,
+            Some(account_data) if account_data.owner != system_program::id() => {
+                return Err(format!(
+                    "Error: Unsupported recipient address at line {}: {}",
+                    row.line, row.recipient
+                )
+                .into());
+            }
+            _ => created_token_accounts.insert(token_account),
+        };
+        if needs_funding && !fund_recipient {
+            return Err(format!(
+                "Error: Associated token account of the recipient at line {} does not exist. \
+                 Add `--fund-recipient` to fund their account",
+                row.line
+            )
+            .into());
+        }
+        transfers.push(DistributionTransfer {
+            row,
+            token_account,
+            create_token_account: needs_funding,
+        });
+    }
+
+    let minimum_balance_for_rent_exemption = config
+        .rpc_client
+        .get_minimum_balance_for_rent_exemption(associated_token_account_len(
+            config,
+            &mint_pubkey,
+        )?)?;
+    let mut state_file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(state_path)
+        .map_err(|err| format!("Unable to open {}: {}", state_path, err))?;
+
+    let mut sent_transfers = vec![];
+    for transfers in transfers.chunks(transfers_per_transaction) {
+        let mut instructions = vec![];
+        let mut lamports_needed = 0;
+        for transfer in transfers
+            .iter()
+            .filter(|transfer| transfer.create_token_account)
+        {
+            instructions.push(create_associated_token_account(
+                &config.fee_payer,
+                &transfer.row.recipient,
+                &mint_pubkey,
+            ));
+            lamports_needed += minimum_balance_for_rent_exemption;
+        }
+        let destinations: Vec<(&Pubkey, u64)> = transfers
+            .iter()
+            .map(|transfer| (&transfer.token_account, transfer.row.amount))
+            .collect();
+        if let Some(memo) = &memo {
+            instructions.push(spl_memo::build_memo(memo.as_bytes(), &[]));
+        }
+        instructions.push(transfer_checked_batch_with_instructions_sysvar(
+            &spl_token::id(),
+            &sender,
+            &mint_pubkey,
+            &destinations,
+            &config.owner,
+            &config.multisigner_pubkeys,
+            decimals,
+        )?);
+
+        let message = Message::new(&instructions, Some(&config.fee_payer));
+        let (recent_blockhash, fee_calculator) = config.rpc_client.get_recent_blockhash()?;
+        check_fee_payer_balance(
+            config,
+            lamports_needed + fee_calculator.calculate_fee(&message),
+        )?;
+        let mut transaction = Transaction::new_unsigned(message);
+        transaction.try_sign(&signer_info.signers, recent_blockhash)?;
+        let signature = transaction.signatures[0];
+
+        for transfer in transfers {
+            println_display(
+                config,
+                format!(
+                    "  Line {}: {} tokens to {}",
+                    transfer.row.line, transfer.row.ui_amount, transfer.token_account
+                ),
+            );
+        }
+        // Record the transaction before sending it, so that a rerun can tell
+        // whether it landed
+        write_distribution_records(
+            &mut state_file,
+            transfers,
+            &signature,
+            &recent_blockhash,
+            "sent",
+        )?;
+        config
+            .rpc_client
+            .send_and_confirm_transaction_with_spinner(&transaction)?;
+        write_distribution_records(
+            &mut state_file,
+            transfers,
+            &signature,
+            &recent_blockhash,
+            "confirmed",
+        )?;
+        println_display(config, format!("Signature: {}", signature));
+        sent_transfers.extend(transfers.iter().map(|transfer| {
+            json!({
+                "line": transfer.row.line,
+                "recipient": transfer.row.recipient.to_string(),
+                "tokenAccount": transfer.token_account.to_string(),
+                "amount": transfer.row.ui_amount,
+                "signature": signature.to_string(),
+            })
+        }));
+    }
+
+    if is_json_output(config) {
+        println_json(
+            config,
+            &json!({
+                "sender": sender.to_string(),
+                "stateFile": state_path,
+                "transfers": sent_transfers,
+            }),
+        );
+    }
+    Ok(None)
 }
 
 fn command_burn(
@@ -549,7 +1162,10 @@ fn command_burn(
     mint_address: Option<Pubkey>,
     mint_decimals: Option<u8>,
 ) -> CommandResult {
-    println!("Burn {} tokens\n  Source: {}", ui_amount, source);
+    println_display(
+        config,
+        format!("Burn {} tokens\n  Source: {}", ui_amount, source),
+    );
 
     let (mint_pubkey, decimals) = resolve_mint_info(config, &source, mint_address, mint_decimals)?;
     let amount = spl_token::ui_amount_to_amount(ui_amount, decimals);
@@ -563,7 +1179,18 @@ fn command_burn(
         amount,
         decimals,
     )?];
-    Ok(Some((0, vec![instructions])))
+    Ok(Some(TransactionInfo {
+        minimum_balance_for_rent_exemption: 0,
+        instruction_batches: vec![instructions],
+        output: json!({
+            "source": source.to_string(),
+            "mint": mint_pubkey.to_string(),
+            "amount": amount.to_string(),
+            "uiAmount": ui_amount,
+            "decimals": decimals,
+        }),
+        token_accounts: vec![source],
+    }))
 }
 
 fn command_mint(
@@ -573,9 +1200,12 @@ fn command_mint(
     recipient: Pubkey,
     mint_decimals: Option<u8>,
 ) -> CommandResult {
-    println!(
-        "Minting {} tokens\n  Token: {}\n  Recipient: {}",
-        ui_amount, token, recipient
+    println_display(
+        config,
+        format!(
+            "Minting {} tokens\n  Token: {}\n  Recipient: {}",
+            ui_amount, token, recipient
+        ),
     );
 
     let (_, decimals) = resolve_mint_info(config, &recipient, None, mint_decimals)?;
@@ -590,13 +1220,27 @@ fn command_mint(
         amount,
         decimals,
     )?];
-    Ok(Some((0, vec![instructions])))
+    Ok(Some(TransactionInfo {
+        minimum_balance_for_rent_exemption: 0,
+        instruction_batches: vec![instructions],
+        output: json!({
+            "mint": token.to_string(),
+            "recipient": recipient.to_string(),
+            "amount": amount.to_string(),
+            "uiAmount": ui_amount,
+            "decimals": decimals,
+        }),
+        token_accounts: vec![recipient],
+    }))
 }
 
 fn command_freeze(config: &Config, account: Pubkey, mint_address: Option<Pubkey>) -> CommandResult {
     let (token, _) = resolve_mint_info(config, &account, mint_address, None)?;
 
-    println!("Freezing account: {}\n  Token: {}", account, token);
+    println_display(
+        config,
+        format!("Freezing account: {}\n  Token: {}", account, token),
+    );
 
     let instructions = vec![freeze_account(
         &spl_token::id(),
@@ -605,13 +1249,24 @@ fn command_freeze(config: &Config, account: Pubkey, mint_address: Option<Pubkey>
         &config.owner,
         &config.multisigner_pubkeys,
     )?];
-    Ok(Some((0, vec![instructions])))
+    Ok(Some(TransactionInfo {
+        minimum_balance_for_rent_exemption: 0,
+        instruction_batches: vec![instructions],
+        output: json!({
+            "account": account.to_string(),
+            "mint": token.to_string(),
+        }),
+        token_accounts: vec![],
+    }))
 }
 
 fn command_thaw(config: &Config, account: Pubkey, mint_address: Option<Pubkey>) -> CommandResult {
     let (token, _) = resolve_mint_info(config, &account, mint_address, None)?;
 
-    println!("Freezing account: {}\n  Token: {}", account, token);
+    println_display(
+        config,
+        format!("Freezing account: {}\n  Token: {}", account, token),
+    );
 
     let instructions = vec![thaw_account(
         &spl_token::id(),
@@ -620,12 +1275,20 @@ fn command_thaw(config: &Config, account: Pubkey, mint_address: Option<Pubkey>)
         &config.owner,
         &config.multisigner_pubkeys,
     )?];
-    Ok(Some((0, vec![instructions])))
+    Ok(Some(TransactionInfo {
+        minimum_balance_for_rent_exemption: 0,
+        instruction_batches: vec![instructions],
+        output: json!({
+            "account": account.to_string(),
+            "mint": token.to_string(),
+        }),
+        token_accounts: vec![],
+    }))
 }
 
 fn command_wrap(config: &Config, sol: f64, account: Pubkey) -> CommandResult {
     let lamports = sol_to_lamports(sol);
-    println!("Wrapping {} SOL into {}", sol, account);
+    println_display(config, format!("Wrapping {} SOL into {}", sol, account));
 
     let instructions = vec![
         system_instruction::create_account(
@@ -645,27 +1308,46 @@ fn command_wrap(config: &Config, sol: f64, account: Pubkey) -> CommandResult {
     if !config.sign_only {
         check_owner_balance(config, lamports)?;
     }
-    Ok(Some((0, vec![instructions])))
+    Ok(Some(TransactionInfo {
+        minimum_balance_for_rent_exemption: 0,
+        instruction_batches: vec![instructions],
+        output: json!({
+            "account": account.to_string(),
+            "lamports": lamports,
+        }),
+        token_accounts: vec![account],
+    }))
 }
 
 fn command_unwrap(config: &Config, address: Pubkey) -> CommandResult {
-    println!("Unwrapping {}", address);
+    println_display(config, format!("Unwrapping {}", address));
     if !config.sign_only {
-        println!(
-            "  Amount: {} SOL",
-            lamports_to_sol(config.rpc_client.get_balance(&address)?),
+        println_display(
+            config,
+            format!(
+                "  Amount: {} SOL",
+                lamports_to_sol(config.rpc_client.get_balance(&address)?),
+            ),
         );
     }
-    println!("  Recipient: {}", &config.owner);
+    println_display(config, format!("  Recipient: {}", &config.owner));
 
-    let instructions = vec![close_account(
+    let instructions = vec![close_account_with_instructions_sysvar(
         &spl_token::id(),
         &address,
         &config.owner,
         &config.owner,
         &config.multisigner_pubkeys,
     )?];
-    Ok(Some((0, vec![instructions])))
+    Ok(Some(TransactionInfo {
+        minimum_balance_for_rent_exemption: 0,
+        instruction_batches: vec![instructions],
+        output: json!({
+            "account": address.to_string(),
+            "recipient": config.owner.to_string(),
+        }),
+        token_accounts: vec![],
+    }))
 }
 
 fn command_approve(
@@ -676,15 +1358,18 @@ fn command_approve(
     mint_address: Option<Pubkey>,
     mint_decimals: Option<u8>,
 ) -> CommandResult {
-    println!(
-        "Approve {} tokens\n  Account: {}\n  Delegate: {}",
-        ui_amount, account, delegate
+    println_display(
+        config,
+        format!(
+            "Approve {} tokens\n  Account: {}\n  Delegate: {}",
+            ui_amount, account, delegate
+        ),
     );
 
     let (mint_pubkey, decimals) = resolve_mint_info(config, &account, mint_address, mint_decimals)?;
     let amount = spl_token::ui_amount_to_amount(ui_amount, decimals);
 
-    let instructions = vec![approve_checked(
+    let instructions = vec![approve_checked_with_instructions_sysvar(
         &spl_token::id(),
         &account,
         &mint_pubkey,
@@ -694,7 +1379,18 @@ fn command_approve(
         amount,
         decimals,
     )?];
-    Ok(Some((0, vec![instructions])))
+    Ok(Some(TransactionInfo {
+        minimum_balance_for_rent_exemption: 0,
+        instruction_batches: vec![instructions],
+        output: json!({
+            "account": account.to_string(),
+            "delegate": delegate.to_string(),
+            "amount": amount.to_string(),
+            "uiAmount": ui_amount,
+            "decimals": decimals,
+        }),
+        token_accounts: vec![],
+    }))
 }
 
 fn command_revoke(config: &Config, account: Pubkey, delegate: Option<Pubkey>) -> CommandResult {
@@ -712,14 +1408,18 @@ fn command_revoke(config: &Config, account: Pubkey, delegate: Option<Pubkey>) ->
         delegate
     };
 
-    if let Some(delegate) = delegate {
-        println!(
-            "Revoking approval\n  Account: {}\n  Delegate: {}",
-            account, delegate
+    let delegate = if let Some(delegate) = delegate {
+        println_display(
+            config,
+            format!(
+                "Revoking approval\n  Account: {}\n  Delegate: {}",
+                account, delegate
+            ),
         );
+        delegate
     } else {
         return Err(format!("No delegate on account {}", account).into());
-    }
+    };
 
     let instructions = vec![revoke(
         &spl_token::id(),
@@ -727,7 +1427,15 @@ fn command_revoke(config: &Config, account: Pubkey, delegate: Option<Pubkey>) ->
         &config.owner,
         &config.multisigner_pubkeys,
     )?];
-    Ok(Some((0, vec![instructions])))
+    Ok(Some(TransactionInfo {
+        minimum_balance_for_rent_exemption: 0,
+        instruction_batches: vec![instructions],
+        output: json!({
+            "account": account.to_string(),
+            "delegate": delegate.to_string(),
+        }),
+        token_accounts: vec![],
+    }))
 }
 
 fn command_close(config: &Config, account: Pubkey, destination: Pubkey) -> CommandResult {
@@ -757,20 +1465,30 @@ fn command_close(config: &Config, account: Pubkey, destination: Pubkey) -> Comma
         }
     }
 
-    let instructions = vec![close_account(
+    let instructions = vec![close_account_with_instructions_sysvar(
         &spl_token::id(),
         &account,
         &destination,
         &config.owner,
         &config.multisigner_pubkeys,
     )?];
-    Ok(Some((0, vec![instructions])))
+    Ok(Some(TransactionInfo {
+        minimum_balance_for_rent_exemption: 0,
+        instruction_batches: vec![instructions],
+        output: json!({
+            "account": account.to_string(),
+            "destination": destination.to_string(),
+        }),
+        token_accounts: vec![],
+    }))
 }
 
 fn command_balance(config: &Config, address: Pubkey) -> CommandResult {
     let balance = config.rpc_client.get_token_account_balance(&address)?;
 
-    if config.verbose {
+    if is_json_output(config) {
+        println_json(config, &serde_json::to_value(&balance)?);
+    } else if config.verbose {
         println!("ui amount: {}", balance.real_number_string_trimmed());
         println!("decimals: {}", balance.decimals);
         println!("amount: {}", balance.amount);
@@ -783,10 +1501,55 @@ fn command_balance(config: &Config, address: Pubkey) -> CommandResult {
 fn command_supply(config: &Config, address: Pubkey) -> CommandResult {
     let supply = config.rpc_client.get_token_supply(&address)?;
 
+    if is_json_output(config) {
+        let mut output = json!({ "supply": supply });
+        if config.verbose {
+            output["tokenMetadata"] = get_token_metadata(config, &address)?
+                .as_ref()
+                .map_or(Value::Null, token_metadata_json);
+        }
+        println_json(config, &output);
+        return Ok(None);
+    }
+
     println!("{}", supply.real_number_string_trimmed());
+    if config.verbose {
+        if let Some(token_metadata) = get_token_metadata(config, &address)? {
+            println_token_metadata(&token_metadata);
+        }
+    }
     Ok(None)
 }
 
+fn get_token_metadata(config: &Config, mint: &Pubkey) -> Result<Option<TokenMetadata>, Error> {
+    let account = config.rpc_client.get_account(mint)?;
+    get_extension::<TokenMetadata>(&account.data).map_err(|e| e.into())
+}
+
+fn token_metadata_json(token_metadata: &TokenMetadata) -> Value {
+    json!({
+        "name": token_metadata.name,
+        "symbol": token_metadata.symbol,
+        "uri": token_metadata.uri,
+        "updateAuthority": token_metadata
+            .update_authority
+            .map(|pubkey| pubkey.to_string()),
+    })
+}
+
+fn println_token_metadata(token_metadata: &TokenMetadata) {
+    println_name_value("Name:", &token_metadata.name);
+    println_name_value("Symbol:", &token_metadata.symbol);
+    println_name_value("URI:", &token_metadata.uri);
+    println_name_value(
+        "Metadata update authority:",
+        &token_metadata
+            .update_authority
+            .map(|pubkey| pubkey.to_string())
+            .unwrap_or_default(),
+    );
+}
+
 fn command_accounts(config: &Config, token: Option<Pubkey>) -> CommandResult {
     let accounts = config.rpc_client.get_token_accounts_by_owner(
         &config.owner,
@@ -795,6 +1558,28 @@ fn command_accounts(config: &Config, token: Option<Pubkey>) -> CommandResult {
             None => TokenAccountsFilter::ProgramId(spl_token::id()),
         },
     )?;
+
+    if is_json_output(config) {
+        let accounts: Vec<Value> = accounts
+            .into_iter()
+            .filter_map(|keyed_account| match keyed_account.account.data {
+                UiAccountData::Json(parsed_account) if parsed_account.program == "spl-token" => {
+                    match serde_json::from_value(parsed_account.parsed) {
+                        Ok(TokenAccountType::Account(ui_token_account)) => {
+                            let mut account = serde_json::to_value(ui_token_account).ok()?;
+                            account["address"] = Value::String(keyed_account.pubkey);
+                            Some(account)
+                        }
+                        _ => None,
+                    }
+                }
+                _ => None,
+            })
+            .collect();
+        println_json(config, &json!({ "accounts": accounts }));
+        return Ok(None);
+    }
+
     if accounts.is_empty() {
         println!("None");
     }
@@ -851,25 +1636,160 @@ fn command_accounts(config: &Config, token: Option<Pubkey>) -> CommandResult {
     Ok(None)
 }
 
+struct Holder {
+    owner: Pubkey,
+    amount: u64,
+    token_accounts: Vec<Pubkey>,
+}
+
+// Returns the owners of a mint's token accounts with their total balance,
+// largest first.  Accounts listed in `exclude`, or owned by an address listed
+// in `exclude`, are left out, as are owners with a zero balance.
+fn get_holders(config: &Config, mint: &Pubkey, exclude: &[Pubkey]) -> Result<Vec<Holder>, Error> {
+    let accounts = config.rpc_client.get_program_accounts_with_config(
+        &spl_token::id(),
+        RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::Memcmp(Memcmp {
+                offset: 0, // the mint is the first field of a token account
+                bytes: MemcmpEncodedBytes::Binary(mint.to_string()),
+                encoding: None,
+            })]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(config.rpc_client.commitment()),
+                ..RpcAccountInfoConfig::default()
+            },
+        },
+    )?;
+    Ok(aggregate_holders(mint, exclude, accounts))
+}
+
+// Sums the balances of the token accounts of a mint by owner, largest first
+fn aggregate_holders(
+    mint: &Pubkey,
+    exclude: &[Pubkey],
+    accounts: Vec<(Pubkey, SolanaAccount)>,
+) -> Vec<Holder> {
+    let mut holders = BTreeMap::new();
+    for (address, account) in accounts {
+        // a mint or multisig could start with the same bytes
+        if !Account::is_account_data(&account.data) {
+            continue;
+        }
+        let token_account = match Account::unpack_from_account_data(&account.data) {
+            Ok(token_account) => token_account,
+            Err(_) => continue,
+        };
+        if token_account.mint != *mint
+            || exclude.contains(&address)
+            || exclude.contains(&token_account.owner)
+        {
+            continue;
+        }
+        let holder = holders
+            .entry(token_account.owner)
+            .or_insert_with(|| Holder {
+                owner: token_account.owner,
+                amount: 0,
+                token_accounts: vec![],
+            });
+        // cannot overflow, the balances of a mint's accounts sum to its supply
+        holder.amount += token_account.amount;
+        holder.token_accounts.push(address);
+    }
+
+    let mut holders: Vec<Holder> = holders
+        .into_iter()
+        .map(|(_, holder)| holder)
+        .filter(|holder| holder.amount > 0)
+        .collect();
+    holders.sort_by(|a, b| b.amount.cmp(&a.amount).then(a.owner.cmp(&b.owner)));
+    holders
+}
+
+fn command_holders(
+    config: &Config,
+    mint: Pubkey,
+    exclude: Vec<Pubkey>,
+    output_file: Option<&str>,
+) -> CommandResult {
+    let mint_account = config.rpc_client.get_account(&mint)?;
+    if mint_account.owner != spl_token::id() {
+        return Err(format!("Error: {} is not a token mint", mint).into());
+    }
+    let decimals = Mint::unpack_from_account_data(&mint_account.data)
+        .map_err(|_| format!("Error: {} is not a token mint", mint))?
+        .decimals;
+    let slot = config.rpc_client.get_slot()?;
+    let holders = get_holders(config, &mint, &exclude)?;
+    let ui_amount =
+        |amount: u64| token_amount_to_ui_amount(amount, decimals).real_number_string_trimmed();
+
+    let output = if is_json_output(config) {
+        let value = json!({
+            "mint": mint.to_string(),
+            "decimals": decimals,
+            "slot": slot,
+            "excluded": exclude.iter().map(|pubkey| pubkey.to_string()).collect::<Vec<_>>(),
+            "holders": holders
+                .iter()
+                .map(|holder| {
+                    json!({
+                        "owner": holder.owner.to_string(),
+                        "uiAmount": ui_amount(holder.amount),
+                        "amount": holder.amount.to_string(),
+                        "tokenAccounts": holder
+                            .token_accounts
+                            .iter()
+                            .map(|pubkey| pubkey.to_string())
+                            .collect::<Vec<_>>(),
+                    })
+                })
+                .collect::<Vec<_>>(),
+        });
+        format!("{}\n", format_json(&config.output_format, &value))
+    } else {
+        let mut output = "owner,ui_amount,amount,token_accounts\n".to_string();
+        for holder in &holders {
+            output.push_str(&format!(
+                "{},{},{},{}\n",
+                holder.owner,
+                ui_amount(holder.amount),
+                holder.amount,
+                holder.token_accounts.len()
+            ));
+        }
+        output
+    };
+
+    match output_file {
+        Some(path) => {
+            std::fs::write(path, output)
+                .map_err(|err| format!("Unable to write {}: {}", path, err))?;
+            eprintln!(
+                "Wrote {} holders of {} at slot {} to {}",
+                holders.len(),
+                mint,
+                slot,
+                path
+            );
+        }
+        None => print!("{}", output),
+    }
+    Ok(None)
+}
+
 fn command_gc(config: &Config) -> CommandResult {
-    println!("Fetching token accounts");
+    println_display(config, "Fetching token accounts".to_string());
     let accounts = config.rpc_client.get_token_accounts_by_owner(
         &config.owner,
         TokenAccountsFilter::ProgramId(spl_token::id()),
     )?;
     if accounts.is_empty() {
-        println!("Nothing to do");
+        println_display(config, "Nothing to do".to_string());
         return Ok(None);
     }
 
-    let minimum_balance_for_rent_exemption = if !config.sign_only {
-        config
-            .rpc_client
-            .get_minimum_balance_for_rent_exemption(Account::LEN)?
-    } else {
-        0
-    };
-
     let mut accounts_by_token = HashMap::new();
 
     for keyed_account in accounts {
@@ -917,11 +1837,14 @@ fn command_gc(config: &Config) -> CommandResult {
 
     let mut instructions = vec![];
     let mut lamports_needed = 0;
+    let mut associated_token_accounts = vec![];
+    let mut closed_accounts = vec![];
 
     for (token, accounts) in accounts_by_token.into_iter() {
-        println!("Processing token: {}", token);
+        println_display(config, format!("Processing token: {}", token));
         let associated_token_account = get_associated_token_address(&config.owner, &token);
         let total_balance: u64 = accounts.values().map(|account| account.0).sum();
+        associated_token_accounts.push(associated_token_account);
 
         if total_balance > 0 && !accounts.contains_key(&associated_token_account) {
             // Create the associated token account
@@ -930,7 +1853,11 @@ fn command_gc(config: &Config) -> CommandResult {
                 &config.owner,
                 &token,
             )]);
-            lamports_needed += minimum_balance_for_rent_exemption;
+            if !config.sign_only {
+                lamports_needed += config.rpc_client.get_minimum_balance_for_rent_exemption(
+                    associated_token_account_len(config, &token)?,
+                )?;
+            }
         }
 
         for (address, (amount, decimals, frozen, close_authority)) in accounts {
@@ -948,7 +1875,7 @@ fn command_gc(config: &Config) -> CommandResult {
 
             // Transfer the account balance into the associated token account
             if amount > 0 {
-                account_instructions.push(transfer_checked(
+                account_instructions.push(transfer_checked_with_instructions_sysvar(
                     &spl_token::id(),
                     &address,
                     &token,
@@ -961,13 +1888,14 @@ fn command_gc(config: &Config) -> CommandResult {
             }
             // Close the account if config.owner is able to
             if close_authority == config.owner {
-                account_instructions.push(close_account(
+                account_instructions.push(close_account_with_instructions_sysvar(
                     &spl_token::id(),
                     &address,
                     &config.owner,
                     &config.owner,
                     &config.multisigner_pubkeys,
                 )?);
+                closed_accounts.push(address.to_string());
             }
 
             if !account_instructions.is_empty() {
@@ -976,11 +1904,29 @@ fn command_gc(config: &Config) -> CommandResult {
         }
     }
 
-    Ok(Some((lamports_needed, instructions)))
+    Ok(Some(TransactionInfo {
+        minimum_balance_for_rent_exemption: lamports_needed,
+        instruction_batches: instructions,
+        output: json!({
+            "owner": config.owner.to_string(),
+            "closedAccounts": closed_accounts,
+        }),
+        token_accounts: associated_token_accounts,
+    }))
 }
 
 fn command_account_info(config: &Config, address: Pubkey) -> CommandResult {
     let account = config.rpc_client.get_token_account(&address)?.unwrap();
+    if is_json_output(config) {
+        let token_metadata = get_token_metadata(config, &Pubkey::from_str(&account.mint)?)?;
+        let mut output = serde_json::to_value(&account)?;
+        output["address"] = Value::String(address.to_string());
+        output["tokenMetadata"] = token_metadata
+            .as_ref()
+            .map_or(Value::Null, token_metadata_json);
+        println_json(config, &output);
+        return Ok(None);
+    }
     println!();
     println_name_value("Address:", &address.to_string());
     println_name_value(
@@ -993,6 +1939,9 @@ fn command_account_info(config: &Config, address: Pubkey) -> CommandResult {
         if account.is_native { " (native)" } else { "" }
     );
     println_name_value("Mint:", &mint);
+    if let Some(token_metadata) = get_token_metadata(config, &Pubkey::from_str(&account.mint)?)? {
+        println_token_metadata(&token_metadata);
+    }
     println_name_value("Owner:", &account.owner);
     println_name_value("State:", &format!("{:?}", account.state));
     if let Some(delegate) = &account.delegate {
@@ -1019,6 +1968,21 @@ fn command_multisig(config: &Config, address: Pubkey) -> CommandResult {
     let multisig = get_multisig(config, &address)?;
     let n = multisig.n as usize;
     assert!(n <= multisig.signers.len());
+    if is_json_output(config) {
+        println_json(
+            config,
+            &json!({
+                "address": address.to_string(),
+                "m": multisig.m,
+                "n": n,
+                "signers": multisig.signers[..n]
+                    .iter()
+                    .map(|pubkey| pubkey.to_string())
+                    .collect::<Vec<_>>(),
+            }),
+        );
+        return Ok(None);
+    }
     println!();
     println_name_value("Address:", &address.to_string());
     println_name_value("M/N:", &format!("{}/{}", multisig.m, n));
@@ -1032,6 +1996,538 @@ fn command_multisig(config: &Config, address: Pubkey) -> CommandResult {
     Ok(None)
 }
 
+enum DecodedAccount {
+    Mint(Mint),
+    Account(Account),
+    Multisig(Multisig),
+    MultisigProposal(MultisigProposal),
+}
+
+// Reads the data to decode from a file, raw or base64-encoded, or from the
+// argument itself if it is not a file
+fn read_decode_input(input: &str) -> Result<Vec<u8>, Error> {
+    let base64_decode = |text: &str| base64::decode(text.split_whitespace().collect::<String>());
+    if Path::new(input).is_file() {
+        let data =
+            std::fs::read(input).map_err(|err| format!("Unable to read {}: {}", input, err))?;
+        Ok(std::str::from_utf8(&data)
+            .ok()
+            .and_then(|text| base64_decode(text).ok())
+            .unwrap_or(data))
+    } else {
+        base64_decode(input)
+            .map_err(|err| format!("Error: {} is neither a file nor base64: {}", input, err).into())
+    }
+}
+
+// The data may be crafted, so the counts it holds are checked here, before
+// the printing code slices by them
+fn unpack_token_program_account(data: &[u8]) -> Result<DecodedAccount, Error> {
+    let decoded = if data.len() == Multisig::LEN {
+        let multisig = Multisig::unpack(data)?;
+        if multisig.signers.get(..multisig.n as usize).is_none() {
+            return Err(format!(
+                "Error: Multisig data holds {} signers, more than {}",
+                multisig.n, MAX_SIGNERS
+            )
+            .into());
+        }
+        DecodedAccount::Multisig(multisig)
+    } else if Mint::is_mint_data(data) {
+        DecodedAccount::Mint(Mint::unpack_from_account_data(data)?)
+    } else if Account::is_account_data(data) {
+        DecodedAccount::Account(Account::unpack_from_account_data(data)?)
+    } else if data.len() == MultisigProposal::LEN {
+        DecodedAccount::MultisigProposal(MultisigProposal::unpack(data)?)
+    } else {
+        return Err("Error: Data is not that of a token program account".into());
+    };
+    Ok(decoded)
+}
+
+fn coption_pubkey_json(pubkey: &COption<Pubkey>) -> Value {
+    match pubkey {
+        COption::Some(pubkey) => Value::String(pubkey.to_string()),
+        COption::None => Value::Null,
+    }
+}
+
+fn coption_pubkey_string(pubkey: &COption<Pubkey>) -> String {
+    match pubkey {
+        COption::Some(pubkey) => pubkey.to_string(),
+        COption::None => String::new(),
+    }
+}
+
+fn decoded_account_json(decoded: &DecodedAccount, extension_types: &[ExtensionType]) -> Value {
+    let extensions: Vec<String> = extension_types
+        .iter()
+        .map(|extension_type| format!("{:?}", extension_type))
+        .collect();
+    match decoded {
+        DecodedAccount::Mint(mint) => json!({
+            "type": "mint",
+            "mintAuthority": coption_pubkey_json(&mint.mint_authority),
+            "supply": mint.supply.to_string(),
+            "decimals": mint.decimals,
+            "freezeAuthority": coption_pubkey_json(&mint.freeze_authority),
+            "extensions": extensions,
+        }),
+        DecodedAccount::Account(account) => json!({
+            "type": "account",
+            "mint": account.mint.to_string(),
+            "owner": account.owner.to_string(),
+            "amount": account.amount.to_string(),
+            "delegate": coption_pubkey_json(&account.delegate),
+            "delegatedAmount": account.delegated_amount.to_string(),
+            "state": format!("{:?}", account.state).to_lowercase(),
+            "rentExemptReserve": match account.is_native {
+                COption::Some(reserve) => Value::String(reserve.to_string()),
+                COption::None => Value::Null,
+            },
+            "closeAuthority": coption_pubkey_json(&account.close_authority),
+            "extensions": extensions,
+        }),
+        DecodedAccount::Multisig(multisig) => json!({
+            "type": "multisig",
+            "m": multisig.m,
+            "n": multisig.n,
+            "signers": multisig.signers[..multisig.n as usize]
+                .iter()
+                .map(|pubkey| pubkey.to_string())
+                .collect::<Vec<_>>(),
+        }),
+        DecodedAccount::MultisigProposal(proposal) => {
+            let accounts = &proposal.accounts[..proposal.num_accounts as usize];
+            let data = &proposal.data[..proposal.data_len as usize];
+            json!({
+                "type": "multisigProposal",
+                "multisig": proposal.multisig.to_string(),
+                "executed": proposal.is_executed,
+                "approvedSigners": proposal
+                    .approved
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, approved)| **approved)
+                    .map(|(position, _)| position + 1)
+                    .collect::<Vec<_>>(),
+                "instruction": parse_token(data, accounts).unwrap_or(Value::Null),
+                "accounts": accounts.iter().map(|pubkey| pubkey.to_string()).collect::<Vec<_>>(),
+                "data": base64::encode(data),
+            })
+        }
+    }
+}
+
+fn println_decoded_account(decoded: &DecodedAccount, extension_types: &[ExtensionType]) {
+    let extensions = extension_types
+        .iter()
+        .map(|extension_type| format!("{:?}", extension_type))
+        .collect::<Vec<_>>()
+        .join(", ");
+    println!();
+    match decoded {
+        DecodedAccount::Mint(mint) => {
+            println_name_value("Type:", "Mint");
+            println_name_value(
+                "Mint authority:",
+                &coption_pubkey_string(&mint.mint_authority),
+            );
+            println_name_value("Supply:", &mint.supply.to_string());
+            println_name_value("Decimals:", &mint.decimals.to_string());
+            println_name_value(
+                "Freeze authority:",
+                &coption_pubkey_string(&mint.freeze_authority),
+            );
+            println_name_value("Extensions:", &extensions);
+        }
+        DecodedAccount::Account(account) => {
+            println_name_value("Type:", "Token account");
+            println_name_value("Mint:", &account.mint.to_string());
+            println_name_value("Owner:", &account.owner.to_string());
+            println_name_value("Amount:", &account.amount.to_string());
+            println_name_value("State:", &format!("{:?}", account.state));
+            if let COption::Some(delegate) = account.delegate {
+                println!("Delegation:");
+                println_name_value("  Delegate:", &delegate.to_string());
+                println_name_value("  Allowance:", &account.delegated_amount.to_string());
+            } else {
+                println_name_value("Delegation:", "");
+            }
+            if let COption::Some(reserve) = account.is_native {
+                println_name_value("Rent-exempt reserve:", &reserve.to_string());
+            }
+            println_name_value(
+                "Close authority:",
+                &coption_pubkey_string(&account.close_authority),
+            );
+            println_name_value("Extensions:", &extensions);
+        }
+        DecodedAccount::Multisig(multisig) => {
+            println_name_value("Type:", "Multisig");
+            println_name_value("M/N:", &format!("{}/{}", multisig.m, multisig.n));
+            println_name_value("Signers:", " ");
+            for (i, pubkey) in multisig.signers[..multisig.n as usize].iter().enumerate() {
+                println_name_value(&format!("{:>3}:", i + 1), &pubkey.to_string());
+            }
+        }
+        DecodedAccount::MultisigProposal(proposal) => {
+            let accounts = &proposal.accounts[..proposal.num_accounts as usize];
+            let data = &proposal.data[..proposal.data_len as usize];
+            let approved_signers = proposal
+                .approved
+                .iter()
+                .enumerate()
+                .filter(|(_, approved)| **approved)
+                .map(|(position, _)| (position + 1).to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            println_name_value("Type:", "Multisig proposal");
+            println_name_value("Multisig:", &proposal.multisig.to_string());
+            println_name_value("Executed:", &proposal.is_executed.to_string());
+            println_name_value("Approved by signers:", &approved_signers);
+            match parse_token(data, accounts) {
+                Ok(instruction) => println_parsed_instruction("", &instruction),
+                Err(_) => {
+                    println_name_value("Accounts:", " ");
+                    for (i, pubkey) in accounts.iter().enumerate() {
+                        println_name_value(&format!("{:>3}:", i + 1), &pubkey.to_string());
+                    }
+                    println_name_value("Data:", &base64::encode(data));
+                }
+            }
+        }
+    }
+}
+
+// Prints a token instruction parsed by `spl_token::parser`, one field per line
+fn println_parsed_instruction(indent: &str, instruction: &Value) {
+    if let Value::Object(fields) = instruction {
+        for (name, value) in fields {
+            let value = match value {
+                Value::String(value) => value.clone(),
+                value => value.to_string(),
+            };
+            let name = if name == "type" { "instruction" } else { name };
+            println_name_value(&format!("{}{}:", indent, name), &value);
+        }
+    }
+}
+
+fn transaction_json(transaction: &Transaction) -> Value {
+    let message = &transaction.message;
+    json!({
+        "signers": message
+            .account_keys
+            .iter()
+            .zip(transaction.signatures.iter())
+            .map(|(signer, signature)| {
+                json!({
+                    "signer": signer.to_string(),
+                    "signature": if *signature == Signature::default() {
+                        Value::Null
+                    } else {
+                        Value::String(signature.to_string())
+                    },
+                })
+            })
+            .collect::<Vec<_>>(),
+        "recentBlockhash": message.recent_blockhash.to_string(),
+        "instructions": message
+            .instructions
+            .iter()
+            .map(|instruction| {
+                let program_id = message.account_keys.get(instruction.program_id_index as usize);
+                let parsed = if program_id == Some(&spl_token::id()) {
+                    parse_compiled_instruction(instruction, &message.account_keys)
+                        .unwrap_or(Value::Null)
+                } else {
+                    Value::Null
+                };
+                json!({
+                    "programId": program_id.map(|program_id| program_id.to_string()),
+                    "accounts": instruction
+                        .accounts
+                        .iter()
+                        .filter_map(|index| message.account_keys.get(*index as usize))
+                        .map(|pubkey| pubkey.to_string())
+                        .collect::<Vec<_>>(),
+                    "data": base64::encode(&instruction.data),
+                    "parsed": parsed,
+                })
+            })
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn println_transaction(transaction: &Transaction) {
+    let message = &transaction.message;
+    println!();
+    println_name_value("Recent blockhash:", &message.recent_blockhash.to_string());
+    println_name_value("Signatures:", " ");
+    // the signers are the first account keys, in the order of the signatures
+    for (signer, signature) in message
+        .account_keys
+        .iter()
+        .zip(transaction.signatures.iter())
+    {
+        let signature = if *signature == Signature::default() {
+            "missing".to_string()
+        } else {
+            signature.to_string()
+        };
+        println_name_value(&format!("  {}:", signer), &signature);
+    }
+    for (i, instruction) in message.instructions.iter().enumerate() {
+        println!("Instruction {}:", i + 1);
+        let program_id = message
+            .account_keys
+            .get(instruction.program_id_index as usize);
+        let parsed = if program_id == Some(&spl_token::id()) {
+            parse_compiled_instruction(instruction, &message.account_keys).ok()
+        } else {
+            None
+        };
+        match parsed {
+            Some(parsed) => {
+                println_name_value("  Program:", "spl-token");
+                println_parsed_instruction("  ", &parsed);
+            }
+            None => {
+                println_name_value(
+                    "  Program:",
+                    &program_id
+                        .map(|program_id| program_id.to_string())
+                        .unwrap_or_default(),
+                );
+                println_name_value("  Accounts:", " ");
+                for index in &instruction.accounts {
+                    if let Some(pubkey) = message.account_keys.get(*index as usize) {
+                        println_name_value("   ", &pubkey.to_string());
+                    }
+                }
+                println_name_value("  Data:", &base64::encode(&instruction.data));
+            }
+        }
+    }
+}
+
+fn command_decode(
+    output_format: &OutputFormat,
+    input: &str,
+    data_type: Option<&str>,
+) -> Result<(), Error> {
+    let data = read_decode_input(input)?;
+    let json_output = matches!(
+        output_format,
+        OutputFormat::Json | OutputFormat::JsonCompact
+    );
+
+    let decoded_account = match data_type {
+        Some("transaction") => None,
+        Some(_) => Some(unpack_token_program_account(&data)?),
+        None => unpack_token_program_account(&data).ok(),
+    };
+    if let Some(decoded) = decoded_account {
+        let extension_types = match decoded {
+            DecodedAccount::Mint(_) | DecodedAccount::Account(_) => get_extension_types(&data)?,
+            _ => vec![],
+        };
+        if json_output {
+            let value = decoded_account_json(&decoded, &extension_types);
+            println!("{}", format_json(output_format, &value));
+        } else {
+            println_decoded_account(&decoded, &extension_types);
+        }
+        return Ok(());
+    }
+
+    let transaction: Transaction = bincode::deserialize(&data).map_err(|err| {
+        if data_type.is_some() {
+            format!("Error: Data is not a serialized transaction: {}", err)
+        } else {
+            "Error: Data is neither a token program account nor a serialized transaction"
+                .to_string()
+        }
+    })?;
+    if json_output {
+        println!(
+            "{}",
+            format_json(output_format, &transaction_json(&transaction))
+        );
+    } else {
+        println_transaction(&transaction);
+    }
+    Ok(())
+}
+
+// Transaction files hold a JSON object listing the base64-encoded serialized
+// transactions of a command, signed as far as their creator could
+fn write_transaction_file(path: &str, transactions: &[Transaction]) -> Result<(), Error> {
+    let transactions = transactions
+        .iter()
+        .map(|transaction| bincode::serialize(transaction).map(base64::encode))
+        .collect::<Result<Vec<_>, _>>()?;
+    let contents = serde_json::to_string_pretty(&json!({ "transactions": transactions }))?;
+    std::fs::write(path, contents)
+        .map_err(|err| format!("Unable to write {}: {}", path, err).into())
+}
+
+fn read_transaction_file(path: &str) -> Result<Vec<Transaction>, Error> {
+    let file = File::open(path).map_err(|err| format!("Unable to open {}: {}", path, err))?;
+    let contents: Value = serde_json::from_reader(BufReader::new(file))
+        .map_err(|err| format!("Unable to parse {}: {}", path, err))?;
+    contents["transactions"]
+        .as_array()
+        .ok_or_else(|| format!("Error: {} is not a transaction file", path))?
+        .iter()
+        .map(|transaction| {
+            let data = base64::decode(
+                transaction
+                    .as_str()
+                    .ok_or_else(|| format!("Error: {} is not a transaction file", path))?,
+            )?;
+            Ok(bincode::deserialize(&data)?)
+        })
+        .collect()
+}
+
+// Returns the signers whose signature is missing from the transactions, in
+// order of appearance
+fn missing_signers(transactions: &[Transaction]) -> Vec<Pubkey> {
+    let mut missing_signers = vec![];
+    for transaction in transactions {
+        for (signer, signature) in transaction
+            .message
+            .account_keys
+            .iter()
+            .zip(transaction.signatures.iter())
+        {
+            if *signature == Signature::default() && !missing_signers.contains(signer) {
+                missing_signers.push(*signer);
+            }
+        }
+    }
+    missing_signers
+}
+
+fn command_sign(
+    output_format: &OutputFormat,
+    path: &str,
+    signer: &dyn Signer,
+) -> Result<(), Error> {
+    let mut transactions = read_transaction_file(path)?;
+    let json_output = matches!(
+        output_format,
+        OutputFormat::Json | OutputFormat::JsonCompact
+    );
+    let pubkey = signer.pubkey();
+
+    let mut signed = 0;
+    for transaction in transactions.iter_mut() {
+        if !json_output {
+            println_transaction(transaction);
+        }
+        let position = transaction
+            .message
+            .account_keys
+            .iter()
+            .take(transaction.signatures.len())
+            .position(|signer| *signer == pubkey);
+        if let Some(position) = position {
+            // Sign the message as created, keeping its blockhash or nonce
+            transaction.signatures[position] =
+                signer.try_sign_message(&transaction.message_data())?;
+            signed += 1;
+        }
+    }
+    if signed == 0 {
+        return Err(format!(
+            "Error: {} does not sign the transactions in {}",
+            pubkey, path
+        )
+        .into());
+    }
+    write_transaction_file(path, &transactions)?;
+
+    let missing_signers = missing_signers(&transactions);
+    if json_output {
+        let value = json!({
+            "signer": pubkey.to_string(),
+            "transactionFile": path,
+            "missingSigners": missing_signers
+                .iter()
+                .map(|pubkey| pubkey.to_string())
+                .collect::<Vec<_>>(),
+        });
+        println!("{}", format_json(output_format, &value));
+    } else {
+        println!();
+        println!("Signed {} transaction(s) in {} by {}", signed, path, pubkey);
+        if missing_signers.is_empty() {
+            println!(
+                "All signatures are present, send the transactions with `spl-token broadcast {}`",
+                path
+            );
+        } else {
+            println!("Awaiting signatures from:");
+            for signer in missing_signers {
+                println!("  {}", signer);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn command_broadcast(config: &Config, path: &str) -> CommandResult {
+    let transactions = read_transaction_file(path)?;
+    let missing_signers = missing_signers(&transactions);
+    if !missing_signers.is_empty() {
+        return Err(format!(
+            "Error: {} is missing signatures from: {}",
+            path,
+            missing_signers
+                .iter()
+                .map(|pubkey| pubkey.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+        .into());
+    }
+    for transaction in &transactions {
+        transaction
+            .verify()
+            .map_err(|_| format!("Error: {} holds an invalid signature", path))?;
+    }
+
+    let mut signatures = vec![];
+    for transaction in &transactions {
+        let signature = transaction.signatures[0];
+        // An interrupted broadcast may have already landed some transactions.
+        // Sending one again otherwise is safe, since a signed transaction
+        // lands at most once.
+        if let Some(Ok(())) = get_sent_transaction_status(config, &signature)? {
+            println_display(config, format!("Already confirmed: {}", signature));
+        } else {
+            config
+                .rpc_client
+                .send_and_confirm_transaction_with_spinner(transaction)?;
+            println_display(config, format!("Signature: {}", signature));
+        }
+        signatures.push(signature.to_string());
+    }
+    if is_json_output(config) {
+        println_json(
+            config,
+            &json!({
+                "transactionFile": path,
+                "signatures": signatures,
+            }),
+        );
+    }
+    Ok(None)
+}
+
 struct SignOnlyNeedsFullMintSpec {}
 impl offline::ArgsConfig for SignOnlyNeedsFullMintSpec {
     fn sign_only_arg<'a, 'b>(&self, arg: Arg<'a, 'b>) -> Arg<'a, 'b> {
@@ -1088,6 +2584,15 @@ fn main() {
                 .global(true)
                 .help("Show additional information"),
         )
+        .arg(
+            Arg::with_name("output_format")
+                .long("output")
+                .value_name("FORMAT")
+                .global(true)
+                .takes_value(true)
+                .possible_values(&["json", "json-compact"])
+                .help("Return information in specified output format"),
+        )
         .arg(
             Arg::with_name("json_rpc_url")
                 .short("u")
@@ -1236,11 +2741,23 @@ fn main() {
                     Arg::with_name("authority_type")
                         .value_name("AUTHORITY_TYPE")
                         .takes_value(true)
-                        .possible_values(&["mint", "freeze", "owner", "close"])
+                        .possible_values(&[
+                            "mint",
+                            "freeze",
+                            "owner",
+                            "close",
+                            "permanent-delegate",
+                            "withdraw-withheld",
+                            "close-mint",
+                            "metadata",
+                            "interest-rate",
+                        ])
                         .index(2)
                         .required(true)
                         .help("The new authority type. \
-                            Token mints support `mint` and `freeze` authorities;\
+                            Token mints support `mint`, `freeze`, `permanent-delegate`, \
+                            `withdraw-withheld`, `close-mint`, `metadata` and `interest-rate` \
+                            authorities;\
                             Token accounts support `owner` and `close` authorities."),
                 )
                 .arg(
@@ -1260,6 +2777,7 @@ fn main() {
                         .help("Disable mint, freeze, or close functionality by setting authority to None.")
                 )
                 .arg(multisig_signer_arg())
+                .arg(transaction_file_arg())
                 .nonce_args(true)
                 .offline_args(),
         )
@@ -1307,11 +2825,77 @@ fn main() {
                         .takes_value(false)
                         .help("Create the associated token account for the recipient if doesn't already exist")
                 )
+                .arg(
+                    Arg::with_name("memo")
+                        .long("memo")
+                        .value_name("MEMO")
+                        .takes_value(true)
+                        .help("Memo to include with the transfer, required by recipient accounts \
+                               that only accept transfers with memos")
+                )
                 .arg(multisig_signer_arg())
+                .arg(transaction_file_arg())
                 .mint_args()
                 .nonce_args(true)
                 .offline_args_config(&SignOnlyNeedsFullMintSpec{}),
         )
+        .subcommand(
+            SubCommand::with_name("distribute")
+                .about("Transfer tokens to the recipients listed in a CSV file")
+                .arg(
+                    Arg::with_name("sender")
+                        .validator(is_valid_pubkey)
+                        .value_name("SENDER_TOKEN_ACCOUNT_ADDRESS")
+                        .takes_value(true)
+                        .index(1)
+                        .required(true)
+                        .help("The token account address of the sender"),
+                )
+                .arg(
+                    Arg::with_name("csv")
+                        .value_name("CSV_PATH")
+                        .takes_value(true)
+                        .index(2)
+                        .required(true)
+                        .help("CSV file with one RECIPIENT_ADDRESS,TOKEN_AMOUNT row per transfer. \
+                               Like `transfer`, a recipient wallet address receives the tokens in \
+                               its associated token account"),
+                )
+                .arg(
+                    Arg::with_name("state_file")
+                        .long("state-file")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .help("File recording the signature of each row's transfer, so that \
+                               rerunning the distribution skips confirmed rows \
+                               [default: CSV_PATH.state]"),
+                )
+                .arg(
+                    Arg::with_name("transfers_per_transaction")
+                        .long("transfers-per-transaction")
+                        .value_name("NUMBER")
+                        .validator(is_transfers_per_transaction)
+                        .takes_value(true)
+                        .default_value("5")
+                        .help("Maximum number of rows transferred by each transaction. \
+                               Lower it if transactions exceed the size limit"),
+                )
+                .arg(
+                    Arg::with_name("fund_recipient")
+                        .long("fund-recipient")
+                        .takes_value(false)
+                        .help("Create the associated token accounts of recipients that don't have one")
+                )
+                .arg(
+                    Arg::with_name("memo")
+                        .long("memo")
+                        .value_name("MEMO")
+                        .takes_value(true)
+                        .help("Memo to include with each transaction, required by recipient \
+                               accounts that only accept transfers with memos")
+                )
+                .arg(multisig_signer_arg()),
+        )
         .subcommand(
             SubCommand::with_name("burn")
                 .about("Burn tokens from an account")
@@ -1334,6 +2918,7 @@ fn main() {
                         .help("Amount to burn, in tokens"),
                 )
                 .arg(multisig_signer_arg())
+                .arg(transaction_file_arg())
                 .mint_args()
                 .nonce_args(true)
                 .offline_args_config(&SignOnlyNeedsFullMintSpec{}),
@@ -1369,6 +2954,7 @@ fn main() {
                 )
                 .arg(mint_decimals_arg())
                 .arg(multisig_signer_arg())
+                .arg(transaction_file_arg())
                 .nonce_args(true)
                 .offline_args_config(&SignOnlyNeedsMintDecimals{}),
         )
@@ -1386,6 +2972,7 @@ fn main() {
                 )
                 .arg(mint_address_arg())
                 .arg(multisig_signer_arg())
+                .arg(transaction_file_arg())
                 .nonce_args(true)
                 .offline_args_config(&SignOnlyNeedsMintAddress{}),
         )
@@ -1403,6 +2990,7 @@ fn main() {
                 )
                 .arg(mint_address_arg())
                 .arg(multisig_signer_arg())
+                .arg(transaction_file_arg())
                 .nonce_args(true)
                 .offline_args_config(&SignOnlyNeedsMintAddress{}),
         )
@@ -1444,6 +3032,42 @@ fn main() {
                         .help("Limit results to the given token. [Default: list accounts for all tokens]"),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("holders")
+                .about("Export the holders of a token and their balances")
+                .arg(
+                    Arg::with_name("token")
+                        .validator(is_valid_pubkey)
+                        .value_name("TOKEN_ADDRESS")
+                        .takes_value(true)
+                        .index(1)
+                        .required(true)
+                        .help("The token to list the holders of"),
+                )
+                .arg(
+                    Arg::with_name("exclude")
+                        .long("exclude")
+                        .validator(is_valid_pubkey)
+                        .value_name("ADDRESS")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help(
+                            "Leave out a token account, or all token accounts of an owner, \
+                             such as a treasury. May be specified multiple times",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("output_file")
+                        .long("output-file")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .help(
+                            "Write the holders to this file instead of standard output. \
+                             Holders are written as CSV, or as JSON with `--output json`",
+                        ),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("wrap")
                 .about("Wrap native SOL in a SOL token account")
@@ -1472,6 +3096,7 @@ fn main() {
                         .help("The address of the token account to unwrap"),
                 )
                 .arg(multisig_signer_arg())
+                .arg(transaction_file_arg())
                 .nonce_args(true)
                 .offline_args(),
         )
@@ -1532,6 +3157,7 @@ fn main() {
                         .help("The token account address of delegate"),
                 )
                 .arg(multisig_signer_arg())
+                .arg(transaction_file_arg())
                 .mint_args()
                 .nonce_args(true)
                 .offline_args_config(&SignOnlyNeedsFullMintSpec{}),
@@ -1550,6 +3176,7 @@ fn main() {
                 )
                 .arg(delegate_address_arg())
                 .arg(multisig_signer_arg())
+                .arg(transaction_file_arg())
                 .nonce_args(true)
                 .offline_args_config(&SignOnlyNeedsDelegateAddress{}),
         )
@@ -1574,6 +3201,7 @@ fn main() {
                         .help("The address of the account to receive remaining SOL [default: --owner]"),
                 )
                 .arg(multisig_signer_arg())
+                .arg(transaction_file_arg())
                 .nonce_args(true)
                 .offline_args(),
         )
@@ -1581,6 +3209,64 @@ fn main() {
             SubCommand::with_name("gc")
                 .about("Cleanup unnecessary token accounts")
         )
+        .subcommand(
+            SubCommand::with_name("sign")
+                .about("Sign a transaction file written with --transaction-file, without network access")
+                .arg(
+                    Arg::with_name("file")
+                        .value_name("TRANSACTION_FILE")
+                        .takes_value(true)
+                        .index(1)
+                        .required(true)
+                        .help("The transaction file to add the signature to"),
+                )
+                .arg(
+                    Arg::with_name("signer")
+                        .long("signer")
+                        .value_name("KEYPAIR")
+                        .validator(is_valid_signer)
+                        .takes_value(true)
+                        .help("Specify the signer to sign with. [default: client keypair]"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("broadcast")
+                .about("Send the transactions of a fully signed transaction file")
+                .arg(
+                    Arg::with_name("file")
+                        .value_name("TRANSACTION_FILE")
+                        .takes_value(true)
+                        .index(1)
+                        .required(true)
+                        .help("The transaction file to send"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("decode")
+                .about("Decode token account data or a transaction, without network access")
+                .arg(
+                    Arg::with_name("input")
+                        .value_name("FILE_OR_BASE64")
+                        .takes_value(true)
+                        .index(1)
+                        .required(true)
+                        .help(
+                            "A file holding the data, raw or base64-encoded, \
+                             or the base64-encoded data itself",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("type")
+                        .long("type")
+                        .value_name("TYPE")
+                        .takes_value(true)
+                        .possible_values(&["account", "transaction"])
+                        .help(
+                            "Decode the data as account data or as a serialized transaction \
+                             [default: account data if its layout matches a token account]",
+                        ),
+                ),
+        )
         .get_matches();
 
     let mut wallet_manager = None;
@@ -1590,12 +3276,53 @@ fn main() {
     let (sub_command, sub_matches) = app_matches.subcommand();
     let matches = sub_matches.unwrap();
 
+    let output_format = matches
+        .value_of("output_format")
+        .map(|value| match value {
+            "json" => OutputFormat::Json,
+            "json-compact" => OutputFormat::JsonCompact,
+            _ => unreachable!(),
+        })
+        .unwrap_or(OutputFormat::Display);
+
+    let cli_config = if let Some(config_file) = matches.value_of("config_file") {
+        solana_cli_config::Config::load(config_file).unwrap_or_default()
+    } else {
+        solana_cli_config::Config::default()
+    };
+
+    // Decoding and signing are fully offline, so they need no RPC URL
+    if sub_command == "decode" {
+        let input = matches.value_of("input").unwrap();
+        let data_type = matches.value_of("type");
+        command_decode(&output_format, input, data_type).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            exit(1);
+        });
+        return;
+    }
+    if sub_command == "sign" {
+        let path = matches.value_of("file").unwrap();
+        let signer = signer_from_path(
+            &matches,
+            matches
+                .value_of("signer")
+                .unwrap_or(&cli_config.keypair_path),
+            "signer",
+            &mut wallet_manager,
+        )
+        .unwrap_or_else(|e| {
+            eprintln!("error: {}", e);
+            exit(1);
+        });
+        command_sign(&output_format, path, signer.as_ref()).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            exit(1);
+        });
+        return;
+    }
+
     let config = {
-        let cli_config = if let Some(config_file) = matches.value_of("config_file") {
-            solana_cli_config::Config::load(config_file).unwrap_or_default()
-        } else {
-            solana_cli_config::Config::default()
-        };
         let json_rpc_url = normalize_to_url_if_moniker(
             matches
                 .value_of("json_rpc_url")
@@ -1611,8 +3338,12 @@ fn main() {
             path: default_signer_path,
             arg_name: default_signer_arg_name,
         };
-        // Owner doesn't sign when it's a multisig; and the `accounts` command is read-only
-        let owner = if matches.is_present(MULTISIG_SIGNER_ARG.name) || sub_command == "accounts" {
+        // Owner doesn't sign when it's a multisig; and the `accounts` and `broadcast` commands
+        // don't sign either
+        let owner = if matches.is_present(MULTISIG_SIGNER_ARG.name)
+            || sub_command == "accounts"
+            || sub_command == "broadcast"
+        {
             let owner_val = matches
                 .value_of("owner")
                 .unwrap_or(&cli_config.keypair_path);
@@ -1672,15 +3403,34 @@ fn main() {
         let blockhash_query = BlockhashQuery::new_from_matches(matches);
         let sign_only = matches.is_present(SIGN_ONLY_ARG.name);
 
-        let multisig_signers = signers_of(&matches, MULTISIG_SIGNER_ARG.name, &mut wallet_manager)
+        let transaction_file = matches
+            .value_of(TRANSACTION_FILE_ARG.name)
+            .map(|path| path.to_string());
+        if transaction_file.is_some() {
+            // The multisig signers sign the transaction file later on
+            multisigner_ids = pubkeys_of_multiple_signers(
+                &matches,
+                MULTISIG_SIGNER_ARG.name,
+                &mut wallet_manager,
+            )
             .unwrap_or_else(|e| {
                 eprintln!("error: {}", e);
                 exit(1);
-            });
-        if let Some(multisig_signers) = multisig_signers {
-            let (signers, pubkeys): (Vec<_>, Vec<_>) = multisig_signers.into_iter().unzip();
-            bulk_signers.extend(signers.into_iter().map(Some));
-            multisigner_ids = pubkeys;
+            })
+            .unwrap_or_default();
+        } else {
+            let multisig_signers =
+                signers_of(&matches, MULTISIG_SIGNER_ARG.name, &mut wallet_manager).unwrap_or_else(
+                    |e| {
+                        eprintln!("error: {}", e);
+                        exit(1);
+                    },
+                );
+            if let Some(multisig_signers) = multisig_signers {
+                let (signers, pubkeys): (Vec<_>, Vec<_>) = multisig_signers.into_iter().unzip();
+                bulk_signers.extend(signers.into_iter().map(Some));
+                multisigner_ids = pubkeys;
+            }
         }
         let multisigner_pubkeys = multisigner_ids.iter().collect::<Vec<_>>();
 
@@ -1695,15 +3445,44 @@ fn main() {
             blockhash_query,
             sign_only,
             multisigner_pubkeys,
+            output_format,
+            transaction_file,
         }
     };
 
-    if matches.is_present(MULTISIG_SIGNER_ARG.name)
-        && !config.sign_only
-        && get_multisig(&config, &config.owner).is_err()
-    {
-        eprintln!("error: {} is not a multisig account", config.owner);
-        exit(1);
+    if matches.is_present(MULTISIG_SIGNER_ARG.name) && !config.sign_only {
+        let multisig = get_multisig(&config, &config.owner).unwrap_or_else(|_| {
+            eprintln!("error: {} is not a multisig account", config.owner);
+            exit(1);
+        });
+        // Every signer of a transaction file must sign it before it is sent, so
+        // the message lists exactly as many signers as the multisig requires:
+        // with fewer it would fail, and with more an absent signer would hold
+        // back a transaction that does not need them
+        if config.transaction_file.is_some() {
+            let members = &multisig.signers[..multisig.n as usize];
+            if let Some(signer) = config
+                .multisigner_pubkeys
+                .iter()
+                .find(|signer| !members.contains(signer))
+            {
+                eprintln!(
+                    "error: {} is not a signer of multisig {}",
+                    signer, config.owner
+                );
+                exit(1);
+            }
+            if config.multisigner_pubkeys.len() != multisig.m as usize {
+                eprintln!(
+                    "error: a transaction file for multisig {} is signed by exactly {} signers, \
+                     {} were given",
+                    config.owner,
+                    multisig.m,
+                    config.multisigner_pubkeys.len()
+                );
+                exit(1);
+            }
+        }
     }
 
     solana_logger::setup_with_default("solana=info");
@@ -1790,6 +3569,11 @@ fn main() {
                 "freeze" => AuthorityType::FreezeAccount,
                 "owner" => AuthorityType::AccountOwner,
                 "close" => AuthorityType::CloseAccount,
+                "permanent-delegate" => AuthorityType::PermanentDelegate,
+                "withdraw-withheld" => AuthorityType::WithdrawWithheldTokens,
+                "close-mint" => AuthorityType::CloseMint,
+                "metadata" => AuthorityType::MetadataUpdate,
+                "interest-rate" => AuthorityType::InterestRate,
                 _ => unreachable!(),
             };
             let new_authority =
@@ -1813,6 +3597,7 @@ fn main() {
             let mint_decimals = value_of::<u8>(&arg_matches, MINT_DECIMALS_ARG.name);
             let fund_recipient = matches.is_present("fund_recipient");
             let allow_empty_recipient = matches.is_present("allow_empty_recipient");
+            let memo = arg_matches.value_of("memo").map(String::from);
             command_transfer(
                 &config,
                 sender,
@@ -1822,6 +3607,44 @@ fn main() {
                 fund_recipient,
                 mint_address,
                 mint_decimals,
+                memo,
+            )
+        }
+        ("distribute", Some(arg_matches)) => {
+            let sender = pubkey_of_signer(arg_matches, "sender", &mut wallet_manager)
+                .unwrap()
+                .unwrap();
+            let csv_path = arg_matches.value_of("csv").unwrap();
+            let state_path = arg_matches
+                .value_of("state_file")
+                .map(|path| path.to_string())
+                .unwrap_or_else(|| format!("{}.state", csv_path));
+            let transfers_per_transaction =
+                value_t_or_exit!(arg_matches, "transfers_per_transaction", usize);
+            let fund_recipient = arg_matches.is_present("fund_recipient");
+            let memo = arg_matches.value_of("memo").map(String::from);
+
+            // Each transaction is signed and recorded as it is sent
+            let signer_info = config
+                .default_signer
+                .generate_unique_signers(
+                    std::mem::take(&mut bulk_signers),
+                    &matches,
+                    &mut wallet_manager,
+                )
+                .unwrap_or_else(|e| {
+                    eprintln!("error: {}", e);
+                    exit(1);
+                });
+            command_distribute(
+                &config,
+                &signer_info,
+                sender,
+                csv_path,
+                &state_path,
+                fund_recipient,
+                transfers_per_transaction,
+                memo,
             )
         }
         ("burn", Some(arg_matches)) => {
@@ -1928,6 +3751,19 @@ fn main() {
             let token = pubkey_of_signer(arg_matches, "token", &mut wallet_manager).unwrap();
             command_accounts(&config, token)
         }
+        ("holders", Some(arg_matches)) => {
+            let token = pubkey_of_signer(arg_matches, "token", &mut wallet_manager)
+                .unwrap()
+                .unwrap();
+            let exclude = pubkeys_of_multiple_signers(arg_matches, "exclude", &mut wallet_manager)
+                .unwrap_or_else(|e| {
+                    eprintln!("error: {}", e);
+                    exit(1);
+                })
+                .unwrap_or_default();
+            let output_file = arg_matches.value_of("output_file");
+            command_holders(&config, token, exclude, output_file)
+        }
         ("account-info", Some(arg_matches)) => {
             let address = pubkey_of_signer(arg_matches, "address", &mut wallet_manager)
                 .unwrap()
@@ -1941,10 +3777,20 @@ fn main() {
             command_multisig(&config, address)
         }
         ("gc", Some(_arg_matches)) => command_gc(&config),
+        ("broadcast", Some(arg_matches)) => {
+            let path = arg_matches.value_of("file").unwrap();
+            command_broadcast(&config, path)
+        }
         _ => unreachable!(),
     }
     .and_then(|transaction_info| {
-        if let Some((minimum_balance_for_rent_exemption, instruction_batches)) = transaction_info {
+        if let Some(TransactionInfo {
+            minimum_balance_for_rent_exemption,
+            instruction_batches,
+            mut output,
+            token_accounts,
+        }) = transaction_info
+        {
             let fee_payer = Some(&config.fee_payer);
             let signer_info = config
                 .default_signer
@@ -1954,6 +3800,9 @@ fn main() {
                     exit(1);
                 });
 
+            let mut signatures = vec![];
+            let mut transactions = vec![];
+            let mut unsigned_transactions = vec![];
             for instructions in instruction_batches {
                 let message = if let Some(nonce_account) = config.nonce_account.as_ref() {
                     Message::new_with_nonce(
@@ -1987,14 +3836,63 @@ fn main() {
 
                 if config.sign_only {
                     transaction.try_partial_sign(&signer_info.signers, recent_blockhash)?;
-                    println!("{}", return_signers(&transaction, &OutputFormat::Display)?);
+                    let signers = return_signers(&transaction, &config.output_format)?;
+                    if is_json_output(&config) {
+                        transactions.push(serde_json::from_str::<Value>(&signers)?);
+                    } else {
+                        println!("{}", signers);
+                    }
+                } else if config.transaction_file.is_some() {
+                    transaction.try_partial_sign(&signer_info.signers, recent_blockhash)?;
+                    unsigned_transactions.push(transaction);
                 } else {
                     transaction.try_sign(&signer_info.signers, recent_blockhash)?;
                     let signature = config
                         .rpc_client
                         .send_and_confirm_transaction_with_spinner(&transaction)?;
-                    println!("Signature: {}", signature);
+                    println_display(&config, format!("Signature: {}", signature));
+                    signatures.push(signature.to_string());
+                }
+            }
+
+            if let Some(path) = &config.transaction_file {
+                write_transaction_file(path, &unsigned_transactions)?;
+                let missing_signers = missing_signers(&unsigned_transactions);
+                println_display(
+                    &config,
+                    format!(
+                        "Transaction file: {}\nAwaiting signatures from:\n{}",
+                        path,
+                        missing_signers
+                            .iter()
+                            .map(|signer| format!("  {}", signer))
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    ),
+                );
+                output["transactionFile"] = Value::from(path.as_str());
+                output["missingSigners"] = missing_signers
+                    .iter()
+                    .map(|signer| signer.to_string())
+                    .collect();
+            }
+
+            if is_json_output(&config) {
+                if config.sign_only {
+                    output["transactions"] = Value::from(transactions);
+                } else if config.transaction_file.is_none() {
+                    output["signatures"] = Value::from(signatures);
+                    let balances: serde_json::Map<String, Value> = token_accounts
+                        .iter()
+                        .filter_map(|address| {
+                            let balance =
+                                config.rpc_client.get_token_account_balance(address).ok()?;
+                            Some((address.to_string(), serde_json::to_value(balance).ok()?))
+                        })
+                        .collect();
+                    output["balances"] = Value::Object(balances);
                 }
+                println_json(&config, &output);
             }
         }
         Ok(())
@@ -2004,3 +3902,436 @@ fn main() {
         exit(1);
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ui_amount() {
+        assert_eq!(parse_ui_amount("1", 2), Ok(100));
+        assert_eq!(parse_ui_amount("1.5", 2), Ok(150));
+        assert_eq!(parse_ui_amount("0.1", 9), Ok(100_000_000));
+        assert_eq!(parse_ui_amount(".5", 1), Ok(5));
+        assert_eq!(parse_ui_amount("5.", 0), Ok(5));
+        assert_eq!(parse_ui_amount("1.10", 1), Ok(11));
+        assert_eq!(parse_ui_amount("0.000000001", 9), Ok(1));
+        assert_eq!(parse_ui_amount("0", 0), Ok(0));
+        assert_eq!(parse_ui_amount(".0", 0), Ok(0));
+        assert_eq!(parse_ui_amount("184467440737.09551615", 8), Ok(u64::MAX));
+
+        // an f64 would round these
+        assert_eq!(
+            parse_ui_amount("9007199254740993", 0),
+            Ok(9_007_199_254_740_993)
+        );
+        assert_eq!(parse_ui_amount("0.29", 2), Ok(29));
+
+        assert!(parse_ui_amount("1.15", 1).is_err());
+        assert!(parse_ui_amount("18446744073709551616", 0).is_err());
+        assert!(parse_ui_amount("184467440737.09551616", 8).is_err());
+        assert!(parse_ui_amount("", 2).is_err());
+        assert!(parse_ui_amount(".", 2).is_err());
+        assert!(parse_ui_amount("-1", 2).is_err());
+        assert!(parse_ui_amount("+1", 2).is_err());
+        assert!(parse_ui_amount("1e3", 2).is_err());
+        assert!(parse_ui_amount("1.2.3", 2).is_err());
+    }
+
+    #[test]
+    fn test_parse_distribution_csv() {
+        let recipient1 = Pubkey::new_unique();
+        let recipient2 = Pubkey::new_unique();
+        let csv = format!(
+            "recipient,amount\n{},1.5\n\n# comment\n {} , 0.01 \n",
+            recipient1, recipient2
+        );
+        let rows = parse_distribution_csv("test.csv", csv.as_bytes(), 2).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].line, 2);
+        assert_eq!(rows[0].recipient, recipient1);
+        assert_eq!(rows[0].ui_amount, "1.5");
+        assert_eq!(rows[0].amount, 150);
+        assert_eq!(rows[1].line, 5);
+        assert_eq!(rows[1].recipient, recipient2);
+        assert_eq!(rows[1].ui_amount, "0.01");
+        assert_eq!(rows[1].amount, 1);
+
+        // only the first line may be a header
+        let csv = format!("{},1\nrecipient,amount\n", recipient1);
+        assert!(parse_distribution_csv("test.csv", csv.as_bytes(), 2).is_err());
+
+        let csv = format!("{},1,2\n", recipient1);
+        assert!(parse_distribution_csv("test.csv", csv.as_bytes(), 2).is_err());
+
+        let csv = format!("{},0.001\n", recipient1);
+        assert!(parse_distribution_csv("test.csv", csv.as_bytes(), 2).is_err());
+    }
+
+    #[test]
+    fn test_parse_distribution_state() {
+        let csv = format!(
+            "{},1\n{},2\n{},3\n",
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique()
+        );
+        let rows = parse_distribution_csv("test.csv", csv.as_bytes(), 0).unwrap();
+        let signature1 = Signature::new(&[1; 64]);
+        let signature2 = Signature::new(&[2; 64]);
+        let blockhash = Hash::new(&[3; 32]);
+
+        let state = [
+            distribution_record(&rows[0], &signature1, &blockhash, "sent"),
+            distribution_record(&rows[1], &signature1, &blockhash, "sent"),
+            distribution_record(&rows[0], &signature1, &blockhash, "confirmed"),
+            distribution_record(&rows[1], &signature1, &blockhash, "confirmed"),
+            distribution_record(&rows[2], &signature2, &blockhash, "sent"),
+        ]
+        .join("\n");
+        let (confirmed, unconfirmed) =
+            parse_distribution_state("test.state", state.as_bytes(), &rows).unwrap();
+        assert_eq!(confirmed, vec![1, 2].into_iter().collect::<HashSet<_>>());
+        assert_eq!(unconfirmed.len(), 1);
+        assert_eq!(unconfirmed[&3], (signature2, blockhash));
+
+        // a later record for a confirmed row does not unconfirm it
+        let state = [
+            distribution_record(&rows[0], &signature1, &blockhash, "confirmed"),
+            distribution_record(&rows[0], &signature2, &blockhash, "sent"),
+        ]
+        .join("\n");
+        let (confirmed, unconfirmed) =
+            parse_distribution_state("test.state", state.as_bytes(), &rows).unwrap();
+        assert_eq!(confirmed, vec![1].into_iter().collect::<HashSet<_>>());
+        assert!(unconfirmed.is_empty());
+
+        // the state of a modified distribution is refused
+        let csv = format!("{},1\n", Pubkey::new_unique());
+        let other_rows = parse_distribution_csv("test.csv", csv.as_bytes(), 0).unwrap();
+        let state = distribution_record(&other_rows[0], &signature1, &blockhash, "confirmed");
+        assert!(parse_distribution_state("test.state", state.as_bytes(), &rows).is_err());
+
+        let state = distribution_record(&rows[0], &signature1, &blockhash, "confirmed");
+        let csv = format!("{},1.0\n", rows[0].recipient);
+        let modified_rows = parse_distribution_csv("test.csv", csv.as_bytes(), 1).unwrap();
+        assert!(parse_distribution_state("test.state", state.as_bytes(), &modified_rows).is_err());
+
+        assert!(parse_distribution_state("test.state", "{}".as_bytes(), &rows).is_err());
+        assert!(parse_distribution_state("test.state", "not json".as_bytes(), &rows).is_err());
+    }
+
+    fn token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> SolanaAccount {
+        let mut data = vec![0; Account::LEN];
+        Account {
+            mint: *mint,
+            owner: *owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            ..Account::default()
+        }
+        .pack_into_slice(&mut data);
+        SolanaAccount {
+            lamports: 1,
+            data,
+            owner: spl_token::id(),
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn test_aggregate_holders() {
+        let mint = Pubkey::new_unique();
+        let other_mint = Pubkey::new_unique();
+        let owner1 = Pubkey::new_unique();
+        let owner2 = Pubkey::new_unique();
+        let empty_owner = Pubkey::new_unique();
+        let excluded_owner = Pubkey::new_unique();
+        let excluded_account = Pubkey::new_unique();
+        let account1 = Pubkey::new_unique();
+        let account2 = Pubkey::new_unique();
+        let account3 = Pubkey::new_unique();
+
+        let mut mint_data = vec![0; Mint::LEN];
+        Mint {
+            supply: 1000,
+            decimals: 2,
+            is_initialized: true,
+            ..Mint::default()
+        }
+        .pack_into_slice(&mut mint_data);
+        let accounts = vec![
+            (account1, token_account(&mint, &owner1, 10)),
+            (account2, token_account(&mint, &owner1, 5)),
+            (account3, token_account(&mint, &owner2, 20)),
+            (Pubkey::new_unique(), token_account(&mint, &empty_owner, 0)),
+            (
+                Pubkey::new_unique(),
+                token_account(&mint, &excluded_owner, 100),
+            ),
+            (excluded_account, token_account(&mint, &owner1, 1000)),
+            (
+                Pubkey::new_unique(),
+                token_account(&other_mint, &owner1, 1000),
+            ),
+            (
+                mint,
+                SolanaAccount {
+                    lamports: 1,
+                    data: mint_data,
+                    owner: spl_token::id(),
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            ),
+        ];
+
+        let holders = aggregate_holders(&mint, &[excluded_owner, excluded_account], accounts);
+        assert_eq!(holders.len(), 2);
+        assert_eq!(holders[0].owner, owner2);
+        assert_eq!(holders[0].amount, 20);
+        assert_eq!(holders[0].token_accounts, vec![account3]);
+        assert_eq!(holders[1].owner, owner1);
+        assert_eq!(holders[1].amount, 15);
+        assert_eq!(holders[1].token_accounts, vec![account1, account2]);
+    }
+
+    fn temp_file(name: &str, contents: &[u8]) -> String {
+        let path = std::env::temp_dir().join(format!("spl-token-{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_read_decode_input() {
+        let data = vec![0, 1, 2, 255];
+        let encoded = base64::encode(&data);
+        assert_eq!(read_decode_input(&encoded).unwrap(), data);
+
+        // base64 may be wrapped over several lines
+        let data = vec![7; 100];
+        let encoded = base64::encode(&data);
+        let wrapped = format!("{}\n{}\n", &encoded[..60], &encoded[60..]);
+        assert_eq!(read_decode_input(&wrapped).unwrap(), data);
+
+        let path = temp_file("base64", wrapped.as_bytes());
+        assert_eq!(read_decode_input(&path).unwrap(), data);
+        std::fs::remove_file(&path).unwrap();
+
+        let raw = vec![0, 159, 146, 150];
+        let path = temp_file("raw", &raw);
+        assert_eq!(read_decode_input(&path).unwrap(), raw);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(read_decode_input("not base64!").is_err());
+    }
+
+    #[test]
+    fn test_unpack_token_program_account() {
+        let mut data = vec![0; Mint::LEN];
+        Mint {
+            supply: 42,
+            decimals: 2,
+            is_initialized: true,
+            ..Mint::default()
+        }
+        .pack_into_slice(&mut data);
+        match unpack_token_program_account(&data).unwrap() {
+            DecodedAccount::Mint(mint) => assert_eq!(mint.supply, 42),
+            _ => panic!("not a mint"),
+        }
+
+        let owner = Pubkey::new_unique();
+        let data = token_account(&Pubkey::new_unique(), &owner, 42).data;
+        match unpack_token_program_account(&data).unwrap() {
+            DecodedAccount::Account(account) => assert_eq!(account.owner, owner),
+            _ => panic!("not a token account"),
+        }
+
+        let mut data = vec![0; Multisig::LEN];
+        Multisig {
+            m: 1,
+            n: 2,
+            is_initialized: true,
+            signers: [owner; MAX_SIGNERS],
+        }
+        .pack_into_slice(&mut data);
+        match unpack_token_program_account(&data).unwrap() {
+            DecodedAccount::Multisig(multisig) => assert_eq!(multisig.n, 2),
+            _ => panic!("not a multisig"),
+        }
+
+        let mut data = vec![0; MultisigProposal::LEN];
+        MultisigProposal {
+            is_initialized: true,
+            is_executed: false,
+            multisig: owner,
+            approved: [false; MAX_SIGNERS],
+            num_accounts: 1,
+            accounts: [owner; MAX_PROPOSAL_ACCOUNTS],
+            data_len: 1,
+            data: [0; MAX_PROPOSAL_DATA_LEN],
+        }
+        .pack_into_slice(&mut data);
+        match unpack_token_program_account(&data).unwrap() {
+            DecodedAccount::MultisigProposal(proposal) => assert_eq!(proposal.multisig, owner),
+            _ => panic!("not a multisig proposal"),
+        }
+
+        assert!(unpack_token_program_account(&[0; 42]).is_err());
+        // uninitialized accounts hold nothing to decode
+        assert!(unpack_token_program_account(&[0; Multisig::LEN]).is_err());
+    }
+
+    #[test]
+    fn test_decode_malformed_input() {
+        let output_format = OutputFormat::Json;
+
+        // a multisig claiming more signers than it holds
+        let mut data = vec![0; Multisig::LEN];
+        data[0] = 1;
+        data[1] = u8::MAX;
+        data[2] = 1;
+        let input = base64::encode(&data);
+        assert!(unpack_token_program_account(&data).is_err());
+        assert!(command_decode(&output_format, &input, Some("account")).is_err());
+
+        // a multisig proposal claiming more accounts or data than it holds
+        let mut data = vec![0; MultisigProposal::LEN];
+        data[0] = 1;
+        data[2 + 32 + MAX_SIGNERS] = u8::MAX;
+        assert!(unpack_token_program_account(&data).is_err());
+        assert!(command_decode(&output_format, &base64::encode(&data), Some("account")).is_err());
+        let mut data = vec![0; MultisigProposal::LEN];
+        data[0] = 1;
+        data[2 + 32 + MAX_SIGNERS + 1 + 32 * MAX_PROPOSAL_ACCOUNTS] = u8::MAX;
+        assert!(unpack_token_program_account(&data).is_err());
+        assert!(command_decode(&output_format, &base64::encode(&data), Some("account")).is_err());
+
+        // an extended account whose extension lengths run past its data
+        let mut data = token_account(&Pubkey::new_unique(), &Pubkey::new_unique(), 42).data;
+        data.resize(Account::LEN + 1, 0);
+        data[Account::LEN] = 2; // AccountType::Account
+        data.extend_from_slice(&[8, 0, 255, 255]);
+        assert!(command_decode(&output_format, &base64::encode(&data), None).is_err());
+
+        // a transaction whose instructions index missing accounts
+        let payer = Pubkey::new_unique();
+        let mut transaction = Transaction::new_unsigned(Message::new(
+            &[transfer(
+                &spl_token::id(),
+                &Pubkey::new_unique(),
+                &Pubkey::new_unique(),
+                &payer,
+                &[],
+                1,
+            )
+            .unwrap()],
+            Some(&payer),
+        ));
+        let input = base64::encode(bincode::serialize(&transaction).unwrap());
+        assert!(command_decode(&output_format, &input, Some("transaction")).is_ok());
+        transaction.message.instructions[0].program_id_index = u8::MAX;
+        transaction.message.instructions[0].accounts = vec![u8::MAX; 3];
+        let input = base64::encode(bincode::serialize(&transaction).unwrap());
+        assert!(command_decode(&output_format, &input, Some("transaction")).is_ok());
+
+        // data that is neither
+        let input = base64::encode(&[1; 10]);
+        assert!(command_decode(&output_format, &input, None).is_err());
+        let input = base64::encode(&bincode::serialize(&transaction).unwrap()[..50]);
+        assert!(command_decode(&output_format, &input, Some("transaction")).is_err());
+    }
+
+    fn multisig_transaction(
+        fee_payer: &Keypair,
+        multisig: &Pubkey,
+        signers: &[&Pubkey],
+    ) -> Transaction {
+        let message = Message::new(
+            &[mint_to(
+                &spl_token::id(),
+                &Pubkey::new_unique(),
+                &Pubkey::new_unique(),
+                multisig,
+                signers,
+                1,
+            )
+            .unwrap()],
+            Some(&fee_payer.pubkey()),
+        );
+        let mut transaction = Transaction::new_unsigned(message);
+        transaction.partial_sign(&[fee_payer], Hash::new(&[1; 32]));
+        transaction
+    }
+
+    #[test]
+    fn test_missing_signers() {
+        let sorted_missing_signers = |transactions: &[Transaction]| {
+            let mut signers = missing_signers(transactions);
+            signers.sort();
+            signers
+        };
+        let fee_payer = Keypair::new();
+        let multisig = Pubkey::new_unique();
+        let signer1 = Keypair::new();
+        let signer2 = Keypair::new();
+        let signer3 = Keypair::new();
+        let mut transactions = vec![
+            multisig_transaction(
+                &fee_payer,
+                &multisig,
+                &[&signer1.pubkey(), &signer2.pubkey()],
+            ),
+            multisig_transaction(
+                &fee_payer,
+                &multisig,
+                &[&signer3.pubkey(), &signer1.pubkey()],
+            ),
+        ];
+        let mut expected = vec![signer1.pubkey(), signer2.pubkey(), signer3.pubkey()];
+        expected.sort();
+        // each missing signer is listed once
+        assert_eq!(sorted_missing_signers(&transactions), expected);
+
+        transactions[0].partial_sign(&[&signer1, &signer2], Hash::new(&[1; 32]));
+        let mut expected = vec![signer1.pubkey(), signer3.pubkey()];
+        expected.sort();
+        assert_eq!(sorted_missing_signers(&transactions), expected);
+
+        transactions[1].partial_sign(&[&signer1, &signer3], Hash::new(&[1; 32]));
+        assert!(missing_signers(&transactions).is_empty());
+    }
+
+    #[test]
+    fn test_sign_transaction_file() {
+        let output_format = OutputFormat::Json;
+        let fee_payer = Keypair::new();
+        let multisig = Pubkey::new_unique();
+        let signer1 = Keypair::new();
+        let signer2 = Keypair::new();
+        let transaction = multisig_transaction(
+            &fee_payer,
+            &multisig,
+            &[&signer1.pubkey(), &signer2.pubkey()],
+        );
+        let path = temp_file("transactions.json", &[]);
+        write_transaction_file(&path, &[transaction.clone()]).unwrap();
+
+        // only the signers of the transaction may sign it
+        assert!(command_sign(&output_format, &path, &Keypair::new()).is_err());
+
+        command_sign(&output_format, &path, &signer2).unwrap();
+        let transactions = read_transaction_file(&path).unwrap();
+        assert_eq!(missing_signers(&transactions), vec![signer1.pubkey()]);
+        // signing keeps the message, and so its blockhash or nonce
+        assert_eq!(transactions[0].message, transaction.message);
+
+        command_sign(&output_format, &path, &signer1).unwrap();
+        let transactions = read_transaction_file(&path).unwrap();
+        assert!(missing_signers(&transactions).is_empty());
+        transactions[0].verify().unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -7,6 +7,20 @@
 #include <stdint.h>
 #include <stdlib.h>
 
+/**
+ * Rust-owned buffer of variable-length instruction data, opaque to C
+ */
+struct Token_String { uintptr_t _private[3]; };
+struct Token_Vec_u8 { uintptr_t _private[3]; };
+struct Token_Vec_u64 { uintptr_t _private[3]; };
+struct Token_Vec_ExtensionType { uintptr_t _private[3]; };
+
+/**
+ * Version of the event log format, bumped whenever the fields of an
+ * existing event change
+ */
+#define Token_EVENT_LOG_VERSION 1
+
 /**
  * Minimum number of multisignature signers (min N)
  */
@@ -17,6 +31,36 @@
  */
 #define Token_MAX_SIGNERS 11
 
+/**
+ * Maximum number of accounts of an instruction held by a multisignature proposal
+ */
+#define Token_MAX_PROPOSAL_ACCOUNTS 8
+
+/**
+ * Maximum length of the data of an instruction held by a multisignature proposal
+ */
+#define Token_MAX_PROPOSAL_DATA_LEN 64
+
+/**
+ * Maximum transfer fee, in basis points of the transferred amount
+ */
+#define Token_MAX_FEE_BASIS_POINTS 10000
+
+/**
+ * Maximum length in bytes of the name of a token
+ */
+#define Token_MAX_METADATA_NAME_LEN 32
+
+/**
+ * Maximum length in bytes of the symbol of a token
+ */
+#define Token_MAX_METADATA_SYMBOL_LEN 10
+
+/**
+ * Maximum length in bytes of the URI of a token's off-chain metadata
+ */
+#define Token_MAX_METADATA_URI_LEN 200
+
 /**
  * Account state.
  */
@@ -68,11 +112,40 @@ enum Token_AuthorityType
      * Authority to close a token account
      */
     Token_AuthorityType_CloseAccount,
+    /**
+     * Authority to transfer or burn tokens from any account of a Mint
+     */
+    Token_AuthorityType_PermanentDelegate,
+    /**
+     * Authority to withdraw the transfer fees withheld in a Mint and its
+     * accounts
+     */
+    Token_AuthorityType_WithdrawWithheldTokens,
+    /**
+     * Authority to close a Mint
+     */
+    Token_AuthorityType_CloseMint,
+    /**
+     * Authority to update the metadata of a Mint
+     */
+    Token_AuthorityType_MetadataUpdate,
+    /**
+     * Authority to update the interest rate of a Mint
+     */
+    Token_AuthorityType_InterestRate,
 };
 #ifndef __cplusplus
 typedef uint8_t Token_AuthorityType;
 #endif // __cplusplus
 
+typedef struct Token_String Token_String;
+
+typedef struct Token_Vec_ExtensionType Token_Vec_ExtensionType;
+
+typedef struct Token_Vec_u64 Token_Vec_u64;
+
+typedef struct Token_Vec_u8 Token_Vec_u8;
+
 typedef uint8_t Token_Pubkey[32];
 
 /**
@@ -168,6 +241,17 @@ typedef enum Token_TokenInstruction_Tag {
      * amounts of SOL and Tokens will be transferred to the destination
      * account.
      *
+     * A mint's permanent delegate, and any transfer of a mint charging
+     * transfer fees, must use `TransferChecked` instead.
+     *
+     * If the destination account requires memos, the transfer must
+     * immediately follow a memo instruction, and the instructions sysvar
+     * must be appended to the accounts.
+     *
+     * If the source account's CPI guard is enabled, transfers signed by its
+     * owner must not be invoked by another program, and the instructions
+     * sysvar must be appended to the accounts.
+     *
      * Accounts expected by this instruction:
      *
      *   * Single owner/delegate
@@ -186,6 +270,10 @@ typedef enum Token_TokenInstruction_Tag {
      * Approves a delegate.  A delegate is given the authority over tokens on
      * behalf of the source account's owner.
      *
+     * If the account's CPI guard is enabled, this instruction must not be
+     * invoked by another program, and the instructions sysvar must be
+     * appended to the accounts.
+     *
      * Accounts expected by this instruction:
      *
      *   * Single owner
@@ -218,6 +306,10 @@ typedef enum Token_TokenInstruction_Tag {
     /**
      * Sets a new authority of a mint or account.
      *
+     * If the account's CPI guard is enabled, this instruction must not be
+     * invoked by another program, and the instructions sysvar must be
+     * appended to the accounts.
+     *
      * Accounts expected by this instruction:
      *
      *   * Single authority
@@ -252,6 +344,9 @@ typedef enum Token_TokenInstruction_Tag {
      * Burns tokens by removing them from an account.  `Burn` does not support
      * accounts associated with the native mint, use `CloseAccount` instead.
      *
+     * If the mint has a permanent delegate, it may burn tokens from any
+     * account of the mint.
+     *
      * Accounts expected by this instruction:
      *
      *   * Single owner/delegate
@@ -270,17 +365,25 @@ typedef enum Token_TokenInstruction_Tag {
      * Close an account by transferring all its SOL to the destination account.
      * Non-native accounts may only be closed if its token amount is zero.
      *
+     * A mint may also be closed by its close authority, see
+     * `InitializeMintCloseAuthority`, once its supply is zero.
+     *
+     * If the account's CPI guard is enabled, this instruction must not be
+     * invoked by another program, and the instructions sysvar must be
+     * appended to the accounts.
+     *
      * Accounts expected by this instruction:
      *
      *   * Single owner
      *   0. `[writable]` The account to close.
      *   1. `[writable]` The destination account.
-     *   2. `[signer]` The account's owner.
+     *   2. `[signer]` The account's owner, or the mint's close authority.
      *
      *   * Multisignature owner
      *   0. `[writable]` The account to close.
      *   1. `[writable]` The destination account.
-     *   2. `[]` The account's multisignature owner.
+     *   2. `[]` The account's multisignature owner, or the mint's
+     *      multisignature close authority.
      *   3. ..3+M `[signer]` M signer accounts.
      */
     Token_TokenInstruction_CloseAccount,
@@ -329,6 +432,18 @@ typedef enum Token_TokenInstruction_Tag {
      * decimals value is checked by the caller.  This may be useful when
      * creating transactions offline or within a hardware wallet.
      *
+     * If the mint has a permanent delegate, it may transfer tokens from any
+     * account of the mint.  If the mint charges a transfer fee, the fee is
+     * withheld in the destination account.
+     *
+     * If the destination account requires memos, the transfer must
+     * immediately follow a memo instruction, and the instructions sysvar
+     * must be appended to the accounts.
+     *
+     * If the source account's CPI guard is enabled, transfers signed by its
+     * owner must not be invoked by another program, and the instructions
+     * sysvar must be appended to the accounts.
+     *
      * Accounts expected by this instruction:
      *
      *   * Single owner/delegate
@@ -353,6 +468,10 @@ typedef enum Token_TokenInstruction_Tag {
      * decimals value is checked by the caller.  This may be useful when
      * creating transactions offline or within a hardware wallet.
      *
+     * If the account's CPI guard is enabled, this instruction must not be
+     * invoked by another program, and the instructions sysvar must be
+     * appended to the accounts.
+     *
      * Accounts expected by this instruction:
      *
      *   * Single owner
@@ -400,6 +519,9 @@ typedef enum Token_TokenInstruction_Tag {
      * by the caller. This may be useful when creating transactions offline or
      * within a hardware wallet.
      *
+     * If the mint has a permanent delegate, it may burn tokens from any
+     * account of the mint.
+     *
      * Accounts expected by this instruction:
      *
      *   * Single owner/delegate
@@ -427,6 +549,581 @@ typedef enum Token_TokenInstruction_Tag {
      *   3. `[]` Rent sysvar
      */
     Token_TokenInstruction_InitializeAccount2,
+    /**
+     * Given a wrapped / native token account (a token account containing SOL)
+     * updates its amount field based on the account's underlying `lamports`.
+     * This is useful if a non-wrapped SOL account uses `system_instruction::transfer`
+     * to move lamports to a wrapped token account, and needs to have its token
+     * `amount` field updated.
+     *
+     * Accounts expected by this instruction:
+     *
+     *   0. `[writable]`  The native token account to sync with its underlying lamports.
+     */
+    Token_TokenInstruction_SyncNative,
+    /**
+     * Like InitializeAccount2, but does not require the Rent sysvar to be provided
+     *
+     * Accounts expected by this instruction:
+     *
+     *   0. `[writable]`  The account to initialize.
+     *   1. `[]` The mint this account will be associated with.
+     */
+    Token_TokenInstruction_InitializeAccount3,
+    /**
+     * Like InitializeMint, but does not require the Rent sysvar to be provided
+     *
+     * Accounts expected by this instruction:
+     *
+     *   0. `[writable]` The mint to initialize.
+     *
+     */
+    Token_TokenInstruction_InitializeMint2,
+    /**
+     * Transfers tokens from one account to several destination accounts,
+     * either directly or via a delegate, validating the source, mint and
+     * authority only once.  If the source account is associated with the
+     * native mint then equal amounts of SOL and Tokens will be transferred to
+     * each destination account.
+     *
+     * Like TransferChecked, the token mint and decimals value is checked by
+     * the caller.  The number of destination accounts must equal the number
+     * of amounts.  If the mint charges a transfer fee, the fee on each amount
+     * is withheld in its destination account.
+     *
+     * If any destination account requires memos, the transfer must
+     * immediately follow a memo instruction, and the instructions sysvar
+     * must be appended to the accounts.
+     *
+     * If the source account's CPI guard is enabled, transfers signed by its
+     * owner must not be invoked by another program, and the instructions
+     * sysvar must be appended to the accounts.
+     *
+     * Accounts expected by this instruction:
+     *
+     *   * Single owner/delegate
+     *   0. `[writable]` The source account.
+     *   1. `[]` The token mint.
+     *   2. `[signer]` The source account's owner/delegate.
+     *   3. ..3+N `[writable]` The N destination accounts, in the same order
+     *      as the amounts.
+     *
+     *   * Multisignature owner/delegate
+     *   0. `[writable]` The source account.
+     *   1. `[]` The token mint.
+     *   2. `[]` The source account's multisignature owner/delegate.
+     *   3. ..3+N `[writable]` The N destination accounts, in the same order
+     *      as the amounts.
+     *   3+N. ..3+N+M `[signer]` M signer accounts.
+     */
+    Token_TokenInstruction_TransferCheckedBatch,
+    /**
+     * Sets the maximum supply of a new mint.  `MintTo` and `MintToChecked`
+     * fail if they would bring the mint's total supply above this amount.
+     *
+     * The mint account must be allocated with space for the extension, see
+     * `ExtensionType::get_account_len`, and this instruction must precede the
+     * `InitializeMint` instruction of the same Transaction.  It requires no
+     * signers, so it MUST be included within the same Transaction as the
+     * system program's `CreateAccount` instruction that creates the mint.
+     *
+     * Accounts expected by this instruction:
+     *
+     *   0. `[writable]` The mint to configure.
+     */
+    Token_TokenInstruction_InitializeMaxSupply,
+    /**
+     * Approves a delegate until the given slot.  A delegate is given the
+     * authority over tokens on behalf of the source account's owner, and may
+     * no longer transfer or burn them once the current slot is past
+     * `expiry_slot`.
+     *
+     * This instruction differs from ApproveChecked in that the approval
+     * expires.  The source account must have space for the `DelegationExpiry`
+     * extension, which existing accounts can gain with `MigrateAccount`.
+     * Approvals made with Approve or ApproveChecked do not expire.
+     *
+     * If the account's CPI guard is enabled, this instruction must not be
+     * invoked by another program, and the instructions sysvar must be
+     * appended to the accounts.
+     *
+     * Accounts expected by this instruction:
+     *
+     *   * Single owner
+     *   0. `[writable]` The source account.
+     *   1. `[]` The token mint.
+     *   2. `[]` The delegate.
+     *   3. `[signer]` The source account owner.
+     *
+     *   * Multisignature owner
+     *   0. `[writable]` The source account.
+     *   1. `[]` The token mint.
+     *   2. `[]` The delegate.
+     *   3. `[]` The source account's multisignature owner.
+     *   4. ..4+M `[signer]` M signer accounts
+     */
+    Token_TokenInstruction_ApproveCheckedWithExpiry,
+    /**
+     * Creates a proposal for a multisignature account to execute a token
+     * instruction.  Rather than requiring all M signers in the same
+     * Transaction, the signers approve the proposal one at a time with
+     * `ApproveMultisigProposal`, and it can be executed with
+     * `ExecuteMultisigProposal` once M approvals are recorded.  The proposer
+     * must be one of the multisignature's signers, and its approval is
+     * recorded on creation.
+     *
+     * The proposed instruction may hold at most `MAX_PROPOSAL_ACCOUNTS`
+     * accounts, listed without the multisignature's signer accounts, and at
+     * most `MAX_PROPOSAL_DATA_LEN` bytes of data.
+     *
+     * The `CreateMultisigProposal` instruction MUST be included within the
+     * same Transaction as the system program's `CreateAccount` instruction
+     * that creates the proposal account being initialized.
+     *
+     * Accounts expected by this instruction:
+     *
+     *   0. `[writable]` The proposal account to initialize.
+     *   1. `[]` The multisignature account.
+     *   2. `[signer]` The proposer, one of the multisignature's signers.
+     *   3. ..3+K `[]` The K accounts of the proposed instruction, in order.
+     */
+    Token_TokenInstruction_CreateMultisigProposal,
+    /**
+     * Records the approval of a multisignature proposal by one of the
+     * multisignature's signers.
+     *
+     * Accounts expected by this instruction:
+     *
+     *   0. `[writable]` The proposal account.
+     *   1. `[]` The multisignature account.
+     *   2. `[signer]` The approving signer.
+     */
+    Token_TokenInstruction_ApproveMultisigProposal,
+    /**
+     * Executes the instruction held by a multisignature proposal once at least
+     * M of the multisignature's signers have approved it.  The authority of
+     * the proposed instruction must be the multisignature itself, and the
+     * approving signers are treated as its signers, so it is processed as if
+     * all of them had signed it.  A proposal can only be executed once.
+     *
+     * Accounts expected by this instruction:
+     *
+     *   0. `[writable]` The proposal account.
+     *   1. `[]` The multisignature account.
+     *   2. ..2+K `[writable]` or `[]` The K accounts of the proposed
+     *      instruction, in order, as writable as the proposed instruction
+     *      requires.
+     *   2+K. ..2+K+M `[]` M signer accounts that approved the proposal.
+     */
+    Token_TokenInstruction_ExecuteMultisigProposal,
+    /**
+     * Sets the permanent delegate of a new mint.  The permanent delegate may
+     * transfer or burn tokens from any account of the mint, regardless of the
+     * account's owner or delegate, and can be changed or removed with
+     * `SetAuthority` but never added after the mint is initialized.
+     *
+     * The mint account must be allocated with space for the extension, see
+     * `ExtensionType::get_account_len`, and this instruction must precede the
+     * `InitializeMint` instruction of the same Transaction.  It requires no
+     * signers, so it MUST be included within the same Transaction as the
+     * system program's `CreateAccount` instruction that creates the mint.
+     *
+     * Accounts expected by this instruction:
+     *
+     *   0. `[writable]` The mint to configure.
+     */
+    Token_TokenInstruction_InitializePermanentDelegate,
+    /**
+     * Configures a new mint so that its new accounts start frozen, and can
+     * only be used once thawed by the mint's freeze authority.  The mint must
+     * be initialized with a freeze authority.
+     *
+     * The mint account must be allocated with space for the extension, see
+     * `ExtensionType::get_account_len`, and this instruction must precede the
+     * `InitializeMint` instruction of the same Transaction.  It requires no
+     * signers, so it MUST be included within the same Transaction as the
+     * system program's `CreateAccount` instruction that creates the mint.
+     *
+     * Accounts expected by this instruction:
+     *
+     *   0. `[writable]` The mint to configure.
+     */
+    Token_TokenInstruction_InitializeDefaultFrozen,
+    /**
+     * Sets whether new accounts of a mint start frozen.  Accounts that
+     * already exist keep their state.
+     *
+     * Accounts expected by this instruction:
+     *
+     *   * Single authority
+     *   0. `[writable]` The mint.
+     *   1. `[signer]` The mint freeze authority.
+     *
+     *   * Multisignature authority
+     *   0. `[writable]` The mint.
+     *   1. `[]` The mint's multisignature freeze authority.
+     *   2. ..2+M `[signer]` M signer accounts.
+     */
+    Token_TokenInstruction_SetDefaultFrozen,
+    /**
+     * Makes a new mint non-transferable.  Tokens of the mint can be minted,
+     * burned and have their accounts closed, but never transferred.  Accounts
+     * of the mint must be allocated with space for the
+     * `NonTransferableAccount` extension.
+     *
+     * The mint account must be allocated with space for the extension, see
+     * `ExtensionType::get_account_len`, and this instruction must precede the
+     * `InitializeMint` instruction of the same Transaction.  It requires no
+     * signers, so it MUST be included within the same Transaction as the
+     * system program's `CreateAccount` instruction that creates the mint.
+     *
+     * Accounts expected by this instruction:
+     *
+     *   0. `[writable]` The mint to configure.
+     */
+    Token_TokenInstruction_InitializeNonTransferableMint,
+    /**
+     * Migrates a token account to a new, larger account with space for the
+     * given extensions, carrying over its state and extensions, and closes
+     * the old account by transferring all its SOL to the destination
+     * account.  Native accounts are not supported.
+     *
+     * Accounts cannot be resized, so the migrated account lives under a new
+     * address and the old address can no longer be used.  Anything that
+     * refers to the account by its address, such as wallets, indexers, or
+     * other programs' state, must be updated to the new one, and transfers
+     * sent to the old address fail.  For that reason, associated token
+     * accounts and accounts with the `ImmutableOwner` extension, which are
+     * found by their address, cannot be moved.  Instead, passing the token
+     * account as the new account migrates it in place, which succeeds if it
+     * was allocated with space for the given extensions, as associated
+     * token accounts are, and leaves the destination account untouched.
+     *
+     * Moving an account closes it, so it fails if the account's close
+     * authority is not its owner, and if the account's CPI guard is enabled
+     * this instruction must not be invoked by another program, and the
+     * instructions sysvar must be appended to the accounts.
+     *
+     * The new account must be allocated with at least
+     * `ExtensionType::get_account_len` bytes for the extensions it will hold,
+     * and the `MigrateAccount` instruction MUST be included within the same
+     * Transaction as the system program's `CreateAccount` instruction that
+     * creates it.
+     *
+     * Accounts expected by this instruction:
+     *
+     *   * Single owner
+     *   0. `[writable]` The token account to migrate.
+     *   1. `[writable]` The new token account.
+     *   2. `[writable]` The destination account.
+     *   3. `[signer]` The account's owner.
+     *
+     *   * Multisignature owner
+     *   0. `[writable]` The token account to migrate.
+     *   1. `[writable]` The new token account.
+     *   2. `[writable]` The destination account.
+     *   3. `[]` The account's multisignature owner.
+     *   4. ..4+M `[signer]` M signer accounts.
+     */
+    Token_TokenInstruction_MigrateAccount,
+    /**
+     * Configures a new mint to charge a fee on every transfer.  The fee is
+     * withheld in the destination account, and can be harvested to the mint
+     * by anyone or withdrawn by the withdraw withheld authority.  Accounts
+     * of the mint must be allocated with space for the `TransferFeeAmount`
+     * extension, and the mint's tokens can only be transferred with
+     * instructions that include the mint.
+     *
+     * The mint account must be allocated with space for the extension, see
+     * `ExtensionType::get_account_len`, and this instruction must precede the
+     * `InitializeMint` instruction of the same Transaction.  It requires no
+     * signers, so it MUST be included within the same Transaction as the
+     * system program's `CreateAccount` instruction that creates the mint.
+     *
+     * Accounts expected by this instruction:
+     *
+     *   0. `[writable]` The mint to configure.
+     */
+    Token_TokenInstruction_InitializeTransferFeeConfig,
+    /**
+     * Transfers tokens from one account to another either directly or via a
+     * delegate, withholding the mint's transfer fee in the destination
+     * account.  The destination receives the amount less the fee.
+     *
+     * This instruction differs from TransferChecked in that the fee is
+     * checked by the caller, and the transfer fails if the mint would charge
+     * a different fee.
+     *
+     * If the destination account requires memos, the transfer must
+     * immediately follow a memo instruction, and the instructions sysvar
+     * must be appended to the accounts.
+     *
+     * If the source account's CPI guard is enabled, transfers signed by its
+     * owner must not be invoked by another program, and the instructions
+     * sysvar must be appended to the accounts.
+     *
+     * Accounts expected by this instruction:
+     *
+     *   * Single owner/delegate
+     *   0. `[writable]` The source account.
+     *   1. `[]` The token mint.
+     *   2. `[writable]` The destination account.
+     *   3. `[signer]` The source account's owner/delegate.
+     *
+     *   * Multisignature owner/delegate
+     *   0. `[writable]` The source account.
+     *   1. `[]` The token mint.
+     *   2. `[writable]` The destination account.
+     *   3. `[]` The source account's multisignature owner/delegate.
+     *   4. ..4+M `[signer]` M signer accounts.
+     */
+    Token_TokenInstruction_TransferCheckedWithFee,
+    /**
+     * Withdraws the fees harvested to a mint into a token account of the
+     * mint.
+     *
+     * Accounts expected by this instruction:
+     *
+     *   * Single authority
+     *   0. `[writable]` The token mint.
+     *   1. `[writable]` The destination account.
+     *   2. `[signer]` The mint's withdraw withheld authority.
+     *
+     *   * Multisignature authority
+     *   0. `[writable]` The token mint.
+     *   1. `[writable]` The destination account.
+     *   2. `[]` The mint's multisignature withdraw withheld authority.
+     *   3. ..3+M `[signer]` M signer accounts.
+     */
+    Token_TokenInstruction_WithdrawWithheldTokensFromMint,
+    /**
+     * Withdraws the fees withheld in token accounts of a mint into another
+     * token account of the mint.
+     *
+     * Accounts expected by this instruction:
+     *
+     *   * Single authority
+     *   0. `[]` The token mint.
+     *   1. `[writable]` The destination account.
+     *   2. `[signer]` The mint's withdraw withheld authority.
+     *   3. ..3+N `[writable]` The N source accounts.
+     *
+     *   * Multisignature authority
+     *   0. `[]` The token mint.
+     *   1. `[writable]` The destination account.
+     *   2. `[]` The mint's multisignature withdraw withheld authority.
+     *   3. ..3+N `[writable]` The N source accounts.
+     *   3+N. ..3+N+M `[signer]` M signer accounts.
+     */
+    Token_TokenInstruction_WithdrawWithheldTokensFromAccounts,
+    /**
+     * Moves the fees withheld in token accounts of a mint to the mint, from
+     * which the withdraw withheld authority can withdraw them.  Requires no
+     * signers, so that fees can be collected from accounts whose owners
+     * would otherwise hold them up.
+     *
+     * Accounts expected by this instruction:
+     *
+     *   0. `[writable]` The token mint.
+     *   1. ..1+N `[writable]` The N source accounts.
+     */
+    Token_TokenInstruction_HarvestWithheldTokensToMint,
+    /**
+     * Requires incoming transfers to a token account to immediately follow
+     * a memo instruction.  The account must have space for the
+     * `MemoTransfer` extension, which existing accounts can gain with
+     * `MigrateAccount`.
+     *
+     * Accounts expected by this instruction:
+     *
+     *   * Single owner
+     *   0. `[writable]` The account to update.
+     *   1. `[signer]` The account's owner.
+     *
+     *   * Multisignature owner
+     *   0. `[writable]` The account to update.
+     *   1. `[]` The account's multisignature owner.
+     *   2. ..2+M `[signer]` M signer accounts.
+     */
+    Token_TokenInstruction_EnableRequiredTransferMemos,
+    /**
+     * Stops requiring incoming transfers to a token account to follow a
+     * memo instruction.
+     *
+     * Accounts expected by this instruction:
+     *
+     *   * Single owner
+     *   0. `[writable]` The account to update.
+     *   1. `[signer]` The account's owner.
+     *
+     *   * Multisignature owner
+     *   0. `[writable]` The account to update.
+     *   1. `[]` The account's multisignature owner.
+     *   2. ..2+M `[signer]` M signer accounts.
+     */
+    Token_TokenInstruction_DisableRequiredTransferMemos,
+    /**
+     * Makes the owner of a new token account immutable, so that
+     * `SetAuthority` can never reassign it.
+     *
+     * The account must be allocated with space for the `ImmutableOwner`
+     * extension, see `ExtensionType::get_account_len`, and this instruction
+     * must precede the `InitializeAccount` instruction of the same
+     * Transaction.  It requires no signers, so it MUST be included within
+     * the same Transaction as the system program's `CreateAccount`
+     * instruction that creates the account.
+     *
+     * Accounts expected by this instruction:
+     *
+     *   0. `[writable]` The account to configure.
+     */
+    Token_TokenInstruction_InitializeImmutableOwner,
+    /**
+     * Sets the close authority of a new mint, which may close the mint with
+     * `CloseAccount` once its supply is zero.  The close authority can be
+     * changed or removed with `SetAuthority` but never added after the mint
+     * is initialized.
+     *
+     * The mint account must be allocated with space for the extension, see
+     * `ExtensionType::get_account_len`, and this instruction must precede the
+     * `InitializeMint` instruction of the same Transaction.  It requires no
+     * signers, so it MUST be included within the same Transaction as the
+     * system program's `CreateAccount` instruction that creates the mint.
+     *
+     * Accounts expected by this instruction:
+     *
+     *   0. `[writable]` The mint to configure.
+     */
+    Token_TokenInstruction_InitializeMintCloseAuthority,
+    /**
+     * Sets the human-readable metadata of a mint.  The metadata can be
+     * changed with `UpdateTokenMetadata` by its update authority, which can
+     * be changed or removed with `SetAuthority`.
+     *
+     * The name, symbol and URI may be at most `MAX_METADATA_NAME_LEN`,
+     * `MAX_METADATA_SYMBOL_LEN` and `MAX_METADATA_URI_LEN` bytes long.
+     *
+     * The mint account must be allocated with space for the extension, see
+     * `ExtensionType::get_account_len`.  For a new mint, this instruction
+     * must precede the `InitializeMint` instruction of the same Transaction.
+     * It then requires no signers, so it MUST be included within the same
+     * Transaction as the system program's `CreateAccount` instruction that
+     * creates the mint.  A mint that is already initialized, and was
+     * allocated with the space but holds no metadata yet, may gain it with
+     * the approval of its mint authority.
+     *
+     * Accounts expected by this instruction:
+     *
+     *   * New mint
+     *   0. `[writable]` The mint to configure.
+     *
+     *   * Initialized mint, single mint authority
+     *   0. `[writable]` The mint to configure.
+     *   1. `[signer]` The mint's minting authority.
+     *
+     *   * Initialized mint, multisignature mint authority
+     *   0. `[writable]` The mint to configure.
+     *   1. `[]` The mint's multisignature minting authority.
+     *   2. ..2+M `[signer]` M signer accounts.
+     */
+    Token_TokenInstruction_InitializeTokenMetadata,
+    /**
+     * Updates the human-readable metadata of a mint.
+     *
+     * Accounts expected by this instruction:
+     *
+     *   * Single authority
+     *   0. `[writable]` The mint to update.
+     *   1. `[signer]` The metadata's update authority.
+     *
+     *   * Multisignature authority
+     *   0. `[writable]` The mint to update.
+     *   1. `[]` The metadata's multisignature update authority.
+     *   2. ..2+M `[signer]` M signer accounts.
+     */
+    Token_TokenInstruction_UpdateTokenMetadata,
+    /**
+     * Configures a new mint to accrue interest on the UI amounts of its
+     * tokens, compounded continuously from the current time.  The raw
+     * amounts held by accounts never change.  The rate can be updated with
+     * `UpdateInterestRate` by its rate authority, which can be changed or
+     * removed with `SetAuthority`.
+     *
+     * The mint account must be allocated with space for the extension, see
+     * `ExtensionType::get_account_len`, and this instruction must precede the
+     * `InitializeMint` instruction of the same Transaction.  It requires no
+     * signers, so it MUST be included within the same Transaction as the
+     * system program's `CreateAccount` instruction that creates the mint.
+     *
+     * Accounts expected by this instruction:
+     *
+     *   0. `[writable]` The mint to configure.
+     */
+    Token_TokenInstruction_InitializeInterestBearingMint,
+    /**
+     * Updates the interest rate of a mint.  Interest accrued at the previous
+     * rate is kept.
+     *
+     * Accounts expected by this instruction:
+     *
+     *   * Single authority
+     *   0. `[writable]` The mint to update.
+     *   1. `[signer]` The mint's rate authority.
+     *
+     *   * Multisignature authority
+     *   0. `[writable]` The mint to update.
+     *   1. `[]` The mint's multisignature rate authority.
+     *   2. ..2+M `[signer]` M signer accounts.
+     */
+    Token_TokenInstruction_UpdateInterestRate,
+    /**
+     * Blocks approvals, authority changes, closing, migration to a new
+     * address and owner-signed transfers of a token account when they are
+     * invoked by another program, so that a program cannot misuse the
+     * owner's signature.  Burns, and transfers signed by a delegate, are not
+     * guarded.  The account must have space for the `CpiGuard` extension,
+     * which existing accounts can gain with `MigrateAccount`.
+     *
+     * This runtime does not expose the invocation stack, so the check reads
+     * the instructions sysvar: a guarded action only succeeds if the
+     * top-level instruction being executed belongs to the token program,
+     * which never invokes itself.  Guarded actions must therefore append the
+     * instructions sysvar to their accounts, as done by the
+     * `*_with_instructions_sysvar` instruction builders.  Enabling the guard
+     * breaks clients that build guarded actions without the sysvar, such as
+     * the plain instruction builders, which fail with `CpiGuarded` until
+     * they switch to these builders.
+     *
+     * Accounts expected by this instruction:
+     *
+     *   * Single owner
+     *   0. `[writable]` The account to update.
+     *   1. `[signer]` The account's owner.
+     *
+     *   * Multisignature owner
+     *   0. `[writable]` The account to update.
+     *   1. `[]` The account's multisignature owner.
+     *   2. ..2+M `[signer]` M signer accounts.
+     */
+    Token_TokenInstruction_EnableCpiGuard,
+    /**
+     * Stops blocking privileged actions on a token account invoked by
+     * another program.  Disabling the guard is itself guarded: it must not
+     * be invoked by another program, and the instructions sysvar must be
+     * appended to the accounts.
+     *
+     * Accounts expected by this instruction:
+     *
+     *   * Single owner
+     *   0. `[writable]` The account to update.
+     *   1. `[signer]` The account's owner.
+     *
+     *   * Multisignature owner
+     *   0. `[writable]` The account to update.
+     *   1. `[]` The account's multisignature owner.
+     *   2. ..2+M `[signer]` M signer accounts.
+     */
+    Token_TokenInstruction_DisableCpiGuard,
 } Token_TokenInstruction_Tag;
 
 typedef struct Token_TokenInstruction_Token_InitializeMint_Body {
@@ -542,6 +1239,187 @@ typedef struct Token_TokenInstruction_Token_InitializeAccount2_Body {
     Token_Pubkey owner;
 } Token_TokenInstruction_Token_InitializeAccount2_Body;
 
+typedef struct Token_TokenInstruction_Token_InitializeAccount3_Body {
+    /**
+     * The new account's owner/multisignature.
+     */
+    Token_Pubkey owner;
+} Token_TokenInstruction_Token_InitializeAccount3_Body;
+
+typedef struct Token_TokenInstruction_Token_InitializeMint2_Body {
+    /**
+     * Number of base 10 digits to the right of the decimal place.
+     */
+    uint8_t decimals;
+    /**
+     * The authority/multisignature to mint tokens.
+     */
+    Token_Pubkey mint_authority;
+    /**
+     * The freeze authority/multisignature of the mint.
+     */
+    struct Token_COption_Pubkey freeze_authority;
+} Token_TokenInstruction_Token_InitializeMint2_Body;
+
+typedef struct Token_TokenInstruction_Token_TransferCheckedBatch_Body {
+    /**
+     * The amount of tokens to transfer to each destination account.
+     */
+    struct Token_Vec_u64 amounts;
+    /**
+     * Expected number of base 10 digits to the right of the decimal place.
+     */
+    uint8_t decimals;
+} Token_TokenInstruction_Token_TransferCheckedBatch_Body;
+
+typedef struct Token_TokenInstruction_Token_InitializeMaxSupply_Body {
+    /**
+     * The maximum number of tokens that may ever be in circulation.
+     */
+    uint64_t max_supply;
+} Token_TokenInstruction_Token_InitializeMaxSupply_Body;
+
+typedef struct Token_TokenInstruction_Token_ApproveCheckedWithExpiry_Body {
+    /**
+     * The amount of tokens the delegate is approved for.
+     */
+    uint64_t amount;
+    /**
+     * Expected number of base 10 digits to the right of the decimal place.
+     */
+    uint8_t decimals;
+    /**
+     * The last slot in which the delegate may use the approval.
+     */
+    uint64_t expiry_slot;
+} Token_TokenInstruction_Token_ApproveCheckedWithExpiry_Body;
+
+typedef struct Token_TokenInstruction_Token_CreateMultisigProposal_Body {
+    /**
+     * The packed [TokenInstruction](enum.TokenInstruction.html) to execute.
+     */
+    struct Token_Vec_u8 instruction_data;
+} Token_TokenInstruction_Token_CreateMultisigProposal_Body;
+
+typedef struct Token_TokenInstruction_Token_InitializePermanentDelegate_Body {
+    /**
+     * The authority that may transfer or burn any of the mint's tokens.
+     */
+    Token_Pubkey delegate;
+} Token_TokenInstruction_Token_InitializePermanentDelegate_Body;
+
+typedef struct Token_TokenInstruction_Token_SetDefaultFrozen_Body {
+    /**
+     * Whether new accounts of the mint start frozen.
+     */
+    bool default_frozen;
+} Token_TokenInstruction_Token_SetDefaultFrozen_Body;
+
+typedef struct Token_TokenInstruction_Token_MigrateAccount_Body {
+    /**
+     * The extensions the new account must have space for, in addition
+     * to those already held by the token account.
+     */
+    struct Token_Vec_ExtensionType extension_types;
+} Token_TokenInstruction_Token_MigrateAccount_Body;
+
+typedef struct Token_TokenInstruction_Token_InitializeTransferFeeConfig_Body {
+    /**
+     * The authority that may withdraw withheld fees.
+     */
+    struct Token_COption_Pubkey withdraw_withheld_authority;
+    /**
+     * The fee charged on transfers, in basis points of the transferred
+     * amount.
+     */
+    uint16_t transfer_fee_basis_points;
+    /**
+     * The maximum fee charged on a single transfer.
+     */
+    uint64_t maximum_fee;
+} Token_TokenInstruction_Token_InitializeTransferFeeConfig_Body;
+
+typedef struct Token_TokenInstruction_Token_TransferCheckedWithFee_Body {
+    /**
+     * The amount of tokens to transfer, including the fee.
+     */
+    uint64_t amount;
+    /**
+     * Expected number of base 10 digits to the right of the decimal place.
+     */
+    uint8_t decimals;
+    /**
+     * Expected fee withheld from the transferred amount.
+     */
+    uint64_t fee;
+} Token_TokenInstruction_Token_TransferCheckedWithFee_Body;
+
+typedef struct Token_TokenInstruction_Token_WithdrawWithheldTokensFromAccounts_Body {
+    /**
+     * The number of source accounts (N).
+     */
+    uint8_t num_token_accounts;
+} Token_TokenInstruction_Token_WithdrawWithheldTokensFromAccounts_Body;
+
+typedef struct Token_TokenInstruction_Token_InitializeMintCloseAuthority_Body {
+    /**
+     * The authority that may close the mint.
+     */
+    Token_Pubkey close_authority;
+} Token_TokenInstruction_Token_InitializeMintCloseAuthority_Body;
+
+typedef struct Token_TokenInstruction_Token_InitializeTokenMetadata_Body {
+    /**
+     * The authority that may update the metadata.
+     */
+    struct Token_COption_Pubkey update_authority;
+    /**
+     * The name of the token.
+     */
+    struct Token_String name;
+    /**
+     * The symbol of the token.
+     */
+    struct Token_String symbol;
+    /**
+     * The URI of the token's off-chain metadata.
+     */
+    struct Token_String uri;
+} Token_TokenInstruction_Token_InitializeTokenMetadata_Body;
+
+typedef struct Token_TokenInstruction_Token_UpdateTokenMetadata_Body {
+    /**
+     * The new name of the token.
+     */
+    struct Token_String name;
+    /**
+     * The new symbol of the token.
+     */
+    struct Token_String symbol;
+    /**
+     * The new URI of the token's off-chain metadata.
+     */
+    struct Token_String uri;
+} Token_TokenInstruction_Token_UpdateTokenMetadata_Body;
+
+typedef struct Token_TokenInstruction_Token_InitializeInterestBearingMint_Body {
+    /**
+     * The authority that may update the rate.
+     */
+    struct Token_COption_Pubkey rate_authority;
+    /**
+     * The interest rate, in basis points per year.
+     */
+    int16_t rate;
+} Token_TokenInstruction_Token_InitializeInterestBearingMint_Body;
+
+typedef struct Token_TokenInstruction_Token_UpdateInterestRate_Body {
+    /**
+     * The new interest rate, in basis points per year.
+     */
+    int16_t rate;
+} Token_TokenInstruction_Token_UpdateInterestRate_Body;
+
 typedef struct Token_TokenInstruction {
     Token_TokenInstruction_Tag tag;
     union {
@@ -557,6 +1435,23 @@ typedef struct Token_TokenInstruction {
         Token_TokenInstruction_Token_MintToChecked_Body mint_to_checked;
         Token_TokenInstruction_Token_BurnChecked_Body burn_checked;
         Token_TokenInstruction_Token_InitializeAccount2_Body initialize_account2;
+        Token_TokenInstruction_Token_InitializeAccount3_Body initialize_account3;
+        Token_TokenInstruction_Token_InitializeMint2_Body initialize_mint2;
+        Token_TokenInstruction_Token_TransferCheckedBatch_Body transfer_checked_batch;
+        Token_TokenInstruction_Token_InitializeMaxSupply_Body initialize_max_supply;
+        Token_TokenInstruction_Token_ApproveCheckedWithExpiry_Body approve_checked_with_expiry;
+        Token_TokenInstruction_Token_CreateMultisigProposal_Body create_multisig_proposal;
+        Token_TokenInstruction_Token_InitializePermanentDelegate_Body initialize_permanent_delegate;
+        Token_TokenInstruction_Token_SetDefaultFrozen_Body set_default_frozen;
+        Token_TokenInstruction_Token_MigrateAccount_Body migrate_account;
+        Token_TokenInstruction_Token_InitializeTransferFeeConfig_Body initialize_transfer_fee_config;
+        Token_TokenInstruction_Token_TransferCheckedWithFee_Body transfer_checked_with_fee;
+        Token_TokenInstruction_Token_WithdrawWithheldTokensFromAccounts_Body withdraw_withheld_tokens_from_accounts;
+        Token_TokenInstruction_Token_InitializeMintCloseAuthority_Body initialize_mint_close_authority;
+        Token_TokenInstruction_Token_InitializeTokenMetadata_Body initialize_token_metadata;
+        Token_TokenInstruction_Token_UpdateTokenMetadata_Body update_token_metadata;
+        Token_TokenInstruction_Token_InitializeInterestBearingMint_Body initialize_interest_bearing_mint;
+        Token_TokenInstruction_Token_UpdateInterestRate_Body update_interest_rate;
     };
 } Token_TokenInstruction;
 
@@ -64,6 +64,54 @@ pub enum TokenError {
     /// Mint decimals mismatch between the client and mint
     #[error("The provided decimals value different from the Mint decimals")]
     MintDecimalsMismatch,
+    /// Instruction does not support non-native tokens
+    #[error("Instruction does not support non-native tokens")]
+    NonNativeNotSupported,
+    /// Minting would exceed the maximum supply of the mint
+    #[error("Maximum supply exceeded")]
+    MaxSupplyExceeded,
+    /// The delegate's approval has expired
+    #[error("Delegation has expired")]
+    DelegationExpired,
+    /// Tokens of the mint cannot be transferred
+    #[error("Transfers are disabled for this mint")]
+    NonTransferable,
+    /// The transfer fee exceeds the maximum of 10,000 basis points
+    #[error("Transfer fee exceeds maximum of 10,000 basis points")]
+    TransferFeeExceedsMaximum,
+    /// The calculated transfer fee does not match the expected fee
+    #[error("Calculated fee does not match expected fee")]
+    FeeMismatch,
+    /// Transfers of the mint's tokens must include the mint
+    #[error("Mint required for this transfer, use TransferChecked")]
+    MintRequiredForTransfer,
+    /// The mint does not charge transfer fees
+    #[error("Mint has no transfer fee configuration")]
+    NoTransferFee,
+    /// The account still holds withheld transfer fees
+    #[error("Account has withheld transfer fees")]
+    AccountHasWithheldTransferFees,
+    /// The destination account requires a memo on incoming transfers
+    #[error("No memo in previous instruction; required for recipient to receive a transfer")]
+    NoMemo,
+    /// The account's owner cannot be changed
+    #[error("The owner authority cannot be changed")]
+    ImmutableOwner,
+    /// A mint with a nonzero supply cannot be closed
+    #[error("Mint has a nonzero supply")]
+    MintHasSupply,
+    /// A token metadata field exceeds its maximum length
+    #[error("Token metadata field exceeds its maximum length")]
+    MetadataFieldTooLong,
+    /// The mint has no token metadata
+    #[error("Mint has no token metadata")]
+    NoTokenMetadata,
+    /// The action was invoked by another program on a CPI-guarded account
+    #[error("Account blocks this action when invoked by another program")]
+    CpiGuarded,
+    /// The account is found by its address, so it cannot be migrated
+    #[error("Account is found by its address and cannot be migrated")]
+    AccountNotMigratable,
 }
 impl From<TokenError> for ProgramError {
     fn from(e: TokenError) -> Self {
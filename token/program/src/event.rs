@@ -0,0 +1,531 @@
+//! Structured events logged by the token program
+
+use solana_program::{msg, pubkey::Pubkey};
+use std::{fmt, str::FromStr};
+
+/// Marker that starts every event log line
+pub const EVENT_LOG_PREFIX: &str = "TokenEvent";
+
+/// Version of the event log format, bumped whenever the fields of an
+/// existing event change
+pub const EVENT_LOG_VERSION: u8 = 1;
+
+/// Prefix the runtime adds to messages logged by a program
+const PROGRAM_LOG_PREFIX: &str = "Program log: ";
+
+/// Balance and authority changes reported by the token program.
+///
+/// Each event is logged as a single line of space-separated fields:
+///
+///   `TokenEvent v1 <Name> <fields...>`
+///
+/// with addresses in base58 and amounts as decimal integers, in the order the
+/// fields are declared below.  The transfers of a `TransferBatch` event
+/// follow its other fields, as one `<destination> <amount> <fee>` group per
+/// transfer.
+///
+/// The events cover every change to token balances, approvals, frozen state
+/// and open accounts, but not authority changes made with `SetAuthority`,
+/// the harvesting of withheld fees to the mint, or the initialization and
+/// configuration of mints, accounts and extensions.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenEvent {
+    /// New tokens were minted to an account
+    MintTo {
+        /// The mint
+        mint: Pubkey,
+        /// The account credited
+        account: Pubkey,
+        /// The amount minted
+        amount: u64,
+    },
+    /// Tokens were burned from an account
+    Burn {
+        /// The mint
+        mint: Pubkey,
+        /// The account debited
+        account: Pubkey,
+        /// The amount burned
+        amount: u64,
+    },
+    /// Tokens moved between two accounts
+    Transfer {
+        /// The mint
+        mint: Pubkey,
+        /// The account debited
+        source: Pubkey,
+        /// The account credited
+        destination: Pubkey,
+        /// The owner, delegate or permanent delegate that signed
+        authority: Pubkey,
+        /// The amount debited from the source
+        amount: u64,
+        /// The part of `amount` withheld in the destination as a transfer fee
+        fee: u64,
+    },
+    /// Tokens moved from one account to several with `TransferCheckedBatch`.
+    /// A single event is logged for the whole batch, so that the mint, source
+    /// and authority are formatted once rather than for every transfer.
+    TransferBatch {
+        /// The mint
+        mint: Pubkey,
+        /// The account debited
+        source: Pubkey,
+        /// The owner, delegate or permanent delegate that signed
+        authority: Pubkey,
+        /// The transfers, in the order of the instruction, leaving out those
+        /// to the source itself, which move no tokens
+        transfers: Vec<BatchTransfer>,
+    },
+    /// A delegate was approved to transfer tokens from an account
+    Approve {
+        /// The account
+        account: Pubkey,
+        /// The delegate
+        delegate: Pubkey,
+        /// The amount the delegate may transfer
+        amount: u64,
+    },
+    /// The delegate of an account was revoked
+    Revoke {
+        /// The account
+        account: Pubkey,
+    },
+    /// An account was frozen by the mint's freeze authority
+    Freeze {
+        /// The mint
+        mint: Pubkey,
+        /// The account
+        account: Pubkey,
+    },
+    /// An account was thawed by the mint's freeze authority
+    Thaw {
+        /// The mint
+        mint: Pubkey,
+        /// The account
+        account: Pubkey,
+    },
+    /// An account or mint was closed
+    Close {
+        /// The closed account or mint
+        account: Pubkey,
+        /// The account that received the lamports
+        destination: Pubkey,
+        /// The lamports reclaimed
+        lamports: u64,
+    },
+    /// The token amount of a native account was synced with its lamports
+    SyncNative {
+        /// The account
+        account: Pubkey,
+        /// The new amount of the account
+        amount: u64,
+    },
+    /// Withheld transfer fees were withdrawn by the mint's withdraw withheld
+    /// authority
+    WithdrawWithheld {
+        /// The mint
+        mint: Pubkey,
+        /// The account credited
+        destination: Pubkey,
+        /// The amount withdrawn
+        amount: u64,
+    },
+    /// A token account was migrated to a new address, or in place if both
+    /// addresses are the same
+    Migrate {
+        /// The mint
+        mint: Pubkey,
+        /// The old account, now closed unless migrated in place
+        account: Pubkey,
+        /// The new account holding its state
+        new_account: Pubkey,
+    },
+}
+
+/// A single transfer of a `TransferBatch` event
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchTransfer {
+    /// The account credited
+    pub destination: Pubkey,
+    /// The amount debited from the source
+    pub amount: u64,
+    /// The part of `amount` withheld in the destination as a transfer fee
+    pub fee: u64,
+}
+
+impl TokenEvent {
+    /// Logs the event
+    pub fn log(&self) {
+        msg!("{}", self);
+    }
+
+    /// Parses a single log line, with or without the runtime's
+    /// `Program log: ` prefix.  Returns `None` for lines that are not token
+    /// events or use an unknown version of the format.
+    pub fn from_log(log: &str) -> Option<Self> {
+        let line = log.strip_prefix(PROGRAM_LOG_PREFIX).unwrap_or(log);
+        let mut fields = line.split(' ');
+        if fields.next()? != EVENT_LOG_PREFIX {
+            return None;
+        }
+        if fields.next()? != format!("v{}", EVENT_LOG_VERSION) {
+            return None;
+        }
+        let fields = &mut fields;
+        let event = match fields.next()? {
+            "MintTo" => Self::MintTo {
+                mint: next_pubkey(fields)?,
+                account: next_pubkey(fields)?,
+                amount: next_u64(fields)?,
+            },
+            "Burn" => Self::Burn {
+                mint: next_pubkey(fields)?,
+                account: next_pubkey(fields)?,
+                amount: next_u64(fields)?,
+            },
+            "Transfer" => Self::Transfer {
+                mint: next_pubkey(fields)?,
+                source: next_pubkey(fields)?,
+                destination: next_pubkey(fields)?,
+                authority: next_pubkey(fields)?,
+                amount: next_u64(fields)?,
+                fee: next_u64(fields)?,
+            },
+            "TransferBatch" => {
+                let mint = next_pubkey(fields)?;
+                let source = next_pubkey(fields)?;
+                let authority = next_pubkey(fields)?;
+                let mut transfers = vec![];
+                while let Some(destination) = fields.next() {
+                    transfers.push(BatchTransfer {
+                        destination: Pubkey::from_str(destination).ok()?,
+                        amount: next_u64(fields)?,
+                        fee: next_u64(fields)?,
+                    });
+                }
+                Self::TransferBatch {
+                    mint,
+                    source,
+                    authority,
+                    transfers,
+                }
+            }
+            "Approve" => Self::Approve {
+                account: next_pubkey(fields)?,
+                delegate: next_pubkey(fields)?,
+                amount: next_u64(fields)?,
+            },
+            "Revoke" => Self::Revoke {
+                account: next_pubkey(fields)?,
+            },
+            "Freeze" => Self::Freeze {
+                mint: next_pubkey(fields)?,
+                account: next_pubkey(fields)?,
+            },
+            "Thaw" => Self::Thaw {
+                mint: next_pubkey(fields)?,
+                account: next_pubkey(fields)?,
+            },
+            "Close" => Self::Close {
+                account: next_pubkey(fields)?,
+                destination: next_pubkey(fields)?,
+                lamports: next_u64(fields)?,
+            },
+            "SyncNative" => Self::SyncNative {
+                account: next_pubkey(fields)?,
+                amount: next_u64(fields)?,
+            },
+            "WithdrawWithheld" => Self::WithdrawWithheld {
+                mint: next_pubkey(fields)?,
+                destination: next_pubkey(fields)?,
+                amount: next_u64(fields)?,
+            },
+            "Migrate" => Self::Migrate {
+                mint: next_pubkey(fields)?,
+                account: next_pubkey(fields)?,
+                new_account: next_pubkey(fields)?,
+            },
+            _ => return None,
+        };
+        if fields.next().is_some() {
+            return None;
+        }
+        Some(event)
+    }
+}
+
+fn next_pubkey<'a>(fields: &mut impl Iterator<Item = &'a str>) -> Option<Pubkey> {
+    Pubkey::from_str(fields.next()?).ok()
+}
+
+fn next_u64<'a>(fields: &mut impl Iterator<Item = &'a str>) -> Option<u64> {
+    fields.next()?.parse().ok()
+}
+
+impl fmt::Display for TokenEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} v{} ", EVENT_LOG_PREFIX, EVENT_LOG_VERSION)?;
+        match self {
+            Self::MintTo {
+                mint,
+                account,
+                amount,
+            } => write!(f, "MintTo {} {} {}", mint, account, amount),
+            Self::Burn {
+                mint,
+                account,
+                amount,
+            } => write!(f, "Burn {} {} {}", mint, account, amount),
+            Self::Transfer {
+                mint,
+                source,
+                destination,
+                authority,
+                amount,
+                fee,
+            } => write!(
+                f,
+                "Transfer {} {} {} {} {} {}",
+                mint, source, destination, authority, amount, fee
+            ),
+            Self::TransferBatch {
+                mint,
+                source,
+                authority,
+                transfers,
+            } => {
+                write!(f, "TransferBatch {} {} {}", mint, source, authority)?;
+                for transfer in transfers {
+                    write!(
+                        f,
+                        " {} {} {}",
+                        transfer.destination, transfer.amount, transfer.fee
+                    )?;
+                }
+                Ok(())
+            }
+            Self::Approve {
+                account,
+                delegate,
+                amount,
+            } => write!(f, "Approve {} {} {}", account, delegate, amount),
+            Self::Revoke { account } => write!(f, "Revoke {}", account),
+            Self::Freeze { mint, account } => write!(f, "Freeze {} {}", mint, account),
+            Self::Thaw { mint, account } => write!(f, "Thaw {} {}", mint, account),
+            Self::Close {
+                account,
+                destination,
+                lamports,
+            } => write!(f, "Close {} {} {}", account, destination, lamports),
+            Self::SyncNative { account, amount } => write!(f, "SyncNative {} {}", account, amount),
+            Self::WithdrawWithheld {
+                mint,
+                destination,
+                amount,
+            } => write!(f, "WithdrawWithheld {} {} {}", mint, destination, amount),
+            Self::Migrate {
+                mint,
+                account,
+                new_account,
+            } => write!(f, "Migrate {} {} {}", mint, account, new_account),
+        }
+    }
+}
+
+/// Extracts the events logged by `program_id` from the log messages of a
+/// transaction.
+///
+/// The `Program <id> invoke [n]` and `Program <id> success|failed` lines are
+/// followed to attribute each message to the program that logged it, so
+/// event-shaped lines logged by any other program are ignored.  Events of an
+/// invocation that failed are discarded with it.
+pub fn parse_logs<S: AsRef<str>>(program_id: &Pubkey, logs: &[S]) -> Vec<TokenEvent> {
+    let program_id = program_id.to_string();
+    let mut stack: Vec<(bool, Vec<TokenEvent>)> = vec![];
+    let mut events = vec![];
+    for log in logs {
+        let log = log.as_ref();
+        if log.starts_with(PROGRAM_LOG_PREFIX) {
+            if let Some((true, frame_events)) = stack.last_mut() {
+                if let Some(event) = TokenEvent::from_log(log) {
+                    frame_events.push(event);
+                }
+            }
+            continue;
+        }
+        let mut words = log.split(' ');
+        if words.next() != Some("Program") {
+            continue;
+        }
+        let id = match words.next() {
+            Some(id) => id,
+            None => continue,
+        };
+        match words.next() {
+            Some("invoke") => stack.push((id == program_id, vec![])),
+            Some("success") => {
+                if let Some((_, frame_events)) = stack.pop() {
+                    match stack.last_mut() {
+                        Some((_, parent_events)) => parent_events.extend(frame_events),
+                        None => events.extend(frame_events),
+                    }
+                }
+            }
+            Some(status) if status.starts_with("failed") => {
+                stack.pop();
+            }
+            _ => {}
+        }
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_events() -> Vec<TokenEvent> {
+        vec![
+            TokenEvent::MintTo {
+                mint: Pubkey::new_unique(),
+                account: Pubkey::new_unique(),
+                amount: 42,
+            },
+            TokenEvent::Burn {
+                mint: Pubkey::new_unique(),
+                account: Pubkey::new_unique(),
+                amount: u64::MAX,
+            },
+            TokenEvent::Transfer {
+                mint: Pubkey::new_unique(),
+                source: Pubkey::new_unique(),
+                destination: Pubkey::new_unique(),
+                authority: Pubkey::new_unique(),
+                amount: 1000,
+                fee: 10,
+            },
+            TokenEvent::TransferBatch {
+                mint: Pubkey::new_unique(),
+                source: Pubkey::new_unique(),
+                authority: Pubkey::new_unique(),
+                transfers: vec![
+                    BatchTransfer {
+                        destination: Pubkey::new_unique(),
+                        amount: 1000,
+                        fee: 10,
+                    },
+                    BatchTransfer {
+                        destination: Pubkey::new_unique(),
+                        amount: 2000,
+                        fee: 20,
+                    },
+                ],
+            },
+            TokenEvent::Approve {
+                account: Pubkey::new_unique(),
+                delegate: Pubkey::new_unique(),
+                amount: 0,
+            },
+            TokenEvent::Revoke {
+                account: Pubkey::new_unique(),
+            },
+            TokenEvent::Freeze {
+                mint: Pubkey::new_unique(),
+                account: Pubkey::new_unique(),
+            },
+            TokenEvent::Thaw {
+                mint: Pubkey::new_unique(),
+                account: Pubkey::new_unique(),
+            },
+            TokenEvent::Close {
+                account: Pubkey::new_unique(),
+                destination: Pubkey::new_unique(),
+                lamports: 2039280,
+            },
+            TokenEvent::SyncNative {
+                account: Pubkey::new_unique(),
+                amount: 1_000_000_000,
+            },
+            TokenEvent::WithdrawWithheld {
+                mint: Pubkey::new_unique(),
+                destination: Pubkey::new_unique(),
+                amount: 500,
+            },
+            TokenEvent::Migrate {
+                mint: Pubkey::new_unique(),
+                account: Pubkey::new_unique(),
+                new_account: Pubkey::new_unique(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_event_log_round_trip() {
+        for event in test_events() {
+            let log = format!("{}{}", PROGRAM_LOG_PREFIX, event);
+            assert_eq!(TokenEvent::from_log(&log), Some(event.clone()));
+            assert_eq!(TokenEvent::from_log(&event.to_string()), Some(event));
+        }
+    }
+
+    #[test]
+    fn test_event_log_rejects_malformed_lines() {
+        let mint = Pubkey::new_unique();
+        let account = Pubkey::new_unique();
+        for log in &[
+            "Program log: Instruction: MintTo".to_string(),
+            format!("TokenEvent v2 MintTo {} {} 1", mint, account),
+            format!("TokenEvent v1 Mint {} {} 1", mint, account),
+            format!("TokenEvent v1 MintTo {} {}", mint, account),
+            format!("TokenEvent v1 MintTo {} {} -1", mint, account),
+            format!("TokenEvent v1 MintTo {} {} 1 2", mint, account),
+            format!("TokenEvent v1 MintTo {} invalid 1", mint),
+            format!(
+                "TokenEvent v1 TransferBatch {} {} {} {} 1",
+                mint, account, account, mint
+            ),
+            format!(
+                "TokenEvent v1 TransferBatch {} {} {} 1 1 1",
+                mint, account, account
+            ),
+        ] {
+            assert_eq!(TokenEvent::from_log(log), None);
+        }
+    }
+
+    #[test]
+    fn test_parse_logs() {
+        let program_id = Pubkey::new_unique();
+        let other_program_id = Pubkey::new_unique();
+        let events = test_events();
+        let logs = vec![
+            format!("Program {} invoke [1]", program_id),
+            "Program log: Instruction: MintTo".to_string(),
+            format!("Program log: {}", events[0]),
+            format!(
+                "Program {} consumed 2000 of 200000 compute units",
+                program_id
+            ),
+            format!("Program {} success", program_id),
+            // events spoofed by another program are ignored, while its
+            // successful invocations of the token program are kept
+            format!("Program {} invoke [1]", other_program_id),
+            format!("Program log: {}", events[1]),
+            format!("Program {} invoke [2]", program_id),
+            format!("Program log: {}", events[2]),
+            format!("Program {} success", program_id),
+            format!("Program {} invoke [2]", program_id),
+            format!("Program log: {}", events[3]),
+            format!("Program {} failed: custom program error: 0x1", program_id),
+            format!("Program {} success", other_program_id),
+            format!("Program {} invoke [1]", program_id),
+            format!("Program log: {}", events[4]),
+            format!("Program {} success", program_id),
+        ];
+        assert_eq!(
+            parse_logs(&program_id, &logs),
+            vec![events[0].clone(), events[2].clone(), events[4].clone()]
+        );
+    }
+}
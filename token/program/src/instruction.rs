@@ -1,6 +1,10 @@
 //! Instruction types
 
-use crate::error::TokenError;
+use crate::{
+    error::TokenError,
+    state::{ExtensionType, TokenMetadata},
+};
+use num_enum::TryFromPrimitive;
 use solana_program::{
     instruction::{AccountMeta, Instruction},
     program_error::ProgramError,
@@ -15,6 +19,10 @@ use std::mem::size_of;
 pub const MIN_SIGNERS: usize = 1;
 /// Maximum number of multisignature signers (max N)
 pub const MAX_SIGNERS: usize = 11;
+/// Maximum number of accounts of an instruction held by a multisignature proposal
+pub const MAX_PROPOSAL_ACCOUNTS: usize = 8;
+/// Maximum length of the data of an instruction held by a multisignature proposal
+pub const MAX_PROPOSAL_DATA_LEN: usize = 64;
 
 /// Instructions supported by the token program.
 #[repr(C)]
@@ -90,6 +98,17 @@ pub enum TokenInstruction {
     /// amounts of SOL and Tokens will be transferred to the destination
     /// account.
     ///
+    /// A mint's permanent delegate, and any transfer of a mint charging
+    /// transfer fees, must use `TransferChecked` instead.
+    ///
+    /// If the destination account requires memos, the transfer must
+    /// immediately follow a memo instruction, and the instructions sysvar
+    /// must be appended to the accounts.
+    ///
+    /// If the source account's CPI guard is enabled, transfers signed by its
+    /// owner must not be invoked by another program, and the instructions
+    /// sysvar must be appended to the accounts.
+    ///
     /// Accounts expected by this instruction:
     ///
     ///   * Single owner/delegate
@@ -109,6 +128,10 @@ pub enum TokenInstruction {
     /// Approves a delegate.  A delegate is given the authority over tokens on
     /// behalf of the source account's owner.
     ///
+    /// If the account's CPI guard is enabled, this instruction must not be
+    /// invoked by another program, and the instructions sysvar must be
+    /// appended to the accounts.
+    ///
     /// Accounts expected by this instruction:
     ///
     ///   * Single owner
@@ -140,6 +163,10 @@ pub enum TokenInstruction {
     Revoke,
     /// Sets a new authority of a mint or account.
     ///
+    /// If the account's CPI guard is enabled, this instruction must not be
+    /// invoked by another program, and the instructions sysvar must be
+    /// appended to the accounts.
+    ///
     /// Accounts expected by this instruction:
     ///
     ///   * Single authority
@@ -178,6 +205,9 @@ pub enum TokenInstruction {
     /// Burns tokens by removing them from an account.  `Burn` does not support
     /// accounts associated with the native mint, use `CloseAccount` instead.
     ///
+    /// If the mint has a permanent delegate, it may burn tokens from any
+    /// account of the mint.
+    ///
     /// Accounts expected by this instruction:
     ///
     ///   * Single owner/delegate
@@ -197,17 +227,25 @@ pub enum TokenInstruction {
     /// Close an account by transferring all its SOL to the destination account.
     /// Non-native accounts may only be closed if its token amount is zero.
     ///
+    /// A mint may also be closed by its close authority, see
+    /// `InitializeMintCloseAuthority`, once its supply is zero.
+    ///
+    /// If the account's CPI guard is enabled, this instruction must not be
+    /// invoked by another program, and the instructions sysvar must be
+    /// appended to the accounts.
+    ///
     /// Accounts expected by this instruction:
     ///
     ///   * Single owner
     ///   0. `[writable]` The account to close.
     ///   1. `[writable]` The destination account.
-    ///   2. `[signer]` The account's owner.
+    ///   2. `[signer]` The account's owner, or the mint's close authority.
     ///
     ///   * Multisignature owner
     ///   0. `[writable]` The account to close.
     ///   1. `[writable]` The destination account.
-    ///   2. `[]` The account's multisignature owner.
+    ///   2. `[]` The account's multisignature owner, or the mint's
+    ///      multisignature close authority.
     ///   3. ..3+M `[signer]` M signer accounts.
     CloseAccount,
     /// Freeze an Initialized account using the Mint's freeze_authority (if
@@ -251,6 +289,18 @@ pub enum TokenInstruction {
     /// decimals value is checked by the caller.  This may be useful when
     /// creating transactions offline or within a hardware wallet.
     ///
+    /// If the mint has a permanent delegate, it may transfer tokens from any
+    /// account of the mint.  If the mint charges a transfer fee, the fee is
+    /// withheld in the destination account.
+    ///
+    /// If the destination account requires memos, the transfer must
+    /// immediately follow a memo instruction, and the instructions sysvar
+    /// must be appended to the accounts.
+    ///
+    /// If the source account's CPI guard is enabled, transfers signed by its
+    /// owner must not be invoked by another program, and the instructions
+    /// sysvar must be appended to the accounts.
+    ///
     /// Accounts expected by this instruction:
     ///
     ///   * Single owner/delegate
@@ -278,6 +328,10 @@ pub enum TokenInstruction {
     /// decimals value is checked by the caller.  This may be useful when
     /// creating transactions offline or within a hardware wallet.
     ///
+    /// If the account's CPI guard is enabled, this instruction must not be
+    /// invoked by another program, and the instructions sysvar must be
+    /// appended to the accounts.
+    ///
     /// Accounts expected by this instruction:
     ///
     ///   * Single owner
@@ -331,6 +385,9 @@ pub enum TokenInstruction {
     /// by the caller. This may be useful when creating transactions offline or
     /// within a hardware wallet.
     ///
+    /// If the mint has a permanent delegate, it may burn tokens from any
+    /// account of the mint.
+    ///
     /// Accounts expected by this instruction:
     ///
     ///   * Single owner/delegate
@@ -363,6 +420,606 @@ pub enum TokenInstruction {
         /// The new account's owner/multisignature.
         owner: Pubkey,
     },
+    /// Given a wrapped / native token account (a token account containing SOL)
+    /// updates its amount field based on the account's underlying `lamports`.
+    /// This is useful if a non-wrapped SOL account uses `system_instruction::transfer`
+    /// to move lamports to a wrapped token account, and needs to have its token
+    /// `amount` field updated.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]`  The native token account to sync with its underlying lamports.
+    SyncNative,
+    /// Like InitializeAccount2, but does not require the Rent sysvar to be provided
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]`  The account to initialize.
+    ///   1. `[]` The mint this account will be associated with.
+    InitializeAccount3 {
+        /// The new account's owner/multisignature.
+        owner: Pubkey,
+    },
+    /// Like InitializeMint, but does not require the Rent sysvar to be provided
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The mint to initialize.
+    ///
+    InitializeMint2 {
+        /// Number of base 10 digits to the right of the decimal place.
+        decimals: u8,
+        /// The authority/multisignature to mint tokens.
+        mint_authority: Pubkey,
+        /// The freeze authority/multisignature of the mint.
+        freeze_authority: COption<Pubkey>,
+    },
+    /// Transfers tokens from one account to several destination accounts,
+    /// either directly or via a delegate, validating the source, mint and
+    /// authority only once.  If the source account is associated with the
+    /// native mint then equal amounts of SOL and Tokens will be transferred to
+    /// each destination account.
+    ///
+    /// Like TransferChecked, the token mint and decimals value is checked by
+    /// the caller.  The number of destination accounts must equal the number
+    /// of amounts.  If the mint charges a transfer fee, the fee on each amount
+    /// is withheld in its destination account.
+    ///
+    /// If any destination account requires memos, the transfer must
+    /// immediately follow a memo instruction, and the instructions sysvar
+    /// must be appended to the accounts.
+    ///
+    /// If the source account's CPI guard is enabled, transfers signed by its
+    /// owner must not be invoked by another program, and the instructions
+    /// sysvar must be appended to the accounts.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single owner/delegate
+    ///   0. `[writable]` The source account.
+    ///   1. `[]` The token mint.
+    ///   2. `[signer]` The source account's owner/delegate.
+    ///   3. ..3+N `[writable]` The N destination accounts, in the same order
+    ///      as the amounts.
+    ///
+    ///   * Multisignature owner/delegate
+    ///   0. `[writable]` The source account.
+    ///   1. `[]` The token mint.
+    ///   2. `[]` The source account's multisignature owner/delegate.
+    ///   3. ..3+N `[writable]` The N destination accounts, in the same order
+    ///      as the amounts.
+    ///   3+N. ..3+N+M `[signer]` M signer accounts.
+    TransferCheckedBatch {
+        /// The amount of tokens to transfer to each destination account.
+        amounts: Vec<u64>,
+        /// Expected number of base 10 digits to the right of the decimal place.
+        decimals: u8,
+    },
+    /// Sets the maximum supply of a new mint.  `MintTo` and `MintToChecked`
+    /// fail if they would bring the mint's total supply above this amount.
+    ///
+    /// The mint account must be allocated with space for the extension, see
+    /// `ExtensionType::get_account_len`, and this instruction must precede the
+    /// `InitializeMint` instruction of the same Transaction.  It requires no
+    /// signers, so it MUST be included within the same Transaction as the
+    /// system program's `CreateAccount` instruction that creates the mint.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The mint to configure.
+    InitializeMaxSupply {
+        /// The maximum number of tokens that may ever be in circulation.
+        max_supply: u64,
+    },
+    /// Approves a delegate until the given slot.  A delegate is given the
+    /// authority over tokens on behalf of the source account's owner, and may
+    /// no longer transfer or burn them once the current slot is past
+    /// `expiry_slot`.
+    ///
+    /// This instruction differs from ApproveChecked in that the approval
+    /// expires.  The source account must have space for the `DelegationExpiry`
+    /// extension, which existing accounts can gain with `MigrateAccount`.
+    /// Approvals made with Approve or ApproveChecked do not expire.
+    ///
+    /// If the account's CPI guard is enabled, this instruction must not be
+    /// invoked by another program, and the instructions sysvar must be
+    /// appended to the accounts.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single owner
+    ///   0. `[writable]` The source account.
+    ///   1. `[]` The token mint.
+    ///   2. `[]` The delegate.
+    ///   3. `[signer]` The source account owner.
+    ///
+    ///   * Multisignature owner
+    ///   0. `[writable]` The source account.
+    ///   1. `[]` The token mint.
+    ///   2. `[]` The delegate.
+    ///   3. `[]` The source account's multisignature owner.
+    ///   4. ..4+M `[signer]` M signer accounts
+    ApproveCheckedWithExpiry {
+        /// The amount of tokens the delegate is approved for.
+        amount: u64,
+        /// Expected number of base 10 digits to the right of the decimal place.
+        decimals: u8,
+        /// The last slot in which the delegate may use the approval.
+        expiry_slot: u64,
+    },
+    /// Creates a proposal for a multisignature account to execute a token
+    /// instruction.  Rather than requiring all M signers in the same
+    /// Transaction, the signers approve the proposal one at a time with
+    /// `ApproveMultisigProposal`, and it can be executed with
+    /// `ExecuteMultisigProposal` once M approvals are recorded.  The proposer
+    /// must be one of the multisignature's signers, and its approval is
+    /// recorded on creation.
+    ///
+    /// The proposed instruction may hold at most `MAX_PROPOSAL_ACCOUNTS`
+    /// accounts, listed without the multisignature's signer accounts, and at
+    /// most `MAX_PROPOSAL_DATA_LEN` bytes of data.
+    ///
+    /// The `CreateMultisigProposal` instruction MUST be included within the
+    /// same Transaction as the system program's `CreateAccount` instruction
+    /// that creates the proposal account being initialized.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The proposal account to initialize.
+    ///   1. `[]` The multisignature account.
+    ///   2. `[signer]` The proposer, one of the multisignature's signers.
+    ///   3. ..3+K `[]` The K accounts of the proposed instruction, in order.
+    CreateMultisigProposal {
+        /// The packed [TokenInstruction](enum.TokenInstruction.html) to execute.
+        instruction_data: Vec<u8>,
+    },
+    /// Records the approval of a multisignature proposal by one of the
+    /// multisignature's signers.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The proposal account.
+    ///   1. `[]` The multisignature account.
+    ///   2. `[signer]` The approving signer.
+    ApproveMultisigProposal,
+    /// Executes the instruction held by a multisignature proposal once at least
+    /// M of the multisignature's signers have approved it.  The authority of
+    /// the proposed instruction must be the multisignature itself, and the
+    /// approving signers are treated as its signers, so it is processed as if
+    /// all of them had signed it.  A proposal can only be executed once.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The proposal account.
+    ///   1. `[]` The multisignature account.
+    ///   2. ..2+K `[writable]` or `[]` The K accounts of the proposed
+    ///      instruction, in order, as writable as the proposed instruction
+    ///      requires.
+    ///   2+K. ..2+K+M `[]` M signer accounts that approved the proposal.
+    ExecuteMultisigProposal,
+    /// Sets the permanent delegate of a new mint.  The permanent delegate may
+    /// transfer or burn tokens from any account of the mint, regardless of the
+    /// account's owner or delegate, and can be changed or removed with
+    /// `SetAuthority` but never added after the mint is initialized.
+    ///
+    /// The mint account must be allocated with space for the extension, see
+    /// `ExtensionType::get_account_len`, and this instruction must precede the
+    /// `InitializeMint` instruction of the same Transaction.  It requires no
+    /// signers, so it MUST be included within the same Transaction as the
+    /// system program's `CreateAccount` instruction that creates the mint.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The mint to configure.
+    InitializePermanentDelegate {
+        /// The authority that may transfer or burn any of the mint's tokens.
+        delegate: Pubkey,
+    },
+    /// Configures a new mint so that its new accounts start frozen, and can
+    /// only be used once thawed by the mint's freeze authority.  The mint must
+    /// be initialized with a freeze authority.
+    ///
+    /// The mint account must be allocated with space for the extension, see
+    /// `ExtensionType::get_account_len`, and this instruction must precede the
+    /// `InitializeMint` instruction of the same Transaction.  It requires no
+    /// signers, so it MUST be included within the same Transaction as the
+    /// system program's `CreateAccount` instruction that creates the mint.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The mint to configure.
+    InitializeDefaultFrozen,
+    /// Sets whether new accounts of a mint start frozen.  Accounts that
+    /// already exist keep their state.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[signer]` The mint freeze authority.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[]` The mint's multisignature freeze authority.
+    ///   2. ..2+M `[signer]` M signer accounts.
+    SetDefaultFrozen {
+        /// Whether new accounts of the mint start frozen.
+        default_frozen: bool,
+    },
+    /// Makes a new mint non-transferable.  Tokens of the mint can be minted,
+    /// burned and have their accounts closed, but never transferred.  Accounts
+    /// of the mint must be allocated with space for the
+    /// `NonTransferableAccount` extension.
+    ///
+    /// The mint account must be allocated with space for the extension, see
+    /// `ExtensionType::get_account_len`, and this instruction must precede the
+    /// `InitializeMint` instruction of the same Transaction.  It requires no
+    /// signers, so it MUST be included within the same Transaction as the
+    /// system program's `CreateAccount` instruction that creates the mint.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The mint to configure.
+    InitializeNonTransferableMint,
+    /// Migrates a token account to a new, larger account with space for the
+    /// given extensions, carrying over its state and extensions, and closes
+    /// the old account by transferring all its SOL to the destination
+    /// account.  Native accounts are not supported.
+    ///
+    /// Accounts cannot be resized, so the migrated account lives under a new
+    /// address and the old address can no longer be used.  Anything that
+    /// refers to the account by its address, such as wallets, indexers, or
+    /// other programs' state, must be updated to the new one, and transfers
+    /// sent to the old address fail.  For that reason, associated token
+    /// accounts and accounts with the `ImmutableOwner` extension, which are
+    /// found by their address, cannot be moved.  Instead, passing the token
+    /// account as the new account migrates it in place, which succeeds if it
+    /// was allocated with space for the given extensions, as associated
+    /// token accounts are, and leaves the destination account untouched.
+    ///
+    /// Moving an account closes it, so it fails if the account's close
+    /// authority is not its owner, and if the account's CPI guard is enabled
+    /// this instruction must not be invoked by another program, and the
+    /// instructions sysvar must be appended to the accounts.
+    ///
+    /// The new account must be allocated with at least
+    /// `ExtensionType::get_account_len` bytes for the extensions it will hold,
+    /// and the `MigrateAccount` instruction MUST be included within the same
+    /// Transaction as the system program's `CreateAccount` instruction that
+    /// creates it.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single owner
+    ///   0. `[writable]` The token account to migrate.
+    ///   1. `[writable]` The new token account.
+    ///   2. `[writable]` The destination account.
+    ///   3. `[signer]` The account's owner.
+    ///
+    ///   * Multisignature owner
+    ///   0. `[writable]` The token account to migrate.
+    ///   1. `[writable]` The new token account.
+    ///   2. `[writable]` The destination account.
+    ///   3. `[]` The account's multisignature owner.
+    ///   4. ..4+M `[signer]` M signer accounts.
+    MigrateAccount {
+        /// The extensions the new account must have space for, in addition
+        /// to those already held by the token account.
+        extension_types: Vec<ExtensionType>,
+    },
+    /// Configures a new mint to charge a fee on every transfer.  The fee is
+    /// withheld in the destination account, and can be harvested to the mint
+    /// by anyone or withdrawn by the withdraw withheld authority.  Accounts
+    /// of the mint must be allocated with space for the `TransferFeeAmount`
+    /// extension, and the mint's tokens can only be transferred with
+    /// instructions that include the mint.
+    ///
+    /// The mint account must be allocated with space for the extension, see
+    /// `ExtensionType::get_account_len`, and this instruction must precede the
+    /// `InitializeMint` instruction of the same Transaction.  It requires no
+    /// signers, so it MUST be included within the same Transaction as the
+    /// system program's `CreateAccount` instruction that creates the mint.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The mint to configure.
+    InitializeTransferFeeConfig {
+        /// The authority that may withdraw withheld fees.
+        withdraw_withheld_authority: COption<Pubkey>,
+        /// The fee charged on transfers, in basis points of the transferred
+        /// amount.
+        transfer_fee_basis_points: u16,
+        /// The maximum fee charged on a single transfer.
+        maximum_fee: u64,
+    },
+    /// Transfers tokens from one account to another either directly or via a
+    /// delegate, withholding the mint's transfer fee in the destination
+    /// account.  The destination receives the amount less the fee.
+    ///
+    /// This instruction differs from TransferChecked in that the fee is
+    /// checked by the caller, and the transfer fails if the mint would charge
+    /// a different fee.
+    ///
+    /// If the destination account requires memos, the transfer must
+    /// immediately follow a memo instruction, and the instructions sysvar
+    /// must be appended to the accounts.
+    ///
+    /// If the source account's CPI guard is enabled, transfers signed by its
+    /// owner must not be invoked by another program, and the instructions
+    /// sysvar must be appended to the accounts.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single owner/delegate
+    ///   0. `[writable]` The source account.
+    ///   1. `[]` The token mint.
+    ///   2. `[writable]` The destination account.
+    ///   3. `[signer]` The source account's owner/delegate.
+    ///
+    ///   * Multisignature owner/delegate
+    ///   0. `[writable]` The source account.
+    ///   1. `[]` The token mint.
+    ///   2. `[writable]` The destination account.
+    ///   3. `[]` The source account's multisignature owner/delegate.
+    ///   4. ..4+M `[signer]` M signer accounts.
+    TransferCheckedWithFee {
+        /// The amount of tokens to transfer, including the fee.
+        amount: u64,
+        /// Expected number of base 10 digits to the right of the decimal place.
+        decimals: u8,
+        /// Expected fee withheld from the transferred amount.
+        fee: u64,
+    },
+    /// Withdraws the fees harvested to a mint into a token account of the
+    /// mint.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single authority
+    ///   0. `[writable]` The token mint.
+    ///   1. `[writable]` The destination account.
+    ///   2. `[signer]` The mint's withdraw withheld authority.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The token mint.
+    ///   1. `[writable]` The destination account.
+    ///   2. `[]` The mint's multisignature withdraw withheld authority.
+    ///   3. ..3+M `[signer]` M signer accounts.
+    WithdrawWithheldTokensFromMint,
+    /// Withdraws the fees withheld in token accounts of a mint into another
+    /// token account of the mint.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single authority
+    ///   0. `[]` The token mint.
+    ///   1. `[writable]` The destination account.
+    ///   2. `[signer]` The mint's withdraw withheld authority.
+    ///   3. ..3+N `[writable]` The N source accounts.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[]` The token mint.
+    ///   1. `[writable]` The destination account.
+    ///   2. `[]` The mint's multisignature withdraw withheld authority.
+    ///   3. ..3+N `[writable]` The N source accounts.
+    ///   3+N. ..3+N+M `[signer]` M signer accounts.
+    WithdrawWithheldTokensFromAccounts {
+        /// The number of source accounts (N).
+        num_token_accounts: u8,
+    },
+    /// Moves the fees withheld in token accounts of a mint to the mint, from
+    /// which the withdraw withheld authority can withdraw them.  Requires no
+    /// signers, so that fees can be collected from accounts whose owners
+    /// would otherwise hold them up.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The token mint.
+    ///   1. ..1+N `[writable]` The N source accounts.
+    HarvestWithheldTokensToMint,
+    /// Requires incoming transfers to a token account to immediately follow
+    /// a memo instruction.  The account must have space for the
+    /// `MemoTransfer` extension, which existing accounts can gain with
+    /// `MigrateAccount`.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single owner
+    ///   0. `[writable]` The account to update.
+    ///   1. `[signer]` The account's owner.
+    ///
+    ///   * Multisignature owner
+    ///   0. `[writable]` The account to update.
+    ///   1. `[]` The account's multisignature owner.
+    ///   2. ..2+M `[signer]` M signer accounts.
+    EnableRequiredTransferMemos,
+    /// Stops requiring incoming transfers to a token account to follow a
+    /// memo instruction.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single owner
+    ///   0. `[writable]` The account to update.
+    ///   1. `[signer]` The account's owner.
+    ///
+    ///   * Multisignature owner
+    ///   0. `[writable]` The account to update.
+    ///   1. `[]` The account's multisignature owner.
+    ///   2. ..2+M `[signer]` M signer accounts.
+    DisableRequiredTransferMemos,
+    /// Makes the owner of a new token account immutable, so that
+    /// `SetAuthority` can never reassign it.
+    ///
+    /// The account must be allocated with space for the `ImmutableOwner`
+    /// extension, see `ExtensionType::get_account_len`, and this instruction
+    /// must precede the `InitializeAccount` instruction of the same
+    /// Transaction.  It requires no signers, so it MUST be included within
+    /// the same Transaction as the system program's `CreateAccount`
+    /// instruction that creates the account.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The account to configure.
+    InitializeImmutableOwner,
+    /// Sets the close authority of a new mint, which may close the mint with
+    /// `CloseAccount` once its supply is zero.  The close authority can be
+    /// changed or removed with `SetAuthority` but never added after the mint
+    /// is initialized.
+    ///
+    /// The mint account must be allocated with space for the extension, see
+    /// `ExtensionType::get_account_len`, and this instruction must precede the
+    /// `InitializeMint` instruction of the same Transaction.  It requires no
+    /// signers, so it MUST be included within the same Transaction as the
+    /// system program's `CreateAccount` instruction that creates the mint.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The mint to configure.
+    InitializeMintCloseAuthority {
+        /// The authority that may close the mint.
+        close_authority: Pubkey,
+    },
+    /// Sets the human-readable metadata of a mint.  The metadata can be
+    /// changed with `UpdateTokenMetadata` by its update authority, which can
+    /// be changed or removed with `SetAuthority`.
+    ///
+    /// The name, symbol and URI may be at most `MAX_METADATA_NAME_LEN`,
+    /// `MAX_METADATA_SYMBOL_LEN` and `MAX_METADATA_URI_LEN` bytes long.
+    ///
+    /// The mint account must be allocated with space for the extension, see
+    /// `ExtensionType::get_account_len`.  For a new mint, this instruction
+    /// must precede the `InitializeMint` instruction of the same Transaction.
+    /// It then requires no signers, so it MUST be included within the same
+    /// Transaction as the system program's `CreateAccount` instruction that
+    /// creates the mint.  A mint that is already initialized, and was
+    /// allocated with the space but holds no metadata yet, may gain it with
+    /// the approval of its mint authority.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * New mint
+    ///   0. `[writable]` The mint to configure.
+    ///
+    ///   * Initialized mint, single mint authority
+    ///   0. `[writable]` The mint to configure.
+    ///   1. `[signer]` The mint's minting authority.
+    ///
+    ///   * Initialized mint, multisignature mint authority
+    ///   0. `[writable]` The mint to configure.
+    ///   1. `[]` The mint's multisignature minting authority.
+    ///   2. ..2+M `[signer]` M signer accounts.
+    InitializeTokenMetadata {
+        /// The authority that may update the metadata.
+        update_authority: COption<Pubkey>,
+        /// The name of the token.
+        name: String,
+        /// The symbol of the token.
+        symbol: String,
+        /// The URI of the token's off-chain metadata.
+        uri: String,
+    },
+    /// Updates the human-readable metadata of a mint.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single authority
+    ///   0. `[writable]` The mint to update.
+    ///   1. `[signer]` The metadata's update authority.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The mint to update.
+    ///   1. `[]` The metadata's multisignature update authority.
+    ///   2. ..2+M `[signer]` M signer accounts.
+    UpdateTokenMetadata {
+        /// The new name of the token.
+        name: String,
+        /// The new symbol of the token.
+        symbol: String,
+        /// The new URI of the token's off-chain metadata.
+        uri: String,
+    },
+    /// Configures a new mint to accrue interest on the UI amounts of its
+    /// tokens, compounded continuously from the current time.  The raw
+    /// amounts held by accounts never change.  The rate can be updated with
+    /// `UpdateInterestRate` by its rate authority, which can be changed or
+    /// removed with `SetAuthority`.
+    ///
+    /// The mint account must be allocated with space for the extension, see
+    /// `ExtensionType::get_account_len`, and this instruction must precede the
+    /// `InitializeMint` instruction of the same Transaction.  It requires no
+    /// signers, so it MUST be included within the same Transaction as the
+    /// system program's `CreateAccount` instruction that creates the mint.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The mint to configure.
+    InitializeInterestBearingMint {
+        /// The authority that may update the rate.
+        rate_authority: COption<Pubkey>,
+        /// The interest rate, in basis points per year.
+        rate: i16,
+    },
+    /// Updates the interest rate of a mint.  Interest accrued at the previous
+    /// rate is kept.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single authority
+    ///   0. `[writable]` The mint to update.
+    ///   1. `[signer]` The mint's rate authority.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The mint to update.
+    ///   1. `[]` The mint's multisignature rate authority.
+    ///   2. ..2+M `[signer]` M signer accounts.
+    UpdateInterestRate {
+        /// The new interest rate, in basis points per year.
+        rate: i16,
+    },
+    /// Blocks approvals, authority changes, closing, migration to a new
+    /// address and owner-signed transfers of a token account when they are
+    /// invoked by another program, so that a program cannot misuse the
+    /// owner's signature.  Burns, and transfers signed by a delegate, are not
+    /// guarded.  The account must have space for the `CpiGuard` extension,
+    /// which existing accounts can gain with `MigrateAccount`.
+    ///
+    /// This runtime does not expose the invocation stack, so the check reads
+    /// the instructions sysvar: a guarded action only succeeds if the
+    /// top-level instruction being executed belongs to the token program,
+    /// which never invokes itself.  Guarded actions must therefore append the
+    /// instructions sysvar to their accounts, as done by the
+    /// `*_with_instructions_sysvar` instruction builders.  Enabling the guard
+    /// breaks clients that build guarded actions without the sysvar, such as
+    /// the plain instruction builders, which fail with `CpiGuarded` until
+    /// they switch to these builders.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single owner
+    ///   0. `[writable]` The account to update.
+    ///   1. `[signer]` The account's owner.
+    ///
+    ///   * Multisignature owner
+    ///   0. `[writable]` The account to update.
+    ///   1. `[]` The account's multisignature owner.
+    ///   2. ..2+M `[signer]` M signer accounts.
+    EnableCpiGuard,
+    /// Stops blocking privileged actions on a token account invoked by
+    /// another program.  Disabling the guard is itself guarded: it must not
+    /// be invoked by another program, and the instructions sysvar must be
+    /// appended to the accounts.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single owner
+    ///   0. `[writable]` The account to update.
+    ///   1. `[signer]` The account's owner.
+    ///
+    ///   * Multisignature owner
+    ///   0. `[writable]` The account to update.
+    ///   1. `[]` The account's multisignature owner.
+    ///   2. ..2+M `[signer]` M signer accounts.
+    DisableCpiGuard,
 }
 impl TokenInstruction {
     /// Unpacks a byte buffer into a [TokenInstruction](enum.TokenInstruction.html).
@@ -371,14 +1028,22 @@ impl TokenInstruction {
 
         let (&tag, rest) = input.split_first().ok_or(InvalidInstruction)?;
         Ok(match tag {
-            0 => {
+            0 | 19 => {
                 let (&decimals, rest) = rest.split_first().ok_or(InvalidInstruction)?;
                 let (mint_authority, rest) = Self::unpack_pubkey(rest)?;
                 let (freeze_authority, _rest) = Self::unpack_pubkey_option(rest)?;
-                Self::InitializeMint {
-                    mint_authority,
-                    freeze_authority,
-                    decimals,
+                match tag {
+                    0 => Self::InitializeMint {
+                        mint_authority,
+                        freeze_authority,
+                        decimals,
+                    },
+                    19 => Self::InitializeMint2 {
+                        mint_authority,
+                        freeze_authority,
+                        decimals,
+                    },
+                    _ => unreachable!(),
                 }
             }
             1 => Self::InitializeAccount,
@@ -464,20 +1129,196 @@ impl TokenInstruction {
                 let (owner, _rest) = Self::unpack_pubkey(rest)?;
                 Self::InitializeAccount2 { owner }
             }
-
-            _ => return Err(TokenError::InvalidInstruction.into()),
-        })
-    }
-
-    /// Packs a [TokenInstruction](enum.TokenInstruction.html) into a byte buffer.
-    pub fn pack(&self) -> Vec<u8> {
-        let mut buf = Vec::with_capacity(size_of::<Self>());
-        match self {
-            &Self::InitializeMint {
-                ref mint_authority,
-                ref freeze_authority,
-                decimals,
-            } => {
+            17 => Self::SyncNative,
+            18 => {
+                let (owner, _rest) = Self::unpack_pubkey(rest)?;
+                Self::InitializeAccount3 { owner }
+            }
+            20 => {
+                let (&decimals, rest) = rest.split_first().ok_or(InvalidInstruction)?;
+                let (&count, rest) = rest.split_first().ok_or(InvalidInstruction)?;
+                if count == 0 || rest.len() < count as usize * 8 {
+                    return Err(TokenError::InvalidInstruction.into());
+                }
+                let amounts = rest
+                    .chunks_exact(8)
+                    .take(count as usize)
+                    .map(|slice| slice.try_into().map(u64::from_le_bytes))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| InvalidInstruction)?;
+
+                Self::TransferCheckedBatch { amounts, decimals }
+            }
+            21 => {
+                let max_supply = rest
+                    .get(..8)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u64::from_le_bytes)
+                    .ok_or(InvalidInstruction)?;
+                Self::InitializeMaxSupply { max_supply }
+            }
+            22 => {
+                let amount = rest
+                    .get(..8)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u64::from_le_bytes)
+                    .ok_or(InvalidInstruction)?;
+                let (&decimals, rest) = rest[8..].split_first().ok_or(InvalidInstruction)?;
+                let expiry_slot = rest
+                    .get(..8)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u64::from_le_bytes)
+                    .ok_or(InvalidInstruction)?;
+
+                Self::ApproveCheckedWithExpiry {
+                    amount,
+                    decimals,
+                    expiry_slot,
+                }
+            }
+            23 => Self::CreateMultisigProposal {
+                instruction_data: rest.to_vec(),
+            },
+            24 => Self::ApproveMultisigProposal,
+            25 => Self::ExecuteMultisigProposal,
+            26 => {
+                let (delegate, _rest) = Self::unpack_pubkey(rest)?;
+                Self::InitializePermanentDelegate { delegate }
+            }
+            27 => Self::InitializeDefaultFrozen,
+            28 => {
+                let default_frozen = match rest.get(0) {
+                    Some(0) => false,
+                    Some(1) => true,
+                    _ => return Err(InvalidInstruction.into()),
+                };
+                Self::SetDefaultFrozen { default_frozen }
+            }
+            29 => Self::InitializeNonTransferableMint,
+            30 => {
+                let (&count, rest) = rest.split_first().ok_or(InvalidInstruction)?;
+                let extension_types = rest
+                    .chunks(2)
+                    .take(count as usize)
+                    .map(|chunk| {
+                        chunk
+                            .try_into()
+                            .ok()
+                            .map(u16::from_le_bytes)
+                            .and_then(|value| ExtensionType::try_from_primitive(value).ok())
+                            .ok_or(InvalidInstruction)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                if extension_types.len() != count as usize {
+                    return Err(InvalidInstruction.into());
+                }
+                Self::MigrateAccount { extension_types }
+            }
+            31 => {
+                let (withdraw_withheld_authority, rest) = Self::unpack_pubkey_option(rest)?;
+                let transfer_fee_basis_points = rest
+                    .get(..2)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u16::from_le_bytes)
+                    .ok_or(InvalidInstruction)?;
+                let maximum_fee = rest
+                    .get(2..10)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u64::from_le_bytes)
+                    .ok_or(InvalidInstruction)?;
+
+                Self::InitializeTransferFeeConfig {
+                    withdraw_withheld_authority,
+                    transfer_fee_basis_points,
+                    maximum_fee,
+                }
+            }
+            32 => {
+                let amount = rest
+                    .get(..8)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u64::from_le_bytes)
+                    .ok_or(InvalidInstruction)?;
+                let (&decimals, rest) = rest[8..].split_first().ok_or(InvalidInstruction)?;
+                let fee = rest
+                    .get(..8)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u64::from_le_bytes)
+                    .ok_or(InvalidInstruction)?;
+
+                Self::TransferCheckedWithFee {
+                    amount,
+                    decimals,
+                    fee,
+                }
+            }
+            33 => Self::WithdrawWithheldTokensFromMint,
+            34 => {
+                let &num_token_accounts = rest.get(0).ok_or(InvalidInstruction)?;
+                Self::WithdrawWithheldTokensFromAccounts { num_token_accounts }
+            }
+            35 => Self::HarvestWithheldTokensToMint,
+            36 => Self::EnableRequiredTransferMemos,
+            37 => Self::DisableRequiredTransferMemos,
+            38 => Self::InitializeImmutableOwner,
+            39 => {
+                let (close_authority, _rest) = Self::unpack_pubkey(rest)?;
+                Self::InitializeMintCloseAuthority { close_authority }
+            }
+            40 => {
+                let (update_authority, rest) = Self::unpack_pubkey_option(rest)?;
+                let (name, rest) = Self::unpack_string(rest)?;
+                let (symbol, rest) = Self::unpack_string(rest)?;
+                let (uri, _rest) = Self::unpack_string(rest)?;
+                Self::InitializeTokenMetadata {
+                    update_authority,
+                    name,
+                    symbol,
+                    uri,
+                }
+            }
+            41 => {
+                let (name, rest) = Self::unpack_string(rest)?;
+                let (symbol, rest) = Self::unpack_string(rest)?;
+                let (uri, _rest) = Self::unpack_string(rest)?;
+                Self::UpdateTokenMetadata { name, symbol, uri }
+            }
+            42 => {
+                let (rate_authority, rest) = Self::unpack_pubkey_option(rest)?;
+                let rate = rest
+                    .get(..2)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(i16::from_le_bytes)
+                    .ok_or(InvalidInstruction)?;
+                Self::InitializeInterestBearingMint {
+                    rate_authority,
+                    rate,
+                }
+            }
+            43 => {
+                let rate = rest
+                    .get(..2)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(i16::from_le_bytes)
+                    .ok_or(InvalidInstruction)?;
+                Self::UpdateInterestRate { rate }
+            }
+            44 => Self::EnableCpiGuard,
+            45 => Self::DisableCpiGuard,
+
+            _ => return Err(TokenError::InvalidInstruction.into()),
+        })
+    }
+
+    /// Packs a [TokenInstruction](enum.TokenInstruction.html) into a byte buffer.
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(size_of::<Self>());
+        match self {
+            &Self::InitializeMint {
+                ref mint_authority,
+                ref freeze_authority,
+                decimals,
+            } => {
                 buf.push(0);
                 buf.push(decimals);
                 buf.extend_from_slice(mint_authority.as_ref());
@@ -540,6 +1381,144 @@ impl TokenInstruction {
                 buf.push(16);
                 buf.extend_from_slice(owner.as_ref());
             }
+            Self::SyncNative => buf.push(17),
+            &Self::InitializeAccount3 { owner } => {
+                buf.push(18);
+                buf.extend_from_slice(owner.as_ref());
+            }
+            &Self::InitializeMint2 {
+                ref mint_authority,
+                ref freeze_authority,
+                decimals,
+            } => {
+                buf.push(19);
+                buf.push(decimals);
+                buf.extend_from_slice(mint_authority.as_ref());
+                Self::pack_pubkey_option(freeze_authority, &mut buf);
+            }
+            &Self::TransferCheckedBatch {
+                ref amounts,
+                decimals,
+            } => {
+                buf.push(20);
+                buf.push(decimals);
+                buf.push(amounts.len() as u8);
+                for amount in amounts.iter() {
+                    buf.extend_from_slice(&amount.to_le_bytes());
+                }
+            }
+            &Self::InitializeMaxSupply { max_supply } => {
+                buf.push(21);
+                buf.extend_from_slice(&max_supply.to_le_bytes());
+            }
+            &Self::ApproveCheckedWithExpiry {
+                amount,
+                decimals,
+                expiry_slot,
+            } => {
+                buf.push(22);
+                buf.extend_from_slice(&amount.to_le_bytes());
+                buf.push(decimals);
+                buf.extend_from_slice(&expiry_slot.to_le_bytes());
+            }
+            Self::CreateMultisigProposal {
+                ref instruction_data,
+            } => {
+                buf.push(23);
+                buf.extend_from_slice(instruction_data);
+            }
+            Self::ApproveMultisigProposal => buf.push(24),
+            Self::ExecuteMultisigProposal => buf.push(25),
+            Self::InitializePermanentDelegate { ref delegate } => {
+                buf.push(26);
+                buf.extend_from_slice(delegate.as_ref());
+            }
+            Self::InitializeDefaultFrozen => buf.push(27),
+            &Self::SetDefaultFrozen { default_frozen } => {
+                buf.push(28);
+                buf.push(default_frozen as u8);
+            }
+            Self::InitializeNonTransferableMint => buf.push(29),
+            Self::MigrateAccount {
+                ref extension_types,
+            } => {
+                buf.push(30);
+                buf.push(extension_types.len() as u8);
+                for extension_type in extension_types.iter() {
+                    buf.extend_from_slice(&(*extension_type as u16).to_le_bytes());
+                }
+            }
+            &Self::InitializeTransferFeeConfig {
+                ref withdraw_withheld_authority,
+                transfer_fee_basis_points,
+                maximum_fee,
+            } => {
+                buf.push(31);
+                Self::pack_pubkey_option(withdraw_withheld_authority, &mut buf);
+                buf.extend_from_slice(&transfer_fee_basis_points.to_le_bytes());
+                buf.extend_from_slice(&maximum_fee.to_le_bytes());
+            }
+            &Self::TransferCheckedWithFee {
+                amount,
+                decimals,
+                fee,
+            } => {
+                buf.push(32);
+                buf.extend_from_slice(&amount.to_le_bytes());
+                buf.push(decimals);
+                buf.extend_from_slice(&fee.to_le_bytes());
+            }
+            Self::WithdrawWithheldTokensFromMint => buf.push(33),
+            &Self::WithdrawWithheldTokensFromAccounts { num_token_accounts } => {
+                buf.push(34);
+                buf.push(num_token_accounts);
+            }
+            Self::HarvestWithheldTokensToMint => buf.push(35),
+            Self::EnableRequiredTransferMemos => buf.push(36),
+            Self::DisableRequiredTransferMemos => buf.push(37),
+            Self::InitializeImmutableOwner => buf.push(38),
+            Self::InitializeMintCloseAuthority {
+                ref close_authority,
+            } => {
+                buf.push(39);
+                buf.extend_from_slice(close_authority.as_ref());
+            }
+            Self::InitializeTokenMetadata {
+                ref update_authority,
+                ref name,
+                ref symbol,
+                ref uri,
+            } => {
+                buf.push(40);
+                Self::pack_pubkey_option(update_authority, &mut buf);
+                Self::pack_string(name, &mut buf);
+                Self::pack_string(symbol, &mut buf);
+                Self::pack_string(uri, &mut buf);
+            }
+            Self::UpdateTokenMetadata {
+                ref name,
+                ref symbol,
+                ref uri,
+            } => {
+                buf.push(41);
+                Self::pack_string(name, &mut buf);
+                Self::pack_string(symbol, &mut buf);
+                Self::pack_string(uri, &mut buf);
+            }
+            &Self::InitializeInterestBearingMint {
+                ref rate_authority,
+                rate,
+            } => {
+                buf.push(42);
+                Self::pack_pubkey_option(rate_authority, &mut buf);
+                buf.extend_from_slice(&rate.to_le_bytes());
+            }
+            &Self::UpdateInterestRate { rate } => {
+                buf.push(43);
+                buf.extend_from_slice(&rate.to_le_bytes());
+            }
+            Self::EnableCpiGuard => buf.push(44),
+            Self::DisableCpiGuard => buf.push(45),
         };
         buf
     }
@@ -566,6 +1545,22 @@ impl TokenInstruction {
         }
     }
 
+    fn unpack_string(input: &[u8]) -> Result<(String, &[u8]), ProgramError> {
+        let (&len, rest) = input.split_first().ok_or(TokenError::InvalidInstruction)?;
+        if rest.len() < len as usize {
+            return Err(TokenError::InvalidInstruction.into());
+        }
+        let (bytes, rest) = rest.split_at(len as usize);
+        let value =
+            String::from_utf8(bytes.to_vec()).map_err(|_| TokenError::InvalidInstruction)?;
+        Ok((value, rest))
+    }
+
+    fn pack_string(value: &str, buf: &mut Vec<u8>) {
+        buf.push(value.len() as u8);
+        buf.extend_from_slice(value.as_bytes());
+    }
+
     fn pack_pubkey_option(value: &COption<Pubkey>, buf: &mut Vec<u8>) {
         match *value {
             COption::Some(ref key) => {
@@ -589,6 +1584,17 @@ pub enum AuthorityType {
     AccountOwner,
     /// Authority to close a token account
     CloseAccount,
+    /// Authority to transfer or burn tokens from any account of a Mint
+    PermanentDelegate,
+    /// Authority to withdraw the transfer fees withheld in a Mint and its
+    /// accounts
+    WithdrawWithheldTokens,
+    /// Authority to close a Mint
+    CloseMint,
+    /// Authority to update the metadata of a Mint
+    MetadataUpdate,
+    /// Authority to update the interest rate of a Mint
+    InterestRate,
 }
 
 impl AuthorityType {
@@ -598,6 +1604,11 @@ impl AuthorityType {
             AuthorityType::FreezeAccount => 1,
             AuthorityType::AccountOwner => 2,
             AuthorityType::CloseAccount => 3,
+            AuthorityType::PermanentDelegate => 4,
+            AuthorityType::WithdrawWithheldTokens => 5,
+            AuthorityType::CloseMint => 6,
+            AuthorityType::MetadataUpdate => 7,
+            AuthorityType::InterestRate => 8,
         }
     }
 
@@ -607,6 +1618,11 @@ impl AuthorityType {
             1 => Ok(AuthorityType::FreezeAccount),
             2 => Ok(AuthorityType::AccountOwner),
             3 => Ok(AuthorityType::CloseAccount),
+            4 => Ok(AuthorityType::PermanentDelegate),
+            5 => Ok(AuthorityType::WithdrawWithheldTokens),
+            6 => Ok(AuthorityType::CloseMint),
+            7 => Ok(AuthorityType::MetadataUpdate),
+            8 => Ok(AuthorityType::InterestRate),
             _ => Err(TokenError::InvalidInstruction.into()),
         }
     }
@@ -640,21 +1656,23 @@ pub fn initialize_mint(
     })
 }
 
-/// Creates a `InitializeAccount` instruction.
-pub fn initialize_account(
+/// Creates a `InitializeMint2` instruction.
+pub fn initialize_mint2(
     token_program_id: &Pubkey,
-    account_pubkey: &Pubkey,
     mint_pubkey: &Pubkey,
-    owner_pubkey: &Pubkey,
+    mint_authority_pubkey: &Pubkey,
+    freeze_authority_pubkey: Option<&Pubkey>,
+    decimals: u8,
 ) -> Result<Instruction, ProgramError> {
-    let data = TokenInstruction::InitializeAccount.pack();
+    let freeze_authority = freeze_authority_pubkey.cloned().into();
+    let data = TokenInstruction::InitializeMint2 {
+        mint_authority: *mint_authority_pubkey,
+        freeze_authority,
+        decimals,
+    }
+    .pack();
 
-    let accounts = vec![
-        AccountMeta::new(*account_pubkey, false),
-        AccountMeta::new_readonly(*mint_pubkey, false),
-        AccountMeta::new_readonly(*owner_pubkey, false),
-        AccountMeta::new_readonly(sysvar::rent::id(), false),
-    ];
+    let accounts = vec![AccountMeta::new(*mint_pubkey, false)];
 
     Ok(Instruction {
         program_id: *token_program_id,
@@ -663,23 +1681,15 @@ pub fn initialize_account(
     })
 }
 
-/// Creates a `InitializeAccount2` instruction.
-pub fn initialize_account2(
+/// Creates an `InitializeMaxSupply` instruction.
+pub fn initialize_max_supply(
     token_program_id: &Pubkey,
-    account_pubkey: &Pubkey,
     mint_pubkey: &Pubkey,
-    owner_pubkey: &Pubkey,
+    max_supply: u64,
 ) -> Result<Instruction, ProgramError> {
-    let data = TokenInstruction::InitializeAccount2 {
-        owner: *owner_pubkey,
-    }
-    .pack();
+    let data = TokenInstruction::InitializeMaxSupply { max_supply }.pack();
 
-    let accounts = vec![
-        AccountMeta::new(*account_pubkey, false),
-        AccountMeta::new_readonly(*mint_pubkey, false),
-        AccountMeta::new_readonly(sysvar::rent::id(), false),
-    ];
+    let accounts = vec![AccountMeta::new(*mint_pubkey, false)];
 
     Ok(Instruction {
         program_id: *token_program_id,
@@ -688,27 +1698,18 @@ pub fn initialize_account2(
     })
 }
 
-/// Creates a `InitializeMultisig` instruction.
-pub fn initialize_multisig(
+/// Creates a `InitializePermanentDelegate` instruction.
+pub fn initialize_permanent_delegate(
     token_program_id: &Pubkey,
-    multisig_pubkey: &Pubkey,
-    signer_pubkeys: &[&Pubkey],
-    m: u8,
+    mint_pubkey: &Pubkey,
+    delegate_pubkey: &Pubkey,
 ) -> Result<Instruction, ProgramError> {
-    if !is_valid_signer_index(m as usize)
-        || !is_valid_signer_index(signer_pubkeys.len())
-        || m as usize > signer_pubkeys.len()
-    {
-        return Err(ProgramError::MissingRequiredSignature);
+    let data = TokenInstruction::InitializePermanentDelegate {
+        delegate: *delegate_pubkey,
     }
-    let data = TokenInstruction::InitializeMultisig { m }.pack();
+    .pack();
 
-    let mut accounts = Vec::with_capacity(1 + 1 + signer_pubkeys.len());
-    accounts.push(AccountMeta::new(*multisig_pubkey, false));
-    accounts.push(AccountMeta::new_readonly(sysvar::rent::id(), false));
-    for signer_pubkey in signer_pubkeys.iter() {
-        accounts.push(AccountMeta::new_readonly(**signer_pubkey, false));
-    }
+    let accounts = vec![AccountMeta::new(*mint_pubkey, false)];
 
     Ok(Instruction {
         program_id: *token_program_id,
@@ -717,27 +1718,18 @@ pub fn initialize_multisig(
     })
 }
 
-/// Creates a `Transfer` instruction.
-pub fn transfer(
+/// Creates a `InitializeMintCloseAuthority` instruction.
+pub fn initialize_mint_close_authority(
     token_program_id: &Pubkey,
-    source_pubkey: &Pubkey,
-    destination_pubkey: &Pubkey,
-    authority_pubkey: &Pubkey,
-    signer_pubkeys: &[&Pubkey],
-    amount: u64,
+    mint_pubkey: &Pubkey,
+    close_authority_pubkey: &Pubkey,
 ) -> Result<Instruction, ProgramError> {
-    let data = TokenInstruction::Transfer { amount }.pack();
-
-    let mut accounts = Vec::with_capacity(3 + signer_pubkeys.len());
-    accounts.push(AccountMeta::new(*source_pubkey, false));
-    accounts.push(AccountMeta::new(*destination_pubkey, false));
-    accounts.push(AccountMeta::new_readonly(
-        *authority_pubkey,
-        signer_pubkeys.is_empty(),
-    ));
-    for signer_pubkey in signer_pubkeys.iter() {
-        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    let data = TokenInstruction::InitializeMintCloseAuthority {
+        close_authority: *close_authority_pubkey,
     }
+    .pack();
+
+    let accounts = vec![AccountMeta::new(*mint_pubkey, false)];
 
     Ok(Instruction {
         program_id: *token_program_id,
@@ -746,27 +1738,28 @@ pub fn transfer(
     })
 }
 
-/// Creates an `Approve` instruction.
-pub fn approve(
+/// Creates a `InitializeTokenMetadata` instruction for a new mint.
+pub fn initialize_token_metadata(
     token_program_id: &Pubkey,
-    source_pubkey: &Pubkey,
-    delegate_pubkey: &Pubkey,
-    owner_pubkey: &Pubkey,
-    signer_pubkeys: &[&Pubkey],
-    amount: u64,
+    mint_pubkey: &Pubkey,
+    update_authority_pubkey: Option<&Pubkey>,
+    name: String,
+    symbol: String,
+    uri: String,
 ) -> Result<Instruction, ProgramError> {
-    let data = TokenInstruction::Approve { amount }.pack();
-
-    let mut accounts = Vec::with_capacity(3 + signer_pubkeys.len());
-    accounts.push(AccountMeta::new(*source_pubkey, false));
-    accounts.push(AccountMeta::new_readonly(*delegate_pubkey, false));
-    accounts.push(AccountMeta::new_readonly(
-        *owner_pubkey,
-        signer_pubkeys.is_empty(),
-    ));
-    for signer_pubkey in signer_pubkeys.iter() {
-        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    if !TokenMetadata::has_valid_lengths(&name, &symbol, &uri) {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let update_authority = update_authority_pubkey.cloned().into();
+    let data = TokenInstruction::InitializeTokenMetadata {
+        update_authority,
+        name,
+        symbol,
+        uri,
     }
+    .pack();
+
+    let accounts = vec![AccountMeta::new(*mint_pubkey, false)];
 
     Ok(Instruction {
         program_id: *token_program_id,
@@ -775,24 +1768,54 @@ pub fn approve(
     })
 }
 
-/// Creates a `Revoke` instruction.
-pub fn revoke(
+/// Creates a `InitializeTokenMetadata` instruction for a mint that is already
+/// initialized, approved by its mint authority.
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_token_metadata_with_mint_authority(
     token_program_id: &Pubkey,
-    source_pubkey: &Pubkey,
-    owner_pubkey: &Pubkey,
+    mint_pubkey: &Pubkey,
+    mint_authority_pubkey: &Pubkey,
     signer_pubkeys: &[&Pubkey],
+    update_authority_pubkey: Option<&Pubkey>,
+    name: String,
+    symbol: String,
+    uri: String,
 ) -> Result<Instruction, ProgramError> {
-    let data = TokenInstruction::Revoke.pack();
-
-    let mut accounts = Vec::with_capacity(2 + signer_pubkeys.len());
-    accounts.push(AccountMeta::new(*source_pubkey, false));
-    accounts.push(AccountMeta::new_readonly(
-        *owner_pubkey,
+    let mut instruction = initialize_token_metadata(
+        token_program_id,
+        mint_pubkey,
+        update_authority_pubkey,
+        name,
+        symbol,
+        uri,
+    )?;
+    instruction.accounts.push(AccountMeta::new_readonly(
+        *mint_authority_pubkey,
         signer_pubkeys.is_empty(),
     ));
     for signer_pubkey in signer_pubkeys.iter() {
-        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+        instruction
+            .accounts
+            .push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(instruction)
+}
+
+/// Creates a `InitializeInterestBearingMint` instruction.
+pub fn initialize_interest_bearing_mint(
+    token_program_id: &Pubkey,
+    mint_pubkey: &Pubkey,
+    rate_authority_pubkey: Option<&Pubkey>,
+    rate: i16,
+) -> Result<Instruction, ProgramError> {
+    let rate_authority = rate_authority_pubkey.cloned().into();
+    let data = TokenInstruction::InitializeInterestBearingMint {
+        rate_authority,
+        rate,
     }
+    .pack();
+
+    let accounts = vec![AccountMeta::new(*mint_pubkey, false)];
 
     Ok(Instruction {
         program_id: *token_program_id,
@@ -801,31 +1824,14 @@ pub fn revoke(
     })
 }
 
-/// Creates a `SetAuthority` instruction.
-pub fn set_authority(
+/// Creates a `InitializeDefaultFrozen` instruction.
+pub fn initialize_default_frozen(
     token_program_id: &Pubkey,
-    owned_pubkey: &Pubkey,
-    new_authority_pubkey: Option<&Pubkey>,
-    authority_type: AuthorityType,
-    owner_pubkey: &Pubkey,
-    signer_pubkeys: &[&Pubkey],
+    mint_pubkey: &Pubkey,
 ) -> Result<Instruction, ProgramError> {
-    let new_authority = new_authority_pubkey.cloned().into();
-    let data = TokenInstruction::SetAuthority {
-        authority_type,
-        new_authority,
-    }
-    .pack();
+    let data = TokenInstruction::InitializeDefaultFrozen.pack();
 
-    let mut accounts = Vec::with_capacity(3 + signer_pubkeys.len());
-    accounts.push(AccountMeta::new(*owned_pubkey, false));
-    accounts.push(AccountMeta::new_readonly(
-        *owner_pubkey,
-        signer_pubkeys.is_empty(),
-    ));
-    for signer_pubkey in signer_pubkeys.iter() {
-        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
-    }
+    let accounts = vec![AccountMeta::new(*mint_pubkey, false)];
 
     Ok(Instruction {
         program_id: *token_program_id,
@@ -834,20 +1840,69 @@ pub fn set_authority(
     })
 }
 
-/// Creates a `MintTo` instruction.
-pub fn mint_to(
+/// Creates a `InitializeNonTransferableMint` instruction.
+pub fn initialize_non_transferable_mint(
+    token_program_id: &Pubkey,
+    mint_pubkey: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = TokenInstruction::InitializeNonTransferableMint.pack();
+
+    let accounts = vec![AccountMeta::new(*mint_pubkey, false)];
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `InitializeTransferFeeConfig` instruction.
+pub fn initialize_transfer_fee_config(
     token_program_id: &Pubkey,
     mint_pubkey: &Pubkey,
+    withdraw_withheld_authority_pubkey: Option<&Pubkey>,
+    transfer_fee_basis_points: u16,
+    maximum_fee: u64,
+) -> Result<Instruction, ProgramError> {
+    let withdraw_withheld_authority = withdraw_withheld_authority_pubkey.cloned().into();
+    let data = TokenInstruction::InitializeTransferFeeConfig {
+        withdraw_withheld_authority,
+        transfer_fee_basis_points,
+        maximum_fee,
+    }
+    .pack();
+
+    let accounts = vec![AccountMeta::new(*mint_pubkey, false)];
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `MigrateAccount` instruction.
+pub fn migrate_account(
+    token_program_id: &Pubkey,
     account_pubkey: &Pubkey,
+    new_account_pubkey: &Pubkey,
+    destination_pubkey: &Pubkey,
     owner_pubkey: &Pubkey,
     signer_pubkeys: &[&Pubkey],
-    amount: u64,
+    extension_types: &[ExtensionType],
 ) -> Result<Instruction, ProgramError> {
-    let data = TokenInstruction::MintTo { amount }.pack();
+    if extension_types.len() > u8::MAX as usize {
+        return Err(TokenError::InvalidInstruction.into());
+    }
+    let data = TokenInstruction::MigrateAccount {
+        extension_types: extension_types.to_vec(),
+    }
+    .pack();
 
-    let mut accounts = Vec::with_capacity(3 + signer_pubkeys.len());
-    accounts.push(AccountMeta::new(*mint_pubkey, false));
+    let mut accounts = Vec::with_capacity(4 + signer_pubkeys.len());
     accounts.push(AccountMeta::new(*account_pubkey, false));
+    accounts.push(AccountMeta::new(*new_account_pubkey, false));
+    accounts.push(AccountMeta::new(*destination_pubkey, false));
     accounts.push(AccountMeta::new_readonly(
         *owner_pubkey,
         signer_pubkeys.is_empty(),
@@ -863,22 +1918,20 @@ pub fn mint_to(
     })
 }
 
-/// Creates a `Burn` instruction.
-pub fn burn(
+/// Creates a `SetDefaultFrozen` instruction.
+pub fn set_default_frozen(
     token_program_id: &Pubkey,
-    account_pubkey: &Pubkey,
     mint_pubkey: &Pubkey,
-    authority_pubkey: &Pubkey,
+    freeze_authority_pubkey: &Pubkey,
     signer_pubkeys: &[&Pubkey],
-    amount: u64,
+    default_frozen: bool,
 ) -> Result<Instruction, ProgramError> {
-    let data = TokenInstruction::Burn { amount }.pack();
+    let data = TokenInstruction::SetDefaultFrozen { default_frozen }.pack();
 
-    let mut accounts = Vec::with_capacity(3 + signer_pubkeys.len());
-    accounts.push(AccountMeta::new(*account_pubkey, false));
+    let mut accounts = Vec::with_capacity(2 + signer_pubkeys.len());
     accounts.push(AccountMeta::new(*mint_pubkey, false));
     accounts.push(AccountMeta::new_readonly(
-        *authority_pubkey,
+        *freeze_authority_pubkey,
         signer_pubkeys.is_empty(),
     ));
     for signer_pubkey in signer_pubkeys.iter() {
@@ -892,26 +1945,62 @@ pub fn burn(
     })
 }
 
-/// Creates a `CloseAccount` instruction.
-pub fn close_account(
+/// Creates an `InitializeImmutableOwner` instruction.
+pub fn initialize_immutable_owner(
     token_program_id: &Pubkey,
     account_pubkey: &Pubkey,
-    destination_pubkey: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = TokenInstruction::InitializeImmutableOwner.pack();
+
+    let accounts = vec![AccountMeta::new(*account_pubkey, false)];
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `InitializeAccount` instruction.
+pub fn initialize_account(
+    token_program_id: &Pubkey,
+    account_pubkey: &Pubkey,
+    mint_pubkey: &Pubkey,
     owner_pubkey: &Pubkey,
-    signer_pubkeys: &[&Pubkey],
 ) -> Result<Instruction, ProgramError> {
-    let data = TokenInstruction::CloseAccount.pack();
+    let data = TokenInstruction::InitializeAccount.pack();
 
-    let mut accounts = Vec::with_capacity(3 + signer_pubkeys.len());
-    accounts.push(AccountMeta::new(*account_pubkey, false));
-    accounts.push(AccountMeta::new(*destination_pubkey, false));
-    accounts.push(AccountMeta::new_readonly(
-        *owner_pubkey,
-        signer_pubkeys.is_empty(),
-    ));
-    for signer_pubkey in signer_pubkeys.iter() {
-        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    let accounts = vec![
+        AccountMeta::new(*account_pubkey, false),
+        AccountMeta::new_readonly(*mint_pubkey, false),
+        AccountMeta::new_readonly(*owner_pubkey, false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `InitializeAccount2` instruction.
+pub fn initialize_account2(
+    token_program_id: &Pubkey,
+    account_pubkey: &Pubkey,
+    mint_pubkey: &Pubkey,
+    owner_pubkey: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = TokenInstruction::InitializeAccount2 {
+        owner: *owner_pubkey,
     }
+    .pack();
+
+    let accounts = vec![
+        AccountMeta::new(*account_pubkey, false),
+        AccountMeta::new_readonly(*mint_pubkey, false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
 
     Ok(Instruction {
         program_id: *token_program_id,
@@ -920,21 +2009,75 @@ pub fn close_account(
     })
 }
 
-/// Creates a `FreezeAccount` instruction.
-pub fn freeze_account(
+/// Creates a `InitializeAccount3` instruction.
+pub fn initialize_account3(
     token_program_id: &Pubkey,
     account_pubkey: &Pubkey,
     mint_pubkey: &Pubkey,
     owner_pubkey: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = TokenInstruction::InitializeAccount3 {
+        owner: *owner_pubkey,
+    }
+    .pack();
+
+    let accounts = vec![
+        AccountMeta::new(*account_pubkey, false),
+        AccountMeta::new_readonly(*mint_pubkey, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `InitializeMultisig` instruction.
+pub fn initialize_multisig(
+    token_program_id: &Pubkey,
+    multisig_pubkey: &Pubkey,
     signer_pubkeys: &[&Pubkey],
+    m: u8,
 ) -> Result<Instruction, ProgramError> {
-    let data = TokenInstruction::FreezeAccount.pack();
+    if !is_valid_signer_index(m as usize)
+        || !is_valid_signer_index(signer_pubkeys.len())
+        || m as usize > signer_pubkeys.len()
+    {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let data = TokenInstruction::InitializeMultisig { m }.pack();
+
+    let mut accounts = Vec::with_capacity(1 + 1 + signer_pubkeys.len());
+    accounts.push(AccountMeta::new(*multisig_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(sysvar::rent::id(), false));
+    for signer_pubkey in signer_pubkeys.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, false));
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `Transfer` instruction.
+pub fn transfer(
+    token_program_id: &Pubkey,
+    source_pubkey: &Pubkey,
+    destination_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = TokenInstruction::Transfer { amount }.pack();
 
     let mut accounts = Vec::with_capacity(3 + signer_pubkeys.len());
-    accounts.push(AccountMeta::new(*account_pubkey, false));
-    accounts.push(AccountMeta::new_readonly(*mint_pubkey, false));
+    accounts.push(AccountMeta::new(*source_pubkey, false));
+    accounts.push(AccountMeta::new(*destination_pubkey, false));
     accounts.push(AccountMeta::new_readonly(
-        *owner_pubkey,
+        *authority_pubkey,
         signer_pubkeys.is_empty(),
     ));
     for signer_pubkey in signer_pubkeys.iter() {
@@ -948,19 +2091,20 @@ pub fn freeze_account(
     })
 }
 
-/// Creates a `ThawAccount` instruction.
-pub fn thaw_account(
+/// Creates an `Approve` instruction.
+pub fn approve(
     token_program_id: &Pubkey,
-    account_pubkey: &Pubkey,
-    mint_pubkey: &Pubkey,
+    source_pubkey: &Pubkey,
+    delegate_pubkey: &Pubkey,
     owner_pubkey: &Pubkey,
     signer_pubkeys: &[&Pubkey],
+    amount: u64,
 ) -> Result<Instruction, ProgramError> {
-    let data = TokenInstruction::ThawAccount.pack();
+    let data = TokenInstruction::Approve { amount }.pack();
 
     let mut accounts = Vec::with_capacity(3 + signer_pubkeys.len());
-    accounts.push(AccountMeta::new(*account_pubkey, false));
-    accounts.push(AccountMeta::new_readonly(*mint_pubkey, false));
+    accounts.push(AccountMeta::new(*source_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(*delegate_pubkey, false));
     accounts.push(AccountMeta::new_readonly(
         *owner_pubkey,
         signer_pubkeys.is_empty(),
@@ -976,26 +2120,19 @@ pub fn thaw_account(
     })
 }
 
-/// Creates a `TransferChecked` instruction.
-#[allow(clippy::too_many_arguments)]
-pub fn transfer_checked(
+/// Creates a `Revoke` instruction.
+pub fn revoke(
     token_program_id: &Pubkey,
     source_pubkey: &Pubkey,
-    mint_pubkey: &Pubkey,
-    destination_pubkey: &Pubkey,
-    authority_pubkey: &Pubkey,
+    owner_pubkey: &Pubkey,
     signer_pubkeys: &[&Pubkey],
-    amount: u64,
-    decimals: u8,
 ) -> Result<Instruction, ProgramError> {
-    let data = TokenInstruction::TransferChecked { amount, decimals }.pack();
+    let data = TokenInstruction::Revoke.pack();
 
-    let mut accounts = Vec::with_capacity(4 + signer_pubkeys.len());
+    let mut accounts = Vec::with_capacity(2 + signer_pubkeys.len());
     accounts.push(AccountMeta::new(*source_pubkey, false));
-    accounts.push(AccountMeta::new_readonly(*mint_pubkey, false));
-    accounts.push(AccountMeta::new(*destination_pubkey, false));
     accounts.push(AccountMeta::new_readonly(
-        *authority_pubkey,
+        *owner_pubkey,
         signer_pubkeys.is_empty(),
     ));
     for signer_pubkey in signer_pubkeys.iter() {
@@ -1009,24 +2146,24 @@ pub fn transfer_checked(
     })
 }
 
-/// Creates an `ApproveChecked` instruction.
-#[allow(clippy::too_many_arguments)]
-pub fn approve_checked(
+/// Creates a `SetAuthority` instruction.
+pub fn set_authority(
     token_program_id: &Pubkey,
-    source_pubkey: &Pubkey,
-    mint_pubkey: &Pubkey,
-    delegate_pubkey: &Pubkey,
+    owned_pubkey: &Pubkey,
+    new_authority_pubkey: Option<&Pubkey>,
+    authority_type: AuthorityType,
     owner_pubkey: &Pubkey,
     signer_pubkeys: &[&Pubkey],
-    amount: u64,
-    decimals: u8,
 ) -> Result<Instruction, ProgramError> {
-    let data = TokenInstruction::ApproveChecked { amount, decimals }.pack();
+    let new_authority = new_authority_pubkey.cloned().into();
+    let data = TokenInstruction::SetAuthority {
+        authority_type,
+        new_authority,
+    }
+    .pack();
 
-    let mut accounts = Vec::with_capacity(4 + signer_pubkeys.len());
-    accounts.push(AccountMeta::new(*source_pubkey, false));
-    accounts.push(AccountMeta::new_readonly(*mint_pubkey, false));
-    accounts.push(AccountMeta::new_readonly(*delegate_pubkey, false));
+    let mut accounts = Vec::with_capacity(3 + signer_pubkeys.len());
+    accounts.push(AccountMeta::new(*owned_pubkey, false));
     accounts.push(AccountMeta::new_readonly(
         *owner_pubkey,
         signer_pubkeys.is_empty(),
@@ -1042,17 +2179,16 @@ pub fn approve_checked(
     })
 }
 
-/// Creates a `MintToChecked` instruction.
-pub fn mint_to_checked(
+/// Creates a `MintTo` instruction.
+pub fn mint_to(
     token_program_id: &Pubkey,
     mint_pubkey: &Pubkey,
     account_pubkey: &Pubkey,
     owner_pubkey: &Pubkey,
     signer_pubkeys: &[&Pubkey],
     amount: u64,
-    decimals: u8,
 ) -> Result<Instruction, ProgramError> {
-    let data = TokenInstruction::MintToChecked { amount, decimals }.pack();
+    let data = TokenInstruction::MintTo { amount }.pack();
 
     let mut accounts = Vec::with_capacity(3 + signer_pubkeys.len());
     accounts.push(AccountMeta::new(*mint_pubkey, false));
@@ -1072,17 +2208,16 @@ pub fn mint_to_checked(
     })
 }
 
-/// Creates a `BurnChecked` instruction.
-pub fn burn_checked(
+/// Creates a `Burn` instruction.
+pub fn burn(
     token_program_id: &Pubkey,
     account_pubkey: &Pubkey,
     mint_pubkey: &Pubkey,
     authority_pubkey: &Pubkey,
     signer_pubkeys: &[&Pubkey],
     amount: u64,
-    decimals: u8,
 ) -> Result<Instruction, ProgramError> {
-    let data = TokenInstruction::BurnChecked { amount, decimals }.pack();
+    let data = TokenInstruction::Burn { amount }.pack();
 
     let mut accounts = Vec::with_capacity(3 + signer_pubkeys.len());
     accounts.push(AccountMeta::new(*account_pubkey, false));
@@ -1102,9 +2237,948 @@ pub fn burn_checked(
     })
 }
 
-/// Utility function that checks index is between MIN_SIGNERS and MAX_SIGNERS
-pub fn is_valid_signer_index(index: usize) -> bool {
-    (MIN_SIGNERS..=MAX_SIGNERS).contains(&index)
+/// Creates a `CloseAccount` instruction.
+pub fn close_account(
+    token_program_id: &Pubkey,
+    account_pubkey: &Pubkey,
+    destination_pubkey: &Pubkey,
+    owner_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = TokenInstruction::CloseAccount.pack();
+
+    let mut accounts = Vec::with_capacity(3 + signer_pubkeys.len());
+    accounts.push(AccountMeta::new(*account_pubkey, false));
+    accounts.push(AccountMeta::new(*destination_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(
+        *owner_pubkey,
+        signer_pubkeys.is_empty(),
+    ));
+    for signer_pubkey in signer_pubkeys.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `FreezeAccount` instruction.
+pub fn freeze_account(
+    token_program_id: &Pubkey,
+    account_pubkey: &Pubkey,
+    mint_pubkey: &Pubkey,
+    owner_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = TokenInstruction::FreezeAccount.pack();
+
+    let mut accounts = Vec::with_capacity(3 + signer_pubkeys.len());
+    accounts.push(AccountMeta::new(*account_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(*mint_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(
+        *owner_pubkey,
+        signer_pubkeys.is_empty(),
+    ));
+    for signer_pubkey in signer_pubkeys.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `ThawAccount` instruction.
+pub fn thaw_account(
+    token_program_id: &Pubkey,
+    account_pubkey: &Pubkey,
+    mint_pubkey: &Pubkey,
+    owner_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = TokenInstruction::ThawAccount.pack();
+
+    let mut accounts = Vec::with_capacity(3 + signer_pubkeys.len());
+    accounts.push(AccountMeta::new(*account_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(*mint_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(
+        *owner_pubkey,
+        signer_pubkeys.is_empty(),
+    ));
+    for signer_pubkey in signer_pubkeys.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `TransferChecked` instruction.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_checked(
+    token_program_id: &Pubkey,
+    source_pubkey: &Pubkey,
+    mint_pubkey: &Pubkey,
+    destination_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    amount: u64,
+    decimals: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = TokenInstruction::TransferChecked { amount, decimals }.pack();
+
+    let mut accounts = Vec::with_capacity(4 + signer_pubkeys.len());
+    accounts.push(AccountMeta::new(*source_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(*mint_pubkey, false));
+    accounts.push(AccountMeta::new(*destination_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(
+        *authority_pubkey,
+        signer_pubkeys.is_empty(),
+    ));
+    for signer_pubkey in signer_pubkeys.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `TransferCheckedBatch` instruction.
+pub fn transfer_checked_batch(
+    token_program_id: &Pubkey,
+    source_pubkey: &Pubkey,
+    mint_pubkey: &Pubkey,
+    destinations: &[(&Pubkey, u64)],
+    authority_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    decimals: u8,
+) -> Result<Instruction, ProgramError> {
+    if destinations.is_empty() || destinations.len() > u8::MAX as usize {
+        return Err(TokenError::InvalidInstruction.into());
+    }
+    let amounts = destinations.iter().map(|(_, amount)| *amount).collect();
+    let data = TokenInstruction::TransferCheckedBatch { amounts, decimals }.pack();
+
+    let mut accounts = Vec::with_capacity(3 + destinations.len() + signer_pubkeys.len());
+    accounts.push(AccountMeta::new(*source_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(*mint_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(
+        *authority_pubkey,
+        signer_pubkeys.is_empty(),
+    ));
+    for (destination_pubkey, _) in destinations.iter() {
+        accounts.push(AccountMeta::new(**destination_pubkey, false));
+    }
+    for signer_pubkey in signer_pubkeys.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `TransferCheckedWithFee` instruction.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_checked_with_fee(
+    token_program_id: &Pubkey,
+    source_pubkey: &Pubkey,
+    mint_pubkey: &Pubkey,
+    destination_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    amount: u64,
+    decimals: u8,
+    fee: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = TokenInstruction::TransferCheckedWithFee {
+        amount,
+        decimals,
+        fee,
+    }
+    .pack();
+
+    let mut accounts = Vec::with_capacity(4 + signer_pubkeys.len());
+    accounts.push(AccountMeta::new(*source_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(*mint_pubkey, false));
+    accounts.push(AccountMeta::new(*destination_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(
+        *authority_pubkey,
+        signer_pubkeys.is_empty(),
+    ));
+    for signer_pubkey in signer_pubkeys.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `ApproveChecked` instruction.
+#[allow(clippy::too_many_arguments)]
+pub fn approve_checked(
+    token_program_id: &Pubkey,
+    source_pubkey: &Pubkey,
+    mint_pubkey: &Pubkey,
+    delegate_pubkey: &Pubkey,
+    owner_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    amount: u64,
+    decimals: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = TokenInstruction::ApproveChecked { amount, decimals }.pack();
+
+    let mut accounts = Vec::with_capacity(4 + signer_pubkeys.len());
+    accounts.push(AccountMeta::new(*source_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(*mint_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(*delegate_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(
+        *owner_pubkey,
+        signer_pubkeys.is_empty(),
+    ));
+    for signer_pubkey in signer_pubkeys.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `ApproveCheckedWithExpiry` instruction.
+#[allow(clippy::too_many_arguments)]
+pub fn approve_checked_with_expiry(
+    token_program_id: &Pubkey,
+    source_pubkey: &Pubkey,
+    mint_pubkey: &Pubkey,
+    delegate_pubkey: &Pubkey,
+    owner_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    amount: u64,
+    decimals: u8,
+    expiry_slot: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = TokenInstruction::ApproveCheckedWithExpiry {
+        amount,
+        decimals,
+        expiry_slot,
+    }
+    .pack();
+
+    let mut accounts = Vec::with_capacity(4 + signer_pubkeys.len());
+    accounts.push(AccountMeta::new(*source_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(*mint_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(*delegate_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(
+        *owner_pubkey,
+        signer_pubkeys.is_empty(),
+    ));
+    for signer_pubkey in signer_pubkeys.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `MintToChecked` instruction.
+pub fn mint_to_checked(
+    token_program_id: &Pubkey,
+    mint_pubkey: &Pubkey,
+    account_pubkey: &Pubkey,
+    owner_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    amount: u64,
+    decimals: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = TokenInstruction::MintToChecked { amount, decimals }.pack();
+
+    let mut accounts = Vec::with_capacity(3 + signer_pubkeys.len());
+    accounts.push(AccountMeta::new(*mint_pubkey, false));
+    accounts.push(AccountMeta::new(*account_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(
+        *owner_pubkey,
+        signer_pubkeys.is_empty(),
+    ));
+    for signer_pubkey in signer_pubkeys.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `BurnChecked` instruction.
+pub fn burn_checked(
+    token_program_id: &Pubkey,
+    account_pubkey: &Pubkey,
+    mint_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    amount: u64,
+    decimals: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = TokenInstruction::BurnChecked { amount, decimals }.pack();
+
+    let mut accounts = Vec::with_capacity(3 + signer_pubkeys.len());
+    accounts.push(AccountMeta::new(*account_pubkey, false));
+    accounts.push(AccountMeta::new(*mint_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(
+        *authority_pubkey,
+        signer_pubkeys.is_empty(),
+    ));
+    for signer_pubkey in signer_pubkeys.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `SyncNative` instruction.
+pub fn sync_native(
+    token_program_id: &Pubkey,
+    account_pubkey: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = TokenInstruction::SyncNative.pack();
+
+    let accounts = vec![AccountMeta::new(*account_pubkey, false)];
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `CreateMultisigProposal` instruction.
+///
+/// `instruction` is the token instruction to propose, created with the
+/// multisignature as its authority and without signer pubkeys.
+pub fn create_multisig_proposal(
+    token_program_id: &Pubkey,
+    proposal_pubkey: &Pubkey,
+    multisig_pubkey: &Pubkey,
+    proposer_pubkey: &Pubkey,
+    instruction: &Instruction,
+) -> Result<Instruction, ProgramError> {
+    if instruction.program_id != *token_program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if instruction.accounts.len() > MAX_PROPOSAL_ACCOUNTS
+        || instruction.data.len() > MAX_PROPOSAL_DATA_LEN
+    {
+        return Err(TokenError::InvalidInstruction.into());
+    }
+    let data = TokenInstruction::CreateMultisigProposal {
+        instruction_data: instruction.data.clone(),
+    }
+    .pack();
+
+    let mut accounts = Vec::with_capacity(3 + instruction.accounts.len());
+    accounts.push(AccountMeta::new(*proposal_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(*multisig_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(*proposer_pubkey, true));
+    for account_meta in instruction.accounts.iter() {
+        accounts.push(AccountMeta::new_readonly(account_meta.pubkey, false));
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `ApproveMultisigProposal` instruction.
+pub fn approve_multisig_proposal(
+    token_program_id: &Pubkey,
+    proposal_pubkey: &Pubkey,
+    multisig_pubkey: &Pubkey,
+    signer_pubkey: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = TokenInstruction::ApproveMultisigProposal.pack();
+
+    let accounts = vec![
+        AccountMeta::new(*proposal_pubkey, false),
+        AccountMeta::new_readonly(*multisig_pubkey, false),
+        AccountMeta::new_readonly(*signer_pubkey, true),
+    ];
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `ExecuteMultisigProposal` instruction.
+///
+/// `instruction` is the proposed token instruction, as passed to
+/// `create_multisig_proposal`.
+pub fn execute_multisig_proposal(
+    token_program_id: &Pubkey,
+    proposal_pubkey: &Pubkey,
+    multisig_pubkey: &Pubkey,
+    instruction: &Instruction,
+    signer_pubkeys: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    if instruction.program_id != *token_program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let data = TokenInstruction::ExecuteMultisigProposal.pack();
+
+    let mut accounts = Vec::with_capacity(2 + instruction.accounts.len() + signer_pubkeys.len());
+    accounts.push(AccountMeta::new(*proposal_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(*multisig_pubkey, false));
+    for account_meta in instruction.accounts.iter() {
+        accounts.push(AccountMeta {
+            pubkey: account_meta.pubkey,
+            is_signer: false,
+            is_writable: account_meta.is_writable,
+        });
+    }
+    for signer_pubkey in signer_pubkeys.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, false));
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `WithdrawWithheldTokensFromMint` instruction.
+pub fn withdraw_withheld_tokens_from_mint(
+    token_program_id: &Pubkey,
+    mint_pubkey: &Pubkey,
+    destination_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = TokenInstruction::WithdrawWithheldTokensFromMint.pack();
+
+    let mut accounts = Vec::with_capacity(3 + signer_pubkeys.len());
+    accounts.push(AccountMeta::new(*mint_pubkey, false));
+    accounts.push(AccountMeta::new(*destination_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(
+        *authority_pubkey,
+        signer_pubkeys.is_empty(),
+    ));
+    for signer_pubkey in signer_pubkeys.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `WithdrawWithheldTokensFromAccounts` instruction.
+pub fn withdraw_withheld_tokens_from_accounts(
+    token_program_id: &Pubkey,
+    mint_pubkey: &Pubkey,
+    destination_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    source_pubkeys: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    if source_pubkeys.len() > u8::MAX as usize {
+        return Err(TokenError::InvalidInstruction.into());
+    }
+    let data = TokenInstruction::WithdrawWithheldTokensFromAccounts {
+        num_token_accounts: source_pubkeys.len() as u8,
+    }
+    .pack();
+
+    let mut accounts = Vec::with_capacity(3 + source_pubkeys.len() + signer_pubkeys.len());
+    accounts.push(AccountMeta::new_readonly(*mint_pubkey, false));
+    accounts.push(AccountMeta::new(*destination_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(
+        *authority_pubkey,
+        signer_pubkeys.is_empty(),
+    ));
+    for source_pubkey in source_pubkeys.iter() {
+        accounts.push(AccountMeta::new(**source_pubkey, false));
+    }
+    for signer_pubkey in signer_pubkeys.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `HarvestWithheldTokensToMint` instruction.
+pub fn harvest_withheld_tokens_to_mint(
+    token_program_id: &Pubkey,
+    mint_pubkey: &Pubkey,
+    source_pubkeys: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = TokenInstruction::HarvestWithheldTokensToMint.pack();
+
+    let mut accounts = Vec::with_capacity(1 + source_pubkeys.len());
+    accounts.push(AccountMeta::new(*mint_pubkey, false));
+    for source_pubkey in source_pubkeys.iter() {
+        accounts.push(AccountMeta::new(**source_pubkey, false));
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `EnableRequiredTransferMemos` instruction.
+pub fn enable_required_transfer_memos(
+    token_program_id: &Pubkey,
+    account_pubkey: &Pubkey,
+    owner_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = TokenInstruction::EnableRequiredTransferMemos.pack();
+
+    let mut accounts = Vec::with_capacity(2 + signer_pubkeys.len());
+    accounts.push(AccountMeta::new(*account_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(
+        *owner_pubkey,
+        signer_pubkeys.is_empty(),
+    ));
+    for signer_pubkey in signer_pubkeys.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `DisableRequiredTransferMemos` instruction.
+pub fn disable_required_transfer_memos(
+    token_program_id: &Pubkey,
+    account_pubkey: &Pubkey,
+    owner_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = TokenInstruction::DisableRequiredTransferMemos.pack();
+
+    let mut accounts = Vec::with_capacity(2 + signer_pubkeys.len());
+    accounts.push(AccountMeta::new(*account_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(
+        *owner_pubkey,
+        signer_pubkeys.is_empty(),
+    ));
+    for signer_pubkey in signer_pubkeys.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `UpdateTokenMetadata` instruction.
+pub fn update_token_metadata(
+    token_program_id: &Pubkey,
+    mint_pubkey: &Pubkey,
+    update_authority_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    name: String,
+    symbol: String,
+    uri: String,
+) -> Result<Instruction, ProgramError> {
+    if !TokenMetadata::has_valid_lengths(&name, &symbol, &uri) {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let data = TokenInstruction::UpdateTokenMetadata { name, symbol, uri }.pack();
+
+    let mut accounts = Vec::with_capacity(2 + signer_pubkeys.len());
+    accounts.push(AccountMeta::new(*mint_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(
+        *update_authority_pubkey,
+        signer_pubkeys.is_empty(),
+    ));
+    for signer_pubkey in signer_pubkeys.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `UpdateInterestRate` instruction.
+pub fn update_interest_rate(
+    token_program_id: &Pubkey,
+    mint_pubkey: &Pubkey,
+    rate_authority_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    rate: i16,
+) -> Result<Instruction, ProgramError> {
+    let data = TokenInstruction::UpdateInterestRate { rate }.pack();
+
+    let mut accounts = Vec::with_capacity(2 + signer_pubkeys.len());
+    accounts.push(AccountMeta::new(*mint_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(
+        *rate_authority_pubkey,
+        signer_pubkeys.is_empty(),
+    ));
+    for signer_pubkey in signer_pubkeys.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `EnableCpiGuard` instruction.
+pub fn enable_cpi_guard(
+    token_program_id: &Pubkey,
+    account_pubkey: &Pubkey,
+    owner_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = TokenInstruction::EnableCpiGuard.pack();
+
+    let mut accounts = Vec::with_capacity(2 + signer_pubkeys.len());
+    accounts.push(AccountMeta::new(*account_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(
+        *owner_pubkey,
+        signer_pubkeys.is_empty(),
+    ));
+    for signer_pubkey in signer_pubkeys.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `DisableCpiGuard` instruction.
+pub fn disable_cpi_guard(
+    token_program_id: &Pubkey,
+    account_pubkey: &Pubkey,
+    owner_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let data = TokenInstruction::DisableCpiGuard.pack();
+
+    let mut accounts = Vec::with_capacity(3 + signer_pubkeys.len());
+    accounts.push(AccountMeta::new(*account_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(
+        *owner_pubkey,
+        signer_pubkeys.is_empty(),
+    ));
+    for signer_pubkey in signer_pubkeys.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    accounts.push(AccountMeta::new_readonly(sysvar::instructions::id(), false));
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `Transfer` instruction with the instructions sysvar appended, as
+/// required when the source account has its CPI guard enabled or the
+/// destination account requires memos.
+pub fn transfer_with_instructions_sysvar(
+    token_program_id: &Pubkey,
+    source_pubkey: &Pubkey,
+    destination_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    transfer(
+        token_program_id,
+        source_pubkey,
+        destination_pubkey,
+        authority_pubkey,
+        signer_pubkeys,
+        amount,
+    )
+    .map(append_instructions_sysvar)
+}
+
+/// Creates a `TransferChecked` instruction with the instructions sysvar
+/// appended, as required when the source account has its CPI guard enabled
+/// or the destination account requires memos.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_checked_with_instructions_sysvar(
+    token_program_id: &Pubkey,
+    source_pubkey: &Pubkey,
+    mint_pubkey: &Pubkey,
+    destination_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    amount: u64,
+    decimals: u8,
+) -> Result<Instruction, ProgramError> {
+    transfer_checked(
+        token_program_id,
+        source_pubkey,
+        mint_pubkey,
+        destination_pubkey,
+        authority_pubkey,
+        signer_pubkeys,
+        amount,
+        decimals,
+    )
+    .map(append_instructions_sysvar)
+}
+
+/// Creates an `Approve` instruction with the instructions sysvar appended, as
+/// required when the source account has its CPI guard enabled.
+pub fn approve_with_instructions_sysvar(
+    token_program_id: &Pubkey,
+    source_pubkey: &Pubkey,
+    delegate_pubkey: &Pubkey,
+    owner_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    approve(
+        token_program_id,
+        source_pubkey,
+        delegate_pubkey,
+        owner_pubkey,
+        signer_pubkeys,
+        amount,
+    )
+    .map(append_instructions_sysvar)
+}
+
+/// Creates an `ApproveChecked` instruction with the instructions sysvar
+/// appended, as required when the source account has its CPI guard enabled.
+#[allow(clippy::too_many_arguments)]
+pub fn approve_checked_with_instructions_sysvar(
+    token_program_id: &Pubkey,
+    source_pubkey: &Pubkey,
+    mint_pubkey: &Pubkey,
+    delegate_pubkey: &Pubkey,
+    owner_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    amount: u64,
+    decimals: u8,
+) -> Result<Instruction, ProgramError> {
+    approve_checked(
+        token_program_id,
+        source_pubkey,
+        mint_pubkey,
+        delegate_pubkey,
+        owner_pubkey,
+        signer_pubkeys,
+        amount,
+        decimals,
+    )
+    .map(append_instructions_sysvar)
+}
+
+/// Creates a `CloseAccount` instruction with the instructions sysvar
+/// appended, as required when the account has its CPI guard enabled.
+pub fn close_account_with_instructions_sysvar(
+    token_program_id: &Pubkey,
+    account_pubkey: &Pubkey,
+    destination_pubkey: &Pubkey,
+    owner_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    close_account(
+        token_program_id,
+        account_pubkey,
+        destination_pubkey,
+        owner_pubkey,
+        signer_pubkeys,
+    )
+    .map(append_instructions_sysvar)
+}
+
+/// Creates a `SetAuthority` instruction with the instructions sysvar
+/// appended, as required when the account has its CPI guard enabled.
+pub fn set_authority_with_instructions_sysvar(
+    token_program_id: &Pubkey,
+    owned_pubkey: &Pubkey,
+    new_authority_pubkey: Option<&Pubkey>,
+    authority_type: AuthorityType,
+    owner_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    set_authority(
+        token_program_id,
+        owned_pubkey,
+        new_authority_pubkey,
+        authority_type,
+        owner_pubkey,
+        signer_pubkeys,
+    )
+    .map(append_instructions_sysvar)
+}
+
+/// Creates a `TransferCheckedBatch` instruction with the instructions sysvar
+/// appended, as required when the source account has its CPI guard enabled
+/// or a destination account requires memos.
+pub fn transfer_checked_batch_with_instructions_sysvar(
+    token_program_id: &Pubkey,
+    source_pubkey: &Pubkey,
+    mint_pubkey: &Pubkey,
+    destinations: &[(&Pubkey, u64)],
+    authority_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    decimals: u8,
+) -> Result<Instruction, ProgramError> {
+    transfer_checked_batch(
+        token_program_id,
+        source_pubkey,
+        mint_pubkey,
+        destinations,
+        authority_pubkey,
+        signer_pubkeys,
+        decimals,
+    )
+    .map(append_instructions_sysvar)
+}
+
+/// Creates a `TransferCheckedWithFee` instruction with the instructions
+/// sysvar appended, as required when the source account has its CPI guard
+/// enabled or the destination account requires memos.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_checked_with_fee_with_instructions_sysvar(
+    token_program_id: &Pubkey,
+    source_pubkey: &Pubkey,
+    mint_pubkey: &Pubkey,
+    destination_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    amount: u64,
+    decimals: u8,
+    fee: u64,
+) -> Result<Instruction, ProgramError> {
+    transfer_checked_with_fee(
+        token_program_id,
+        source_pubkey,
+        mint_pubkey,
+        destination_pubkey,
+        authority_pubkey,
+        signer_pubkeys,
+        amount,
+        decimals,
+        fee,
+    )
+    .map(append_instructions_sysvar)
+}
+
+/// Creates an `ApproveCheckedWithExpiry` instruction with the instructions
+/// sysvar appended, as required when the source account has its CPI guard
+/// enabled.
+#[allow(clippy::too_many_arguments)]
+pub fn approve_checked_with_expiry_with_instructions_sysvar(
+    token_program_id: &Pubkey,
+    source_pubkey: &Pubkey,
+    mint_pubkey: &Pubkey,
+    delegate_pubkey: &Pubkey,
+    owner_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    amount: u64,
+    decimals: u8,
+    expiry_slot: u64,
+) -> Result<Instruction, ProgramError> {
+    approve_checked_with_expiry(
+        token_program_id,
+        source_pubkey,
+        mint_pubkey,
+        delegate_pubkey,
+        owner_pubkey,
+        signer_pubkeys,
+        amount,
+        decimals,
+        expiry_slot,
+    )
+    .map(append_instructions_sysvar)
+}
+
+/// Creates a `MigrateAccount` instruction with the instructions sysvar
+/// appended, as required when moving an account that has its CPI guard
+/// enabled.
+pub fn migrate_account_with_instructions_sysvar(
+    token_program_id: &Pubkey,
+    account_pubkey: &Pubkey,
+    new_account_pubkey: &Pubkey,
+    destination_pubkey: &Pubkey,
+    owner_pubkey: &Pubkey,
+    signer_pubkeys: &[&Pubkey],
+    extension_types: &[ExtensionType],
+) -> Result<Instruction, ProgramError> {
+    migrate_account(
+        token_program_id,
+        account_pubkey,
+        new_account_pubkey,
+        destination_pubkey,
+        owner_pubkey,
+        signer_pubkeys,
+        extension_types,
+    )
+    .map(append_instructions_sysvar)
+}
+
+fn append_instructions_sysvar(mut instruction: Instruction) -> Instruction {
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(sysvar::instructions::id(), false));
+    instruction
+}
+
+/// Utility function that checks index is between MIN_SIGNERS and MAX_SIGNERS
+pub fn is_valid_signer_index(index: usize) -> bool {
+    (MIN_SIGNERS..=MAX_SIGNERS).contains(&index)
 }
 
 #[cfg(test)]
@@ -1271,5 +3345,313 @@ mod test {
         assert_eq!(packed, expect);
         let unpacked = TokenInstruction::unpack(&expect).unwrap();
         assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::SyncNative;
+        let packed = check.pack();
+        let expect = vec![17u8];
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::InitializeAccount3 {
+            owner: Pubkey::new(&[2u8; 32]),
+        };
+        let packed = check.pack();
+        let mut expect = vec![18u8];
+        expect.extend_from_slice(&[2u8; 32]);
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::InitializeMint2 {
+            decimals: 2,
+            mint_authority: Pubkey::new(&[1u8; 32]),
+            freeze_authority: COption::None,
+        };
+        let packed = check.pack();
+        let mut expect = Vec::from([19u8, 2]);
+        expect.extend_from_slice(&[1u8; 32]);
+        expect.extend_from_slice(&[0]);
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::InitializeMint2 {
+            decimals: 2,
+            mint_authority: Pubkey::new(&[2u8; 32]),
+            freeze_authority: COption::Some(Pubkey::new(&[3u8; 32])),
+        };
+        let packed = check.pack();
+        let mut expect = vec![19u8, 2];
+        expect.extend_from_slice(&[2u8; 32]);
+        expect.extend_from_slice(&[1]);
+        expect.extend_from_slice(&[3u8; 32]);
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::TransferCheckedBatch {
+            amounts: vec![1, 2],
+            decimals: 3,
+        };
+        let packed = check.pack();
+        let expect = Vec::from([20u8, 3, 2, 1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        // no amounts
+        assert_eq!(
+            TokenInstruction::unpack(&[20u8, 3, 0]),
+            Err(TokenError::InvalidInstruction.into())
+        );
+        // truncated amounts
+        assert_eq!(
+            TokenInstruction::unpack(&[20u8, 3, 2, 1, 0, 0, 0, 0, 0, 0, 0, 2]),
+            Err(TokenError::InvalidInstruction.into())
+        );
+
+        let check = TokenInstruction::InitializeMaxSupply { max_supply: 1 };
+        let packed = check.pack();
+        let expect = Vec::from([21u8, 1, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::ApproveCheckedWithExpiry {
+            amount: 1,
+            decimals: 2,
+            expiry_slot: 3,
+        };
+        let packed = check.pack();
+        let expect = Vec::from([22u8, 1, 0, 0, 0, 0, 0, 0, 0, 2, 3, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::CreateMultisigProposal {
+            instruction_data: TokenInstruction::Transfer { amount: 1 }.pack(),
+        };
+        let packed = check.pack();
+        let expect = Vec::from([23u8, 3, 1, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::ApproveMultisigProposal;
+        let packed = check.pack();
+        let expect = Vec::from([24u8]);
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::ExecuteMultisigProposal;
+        let packed = check.pack();
+        let expect = Vec::from([25u8]);
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::InitializePermanentDelegate {
+            delegate: Pubkey::new(&[2u8; 32]),
+        };
+        let packed = check.pack();
+        let mut expect = vec![26u8];
+        expect.extend_from_slice(&[2u8; 32]);
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::InitializeDefaultFrozen;
+        let packed = check.pack();
+        let expect = Vec::from([27u8]);
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::SetDefaultFrozen {
+            default_frozen: true,
+        };
+        let packed = check.pack();
+        let expect = Vec::from([28u8, 1]);
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::InitializeNonTransferableMint;
+        let packed = check.pack();
+        let expect = Vec::from([29u8]);
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::MigrateAccount {
+            extension_types: vec![ExtensionType::DelegationExpiry],
+        };
+        let packed = check.pack();
+        let expect = Vec::from([30u8, 1, 2, 0]);
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::InitializeTransferFeeConfig {
+            withdraw_withheld_authority: COption::Some(Pubkey::new(&[4u8; 32])),
+            transfer_fee_basis_points: 50,
+            maximum_fee: 1,
+        };
+        let packed = check.pack();
+        let mut expect = Vec::from([31u8, 1]);
+        expect.extend_from_slice(&[4u8; 32]);
+        expect.extend_from_slice(&[50, 0, 1, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::TransferCheckedWithFee {
+            amount: 1,
+            decimals: 2,
+            fee: 3,
+        };
+        let packed = check.pack();
+        let expect = Vec::from([32u8, 1, 0, 0, 0, 0, 0, 0, 0, 2, 3, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::WithdrawWithheldTokensFromMint;
+        let packed = check.pack();
+        let expect = Vec::from([33u8]);
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::WithdrawWithheldTokensFromAccounts {
+            num_token_accounts: 3,
+        };
+        let packed = check.pack();
+        let expect = Vec::from([34u8, 3]);
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::HarvestWithheldTokensToMint;
+        let packed = check.pack();
+        let expect = Vec::from([35u8]);
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::EnableRequiredTransferMemos;
+        let packed = check.pack();
+        let expect = Vec::from([36u8]);
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::DisableRequiredTransferMemos;
+        let packed = check.pack();
+        let expect = Vec::from([37u8]);
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::InitializeImmutableOwner;
+        let packed = check.pack();
+        let expect = Vec::from([38u8]);
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::InitializeMintCloseAuthority {
+            close_authority: Pubkey::new(&[3u8; 32]),
+        };
+        let packed = check.pack();
+        let mut expect = vec![39u8];
+        expect.extend_from_slice(&[3u8; 32]);
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::InitializeTokenMetadata {
+            update_authority: COption::Some(Pubkey::new(&[4u8; 32])),
+            name: "Token".to_string(),
+            symbol: "TKN".to_string(),
+            uri: "".to_string(),
+        };
+        let packed = check.pack();
+        let mut expect = vec![40u8, 1];
+        expect.extend_from_slice(&[4u8; 32]);
+        expect.extend_from_slice(&[5, b'T', b'o', b'k', b'e', b'n']);
+        expect.extend_from_slice(&[3, b'T', b'K', b'N']);
+        expect.extend_from_slice(&[0]);
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::UpdateTokenMetadata {
+            name: "".to_string(),
+            symbol: "TKN".to_string(),
+            uri: "a".to_string(),
+        };
+        let packed = check.pack();
+        let expect = vec![41u8, 0, 3, b'T', b'K', b'N', 1, b'a'];
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::InitializeInterestBearingMint {
+            rate_authority: COption::None,
+            rate: -2,
+        };
+        let packed = check.pack();
+        let expect = vec![42u8, 0, 254, 255];
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::UpdateInterestRate { rate: 500 };
+        let packed = check.pack();
+        let expect = vec![43u8, 244, 1];
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::EnableCpiGuard;
+        let packed = check.pack();
+        let expect = Vec::from([44u8]);
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        let check = TokenInstruction::DisableCpiGuard;
+        let packed = check.pack();
+        let expect = Vec::from([45u8]);
+        assert_eq!(packed, expect);
+        let unpacked = TokenInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+    }
+
+    #[test]
+    fn test_instruction_unpack_truncated() {
+        for check in &[
+            TokenInstruction::ApproveCheckedWithExpiry {
+                amount: 1,
+                decimals: 2,
+                expiry_slot: 3,
+            },
+            TokenInstruction::TransferCheckedWithFee {
+                amount: 1,
+                decimals: 2,
+                fee: 3,
+            },
+        ] {
+            let packed = check.pack();
+            for len in 0..packed.len() {
+                assert_eq!(
+                    TokenInstruction::unpack(&packed[..len]),
+                    Err(TokenError::InvalidInstruction.into())
+                );
+            }
+        }
     }
 }
@@ -1,11 +1,14 @@
 #![deny(missing_docs)]
-#![forbid(unsafe_code)]
+#![cfg_attr(not(test), forbid(unsafe_code))]
 
 //! An ERC20-like Token program for the Solana blockchain
 
 pub mod error;
+pub mod event;
 pub mod instruction;
 pub mod native_mint;
+#[cfg(feature = "parser")]
+pub mod parser;
 pub mod processor;
 pub mod state;
 
@@ -0,0 +1,738 @@
+//! Human-readable JSON representations of token instructions
+
+use crate::{
+    instruction::{AuthorityType, TokenInstruction},
+    state::ExtensionType,
+};
+use serde_json::{json, Map, Value};
+use solana_program::{
+    instruction::{CompiledInstruction, Instruction},
+    program_option::COption,
+    pubkey::Pubkey,
+    sysvar,
+};
+use thiserror::Error;
+
+/// Errors that may be returned by the instruction parser.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum ParseInstructionError {
+    /// The instruction data is not a valid token instruction
+    #[error("Invalid token instruction data")]
+    InvalidInstructionData,
+    /// The instruction has fewer accounts than it requires
+    #[error("Token instruction is missing accounts")]
+    NotEnoughAccounts,
+    /// A compiled instruction refers to an account missing from the message
+    #[error("Compiled instruction refers to a missing account key")]
+    InvalidAccountIndex,
+}
+
+/// Parses a token instruction into a JSON object holding its `type`, its
+/// named accounts and its data.
+///
+/// Token amounts are represented as strings, since they may not fit in the
+/// integers of JSON consumers.  Instructions signed by a multisignature
+/// report the multisignature, e.g. as `multisigAuthority` instead of
+/// `authority`, along with the `signers` that signed for it.
+pub fn parse_instruction(instruction: &Instruction) -> Result<Value, ParseInstructionError> {
+    let accounts: Vec<Pubkey> = instruction
+        .accounts
+        .iter()
+        .map(|account_meta| account_meta.pubkey)
+        .collect();
+    parse_token(&instruction.data, &accounts)
+}
+
+/// Parses a token instruction compiled into a message with the given
+/// account keys, see [parse_instruction](fn.parse_instruction.html).
+pub fn parse_compiled_instruction(
+    instruction: &CompiledInstruction,
+    account_keys: &[Pubkey],
+) -> Result<Value, ParseInstructionError> {
+    let accounts = instruction
+        .accounts
+        .iter()
+        .map(|index| account_keys.get(*index as usize).copied())
+        .collect::<Option<Vec<Pubkey>>>()
+        .ok_or(ParseInstructionError::InvalidAccountIndex)?;
+    parse_token(&instruction.data, &accounts)
+}
+
+/// Parses token instruction data along with the keys of its accounts, see
+/// [parse_instruction](fn.parse_instruction.html).
+pub fn parse_token(data: &[u8], accounts: &[Pubkey]) -> Result<Value, ParseInstructionError> {
+    let token_instruction = TokenInstruction::unpack(data)
+        .map_err(|_| ParseInstructionError::InvalidInstructionData)?;
+    let mut map = Map::new();
+    let instruction_type = match token_instruction {
+        TokenInstruction::InitializeMint {
+            decimals,
+            mint_authority,
+            freeze_authority,
+        } => {
+            check_num_accounts(accounts, 2)?;
+            map.insert("mint".to_string(), pubkey(&accounts[0]));
+            map.insert("rentSysvar".to_string(), pubkey(&accounts[1]));
+            map.insert("decimals".to_string(), json!(decimals));
+            map.insert("mintAuthority".to_string(), pubkey(&mint_authority));
+            map.insert("freezeAuthority".to_string(), optional(&freeze_authority));
+            "initializeMint"
+        }
+        TokenInstruction::InitializeMint2 {
+            decimals,
+            mint_authority,
+            freeze_authority,
+        } => {
+            check_num_accounts(accounts, 1)?;
+            map.insert("mint".to_string(), pubkey(&accounts[0]));
+            map.insert("decimals".to_string(), json!(decimals));
+            map.insert("mintAuthority".to_string(), pubkey(&mint_authority));
+            map.insert("freezeAuthority".to_string(), optional(&freeze_authority));
+            "initializeMint2"
+        }
+        TokenInstruction::InitializeAccount => {
+            check_num_accounts(accounts, 4)?;
+            map.insert("account".to_string(), pubkey(&accounts[0]));
+            map.insert("mint".to_string(), pubkey(&accounts[1]));
+            map.insert("owner".to_string(), pubkey(&accounts[2]));
+            map.insert("rentSysvar".to_string(), pubkey(&accounts[3]));
+            "initializeAccount"
+        }
+        TokenInstruction::InitializeAccount2 { owner } => {
+            check_num_accounts(accounts, 3)?;
+            map.insert("account".to_string(), pubkey(&accounts[0]));
+            map.insert("mint".to_string(), pubkey(&accounts[1]));
+            map.insert("owner".to_string(), pubkey(&owner));
+            map.insert("rentSysvar".to_string(), pubkey(&accounts[2]));
+            "initializeAccount2"
+        }
+        TokenInstruction::InitializeAccount3 { owner } => {
+            check_num_accounts(accounts, 2)?;
+            map.insert("account".to_string(), pubkey(&accounts[0]));
+            map.insert("mint".to_string(), pubkey(&accounts[1]));
+            map.insert("owner".to_string(), pubkey(&owner));
+            "initializeAccount3"
+        }
+        TokenInstruction::InitializeMultisig { m } => {
+            check_num_accounts(accounts, 3)?;
+            map.insert("multisig".to_string(), pubkey(&accounts[0]));
+            map.insert("rentSysvar".to_string(), pubkey(&accounts[1]));
+            map.insert("signers".to_string(), pubkeys(&accounts[2..]));
+            map.insert("m".to_string(), json!(m));
+            "initializeMultisig"
+        }
+        TokenInstruction::Transfer { amount } => {
+            check_num_accounts(accounts, 3)?;
+            map.insert("source".to_string(), pubkey(&accounts[0]));
+            map.insert("destination".to_string(), pubkey(&accounts[1]));
+            map.insert("amount".to_string(), token_amount(amount));
+            parse_signers(&mut map, accounts, 2, 3, "authority");
+            "transfer"
+        }
+        TokenInstruction::TransferChecked { amount, decimals } => {
+            check_num_accounts(accounts, 4)?;
+            map.insert("source".to_string(), pubkey(&accounts[0]));
+            map.insert("mint".to_string(), pubkey(&accounts[1]));
+            map.insert("destination".to_string(), pubkey(&accounts[2]));
+            map.insert("amount".to_string(), token_amount(amount));
+            map.insert("decimals".to_string(), json!(decimals));
+            parse_signers(&mut map, accounts, 3, 4, "authority");
+            "transferChecked"
+        }
+        TokenInstruction::TransferCheckedWithFee {
+            amount,
+            decimals,
+            fee,
+        } => {
+            check_num_accounts(accounts, 4)?;
+            map.insert("source".to_string(), pubkey(&accounts[0]));
+            map.insert("mint".to_string(), pubkey(&accounts[1]));
+            map.insert("destination".to_string(), pubkey(&accounts[2]));
+            map.insert("amount".to_string(), token_amount(amount));
+            map.insert("decimals".to_string(), json!(decimals));
+            map.insert("fee".to_string(), token_amount(fee));
+            parse_signers(&mut map, accounts, 3, 4, "authority");
+            "transferCheckedWithFee"
+        }
+        TokenInstruction::TransferCheckedBatch { amounts, decimals } => {
+            let destinations_end = 3 + amounts.len();
+            check_num_accounts(accounts, destinations_end)?;
+            map.insert("source".to_string(), pubkey(&accounts[0]));
+            map.insert("mint".to_string(), pubkey(&accounts[1]));
+            let transfers: Vec<Value> = accounts[3..destinations_end]
+                .iter()
+                .zip(amounts.iter())
+                .map(|(destination, amount)| {
+                    json!({
+                        "destination": destination.to_string(),
+                        "amount": token_amount(*amount),
+                    })
+                })
+                .collect();
+            map.insert("transfers".to_string(), Value::Array(transfers));
+            map.insert("decimals".to_string(), json!(decimals));
+            parse_signers(&mut map, accounts, 2, destinations_end, "authority");
+            "transferCheckedBatch"
+        }
+        TokenInstruction::Approve { amount } => {
+            check_num_accounts(accounts, 3)?;
+            map.insert("source".to_string(), pubkey(&accounts[0]));
+            map.insert("delegate".to_string(), pubkey(&accounts[1]));
+            map.insert("amount".to_string(), token_amount(amount));
+            parse_signers(&mut map, accounts, 2, 3, "owner");
+            "approve"
+        }
+        TokenInstruction::ApproveChecked { amount, decimals } => {
+            check_num_accounts(accounts, 4)?;
+            map.insert("source".to_string(), pubkey(&accounts[0]));
+            map.insert("mint".to_string(), pubkey(&accounts[1]));
+            map.insert("delegate".to_string(), pubkey(&accounts[2]));
+            map.insert("amount".to_string(), token_amount(amount));
+            map.insert("decimals".to_string(), json!(decimals));
+            parse_signers(&mut map, accounts, 3, 4, "owner");
+            "approveChecked"
+        }
+        TokenInstruction::ApproveCheckedWithExpiry {
+            amount,
+            decimals,
+            expiry_slot,
+        } => {
+            check_num_accounts(accounts, 4)?;
+            map.insert("source".to_string(), pubkey(&accounts[0]));
+            map.insert("mint".to_string(), pubkey(&accounts[1]));
+            map.insert("delegate".to_string(), pubkey(&accounts[2]));
+            map.insert("amount".to_string(), token_amount(amount));
+            map.insert("decimals".to_string(), json!(decimals));
+            map.insert("expirySlot".to_string(), json!(expiry_slot));
+            parse_signers(&mut map, accounts, 3, 4, "owner");
+            "approveCheckedWithExpiry"
+        }
+        TokenInstruction::Revoke => {
+            check_num_accounts(accounts, 2)?;
+            map.insert("source".to_string(), pubkey(&accounts[0]));
+            parse_signers(&mut map, accounts, 1, 2, "owner");
+            "revoke"
+        }
+        TokenInstruction::SetAuthority {
+            authority_type,
+            new_authority,
+        } => {
+            check_num_accounts(accounts, 2)?;
+            let owned_field = match authority_type {
+                AuthorityType::AccountOwner | AuthorityType::CloseAccount => "account",
+                _ => "mint",
+            };
+            map.insert(owned_field.to_string(), pubkey(&accounts[0]));
+            map.insert(
+                "authorityType".to_string(),
+                json!(authority_type_name(authority_type)),
+            );
+            map.insert("newAuthority".to_string(), optional(&new_authority));
+            parse_signers(&mut map, accounts, 1, 2, "authority");
+            "setAuthority"
+        }
+        TokenInstruction::MintTo { amount } => {
+            check_num_accounts(accounts, 3)?;
+            map.insert("mint".to_string(), pubkey(&accounts[0]));
+            map.insert("account".to_string(), pubkey(&accounts[1]));
+            map.insert("amount".to_string(), token_amount(amount));
+            parse_signers(&mut map, accounts, 2, 3, "mintAuthority");
+            "mintTo"
+        }
+        TokenInstruction::MintToChecked { amount, decimals } => {
+            check_num_accounts(accounts, 3)?;
+            map.insert("mint".to_string(), pubkey(&accounts[0]));
+            map.insert("account".to_string(), pubkey(&accounts[1]));
+            map.insert("amount".to_string(), token_amount(amount));
+            map.insert("decimals".to_string(), json!(decimals));
+            parse_signers(&mut map, accounts, 2, 3, "mintAuthority");
+            "mintToChecked"
+        }
+        TokenInstruction::Burn { amount } => {
+            check_num_accounts(accounts, 3)?;
+            map.insert("account".to_string(), pubkey(&accounts[0]));
+            map.insert("mint".to_string(), pubkey(&accounts[1]));
+            map.insert("amount".to_string(), token_amount(amount));
+            parse_signers(&mut map, accounts, 2, 3, "authority");
+            "burn"
+        }
+        TokenInstruction::BurnChecked { amount, decimals } => {
+            check_num_accounts(accounts, 3)?;
+            map.insert("account".to_string(), pubkey(&accounts[0]));
+            map.insert("mint".to_string(), pubkey(&accounts[1]));
+            map.insert("amount".to_string(), token_amount(amount));
+            map.insert("decimals".to_string(), json!(decimals));
+            parse_signers(&mut map, accounts, 2, 3, "authority");
+            "burnChecked"
+        }
+        TokenInstruction::CloseAccount => {
+            check_num_accounts(accounts, 3)?;
+            map.insert("account".to_string(), pubkey(&accounts[0]));
+            map.insert("destination".to_string(), pubkey(&accounts[1]));
+            parse_signers(&mut map, accounts, 2, 3, "owner");
+            "closeAccount"
+        }
+        TokenInstruction::FreezeAccount => {
+            check_num_accounts(accounts, 3)?;
+            map.insert("account".to_string(), pubkey(&accounts[0]));
+            map.insert("mint".to_string(), pubkey(&accounts[1]));
+            parse_signers(&mut map, accounts, 2, 3, "freezeAuthority");
+            "freezeAccount"
+        }
+        TokenInstruction::ThawAccount => {
+            check_num_accounts(accounts, 3)?;
+            map.insert("account".to_string(), pubkey(&accounts[0]));
+            map.insert("mint".to_string(), pubkey(&accounts[1]));
+            parse_signers(&mut map, accounts, 2, 3, "freezeAuthority");
+            "thawAccount"
+        }
+        TokenInstruction::SyncNative => {
+            check_num_accounts(accounts, 1)?;
+            map.insert("account".to_string(), pubkey(&accounts[0]));
+            "syncNative"
+        }
+        TokenInstruction::InitializeMaxSupply { max_supply } => {
+            check_num_accounts(accounts, 1)?;
+            map.insert("mint".to_string(), pubkey(&accounts[0]));
+            map.insert("maxSupply".to_string(), token_amount(max_supply));
+            "initializeMaxSupply"
+        }
+        TokenInstruction::CreateMultisigProposal { instruction_data } => {
+            check_num_accounts(accounts, 3)?;
+            map.insert("proposal".to_string(), pubkey(&accounts[0]));
+            map.insert("multisig".to_string(), pubkey(&accounts[1]));
+            map.insert("proposer".to_string(), pubkey(&accounts[2]));
+            map.insert(
+                "instruction".to_string(),
+                parse_token(&instruction_data, &accounts[3..])?,
+            );
+            "createMultisigProposal"
+        }
+        TokenInstruction::ApproveMultisigProposal => {
+            check_num_accounts(accounts, 3)?;
+            map.insert("proposal".to_string(), pubkey(&accounts[0]));
+            map.insert("multisig".to_string(), pubkey(&accounts[1]));
+            map.insert("signer".to_string(), pubkey(&accounts[2]));
+            "approveMultisigProposal"
+        }
+        TokenInstruction::ExecuteMultisigProposal => {
+            check_num_accounts(accounts, 2)?;
+            map.insert("proposal".to_string(), pubkey(&accounts[0]));
+            map.insert("multisig".to_string(), pubkey(&accounts[1]));
+            // The proposed instruction's accounts and the approving signers
+            // can only be told apart with the proposal's data
+            map.insert("accounts".to_string(), pubkeys(&accounts[2..]));
+            "executeMultisigProposal"
+        }
+        TokenInstruction::InitializePermanentDelegate { delegate } => {
+            check_num_accounts(accounts, 1)?;
+            map.insert("mint".to_string(), pubkey(&accounts[0]));
+            map.insert("delegate".to_string(), pubkey(&delegate));
+            "initializePermanentDelegate"
+        }
+        TokenInstruction::InitializeDefaultFrozen => {
+            check_num_accounts(accounts, 1)?;
+            map.insert("mint".to_string(), pubkey(&accounts[0]));
+            "initializeDefaultFrozen"
+        }
+        TokenInstruction::SetDefaultFrozen { default_frozen } => {
+            check_num_accounts(accounts, 2)?;
+            map.insert("mint".to_string(), pubkey(&accounts[0]));
+            map.insert("defaultFrozen".to_string(), json!(default_frozen));
+            parse_signers(&mut map, accounts, 1, 2, "freezeAuthority");
+            "setDefaultFrozen"
+        }
+        TokenInstruction::InitializeNonTransferableMint => {
+            check_num_accounts(accounts, 1)?;
+            map.insert("mint".to_string(), pubkey(&accounts[0]));
+            "initializeNonTransferableMint"
+        }
+        TokenInstruction::MigrateAccount { extension_types } => {
+            check_num_accounts(accounts, 4)?;
+            map.insert("account".to_string(), pubkey(&accounts[0]));
+            map.insert("newAccount".to_string(), pubkey(&accounts[1]));
+            map.insert("destination".to_string(), pubkey(&accounts[2]));
+            let extension_types: Vec<Value> = extension_types
+                .iter()
+                .map(|extension_type| json!(extension_type_name(*extension_type)))
+                .collect();
+            map.insert("extensionTypes".to_string(), Value::Array(extension_types));
+            parse_signers(&mut map, accounts, 3, 4, "owner");
+            "migrateAccount"
+        }
+        TokenInstruction::InitializeTransferFeeConfig {
+            withdraw_withheld_authority,
+            transfer_fee_basis_points,
+            maximum_fee,
+        } => {
+            check_num_accounts(accounts, 1)?;
+            map.insert("mint".to_string(), pubkey(&accounts[0]));
+            map.insert(
+                "withdrawWithheldAuthority".to_string(),
+                optional(&withdraw_withheld_authority),
+            );
+            map.insert(
+                "transferFeeBasisPoints".to_string(),
+                json!(transfer_fee_basis_points),
+            );
+            map.insert("maximumFee".to_string(), token_amount(maximum_fee));
+            "initializeTransferFeeConfig"
+        }
+        TokenInstruction::WithdrawWithheldTokensFromMint => {
+            check_num_accounts(accounts, 3)?;
+            map.insert("mint".to_string(), pubkey(&accounts[0]));
+            map.insert("destination".to_string(), pubkey(&accounts[1]));
+            parse_signers(&mut map, accounts, 2, 3, "withdrawWithheldAuthority");
+            "withdrawWithheldTokensFromMint"
+        }
+        TokenInstruction::WithdrawWithheldTokensFromAccounts { num_token_accounts } => {
+            let sources_end = 3 + num_token_accounts as usize;
+            check_num_accounts(accounts, sources_end)?;
+            map.insert("mint".to_string(), pubkey(&accounts[0]));
+            map.insert("destination".to_string(), pubkey(&accounts[1]));
+            map.insert("sources".to_string(), pubkeys(&accounts[3..sources_end]));
+            parse_signers(
+                &mut map,
+                accounts,
+                2,
+                sources_end,
+                "withdrawWithheldAuthority",
+            );
+            "withdrawWithheldTokensFromAccounts"
+        }
+        TokenInstruction::HarvestWithheldTokensToMint => {
+            check_num_accounts(accounts, 1)?;
+            map.insert("mint".to_string(), pubkey(&accounts[0]));
+            map.insert("sources".to_string(), pubkeys(&accounts[1..]));
+            "harvestWithheldTokensToMint"
+        }
+        TokenInstruction::EnableRequiredTransferMemos => {
+            check_num_accounts(accounts, 2)?;
+            map.insert("account".to_string(), pubkey(&accounts[0]));
+            parse_signers(&mut map, accounts, 1, 2, "owner");
+            "enableRequiredTransferMemos"
+        }
+        TokenInstruction::DisableRequiredTransferMemos => {
+            check_num_accounts(accounts, 2)?;
+            map.insert("account".to_string(), pubkey(&accounts[0]));
+            parse_signers(&mut map, accounts, 1, 2, "owner");
+            "disableRequiredTransferMemos"
+        }
+        TokenInstruction::InitializeImmutableOwner => {
+            check_num_accounts(accounts, 1)?;
+            map.insert("account".to_string(), pubkey(&accounts[0]));
+            "initializeImmutableOwner"
+        }
+        TokenInstruction::InitializeMintCloseAuthority { close_authority } => {
+            check_num_accounts(accounts, 1)?;
+            map.insert("mint".to_string(), pubkey(&accounts[0]));
+            map.insert("closeAuthority".to_string(), pubkey(&close_authority));
+            "initializeMintCloseAuthority"
+        }
+        TokenInstruction::InitializeTokenMetadata {
+            update_authority,
+            name,
+            symbol,
+            uri,
+        } => {
+            check_num_accounts(accounts, 1)?;
+            map.insert("mint".to_string(), pubkey(&accounts[0]));
+            map.insert("updateAuthority".to_string(), optional(&update_authority));
+            map.insert("name".to_string(), json!(name));
+            map.insert("symbol".to_string(), json!(symbol));
+            map.insert("uri".to_string(), json!(uri));
+            if accounts.len() > 1 {
+                parse_signers(&mut map, accounts, 1, 2, "mintAuthority");
+            }
+            "initializeTokenMetadata"
+        }
+        TokenInstruction::UpdateTokenMetadata { name, symbol, uri } => {
+            check_num_accounts(accounts, 2)?;
+            map.insert("mint".to_string(), pubkey(&accounts[0]));
+            map.insert("name".to_string(), json!(name));
+            map.insert("symbol".to_string(), json!(symbol));
+            map.insert("uri".to_string(), json!(uri));
+            parse_signers(&mut map, accounts, 1, 2, "updateAuthority");
+            "updateTokenMetadata"
+        }
+        TokenInstruction::InitializeInterestBearingMint {
+            rate_authority,
+            rate,
+        } => {
+            check_num_accounts(accounts, 1)?;
+            map.insert("mint".to_string(), pubkey(&accounts[0]));
+            map.insert("rateAuthority".to_string(), optional(&rate_authority));
+            map.insert("rate".to_string(), json!(rate));
+            "initializeInterestBearingMint"
+        }
+        TokenInstruction::UpdateInterestRate { rate } => {
+            check_num_accounts(accounts, 2)?;
+            map.insert("mint".to_string(), pubkey(&accounts[0]));
+            map.insert("rate".to_string(), json!(rate));
+            parse_signers(&mut map, accounts, 1, 2, "rateAuthority");
+            "updateInterestRate"
+        }
+        TokenInstruction::EnableCpiGuard => {
+            check_num_accounts(accounts, 2)?;
+            map.insert("account".to_string(), pubkey(&accounts[0]));
+            parse_signers(&mut map, accounts, 1, 2, "owner");
+            "enableCpiGuard"
+        }
+        TokenInstruction::DisableCpiGuard => {
+            check_num_accounts(accounts, 2)?;
+            map.insert("account".to_string(), pubkey(&accounts[0]));
+            parse_signers(&mut map, accounts, 1, 2, "owner");
+            "disableCpiGuard"
+        }
+    };
+    map.insert("type".to_string(), json!(instruction_type));
+    Ok(Value::Object(map))
+}
+
+fn check_num_accounts(accounts: &[Pubkey], num: usize) -> Result<(), ParseInstructionError> {
+    if accounts.len() < num {
+        Err(ParseInstructionError::NotEnoughAccounts)
+    } else {
+        Ok(())
+    }
+}
+
+/// Inserts the authority at `authority_index` under `authority_field`, or,
+/// when signer accounts start at `signers_start`, under the field's
+/// `multisig`-prefixed name along with the signers.  The instructions sysvar, appended
+/// for required memos and CPI guards, is not a signer.
+fn parse_signers(
+    map: &mut Map<String, Value>,
+    accounts: &[Pubkey],
+    authority_index: usize,
+    signers_start: usize,
+    authority_field: &str,
+) {
+    let signers: Vec<Pubkey> = accounts[signers_start..]
+        .iter()
+        .filter(|key| !sysvar::instructions::check_id(key))
+        .copied()
+        .collect();
+    if signers.is_empty() {
+        map.insert(
+            authority_field.to_string(),
+            pubkey(&accounts[authority_index]),
+        );
+    } else {
+        let mut chars = authority_field.chars();
+        let multisig_field = match chars.next() {
+            Some(first) => format!("multisig{}{}", first.to_ascii_uppercase(), chars.as_str()),
+            None => "multisig".to_string(),
+        };
+        map.insert(multisig_field, pubkey(&accounts[authority_index]));
+        map.insert("signers".to_string(), pubkeys(&signers));
+    }
+}
+
+fn pubkey(pubkey: &Pubkey) -> Value {
+    json!(pubkey.to_string())
+}
+
+fn pubkeys(pubkeys: &[Pubkey]) -> Value {
+    Value::Array(pubkeys.iter().map(pubkey).collect())
+}
+
+fn optional(optional_pubkey: &COption<Pubkey>) -> Value {
+    match optional_pubkey {
+        COption::Some(key) => pubkey(key),
+        COption::None => Value::Null,
+    }
+}
+
+fn token_amount(amount: u64) -> Value {
+    json!(amount.to_string())
+}
+
+fn authority_type_name(authority_type: AuthorityType) -> &'static str {
+    match authority_type {
+        AuthorityType::MintTokens => "mintTokens",
+        AuthorityType::FreezeAccount => "freezeAccount",
+        AuthorityType::AccountOwner => "accountOwner",
+        AuthorityType::CloseAccount => "closeAccount",
+        AuthorityType::PermanentDelegate => "permanentDelegate",
+        AuthorityType::WithdrawWithheldTokens => "withdrawWithheldTokens",
+        AuthorityType::CloseMint => "closeMint",
+        AuthorityType::MetadataUpdate => "metadataUpdate",
+        AuthorityType::InterestRate => "interestRate",
+    }
+}
+
+fn extension_type_name(extension_type: ExtensionType) -> &'static str {
+    match extension_type {
+        ExtensionType::Uninitialized => "uninitialized",
+        ExtensionType::MaxSupply => "maxSupply",
+        ExtensionType::PermanentDelegate => "permanentDelegate",
+        ExtensionType::DefaultFrozen => "defaultFrozen",
+        ExtensionType::NonTransferable => "nonTransferable",
+        ExtensionType::DelegationExpiry => "delegationExpiry",
+        ExtensionType::NonTransferableAccount => "nonTransferableAccount",
+        ExtensionType::TransferFeeConfig => "transferFeeConfig",
+        ExtensionType::TransferFeeAmount => "transferFeeAmount",
+        ExtensionType::MemoTransfer => "memoTransfer",
+        ExtensionType::ImmutableOwner => "immutableOwner",
+        ExtensionType::MintCloseAuthority => "mintCloseAuthority",
+        ExtensionType::TokenMetadata => "tokenMetadata",
+        ExtensionType::InterestBearingConfig => "interestBearingConfig",
+        ExtensionType::CpiGuard => "cpiGuard",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instruction::*;
+    use solana_program::{instruction::AccountMeta, message::Message};
+
+    #[test]
+    fn test_parse_transfer_checked() {
+        let program_id = crate::id();
+        let source = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let destination = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let instruction = transfer_checked(
+            &program_id,
+            &source,
+            &mint,
+            &destination,
+            &owner,
+            &[],
+            u64::MAX,
+            6,
+        )
+        .unwrap();
+        assert_eq!(
+            parse_instruction(&instruction).unwrap(),
+            json!({
+                "type": "transferChecked",
+                "source": source.to_string(),
+                "mint": mint.to_string(),
+                "destination": destination.to_string(),
+                "authority": owner.to_string(),
+                "amount": u64::MAX.to_string(),
+                "decimals": 6,
+            })
+        );
+
+        // the instructions sysvar appended for memos is not a signer
+        let mut instruction = instruction;
+        instruction
+            .accounts
+            .push(AccountMeta::new_readonly(sysvar::instructions::id(), false));
+        assert_eq!(
+            parse_instruction(&instruction).unwrap()["authority"],
+            json!(owner.to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_multisig_signers() {
+        let program_id = crate::id();
+        let account = Pubkey::new_unique();
+        let multisig = Pubkey::new_unique();
+        let signer1 = Pubkey::new_unique();
+        let signer2 = Pubkey::new_unique();
+        let instruction = set_authority(
+            &program_id,
+            &account,
+            None,
+            AuthorityType::CloseAccount,
+            &multisig,
+            &[&signer1, &signer2],
+        )
+        .unwrap();
+        assert_eq!(
+            parse_instruction(&instruction).unwrap(),
+            json!({
+                "type": "setAuthority",
+                "account": account.to_string(),
+                "authorityType": "closeAccount",
+                "newAuthority": null,
+                "multisigAuthority": multisig.to_string(),
+                "signers": [signer1.to_string(), signer2.to_string()],
+            })
+        );
+
+        let mint = Pubkey::new_unique();
+        let destination1 = Pubkey::new_unique();
+        let destination2 = Pubkey::new_unique();
+        let instruction = transfer_checked_batch(
+            &program_id,
+            &account,
+            &mint,
+            &[(&destination1, 1), (&destination2, 2)],
+            &multisig,
+            &[&signer1],
+            0,
+        )
+        .unwrap();
+        assert_eq!(
+            parse_instruction(&instruction).unwrap(),
+            json!({
+                "type": "transferCheckedBatch",
+                "source": account.to_string(),
+                "mint": mint.to_string(),
+                "transfers": [
+                    {"destination": destination1.to_string(), "amount": "1"},
+                    {"destination": destination2.to_string(), "amount": "2"},
+                ],
+                "decimals": 0,
+                "multisigAuthority": multisig.to_string(),
+                "signers": [signer1.to_string()],
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_compiled_instruction() {
+        let program_id = crate::id();
+        let mint = Pubkey::new_unique();
+        let mint_authority = Pubkey::new_unique();
+        let instruction = initialize_mint(&program_id, &mint, &mint_authority, None, 2).unwrap();
+        let message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_compiled_instruction(&message.instructions[0], &message.account_keys).unwrap(),
+            json!({
+                "type": "initializeMint",
+                "mint": mint.to_string(),
+                "rentSysvar": sysvar::rent::id().to_string(),
+                "decimals": 2,
+                "mintAuthority": mint_authority.to_string(),
+                "freezeAuthority": null,
+            })
+        );
+        assert_eq!(
+            parse_compiled_instruction(&message.instructions[0], &message.account_keys[..1]),
+            Err(ParseInstructionError::InvalidAccountIndex)
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let program_id = crate::id();
+        let mut instruction = transfer(
+            &program_id,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &[],
+            1,
+        )
+        .unwrap();
+        instruction.accounts.pop();
+        assert_eq!(
+            parse_instruction(&instruction),
+            Err(ParseInstructionError::NotEnoughAccounts)
+        );
+        instruction.data = vec![255];
+        assert_eq!(
+            parse_instruction(&instruction),
+            Err(ParseInstructionError::InvalidInstructionData)
+        );
+    }
+}
@@ -2,8 +2,19 @@
 
 use crate::{
     error::TokenError,
-    instruction::{is_valid_signer_index, AuthorityType, TokenInstruction, MAX_SIGNERS},
-    state::{Account, AccountState, Mint, Multisig},
+    event::{BatchTransfer, TokenEvent},
+    instruction::{
+        is_valid_signer_index, AuthorityType, TokenInstruction, MAX_PROPOSAL_ACCOUNTS,
+        MAX_PROPOSAL_DATA_LEN, MAX_SIGNERS,
+    },
+    state::{
+        get_extension, get_extension_types, init_account_type, set_extension, Account,
+        AccountState, AccountType, CpiGuard, DefaultFrozen, DelegationExpiry, Extension,
+        ExtensionType, ImmutableOwner, InterestBearingConfig, MaxSupply, MemoTransfer, Mint,
+        MintCloseAuthority, Multisig, MultisigProposal, NonTransferable, NonTransferableAccount,
+        PermanentDelegate, TokenMetadata, TransferFeeAmount, TransferFeeConfig,
+        MAX_FEE_BASIS_POINTS,
+    },
 };
 use num_traits::FromPrimitive;
 use solana_program::{
@@ -15,25 +26,35 @@ use solana_program::{
     program_option::COption,
     program_pack::{IsInitialized, Pack},
     pubkey::Pubkey,
-    sysvar::{rent::Rent, Sysvar},
+    sysvar::{clock::Clock, instructions, rent::Rent, Sysvar},
 };
 
+/// The associated token account program, whose accounts are found by their
+/// address
+mod associated_token_program {
+    solana_program::declare_id!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+}
+
 /// Program state handler.
 pub struct Processor {}
 impl Processor {
-    /// Processes an [InitializeMint](enum.TokenInstruction.html) instruction.
-    pub fn process_initialize_mint(
+    fn _process_initialize_mint(
         accounts: &[AccountInfo],
         decimals: u8,
         mint_authority: Pubkey,
         freeze_authority: COption<Pubkey>,
+        rent_sysvar_account: bool,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
         let mint_info = next_account_info(account_info_iter)?;
         let mint_data_len = mint_info.data_len();
-        let rent = &Rent::from_account_info(next_account_info(account_info_iter)?)?;
+        let rent = if rent_sysvar_account {
+            Rent::from_account_info(next_account_info(account_info_iter)?)?
+        } else {
+            Rent::get()?
+        };
 
-        let mut mint = Mint::unpack_unchecked(&mint_info.data.borrow())?;
+        let mut mint = Mint::unpack_unchecked_from_account_data(&mint_info.data.borrow())?;
         if mint.is_initialized {
             return Err(TokenError::AlreadyInUse.into());
         }
@@ -42,19 +63,49 @@ impl Processor {
             return Err(TokenError::NotRentExempt.into());
         }
 
+        let default_frozen = get_extension::<DefaultFrozen>(&mint_info.data.borrow())?;
+        if default_frozen.map_or(false, |default_frozen| default_frozen.frozen)
+            && freeze_authority.is_none()
+        {
+            return Err(TokenError::MintCannotFreeze.into());
+        }
+
         mint.mint_authority = COption::Some(mint_authority);
         mint.decimals = decimals;
         mint.is_initialized = true;
         mint.freeze_authority = freeze_authority;
 
-        Mint::pack(mint, &mut mint_info.data.borrow_mut())?;
+        let mut mint_data = mint_info.data.borrow_mut();
+        init_account_type(&mut mint_data, AccountType::Mint)?;
+        Mint::pack_into_account_data(mint, &mut mint_data)?;
 
         Ok(())
     }
 
+    /// Processes an [InitializeMint](enum.TokenInstruction.html) instruction.
+    pub fn process_initialize_mint(
+        accounts: &[AccountInfo],
+        decimals: u8,
+        mint_authority: Pubkey,
+        freeze_authority: COption<Pubkey>,
+    ) -> ProgramResult {
+        Self::_process_initialize_mint(accounts, decimals, mint_authority, freeze_authority, true)
+    }
+
+    /// Processes an [InitializeMint2](enum.TokenInstruction.html) instruction.
+    pub fn process_initialize_mint2(
+        accounts: &[AccountInfo],
+        decimals: u8,
+        mint_authority: Pubkey,
+        freeze_authority: COption<Pubkey>,
+    ) -> ProgramResult {
+        Self::_process_initialize_mint(accounts, decimals, mint_authority, freeze_authority, false)
+    }
+
     fn _process_initialize_account(
         accounts: &[AccountInfo],
         owner: Option<&Pubkey>,
+        rent_sysvar_account: bool,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
         let new_account_info = next_account_info(account_info_iter)?;
@@ -65,9 +116,14 @@ impl Processor {
             next_account_info(account_info_iter)?.key
         };
         let new_account_info_data_len = new_account_info.data_len();
-        let rent = &Rent::from_account_info(next_account_info(account_info_iter)?)?;
+        let rent = if rent_sysvar_account {
+            Rent::from_account_info(next_account_info(account_info_iter)?)?
+        } else {
+            Rent::get()?
+        };
 
-        let mut account = Account::unpack_unchecked(&new_account_info.data.borrow())?;
+        let mut account =
+            Account::unpack_unchecked_from_account_data(&new_account_info.data.borrow())?;
         if account.is_initialized() {
             return Err(TokenError::AlreadyInUse.into());
         }
@@ -76,16 +132,30 @@ impl Processor {
             return Err(TokenError::NotRentExempt.into());
         }
 
-        if *mint_info.key != crate::native_mint::id() {
-            let _ = Mint::unpack(&mint_info.data.borrow_mut())
-                .map_err(|_| Into::<ProgramError>::into(TokenError::InvalidMint))?;
-        }
+        let (default_frozen, non_transferable, transfer_fee) =
+            if *mint_info.key != crate::native_mint::id() {
+                let mint_data = mint_info.data.borrow();
+                let _ = Mint::unpack_from_account_data(&mint_data)
+                    .map_err(|_| Into::<ProgramError>::into(TokenError::InvalidMint))?;
+                (
+                    get_extension::<DefaultFrozen>(&mint_data)?
+                        .map_or(false, |default_frozen| default_frozen.frozen),
+                    get_extension::<NonTransferable>(&mint_data)?.is_some(),
+                    get_extension::<TransferFeeConfig>(&mint_data)?.is_some(),
+                )
+            } else {
+                (false, false, false)
+            };
 
         account.mint = *mint_info.key;
         account.owner = *owner;
         account.delegate = COption::None;
         account.delegated_amount = 0;
-        account.state = AccountState::Initialized;
+        account.state = if default_frozen {
+            AccountState::Frozen
+        } else {
+            AccountState::Initialized
+        };
         if *mint_info.key == crate::native_mint::id() {
             let rent_exempt_reserve = rent.minimum_balance(new_account_info_data_len);
             account.is_native = COption::Some(rent_exempt_reserve);
@@ -98,19 +168,35 @@ impl Processor {
             account.amount = 0;
         };
 
-        Account::pack(account, &mut new_account_info.data.borrow_mut())?;
+        let mut new_account_data = new_account_info.data.borrow_mut();
+        init_account_type(&mut new_account_data, AccountType::Account)?;
+        // Accounts of a non-transferable mint must have space for the extension
+        if non_transferable {
+            set_extension(&mut new_account_data, &NonTransferableAccount)?;
+        }
+        // Accounts of a mint charging transfer fees must have space to
+        // withhold them
+        if transfer_fee {
+            set_extension(&mut new_account_data, &TransferFeeAmount::default())?;
+        }
+        Account::pack_into_account_data(account, &mut new_account_data)?;
 
         Ok(())
     }
 
     /// Processes an [InitializeAccount](enum.TokenInstruction.html) instruction.
     pub fn process_initialize_account(accounts: &[AccountInfo]) -> ProgramResult {
-        Self::_process_initialize_account(accounts, None)
+        Self::_process_initialize_account(accounts, None, true)
     }
 
     /// Processes an [InitializeAccount2](enum.TokenInstruction.html) instruction.
     pub fn process_initialize_account2(accounts: &[AccountInfo], owner: Pubkey) -> ProgramResult {
-        Self::_process_initialize_account(accounts, Some(&owner))
+        Self::_process_initialize_account(accounts, Some(&owner), true)
+    }
+
+    /// Processes an [InitializeAccount3](enum.TokenInstruction.html) instruction.
+    pub fn process_initialize_account3(accounts: &[AccountInfo], owner: Pubkey) -> ProgramResult {
+        Self::_process_initialize_account(accounts, Some(&owner), false)
     }
 
     /// Processes a [InitializeMultisig](enum.TokenInstruction.html) instruction.
@@ -154,6 +240,7 @@ impl Processor {
         accounts: &[AccountInfo],
         amount: u64,
         expected_decimals: Option<u8>,
+        expected_fee: Option<u64>,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
 
@@ -168,12 +255,16 @@ impl Processor {
         let dest_account_info = next_account_info(account_info_iter)?;
         let authority_info = next_account_info(account_info_iter)?;
 
-        let mut source_account = Account::unpack(&source_account_info.data.borrow())?;
-        let mut dest_account = Account::unpack(&dest_account_info.data.borrow())?;
+        let mut source_account =
+            Account::unpack_from_account_data(&source_account_info.data.borrow())?;
+        let mut dest_account = Account::unpack_from_account_data(&dest_account_info.data.borrow())?;
 
         if source_account.is_frozen() || dest_account.is_frozen() {
             return Err(TokenError::AccountFrozen.into());
         }
+        if get_extension::<NonTransferableAccount>(&source_account_info.data.borrow())?.is_some() {
+            return Err(TokenError::NonTransferable.into());
+        }
         if source_account.amount < amount {
             return Err(TokenError::InsufficientFunds.into());
         }
@@ -181,20 +272,46 @@ impl Processor {
             return Err(TokenError::MintMismatch.into());
         }
 
-        if let Some((mint_info, expected_decimals)) = expected_mint_info {
-            if source_account.mint != *mint_info.key {
-                return Err(TokenError::MintMismatch.into());
-            }
+        let (permanent_delegate, transfer_fee_config) =
+            if let Some((mint_info, expected_decimals)) = expected_mint_info {
+                if source_account.mint != *mint_info.key {
+                    return Err(TokenError::MintMismatch.into());
+                }
 
-            let mint = Mint::unpack(&mint_info.data.borrow_mut())?;
-            if expected_decimals != mint.decimals {
-                return Err(TokenError::MintDecimalsMismatch.into());
+                let mint = Mint::unpack_from_account_data(&mint_info.data.borrow_mut())?;
+                if expected_decimals != mint.decimals {
+                    return Err(TokenError::MintDecimalsMismatch.into());
+                }
+
+                (
+                    Self::get_permanent_delegate(mint_info)?,
+                    get_extension::<TransferFeeConfig>(&mint_info.data.borrow())?,
+                )
+            } else {
+                // Transfer fees cannot be calculated without the mint
+                if get_extension::<TransferFeeAmount>(&source_account_info.data.borrow())?.is_some()
+                {
+                    return Err(TokenError::MintRequiredForTransfer.into());
+                }
+                (COption::None, None)
+            };
+
+        let fee = Self::calculate_transfer_fee(transfer_fee_config.as_ref(), amount)?;
+        if let Some(expected_fee) = expected_fee {
+            if expected_fee != fee {
+                return Err(TokenError::FeeMismatch.into());
             }
         }
 
         let self_transfer = source_account_info.key == dest_account_info.key;
 
         match source_account.delegate {
+            _ if permanent_delegate == COption::Some(*authority_info.key) => Self::validate_owner(
+                program_id,
+                authority_info.key,
+                authority_info,
+                account_info_iter.as_slice(),
+            )?,
             COption::Some(ref delegate) if authority_info.key == delegate => {
                 Self::validate_owner(
                     program_id,
@@ -202,6 +319,7 @@ impl Processor {
                     authority_info,
                     account_info_iter.as_slice(),
                 )?;
+                Self::check_delegation_expiry(source_account_info)?;
                 if source_account.delegated_amount < amount {
                     return Err(TokenError::InsufficientFunds.into());
                 }
@@ -215,14 +333,23 @@ impl Processor {
                     }
                 }
             }
-            _ => Self::validate_owner(
-                program_id,
-                &source_account.owner,
-                authority_info,
-                account_info_iter.as_slice(),
-            )?,
+            _ => {
+                Self::validate_owner(
+                    program_id,
+                    &source_account.owner,
+                    authority_info,
+                    account_info_iter.as_slice(),
+                )?;
+                if Self::is_cpi_guarded(source_account_info)? {
+                    Self::check_not_in_cpi(program_id, account_info_iter.as_slice())?;
+                }
+            }
         };
 
+        if Self::requires_memo(dest_account_info)? {
+            Self::check_previous_instruction_is_memo(account_info_iter.as_slice())?;
+        }
+
         // This check MUST occur just before the amounts are manipulated
         // to ensure self-transfers are fully validated
         if self_transfer {
@@ -235,7 +362,7 @@ impl Processor {
             .ok_or(TokenError::Overflow)?;
         dest_account.amount = dest_account
             .amount
-            .checked_add(amount)
+            .checked_add(amount.checked_sub(fee).ok_or(TokenError::Overflow)?)
             .ok_or(TokenError::Overflow)?;
 
         if source_account.is_native() {
@@ -250,8 +377,192 @@ impl Processor {
                 .ok_or(TokenError::Overflow)?;
         }
 
-        Account::pack(source_account, &mut source_account_info.data.borrow_mut())?;
-        Account::pack(dest_account, &mut dest_account_info.data.borrow_mut())?;
+        Account::pack_into_account_data(
+            source_account,
+            &mut source_account_info.data.borrow_mut(),
+        )?;
+        Account::pack_into_account_data(dest_account, &mut dest_account_info.data.borrow_mut())?;
+        if transfer_fee_config.is_some() {
+            Self::withhold_transfer_fee(dest_account_info, fee)?;
+        }
+
+        TokenEvent::Transfer {
+            mint: source_account.mint,
+            source: *source_account_info.key,
+            destination: *dest_account_info.key,
+            authority: *authority_info.key,
+            amount,
+            fee,
+        }
+        .log();
+
+        Ok(())
+    }
+
+    /// Processes a [TransferCheckedBatch](enum.TokenInstruction.html) instruction.
+    pub fn process_transfer_checked_batch(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amounts: &[u64],
+        expected_decimals: u8,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        let source_account_info = next_account_info(account_info_iter)?;
+        let mint_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let mut dest_account_infos = Vec::with_capacity(amounts.len());
+        for _ in amounts.iter() {
+            dest_account_infos.push(next_account_info(account_info_iter)?);
+        }
+
+        let mut source_account =
+            Account::unpack_from_account_data(&source_account_info.data.borrow())?;
+
+        if source_account.is_frozen() {
+            return Err(TokenError::AccountFrozen.into());
+        }
+        if get_extension::<NonTransferableAccount>(&source_account_info.data.borrow())?.is_some() {
+            return Err(TokenError::NonTransferable.into());
+        }
+        if source_account.mint != *mint_info.key {
+            return Err(TokenError::MintMismatch.into());
+        }
+
+        let mint = Mint::unpack_from_account_data(&mint_info.data.borrow_mut())?;
+        if expected_decimals != mint.decimals {
+            return Err(TokenError::MintDecimalsMismatch.into());
+        }
+        let permanent_delegate = Self::get_permanent_delegate(mint_info)?;
+        let transfer_fee_config = get_extension::<TransferFeeConfig>(&mint_info.data.borrow())?;
+
+        // Self-transfers are validated like any other entry but leave the
+        // source untouched, so only the remaining amounts are debited
+        let mut total_amount = 0u64;
+        for (dest_account_info, &amount) in dest_account_infos.iter().zip(amounts.iter()) {
+            if source_account_info.key != dest_account_info.key {
+                total_amount = total_amount
+                    .checked_add(amount)
+                    .ok_or(TokenError::Overflow)?;
+            }
+        }
+        let max_amount = amounts.iter().copied().max().unwrap_or(0);
+        if source_account.amount < total_amount || source_account.amount < max_amount {
+            return Err(TokenError::InsufficientFunds.into());
+        }
+
+        match source_account.delegate {
+            _ if permanent_delegate == COption::Some(*authority_info.key) => Self::validate_owner(
+                program_id,
+                authority_info.key,
+                authority_info,
+                account_info_iter.as_slice(),
+            )?,
+            COption::Some(ref delegate) if authority_info.key == delegate => {
+                Self::validate_owner(
+                    program_id,
+                    delegate,
+                    authority_info,
+                    account_info_iter.as_slice(),
+                )?;
+                Self::check_delegation_expiry(source_account_info)?;
+                if source_account.delegated_amount < total_amount
+                    || source_account.delegated_amount < max_amount
+                {
+                    return Err(TokenError::InsufficientFunds.into());
+                }
+                source_account.delegated_amount = source_account
+                    .delegated_amount
+                    .checked_sub(total_amount)
+                    .ok_or(TokenError::Overflow)?;
+                if source_account.delegated_amount == 0 {
+                    source_account.delegate = COption::None;
+                }
+            }
+            _ => {
+                Self::validate_owner(
+                    program_id,
+                    &source_account.owner,
+                    authority_info,
+                    account_info_iter.as_slice(),
+                )?;
+                if Self::is_cpi_guarded(source_account_info)? {
+                    Self::check_not_in_cpi(program_id, account_info_iter.as_slice())?;
+                }
+            }
+        };
+
+        let mut transfers = Vec::with_capacity(amounts.len());
+        for (dest_account_info, &amount) in dest_account_infos.iter().zip(amounts.iter()) {
+            let mut dest_account =
+                Account::unpack_from_account_data(&dest_account_info.data.borrow())?;
+
+            if dest_account.is_frozen() {
+                return Err(TokenError::AccountFrozen.into());
+            }
+            if source_account.mint != dest_account.mint {
+                return Err(TokenError::MintMismatch.into());
+            }
+            if Self::requires_memo(dest_account_info)? {
+                Self::check_previous_instruction_is_memo(account_info_iter.as_slice())?;
+            }
+
+            // This check MUST occur just before the amounts are manipulated
+            // to ensure self-transfers are fully validated
+            if source_account_info.key == dest_account_info.key {
+                continue;
+            }
+
+            let fee = Self::calculate_transfer_fee(transfer_fee_config.as_ref(), amount)?;
+            source_account.amount = source_account
+                .amount
+                .checked_sub(amount)
+                .ok_or(TokenError::Overflow)?;
+            dest_account.amount = dest_account
+                .amount
+                .checked_add(amount.checked_sub(fee).ok_or(TokenError::Overflow)?)
+                .ok_or(TokenError::Overflow)?;
+
+            if source_account.is_native() {
+                let source_starting_lamports = source_account_info.lamports();
+                **source_account_info.lamports.borrow_mut() = source_starting_lamports
+                    .checked_sub(amount)
+                    .ok_or(TokenError::Overflow)?;
+
+                let dest_starting_lamports = dest_account_info.lamports();
+                **dest_account_info.lamports.borrow_mut() = dest_starting_lamports
+                    .checked_add(amount)
+                    .ok_or(TokenError::Overflow)?;
+            }
+
+            Account::pack_into_account_data(
+                dest_account,
+                &mut dest_account_info.data.borrow_mut(),
+            )?;
+            if transfer_fee_config.is_some() {
+                Self::withhold_transfer_fee(dest_account_info, fee)?;
+            }
+            transfers.push(BatchTransfer {
+                destination: *dest_account_info.key,
+                amount,
+                fee,
+            });
+        }
+
+        Account::pack_into_account_data(
+            source_account,
+            &mut source_account_info.data.borrow_mut(),
+        )?;
+
+        if !transfers.is_empty() {
+            TokenEvent::TransferBatch {
+                mint: *mint_info.key,
+                source: *source_account_info.key,
+                authority: *authority_info.key,
+                transfers,
+            }
+            .log();
+        }
 
         Ok(())
     }
@@ -262,6 +573,7 @@ impl Processor {
         accounts: &[AccountInfo],
         amount: u64,
         expected_decimals: Option<u8>,
+        expiry_slot: Option<u64>,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
 
@@ -274,7 +586,8 @@ impl Processor {
         let delegate_info = next_account_info(account_info_iter)?;
         let owner_info = next_account_info(account_info_iter)?;
 
-        let mut source_account = Account::unpack(&source_account_info.data.borrow())?;
+        let mut source_account =
+            Account::unpack_from_account_data(&source_account_info.data.borrow())?;
 
         if source_account.is_frozen() {
             return Err(TokenError::AccountFrozen.into());
@@ -285,7 +598,7 @@ impl Processor {
                 return Err(TokenError::MintMismatch.into());
             }
 
-            let mint = Mint::unpack(&mint_info.data.borrow_mut())?;
+            let mint = Mint::unpack_from_account_data(&mint_info.data.borrow_mut())?;
             if expected_decimals != mint.decimals {
                 return Err(TokenError::MintDecimalsMismatch.into());
             }
@@ -297,11 +610,33 @@ impl Processor {
             owner_info,
             account_info_iter.as_slice(),
         )?;
+        if Self::is_cpi_guarded(source_account_info)? {
+            Self::check_not_in_cpi(program_id, account_info_iter.as_slice())?;
+        }
 
         source_account.delegate = COption::Some(*delegate_info.key);
         source_account.delegated_amount = amount;
 
-        Account::pack(source_account, &mut source_account_info.data.borrow_mut())?;
+        let mut source_account_data = source_account_info.data.borrow_mut();
+        // Approvals without an expiry clear the expiry of a previous approval
+        if expiry_slot.is_some()
+            || get_extension::<DelegationExpiry>(&source_account_data)?.is_some()
+        {
+            set_extension(
+                &mut source_account_data,
+                &DelegationExpiry {
+                    expiry_slot: expiry_slot.into(),
+                },
+            )?;
+        }
+        Account::pack_into_account_data(source_account, &mut source_account_data)?;
+
+        TokenEvent::Approve {
+            account: *source_account_info.key,
+            delegate: *delegate_info.key,
+            amount,
+        }
+        .log();
 
         Ok(())
     }
@@ -311,7 +646,8 @@ impl Processor {
         let account_info_iter = &mut accounts.iter();
         let source_account_info = next_account_info(account_info_iter)?;
 
-        let mut source_account = Account::unpack(&source_account_info.data.borrow())?;
+        let mut source_account =
+            Account::unpack_from_account_data(&source_account_info.data.borrow())?;
 
         let owner_info = next_account_info(account_info_iter)?;
 
@@ -329,7 +665,15 @@ impl Processor {
         source_account.delegate = COption::None;
         source_account.delegated_amount = 0;
 
-        Account::pack(source_account, &mut source_account_info.data.borrow_mut())?;
+        Account::pack_into_account_data(
+            source_account,
+            &mut source_account_info.data.borrow_mut(),
+        )?;
+
+        TokenEvent::Revoke {
+            account: *source_account_info.key,
+        }
+        .log();
 
         Ok(())
     }
@@ -345,15 +689,21 @@ impl Processor {
         let account_info = next_account_info(account_info_iter)?;
         let authority_info = next_account_info(account_info_iter)?;
 
-        if account_info.data_len() == Account::get_packed_len() {
-            let mut account = Account::unpack(&account_info.data.borrow())?;
+        if Account::is_account_data(&account_info.data.borrow()) {
+            let mut account = Account::unpack_from_account_data(&account_info.data.borrow())?;
 
             if account.is_frozen() {
                 return Err(TokenError::AccountFrozen.into());
             }
+            if Self::is_cpi_guarded(account_info)? {
+                Self::check_not_in_cpi(program_id, account_info_iter.as_slice())?;
+            }
 
             match authority_type {
                 AuthorityType::AccountOwner => {
+                    if get_extension::<ImmutableOwner>(&account_info.data.borrow())?.is_some() {
+                        return Err(TokenError::ImmutableOwner.into());
+                    }
                     Self::validate_owner(
                         program_id,
                         &account.owner,
@@ -381,9 +731,9 @@ impl Processor {
                     return Err(TokenError::AuthorityTypeNotSupported.into());
                 }
             }
-            Account::pack(account, &mut account_info.data.borrow_mut())?;
-        } else if account_info.data_len() == Mint::get_packed_len() {
-            let mut mint = Mint::unpack(&account_info.data.borrow())?;
+            Account::pack_into_account_data(account, &mut account_info.data.borrow_mut())?;
+        } else if Mint::is_mint_data(&account_info.data.borrow()) {
+            let mut mint = Mint::unpack_from_account_data(&account_info.data.borrow())?;
             match authority_type {
                 AuthorityType::MintTokens => {
                     // Once a mint's supply is fixed, it cannot be undone by setting a new
@@ -413,11 +763,106 @@ impl Processor {
                     )?;
                     mint.freeze_authority = new_authority;
                 }
+                AuthorityType::PermanentDelegate => {
+                    // Once a mint's permanent delegate is removed, it cannot be
+                    // re-added by setting a new permanent_delegate
+                    let permanent_delegate = Self::get_permanent_delegate(account_info)?.ok_or(
+                        Into::<ProgramError>::into(TokenError::AuthorityTypeNotSupported),
+                    )?;
+                    Self::validate_owner(
+                        program_id,
+                        &permanent_delegate,
+                        authority_info,
+                        account_info_iter.as_slice(),
+                    )?;
+                    set_extension(
+                        &mut account_info.data.borrow_mut(),
+                        &PermanentDelegate {
+                            delegate: new_authority,
+                        },
+                    )?;
+                }
+                AuthorityType::CloseMint => {
+                    // Once a mint's close authority is removed, it cannot be
+                    // re-added by setting a new close authority
+                    let close_authority = Self::get_mint_close_authority(account_info)?.ok_or(
+                        Into::<ProgramError>::into(TokenError::AuthorityTypeNotSupported),
+                    )?;
+                    Self::validate_owner(
+                        program_id,
+                        &close_authority,
+                        authority_info,
+                        account_info_iter.as_slice(),
+                    )?;
+                    set_extension(
+                        &mut account_info.data.borrow_mut(),
+                        &MintCloseAuthority {
+                            close_authority: new_authority,
+                        },
+                    )?;
+                }
+                AuthorityType::MetadataUpdate => {
+                    let mut token_metadata =
+                        get_extension::<TokenMetadata>(&account_info.data.borrow())?
+                            .ok_or(TokenError::AuthorityTypeNotSupported)?;
+                    // Once a mint's metadata update authority is removed, it
+                    // cannot be re-added by setting a new update authority
+                    let update_authority = token_metadata
+                        .update_authority
+                        .ok_or(TokenError::AuthorityTypeNotSupported)?;
+                    Self::validate_owner(
+                        program_id,
+                        &update_authority,
+                        authority_info,
+                        account_info_iter.as_slice(),
+                    )?;
+                    token_metadata.update_authority = new_authority;
+                    set_extension(&mut account_info.data.borrow_mut(), &token_metadata)?;
+                }
+                AuthorityType::InterestRate => {
+                    let mut interest_bearing_config =
+                        get_extension::<InterestBearingConfig>(&account_info.data.borrow())?
+                            .ok_or(TokenError::AuthorityTypeNotSupported)?;
+                    // Once a mint's rate authority is removed, it cannot be
+                    // re-added by setting a new rate authority
+                    let rate_authority = interest_bearing_config
+                        .rate_authority
+                        .ok_or(TokenError::AuthorityTypeNotSupported)?;
+                    Self::validate_owner(
+                        program_id,
+                        &rate_authority,
+                        authority_info,
+                        account_info_iter.as_slice(),
+                    )?;
+                    interest_bearing_config.rate_authority = new_authority;
+                    set_extension(
+                        &mut account_info.data.borrow_mut(),
+                        &interest_bearing_config,
+                    )?;
+                }
+                AuthorityType::WithdrawWithheldTokens => {
+                    let mut transfer_fee_config =
+                        get_extension::<TransferFeeConfig>(&account_info.data.borrow())?
+                            .ok_or(TokenError::AuthorityTypeNotSupported)?;
+                    // Once a mint's withdraw withheld authority is disabled, it
+                    // cannot be re-enabled by setting a new authority
+                    let withdraw_withheld_authority = transfer_fee_config
+                        .withdraw_withheld_authority
+                        .ok_or(TokenError::AuthorityTypeNotSupported)?;
+                    Self::validate_owner(
+                        program_id,
+                        &withdraw_withheld_authority,
+                        authority_info,
+                        account_info_iter.as_slice(),
+                    )?;
+                    transfer_fee_config.withdraw_withheld_authority = new_authority;
+                    set_extension(&mut account_info.data.borrow_mut(), &transfer_fee_config)?;
+                }
                 _ => {
                     return Err(TokenError::AuthorityTypeNotSupported.into());
                 }
             }
-            Mint::pack(mint, &mut account_info.data.borrow_mut())?;
+            Mint::pack_into_account_data(mint, &mut account_info.data.borrow_mut())?;
         } else {
             return Err(ProgramError::InvalidArgument);
         }
@@ -437,7 +882,7 @@ impl Processor {
         let dest_account_info = next_account_info(account_info_iter)?;
         let owner_info = next_account_info(account_info_iter)?;
 
-        let mut dest_account = Account::unpack(&dest_account_info.data.borrow())?;
+        let mut dest_account = Account::unpack_from_account_data(&dest_account_info.data.borrow())?;
         if dest_account.is_frozen() {
             return Err(TokenError::AccountFrozen.into());
         }
@@ -449,7 +894,7 @@ impl Processor {
             return Err(TokenError::MintMismatch.into());
         }
 
-        let mut mint = Mint::unpack(&mint_info.data.borrow())?;
+        let mut mint = Mint::unpack_from_account_data(&mint_info.data.borrow())?;
         if let Some(expected_decimals) = expected_decimals {
             if expected_decimals != mint.decimals {
                 return Err(TokenError::MintDecimalsMismatch.into());
@@ -476,8 +921,21 @@ impl Processor {
             .checked_add(amount)
             .ok_or(TokenError::Overflow)?;
 
-        Account::pack(dest_account, &mut dest_account_info.data.borrow_mut())?;
-        Mint::pack(mint, &mut mint_info.data.borrow_mut())?;
+        if let Some(max_supply) = get_extension::<MaxSupply>(&mint_info.data.borrow())? {
+            if mint.supply > max_supply.max_supply {
+                return Err(TokenError::MaxSupplyExceeded.into());
+            }
+        }
+
+        Account::pack_into_account_data(dest_account, &mut dest_account_info.data.borrow_mut())?;
+        Mint::pack_into_account_data(mint, &mut mint_info.data.borrow_mut())?;
+
+        TokenEvent::MintTo {
+            mint: *mint_info.key,
+            account: *dest_account_info.key,
+            amount,
+        }
+        .log();
 
         Ok(())
     }
@@ -495,8 +953,9 @@ impl Processor {
         let mint_info = next_account_info(account_info_iter)?;
         let authority_info = next_account_info(account_info_iter)?;
 
-        let mut source_account = Account::unpack(&source_account_info.data.borrow())?;
-        let mut mint = Mint::unpack(&mint_info.data.borrow())?;
+        let mut source_account =
+            Account::unpack_from_account_data(&source_account_info.data.borrow())?;
+        let mut mint = Mint::unpack_from_account_data(&mint_info.data.borrow())?;
 
         if source_account.is_frozen() {
             return Err(TokenError::AccountFrozen.into());
@@ -517,7 +976,15 @@ impl Processor {
             }
         }
 
+        let permanent_delegate = Self::get_permanent_delegate(mint_info)?;
+
         match source_account.delegate {
+            _ if permanent_delegate == COption::Some(*authority_info.key) => Self::validate_owner(
+                program_id,
+                authority_info.key,
+                authority_info,
+                account_info_iter.as_slice(),
+            )?,
             COption::Some(ref delegate) if authority_info.key == delegate => {
                 Self::validate_owner(
                     program_id,
@@ -525,6 +992,7 @@ impl Processor {
                     authority_info,
                     account_info_iter.as_slice(),
                 )?;
+                Self::check_delegation_expiry(source_account_info)?;
 
                 if source_account.delegated_amount < amount {
                     return Err(TokenError::InsufficientFunds.into());
@@ -554,8 +1022,18 @@ impl Processor {
             .checked_sub(amount)
             .ok_or(TokenError::Overflow)?;
 
-        Account::pack(source_account, &mut source_account_info.data.borrow_mut())?;
-        Mint::pack(mint, &mut mint_info.data.borrow_mut())?;
+        Account::pack_into_account_data(
+            source_account,
+            &mut source_account_info.data.borrow_mut(),
+        )?;
+        Mint::pack_into_account_data(mint, &mut mint_info.data.borrow_mut())?;
+
+        TokenEvent::Burn {
+            mint: *mint_info.key,
+            account: *source_account_info.key,
+            amount,
+        }
+        .log();
 
         Ok(())
     }
@@ -567,10 +1045,28 @@ impl Processor {
         let dest_account_info = next_account_info(account_info_iter)?;
         let authority_info = next_account_info(account_info_iter)?;
 
-        let mut source_account = Account::unpack(&source_account_info.data.borrow())?;
+        if Mint::is_mint_data(&source_account_info.data.borrow()) {
+            return Self::_process_close_mint(
+                program_id,
+                source_account_info,
+                dest_account_info,
+                authority_info,
+                account_info_iter.as_slice(),
+            );
+        }
+
+        let mut source_account =
+            Account::unpack_from_account_data(&source_account_info.data.borrow())?;
         if !source_account.is_native() && source_account.amount != 0 {
             return Err(TokenError::NonNativeHasBalance.into());
         }
+        if let Some(transfer_fee_amount) =
+            get_extension::<TransferFeeAmount>(&source_account_info.data.borrow())?
+        {
+            if transfer_fee_amount.withheld_amount != 0 {
+                return Err(TokenError::AccountHasWithheldTransferFees.into());
+            }
+        }
 
         let authority = source_account
             .close_authority
@@ -581,16 +1077,79 @@ impl Processor {
             authority_info,
             account_info_iter.as_slice(),
         )?;
+        if Self::is_cpi_guarded(source_account_info)? {
+            Self::check_not_in_cpi(program_id, account_info_iter.as_slice())?;
+        }
 
+        let lamports = source_account_info.lamports();
         let dest_starting_lamports = dest_account_info.lamports();
         **dest_account_info.lamports.borrow_mut() = dest_starting_lamports
-            .checked_add(source_account_info.lamports())
+            .checked_add(lamports)
             .ok_or(TokenError::Overflow)?;
 
         **source_account_info.lamports.borrow_mut() = 0;
         source_account.amount = 0;
 
-        Account::pack(source_account, &mut source_account_info.data.borrow_mut())?;
+        Account::pack_into_account_data(
+            source_account,
+            &mut source_account_info.data.borrow_mut(),
+        )?;
+
+        TokenEvent::Close {
+            account: *source_account_info.key,
+            destination: *dest_account_info.key,
+            lamports,
+        }
+        .log();
+
+        Ok(())
+    }
+
+    fn _process_close_mint(
+        program_id: &Pubkey,
+        mint_info: &AccountInfo,
+        dest_account_info: &AccountInfo,
+        authority_info: &AccountInfo,
+        signers: &[AccountInfo],
+    ) -> ProgramResult {
+        let mint = Mint::unpack_from_account_data(&mint_info.data.borrow())?;
+        if mint.supply != 0 {
+            return Err(TokenError::MintHasSupply.into());
+        }
+        if let Some(transfer_fee_config) =
+            get_extension::<TransferFeeConfig>(&mint_info.data.borrow())?
+        {
+            if transfer_fee_config.withheld_amount != 0 {
+                return Err(TokenError::AccountHasWithheldTransferFees.into());
+            }
+        }
+
+        let close_authority =
+            Self::get_mint_close_authority(mint_info)?.ok_or(Into::<ProgramError>::into(
+                TokenError::AuthorityTypeNotSupported,
+            ))?;
+        Self::validate_owner(program_id, &close_authority, authority_info, signers)?;
+
+        let lamports = mint_info.lamports();
+        let dest_starting_lamports = dest_account_info.lamports();
+        **dest_account_info.lamports.borrow_mut() = dest_starting_lamports
+            .checked_add(lamports)
+            .ok_or(TokenError::Overflow)?;
+
+        **mint_info.lamports.borrow_mut() = 0;
+
+        // Clear the mint so that it cannot be used again within the same
+        // Transaction
+        for byte in mint_info.data.borrow_mut().iter_mut() {
+            *byte = 0;
+        }
+
+        TokenEvent::Close {
+            account: *mint_info.key,
+            destination: *dest_account_info.key,
+            lamports,
+        }
+        .log();
 
         Ok(())
     }
@@ -607,7 +1166,8 @@ impl Processor {
         let mint_info = next_account_info(account_info_iter)?;
         let authority_info = next_account_info(account_info_iter)?;
 
-        let mut source_account = Account::unpack(&source_account_info.data.borrow())?;
+        let mut source_account =
+            Account::unpack_from_account_data(&source_account_info.data.borrow())?;
         if freeze && source_account.is_frozen() || !freeze && !source_account.is_frozen() {
             return Err(TokenError::InvalidState.into());
         }
@@ -618,7 +1178,7 @@ impl Processor {
             return Err(TokenError::MintMismatch.into());
         }
 
-        let mint = Mint::unpack(&mint_info.data.borrow_mut())?;
+        let mint = Mint::unpack_from_account_data(&mint_info.data.borrow_mut())?;
         match mint.freeze_authority {
             COption::Some(authority) => Self::validate_owner(
                 program_id,
@@ -635,831 +1195,5148 @@ impl Processor {
             AccountState::Initialized
         };
 
-        Account::pack(source_account, &mut source_account_info.data.borrow_mut())?;
+        Account::pack_into_account_data(
+            source_account,
+            &mut source_account_info.data.borrow_mut(),
+        )?;
 
-        Ok(())
-    }
-
-    /// Processes an [Instruction](enum.Instruction.html).
-    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
-        let instruction = TokenInstruction::unpack(input)?;
-
-        match instruction {
-            TokenInstruction::InitializeMint {
-                decimals,
-                mint_authority,
-                freeze_authority,
-            } => {
-                msg!("Instruction: InitializeMint");
-                Self::process_initialize_mint(accounts, decimals, mint_authority, freeze_authority)
-            }
-            TokenInstruction::InitializeAccount => {
-                msg!("Instruction: InitializeAccount");
-                Self::process_initialize_account(accounts)
-            }
-            TokenInstruction::InitializeAccount2 { owner } => {
-                msg!("Instruction: InitializeAccount2");
-                Self::process_initialize_account2(accounts, owner)
-            }
-            TokenInstruction::InitializeMultisig { m } => {
-                msg!("Instruction: InitializeMultisig");
-                Self::process_initialize_multisig(accounts, m)
-            }
-            TokenInstruction::Transfer { amount } => {
-                msg!("Instruction: Transfer");
-                Self::process_transfer(program_id, accounts, amount, None)
-            }
-            TokenInstruction::Approve { amount } => {
-                msg!("Instruction: Approve");
-                Self::process_approve(program_id, accounts, amount, None)
-            }
-            TokenInstruction::Revoke => {
-                msg!("Instruction: Revoke");
-                Self::process_revoke(program_id, accounts)
-            }
-            TokenInstruction::SetAuthority {
-                authority_type,
-                new_authority,
-            } => {
-                msg!("Instruction: SetAuthority");
-                Self::process_set_authority(program_id, accounts, authority_type, new_authority)
-            }
-            TokenInstruction::MintTo { amount } => {
-                msg!("Instruction: MintTo");
-                Self::process_mint_to(program_id, accounts, amount, None)
-            }
-            TokenInstruction::Burn { amount } => {
-                msg!("Instruction: Burn");
-                Self::process_burn(program_id, accounts, amount, None)
-            }
-            TokenInstruction::CloseAccount => {
-                msg!("Instruction: CloseAccount");
-                Self::process_close_account(program_id, accounts)
-            }
-            TokenInstruction::FreezeAccount => {
-                msg!("Instruction: FreezeAccount");
-                Self::process_toggle_freeze_account(program_id, accounts, true)
-            }
-            TokenInstruction::ThawAccount => {
-                msg!("Instruction: FreezeAccount");
-                Self::process_toggle_freeze_account(program_id, accounts, false)
-            }
-            TokenInstruction::TransferChecked { amount, decimals } => {
-                msg!("Instruction: TransferChecked");
-                Self::process_transfer(program_id, accounts, amount, Some(decimals))
-            }
-            TokenInstruction::ApproveChecked { amount, decimals } => {
-                msg!("Instruction: ApproveChecked");
-                Self::process_approve(program_id, accounts, amount, Some(decimals))
-            }
-            TokenInstruction::MintToChecked { amount, decimals } => {
-                msg!("Instruction: MintToChecked");
-                Self::process_mint_to(program_id, accounts, amount, Some(decimals))
+        let event = if freeze {
+            TokenEvent::Freeze {
+                mint: *mint_info.key,
+                account: *source_account_info.key,
             }
-            TokenInstruction::BurnChecked { amount, decimals } => {
-                msg!("Instruction: BurnChecked");
-                Self::process_burn(program_id, accounts, amount, Some(decimals))
+        } else {
+            TokenEvent::Thaw {
+                mint: *mint_info.key,
+                account: *source_account_info.key,
             }
-        }
+        };
+        event.log();
+
+        Ok(())
     }
 
-    /// Validates owner(s) are present
-    pub fn validate_owner(
+    /// Processes an [EnableRequiredTransferMemos](enum.TokenInstruction.html) or a
+    /// [DisableRequiredTransferMemos](enum.TokenInstruction.html) instruction.
+    pub fn process_toggle_required_transfer_memos(
         program_id: &Pubkey,
-        expected_owner: &Pubkey,
-        owner_account_info: &AccountInfo,
-        signers: &[AccountInfo],
+        accounts: &[AccountInfo],
+        require_memos: bool,
     ) -> ProgramResult {
-        if expected_owner != owner_account_info.key {
-            return Err(TokenError::OwnerMismatch.into());
-        }
-        if program_id == owner_account_info.owner
-            && owner_account_info.data_len() == Multisig::get_packed_len()
-        {
-            let multisig = Multisig::unpack(&owner_account_info.data.borrow())?;
-            let mut num_signers = 0;
-            let mut matched = [false; MAX_SIGNERS];
-            for signer in signers.iter() {
-                for (position, key) in multisig.signers[0..multisig.n as usize].iter().enumerate() {
-                    if key == signer.key && !matched[position] {
-                        if !signer.is_signer {
-                            return Err(ProgramError::MissingRequiredSignature);
-                        }
-                        matched[position] = true;
-                        num_signers += 1;
-                    }
-                }
-            }
-            if num_signers < multisig.m {
-                return Err(ProgramError::MissingRequiredSignature);
-            }
-            return Ok(());
-        } else if !owner_account_info.is_signer {
-            return Err(ProgramError::MissingRequiredSignature);
-        }
-        Ok(())
+        let account_info_iter = &mut accounts.iter();
+        let token_account_info = next_account_info(account_info_iter)?;
+        let owner_info = next_account_info(account_info_iter)?;
+
+        let token_account = Account::unpack_from_account_data(&token_account_info.data.borrow())?;
+        Self::validate_owner(
+            program_id,
+            &token_account.owner,
+            owner_info,
+            account_info_iter.as_slice(),
+        )?;
+
+        set_extension(
+            &mut token_account_info.data.borrow_mut(),
+            &MemoTransfer {
+                require_incoming_transfer_memos: require_memos,
+            },
+        )
     }
-}
 
-impl PrintProgramError for TokenError {
-    fn print<E>(&self)
-    where
-        E: 'static + std::error::Error + DecodeError<E> + PrintProgramError + FromPrimitive,
-    {
-        match self {
-            TokenError::NotRentExempt => msg!("Error: Lamport balance below rent-exempt threshold"),
-            TokenError::InsufficientFunds => msg!("Error: insufficient funds"),
-            TokenError::InvalidMint => msg!("Error: Invalid Mint"),
-            TokenError::MintMismatch => msg!("Error: Account not associated with this Mint"),
-            TokenError::OwnerMismatch => msg!("Error: owner does not match"),
-            TokenError::FixedSupply => msg!("Error: the total supply of this token is fixed"),
-            TokenError::AlreadyInUse => msg!("Error: account or token already in use"),
-            TokenError::InvalidNumberOfProvidedSigners => {
-                msg!("Error: Invalid number of provided signers")
-            }
-            TokenError::InvalidNumberOfRequiredSigners => {
-                msg!("Error: Invalid number of required signers")
-            }
-            TokenError::UninitializedState => msg!("Error: State is uninitialized"),
-            TokenError::NativeNotSupported => {
-                msg!("Error: Instruction does not support native tokens")
-            }
-            TokenError::NonNativeHasBalance => {
-                msg!("Error: Non-native account can only be closed if its balance is zero")
-            }
-            TokenError::InvalidInstruction => msg!("Error: Invalid instruction"),
-            TokenError::InvalidState => msg!("Error: Invalid account state for operation"),
-            TokenError::Overflow => msg!("Error: Operation overflowed"),
-            TokenError::AuthorityTypeNotSupported => {
-                msg!("Error: Account does not support specified authority type")
-            }
-            TokenError::MintCannotFreeze => msg!("Error: This token mint cannot freeze accounts"),
-            TokenError::AccountFrozen => msg!("Error: Account is frozen"),
-            TokenError::MintDecimalsMismatch => {
-                msg!("Error: decimals different from the Mint decimals")
-            }
+    /// Processes an [EnableCpiGuard](enum.TokenInstruction.html) or a
+    /// [DisableCpiGuard](enum.TokenInstruction.html) instruction.
+    pub fn process_toggle_cpi_guard(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        lock_cpi: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let token_account_info = next_account_info(account_info_iter)?;
+        let owner_info = next_account_info(account_info_iter)?;
+
+        let token_account = Account::unpack_from_account_data(&token_account_info.data.borrow())?;
+        Self::validate_owner(
+            program_id,
+            &token_account.owner,
+            owner_info,
+            account_info_iter.as_slice(),
+        )?;
+        if !lock_cpi && Self::is_cpi_guarded(token_account_info)? {
+            Self::check_not_in_cpi(program_id, account_info_iter.as_slice())?;
         }
+
+        set_extension(
+            &mut token_account_info.data.borrow_mut(),
+            &CpiGuard { lock_cpi },
+        )
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::instruction::*;
-    use solana_program::{
-        account_info::IntoAccountInfo, clock::Epoch, instruction::Instruction, sysvar::rent,
-    };
-    use solana_sdk::account::{
-        create_account, create_is_signer_account_infos, Account as SolanaAccount,
-    };
+    /// Processes an [InitializeImmutableOwner](enum.TokenInstruction.html) instruction.
+    pub fn process_initialize_immutable_owner(accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let token_account_info = next_account_info(account_info_iter)?;
 
-    fn do_process_instruction(
-        instruction: Instruction,
-        accounts: Vec<&mut SolanaAccount>,
-    ) -> ProgramResult {
-        let mut meta = instruction
-            .accounts
-            .iter()
-            .zip(accounts)
-            .map(|(account_meta, account)| (&account_meta.pubkey, account_meta.is_signer, account))
-            .collect::<Vec<_>>();
+        let token_account =
+            Account::unpack_unchecked_from_account_data(&token_account_info.data.borrow())?;
+        if token_account.is_initialized() {
+            return Err(TokenError::AlreadyInUse.into());
+        }
 
-        let account_infos = create_is_signer_account_infos(&mut meta);
-        Processor::process(&instruction.program_id, &account_infos, &instruction.data)
+        set_extension(&mut token_account_info.data.borrow_mut(), &ImmutableOwner)
     }
 
-    fn do_process_instruction_dups(
-        instruction: Instruction,
-        account_infos: Vec<AccountInfo>,
+    /// Adds an extension to a mint that is not yet initialized
+    fn _process_initialize_mint_extension<E: Extension>(
+        accounts: &[AccountInfo],
+        extension: &E,
     ) -> ProgramResult {
-        Processor::process(&instruction.program_id, &account_infos, &instruction.data)
-    }
+        let account_info_iter = &mut accounts.iter();
+        let mint_info = next_account_info(account_info_iter)?;
 
-    fn return_token_error_as_program_error() -> ProgramError {
-        TokenError::MintMismatch.into()
-    }
+        let mint = Mint::unpack_unchecked_from_account_data(&mint_info.data.borrow())?;
+        if mint.is_initialized {
+            return Err(TokenError::AlreadyInUse.into());
+        }
 
-    fn rent_sysvar() -> SolanaAccount {
-        create_account(&Rent::default(), 42)
+        set_extension(&mut mint_info.data.borrow_mut(), extension)
     }
 
-    fn mint_minimum_balance() -> u64 {
-        Rent::default().minimum_balance(Mint::get_packed_len())
+    /// Processes an [InitializeMaxSupply](enum.TokenInstruction.html) instruction.
+    pub fn process_initialize_max_supply(
+        accounts: &[AccountInfo],
+        max_supply: u64,
+    ) -> ProgramResult {
+        Self::_process_initialize_mint_extension(accounts, &MaxSupply { max_supply })
     }
 
-    fn account_minimum_balance() -> u64 {
-        Rent::default().minimum_balance(Account::get_packed_len())
+    /// Processes an [InitializePermanentDelegate](enum.TokenInstruction.html) instruction.
+    pub fn process_initialize_permanent_delegate(
+        accounts: &[AccountInfo],
+        delegate: Pubkey,
+    ) -> ProgramResult {
+        Self::_process_initialize_mint_extension(
+            accounts,
+            &PermanentDelegate {
+                delegate: COption::Some(delegate),
+            },
+        )
     }
 
-    fn multisig_minimum_balance() -> u64 {
-        Rent::default().minimum_balance(Multisig::get_packed_len())
+    /// Processes an [InitializeMintCloseAuthority](enum.TokenInstruction.html) instruction.
+    pub fn process_initialize_mint_close_authority(
+        accounts: &[AccountInfo],
+        close_authority: Pubkey,
+    ) -> ProgramResult {
+        Self::_process_initialize_mint_extension(
+            accounts,
+            &MintCloseAuthority {
+                close_authority: COption::Some(close_authority),
+            },
+        )
     }
 
-    #[test]
-    fn test_print_error() {
-        let error = return_token_error_as_program_error();
-        error.print::<TokenError>();
-    }
+    /// Processes an [InitializeTokenMetadata](enum.TokenInstruction.html) instruction.
+    pub fn process_initialize_token_metadata(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        update_authority: COption<Pubkey>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> ProgramResult {
+        if !TokenMetadata::has_valid_lengths(&name, &symbol, &uri) {
+            return Err(TokenError::MetadataFieldTooLong.into());
+        }
+        let token_metadata = TokenMetadata {
+            update_authority,
+            name,
+            symbol,
+            uri,
+        };
 
-    #[test]
-    #[should_panic(expected = "Custom(3)")]
-    fn test_error_unwrap() {
-        Err::<(), ProgramError>(return_token_error_as_program_error()).unwrap();
+        let account_info_iter = &mut accounts.iter();
+        let mint_info = next_account_info(account_info_iter)?;
+        let mint = Mint::unpack_unchecked_from_account_data(&mint_info.data.borrow())?;
+        if !mint.is_initialized {
+            return Self::_process_initialize_mint_extension(accounts, &token_metadata);
+        }
+
+        // Initialized mints gain metadata once, with their mint authority's
+        // approval
+        let authority_info = next_account_info(account_info_iter)?;
+        let mint_authority = mint
+            .mint_authority
+            .ok_or(Into::<ProgramError>::into(TokenError::FixedSupply))?;
+        Self::validate_owner(
+            program_id,
+            &mint_authority,
+            authority_info,
+            account_info_iter.as_slice(),
+        )?;
+        if get_extension::<TokenMetadata>(&mint_info.data.borrow())?.is_some() {
+            return Err(TokenError::AlreadyInUse.into());
+        }
+        set_extension(&mut mint_info.data.borrow_mut(), &token_metadata)
+    }
+
+    /// Processes an [UpdateTokenMetadata](enum.TokenInstruction.html) instruction.
+    pub fn process_update_token_metadata(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let mint_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+
+        if !TokenMetadata::has_valid_lengths(&name, &symbol, &uri) {
+            return Err(TokenError::MetadataFieldTooLong.into());
+        }
+        // Only initialized mints may be updated
+        Mint::unpack_from_account_data(&mint_info.data.borrow())?;
+        let mut token_metadata = get_extension::<TokenMetadata>(&mint_info.data.borrow())?
+            .ok_or(TokenError::NoTokenMetadata)?;
+        let update_authority = token_metadata
+            .update_authority
+            .ok_or(TokenError::AuthorityTypeNotSupported)?;
+        Self::validate_owner(
+            program_id,
+            &update_authority,
+            authority_info,
+            account_info_iter.as_slice(),
+        )?;
+
+        token_metadata.name = name;
+        token_metadata.symbol = symbol;
+        token_metadata.uri = uri;
+        set_extension(&mut mint_info.data.borrow_mut(), &token_metadata)
+    }
+
+    /// Processes an [InitializeInterestBearingMint](enum.TokenInstruction.html) instruction.
+    pub fn process_initialize_interest_bearing_mint(
+        accounts: &[AccountInfo],
+        rate_authority: COption<Pubkey>,
+        rate: i16,
+    ) -> ProgramResult {
+        let unix_timestamp = Clock::get()?.unix_timestamp;
+        Self::_process_initialize_mint_extension(
+            accounts,
+            &InterestBearingConfig {
+                rate_authority,
+                initialization_timestamp: unix_timestamp,
+                pre_update_average_rate: rate,
+                last_update_timestamp: unix_timestamp,
+                current_rate: rate,
+            },
+        )
+    }
+
+    /// Processes an [UpdateInterestRate](enum.TokenInstruction.html) instruction.
+    pub fn process_update_interest_rate(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        rate: i16,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let mint_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+
+        // Only initialized mints may be updated
+        Mint::unpack_from_account_data(&mint_info.data.borrow())?;
+        let mut interest_bearing_config =
+            get_extension::<InterestBearingConfig>(&mint_info.data.borrow())?
+                .ok_or(TokenError::AuthorityTypeNotSupported)?;
+        let rate_authority = interest_bearing_config
+            .rate_authority
+            .ok_or(TokenError::AuthorityTypeNotSupported)?;
+        Self::validate_owner(
+            program_id,
+            &rate_authority,
+            authority_info,
+            account_info_iter.as_slice(),
+        )?;
+
+        let unix_timestamp = Clock::get()?.unix_timestamp;
+        interest_bearing_config.pre_update_average_rate =
+            interest_bearing_config.time_weighted_average_rate(unix_timestamp);
+        interest_bearing_config.last_update_timestamp = unix_timestamp;
+        interest_bearing_config.current_rate = rate;
+        set_extension(&mut mint_info.data.borrow_mut(), &interest_bearing_config)
+    }
+
+    /// Processes an [InitializeDefaultFrozen](enum.TokenInstruction.html) instruction.
+    pub fn process_initialize_default_frozen(accounts: &[AccountInfo]) -> ProgramResult {
+        Self::_process_initialize_mint_extension(accounts, &DefaultFrozen { frozen: true })
+    }
+
+    /// Processes an [InitializeNonTransferableMint](enum.TokenInstruction.html) instruction.
+    pub fn process_initialize_non_transferable_mint(accounts: &[AccountInfo]) -> ProgramResult {
+        Self::_process_initialize_mint_extension(accounts, &NonTransferable)
+    }
+
+    /// Processes an [InitializeTransferFeeConfig](enum.TokenInstruction.html) instruction.
+    pub fn process_initialize_transfer_fee_config(
+        accounts: &[AccountInfo],
+        withdraw_withheld_authority: COption<Pubkey>,
+        transfer_fee_basis_points: u16,
+        maximum_fee: u64,
+    ) -> ProgramResult {
+        if transfer_fee_basis_points > MAX_FEE_BASIS_POINTS {
+            return Err(TokenError::TransferFeeExceedsMaximum.into());
+        }
+        Self::_process_initialize_mint_extension(
+            accounts,
+            &TransferFeeConfig {
+                withdraw_withheld_authority,
+                transfer_fee_basis_points,
+                maximum_fee,
+                withheld_amount: 0,
+            },
+        )
+    }
+
+    /// Processes a [WithdrawWithheldTokensFromMint](enum.TokenInstruction.html) instruction.
+    pub fn process_withdraw_withheld_tokens_from_mint(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let mint_info = next_account_info(account_info_iter)?;
+        let dest_account_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+
+        let mut transfer_fee_config = Self::get_transfer_fee_config(mint_info)?;
+        let withdraw_withheld_authority = transfer_fee_config
+            .withdraw_withheld_authority
+            .ok_or(TokenError::AuthorityTypeNotSupported)?;
+        Self::validate_owner(
+            program_id,
+            &withdraw_withheld_authority,
+            authority_info,
+            account_info_iter.as_slice(),
+        )?;
+
+        let mut dest_account = Account::unpack_from_account_data(&dest_account_info.data.borrow())?;
+        if dest_account.mint != *mint_info.key {
+            return Err(TokenError::MintMismatch.into());
+        }
+        if dest_account.is_frozen() {
+            return Err(TokenError::AccountFrozen.into());
+        }
+
+        let withheld_amount = transfer_fee_config.withheld_amount;
+        dest_account.amount = dest_account
+            .amount
+            .checked_add(withheld_amount)
+            .ok_or(TokenError::Overflow)?;
+        transfer_fee_config.withheld_amount = 0;
+
+        set_extension(&mut mint_info.data.borrow_mut(), &transfer_fee_config)?;
+        Account::pack_into_account_data(dest_account, &mut dest_account_info.data.borrow_mut())?;
+
+        TokenEvent::WithdrawWithheld {
+            mint: *mint_info.key,
+            destination: *dest_account_info.key,
+            amount: withheld_amount,
+        }
+        .log();
+
+        Ok(())
+    }
+
+    /// Processes a [WithdrawWithheldTokensFromAccounts](enum.TokenInstruction.html) instruction.
+    pub fn process_withdraw_withheld_tokens_from_accounts(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        num_token_accounts: u8,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let mint_info = next_account_info(account_info_iter)?;
+        let dest_account_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+        let mut source_account_infos = Vec::with_capacity(num_token_accounts as usize);
+        for _ in 0..num_token_accounts {
+            source_account_infos.push(next_account_info(account_info_iter)?);
+        }
+
+        let transfer_fee_config = Self::get_transfer_fee_config(mint_info)?;
+        let withdraw_withheld_authority = transfer_fee_config
+            .withdraw_withheld_authority
+            .ok_or(TokenError::AuthorityTypeNotSupported)?;
+        Self::validate_owner(
+            program_id,
+            &withdraw_withheld_authority,
+            authority_info,
+            account_info_iter.as_slice(),
+        )?;
+
+        let withheld_amount = Self::take_withheld_amounts(mint_info, &source_account_infos)?;
+
+        // The destination is unpacked last, as it may also be a source
+        let mut dest_account = Account::unpack_from_account_data(&dest_account_info.data.borrow())?;
+        if dest_account.mint != *mint_info.key {
+            return Err(TokenError::MintMismatch.into());
+        }
+        if dest_account.is_frozen() {
+            return Err(TokenError::AccountFrozen.into());
+        }
+        dest_account.amount = dest_account
+            .amount
+            .checked_add(withheld_amount)
+            .ok_or(TokenError::Overflow)?;
+        Account::pack_into_account_data(dest_account, &mut dest_account_info.data.borrow_mut())?;
+
+        TokenEvent::WithdrawWithheld {
+            mint: *mint_info.key,
+            destination: *dest_account_info.key,
+            amount: withheld_amount,
+        }
+        .log();
+
+        Ok(())
+    }
+
+    /// Processes a [HarvestWithheldTokensToMint](enum.TokenInstruction.html) instruction.
+    pub fn process_harvest_withheld_tokens_to_mint(accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let mint_info = next_account_info(account_info_iter)?;
+        let source_account_infos = account_info_iter.collect::<Vec<_>>();
+
+        let mut transfer_fee_config = Self::get_transfer_fee_config(mint_info)?;
+        let withheld_amount = Self::take_withheld_amounts(mint_info, &source_account_infos)?;
+        transfer_fee_config.withheld_amount = transfer_fee_config
+            .withheld_amount
+            .checked_add(withheld_amount)
+            .ok_or(TokenError::Overflow)?;
+        set_extension(&mut mint_info.data.borrow_mut(), &transfer_fee_config)?;
+
+        Ok(())
+    }
+
+    /// Processes a [MigrateAccount](enum.TokenInstruction.html) instruction.
+    pub fn process_migrate_account(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        extension_types: &[ExtensionType],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let source_account_info = next_account_info(account_info_iter)?;
+        let new_account_info = next_account_info(account_info_iter)?;
+        let dest_account_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+
+        let source_account = Account::unpack_from_account_data(&source_account_info.data.borrow())?;
+        if source_account.is_native() {
+            return Err(TokenError::NativeNotSupported.into());
+        }
+
+        Self::validate_owner(
+            program_id,
+            &source_account.owner,
+            authority_info,
+            account_info_iter.as_slice(),
+        )?;
+
+        let mut new_extension_types = get_extension_types(&source_account_info.data.borrow())?;
+        for extension_type in extension_types.iter() {
+            if extension_type.get_account_type() != AccountType::Account {
+                return Err(ProgramError::InvalidArgument);
+            }
+            if !new_extension_types.contains(extension_type) {
+                new_extension_types.push(*extension_type);
+            }
+        }
+        let new_account_len = ExtensionType::get_account_len(&new_extension_types);
+
+        if source_account_info.key == new_account_info.key {
+            // Migrating in place only succeeds within the space the account
+            // was allocated with, as accounts cannot be resized
+            if source_account_info.data_len() < new_account_len {
+                return Err(ProgramError::InvalidAccountData);
+            }
+            init_account_type(
+                &mut source_account_info.data.borrow_mut(),
+                AccountType::Account,
+            )?;
+        } else {
+            if get_extension::<ImmutableOwner>(&source_account_info.data.borrow())?.is_some() {
+                return Err(TokenError::AccountNotMigratable.into());
+            }
+            let (associated_address, _) = Pubkey::find_program_address(
+                &[
+                    source_account.owner.as_ref(),
+                    program_id.as_ref(),
+                    source_account.mint.as_ref(),
+                ],
+                &associated_token_program::id(),
+            );
+            if *source_account_info.key == associated_address {
+                return Err(TokenError::AccountNotMigratable.into());
+            }
+            // The old account is closed, so its close authority must agree
+            if source_account
+                .close_authority
+                .map_or(false, |close_authority| {
+                    close_authority != source_account.owner
+                })
+            {
+                return Err(TokenError::OwnerMismatch.into());
+            }
+            if Self::is_cpi_guarded(source_account_info)? {
+                Self::check_not_in_cpi(program_id, account_info_iter.as_slice())?;
+            }
+
+            let new_account =
+                Account::unpack_unchecked_from_account_data(&new_account_info.data.borrow())?;
+            if new_account.is_initialized() {
+                return Err(TokenError::AlreadyInUse.into());
+            }
+            if !Rent::get()?.is_exempt(new_account_info.lamports(), new_account_info.data_len()) {
+                return Err(TokenError::NotRentExempt.into());
+            }
+
+            let source_data_len = source_account_info.data_len();
+            if new_account_info.data_len() < new_account_len
+                || new_account_info.data_len() < source_data_len
+            {
+                return Err(ProgramError::InvalidAccountData);
+            }
+
+            {
+                let mut source_data = source_account_info.data.borrow_mut();
+                let mut new_data = new_account_info.data.borrow_mut();
+                new_data[..source_data_len].copy_from_slice(&source_data);
+                init_account_type(&mut new_data, AccountType::Account)?;
+                for byte in source_data.iter_mut() {
+                    *byte = 0;
+                }
+            }
+
+            let dest_starting_lamports = dest_account_info.lamports();
+            **dest_account_info.lamports.borrow_mut() = dest_starting_lamports
+                .checked_add(source_account_info.lamports())
+                .ok_or(TokenError::Overflow)?;
+            **source_account_info.lamports.borrow_mut() = 0;
+        }
+
+        TokenEvent::Migrate {
+            mint: source_account.mint,
+            account: *source_account_info.key,
+            new_account: *new_account_info.key,
+        }
+        .log();
+
+        Ok(())
+    }
+
+    /// Processes a [SetDefaultFrozen](enum.TokenInstruction.html) instruction.
+    pub fn process_set_default_frozen(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        default_frozen: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let mint_info = next_account_info(account_info_iter)?;
+        let authority_info = next_account_info(account_info_iter)?;
+
+        let mint = Mint::unpack_from_account_data(&mint_info.data.borrow())?;
+        match mint.freeze_authority {
+            COption::Some(authority) => Self::validate_owner(
+                program_id,
+                &authority,
+                authority_info,
+                account_info_iter.as_slice(),
+            ),
+            COption::None => Err(TokenError::MintCannotFreeze.into()),
+        }?;
+
+        set_extension(
+            &mut mint_info.data.borrow_mut(),
+            &DefaultFrozen {
+                frozen: default_frozen,
+            },
+        )?;
+
+        Ok(())
+    }
+
+    /// Processes a [SyncNative](enum.TokenInstruction.html) instruction
+    pub fn process_sync_native(accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let native_account_info = next_account_info(account_info_iter)?;
+
+        let mut native_account =
+            Account::unpack_from_account_data(&native_account_info.data.borrow())?;
+
+        if let COption::Some(rent_exempt_reserve) = native_account.is_native {
+            let new_amount = native_account_info
+                .lamports()
+                .checked_sub(rent_exempt_reserve)
+                .ok_or(TokenError::Overflow)?;
+            if new_amount < native_account.amount {
+                return Err(TokenError::InvalidState.into());
+            }
+            native_account.amount = new_amount;
+        } else {
+            return Err(TokenError::NonNativeNotSupported.into());
+        }
+
+        let amount = native_account.amount;
+        Account::pack_into_account_data(
+            native_account,
+            &mut native_account_info.data.borrow_mut(),
+        )?;
+
+        TokenEvent::SyncNative {
+            account: *native_account_info.key,
+            amount,
+        }
+        .log();
+
+        Ok(())
+    }
+
+    /// Processes a [CreateMultisigProposal](enum.TokenInstruction.html) instruction.
+    pub fn process_create_multisig_proposal(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        instruction_data: &[u8],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let proposal_info = next_account_info(account_info_iter)?;
+        let proposal_data_len = proposal_info.data_len();
+        let multisig_info = next_account_info(account_info_iter)?;
+        let proposer_info = next_account_info(account_info_iter)?;
+        let inner_account_infos = account_info_iter.as_slice();
+
+        let mut proposal = MultisigProposal::unpack_unchecked(&proposal_info.data.borrow())?;
+        if proposal.is_initialized {
+            return Err(TokenError::AlreadyInUse.into());
+        }
+
+        if !Rent::get()?.is_exempt(proposal_info.lamports(), proposal_data_len) {
+            return Err(TokenError::NotRentExempt.into());
+        }
+
+        if inner_account_infos.len() > MAX_PROPOSAL_ACCOUNTS
+            || instruction_data.len() > MAX_PROPOSAL_DATA_LEN
+        {
+            return Err(TokenError::InvalidInstruction.into());
+        }
+        match TokenInstruction::unpack(instruction_data)? {
+            TokenInstruction::CreateMultisigProposal { .. }
+            | TokenInstruction::ApproveMultisigProposal
+            | TokenInstruction::ExecuteMultisigProposal => {
+                return Err(TokenError::InvalidInstruction.into());
+            }
+            _ => {}
+        }
+
+        if multisig_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let multisig = Multisig::unpack(&multisig_info.data.borrow())?;
+
+        proposal.is_executed = false;
+        proposal.multisig = *multisig_info.key;
+        proposal.approved = [false; MAX_SIGNERS];
+        Self::approve_multisig_proposal(&mut proposal, &multisig, proposer_info)?;
+
+        proposal.num_accounts = inner_account_infos.len() as u8;
+        proposal.accounts = [Pubkey::default(); MAX_PROPOSAL_ACCOUNTS];
+        for (key, account_info) in proposal.accounts.iter_mut().zip(inner_account_infos.iter()) {
+            *key = *account_info.key;
+        }
+        proposal.data_len = instruction_data.len() as u8;
+        proposal.data = [0; MAX_PROPOSAL_DATA_LEN];
+        proposal.data[..instruction_data.len()].copy_from_slice(instruction_data);
+        proposal.is_initialized = true;
+
+        MultisigProposal::pack(proposal, &mut proposal_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    /// Processes an [ApproveMultisigProposal](enum.TokenInstruction.html) instruction.
+    pub fn process_approve_multisig_proposal(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let proposal_info = next_account_info(account_info_iter)?;
+        let multisig_info = next_account_info(account_info_iter)?;
+        let signer_info = next_account_info(account_info_iter)?;
+
+        let mut proposal = MultisigProposal::unpack(&proposal_info.data.borrow())?;
+        let multisig = Self::unpack_proposal_multisig(program_id, &proposal, multisig_info)?;
+
+        Self::approve_multisig_proposal(&mut proposal, &multisig, signer_info)?;
+
+        MultisigProposal::pack(proposal, &mut proposal_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    /// Processes an [ExecuteMultisigProposal](enum.TokenInstruction.html) instruction.
+    pub fn process_execute_multisig_proposal(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let proposal_info = next_account_info(account_info_iter)?;
+        let multisig_info = next_account_info(account_info_iter)?;
+
+        let mut proposal = MultisigProposal::unpack(&proposal_info.data.borrow())?;
+        let multisig = Self::unpack_proposal_multisig(program_id, &proposal, multisig_info)?;
+
+        if proposal.num_approvals(multisig.n) < multisig.m {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let num_accounts = proposal.num_accounts as usize;
+        let instruction_data = &proposal.data[..proposal.data_len as usize];
+        let authority_index = Self::proposal_authority_index(
+            &TokenInstruction::unpack(instruction_data)?,
+            &proposal.accounts[..num_accounts],
+        );
+        if authority_index.map(|index| proposal.accounts[index]) != Some(proposal.multisig) {
+            return Err(TokenError::OwnerMismatch.into());
+        }
+
+        let mut inner_account_infos = Vec::with_capacity(accounts.len() - 2);
+        for key in proposal.accounts[..num_accounts].iter() {
+            let account_info = next_account_info(account_info_iter)?;
+            if account_info.key != key {
+                return Err(ProgramError::InvalidArgument);
+            }
+            inner_account_infos.push(account_info.clone());
+        }
+        for signer_info in account_info_iter {
+            let approved = multisig.signers[0..multisig.n as usize]
+                .iter()
+                .zip(proposal.approved.iter())
+                .any(|(key, approved)| *approved && key == signer_info.key);
+            if !approved {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            let mut signer_info = signer_info.clone();
+            signer_info.is_signer = true;
+            inner_account_infos.push(signer_info);
+        }
+
+        proposal.is_executed = true;
+        MultisigProposal::pack(proposal, &mut proposal_info.data.borrow_mut())?;
+
+        Self::process(
+            program_id,
+            &inner_account_infos,
+            &proposal.data[..proposal.data_len as usize],
+        )
+    }
+
+    /// Finds the position of the authority among the accounts of a proposed
+    /// instruction.  The authority is the last account, ignoring a trailing
+    /// instructions sysvar, except for instructions that list a variable
+    /// number of accounts after it.
+    fn proposal_authority_index(instruction: &TokenInstruction, keys: &[Pubkey]) -> Option<usize> {
+        match instruction {
+            TokenInstruction::TransferCheckedBatch { .. }
+            | TokenInstruction::WithdrawWithheldTokensFromAccounts { .. } => {
+                Some(2).filter(|index| *index < keys.len())
+            }
+            _ => match keys.split_last() {
+                Some((key, rest)) if instructions::check_id(key) => rest.len().checked_sub(1),
+                _ => keys.len().checked_sub(1),
+            },
+        }
+    }
+
+    /// Unpacks the multisignature of a proposal that has yet to be executed
+    fn unpack_proposal_multisig(
+        program_id: &Pubkey,
+        proposal: &MultisigProposal,
+        multisig_info: &AccountInfo,
+    ) -> Result<Multisig, ProgramError> {
+        if proposal.is_executed {
+            return Err(TokenError::InvalidState.into());
+        }
+        if proposal.multisig != *multisig_info.key {
+            return Err(TokenError::OwnerMismatch.into());
+        }
+        if multisig_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        Multisig::unpack(&multisig_info.data.borrow())
+    }
+
+    /// Records the approval of a proposal by one of the multisignature's signers
+    fn approve_multisig_proposal(
+        proposal: &mut MultisigProposal,
+        multisig: &Multisig,
+        signer_info: &AccountInfo,
+    ) -> ProgramResult {
+        if !signer_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let mut matched = false;
+        for (position, key) in multisig.signers[0..multisig.n as usize].iter().enumerate() {
+            if key == signer_info.key {
+                proposal.approved[position] = true;
+                matched = true;
+            }
+        }
+        if !matched {
+            return Err(TokenError::OwnerMismatch.into());
+        }
+        Ok(())
+    }
+
+    /// Processes an [Instruction](enum.Instruction.html).
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        let instruction = TokenInstruction::unpack(input)?;
+
+        match instruction {
+            TokenInstruction::InitializeMint {
+                decimals,
+                mint_authority,
+                freeze_authority,
+            } => {
+                msg!("Instruction: InitializeMint");
+                Self::process_initialize_mint(accounts, decimals, mint_authority, freeze_authority)
+            }
+            TokenInstruction::InitializeMint2 {
+                decimals,
+                mint_authority,
+                freeze_authority,
+            } => {
+                msg!("Instruction: InitializeMint2");
+                Self::process_initialize_mint2(accounts, decimals, mint_authority, freeze_authority)
+            }
+            TokenInstruction::InitializeAccount => {
+                msg!("Instruction: InitializeAccount");
+                Self::process_initialize_account(accounts)
+            }
+            TokenInstruction::InitializeAccount2 { owner } => {
+                msg!("Instruction: InitializeAccount2");
+                Self::process_initialize_account2(accounts, owner)
+            }
+            TokenInstruction::InitializeAccount3 { owner } => {
+                msg!("Instruction: InitializeAccount3");
+                Self::process_initialize_account3(accounts, owner)
+            }
+            TokenInstruction::InitializeMultisig { m } => {
+                msg!("Instruction: InitializeMultisig");
+                Self::process_initialize_multisig(accounts, m)
+            }
+            TokenInstruction::Transfer { amount } => {
+                msg!("Instruction: Transfer");
+                Self::process_transfer(program_id, accounts, amount, None, None)
+            }
+            TokenInstruction::Approve { amount } => {
+                msg!("Instruction: Approve");
+                Self::process_approve(program_id, accounts, amount, None, None)
+            }
+            TokenInstruction::Revoke => {
+                msg!("Instruction: Revoke");
+                Self::process_revoke(program_id, accounts)
+            }
+            TokenInstruction::SetAuthority {
+                authority_type,
+                new_authority,
+            } => {
+                msg!("Instruction: SetAuthority");
+                Self::process_set_authority(program_id, accounts, authority_type, new_authority)
+            }
+            TokenInstruction::MintTo { amount } => {
+                msg!("Instruction: MintTo");
+                Self::process_mint_to(program_id, accounts, amount, None)
+            }
+            TokenInstruction::Burn { amount } => {
+                msg!("Instruction: Burn");
+                Self::process_burn(program_id, accounts, amount, None)
+            }
+            TokenInstruction::CloseAccount => {
+                msg!("Instruction: CloseAccount");
+                Self::process_close_account(program_id, accounts)
+            }
+            TokenInstruction::FreezeAccount => {
+                msg!("Instruction: FreezeAccount");
+                Self::process_toggle_freeze_account(program_id, accounts, true)
+            }
+            TokenInstruction::ThawAccount => {
+                msg!("Instruction: FreezeAccount");
+                Self::process_toggle_freeze_account(program_id, accounts, false)
+            }
+            TokenInstruction::TransferChecked { amount, decimals } => {
+                msg!("Instruction: TransferChecked");
+                Self::process_transfer(program_id, accounts, amount, Some(decimals), None)
+            }
+            TokenInstruction::ApproveChecked { amount, decimals } => {
+                msg!("Instruction: ApproveChecked");
+                Self::process_approve(program_id, accounts, amount, Some(decimals), None)
+            }
+            TokenInstruction::MintToChecked { amount, decimals } => {
+                msg!("Instruction: MintToChecked");
+                Self::process_mint_to(program_id, accounts, amount, Some(decimals))
+            }
+            TokenInstruction::BurnChecked { amount, decimals } => {
+                msg!("Instruction: BurnChecked");
+                Self::process_burn(program_id, accounts, amount, Some(decimals))
+            }
+            TokenInstruction::SyncNative => {
+                msg!("Instruction: SyncNative");
+                Self::process_sync_native(accounts)
+            }
+            TokenInstruction::TransferCheckedBatch { amounts, decimals } => {
+                msg!("Instruction: TransferCheckedBatch");
+                Self::process_transfer_checked_batch(program_id, accounts, &amounts, decimals)
+            }
+            TokenInstruction::InitializeMaxSupply { max_supply } => {
+                msg!("Instruction: InitializeMaxSupply");
+                Self::process_initialize_max_supply(accounts, max_supply)
+            }
+            TokenInstruction::ApproveCheckedWithExpiry {
+                amount,
+                decimals,
+                expiry_slot,
+            } => {
+                msg!("Instruction: ApproveCheckedWithExpiry");
+                Self::process_approve(
+                    program_id,
+                    accounts,
+                    amount,
+                    Some(decimals),
+                    Some(expiry_slot),
+                )
+            }
+            TokenInstruction::CreateMultisigProposal { instruction_data } => {
+                msg!("Instruction: CreateMultisigProposal");
+                Self::process_create_multisig_proposal(program_id, accounts, &instruction_data)
+            }
+            TokenInstruction::ApproveMultisigProposal => {
+                msg!("Instruction: ApproveMultisigProposal");
+                Self::process_approve_multisig_proposal(program_id, accounts)
+            }
+            TokenInstruction::ExecuteMultisigProposal => {
+                msg!("Instruction: ExecuteMultisigProposal");
+                Self::process_execute_multisig_proposal(program_id, accounts)
+            }
+            TokenInstruction::InitializePermanentDelegate { delegate } => {
+                msg!("Instruction: InitializePermanentDelegate");
+                Self::process_initialize_permanent_delegate(accounts, delegate)
+            }
+            TokenInstruction::InitializeDefaultFrozen => {
+                msg!("Instruction: InitializeDefaultFrozen");
+                Self::process_initialize_default_frozen(accounts)
+            }
+            TokenInstruction::SetDefaultFrozen { default_frozen } => {
+                msg!("Instruction: SetDefaultFrozen");
+                Self::process_set_default_frozen(program_id, accounts, default_frozen)
+            }
+            TokenInstruction::InitializeNonTransferableMint => {
+                msg!("Instruction: InitializeNonTransferableMint");
+                Self::process_initialize_non_transferable_mint(accounts)
+            }
+            TokenInstruction::MigrateAccount { extension_types } => {
+                msg!("Instruction: MigrateAccount");
+                Self::process_migrate_account(program_id, accounts, &extension_types)
+            }
+            TokenInstruction::InitializeTransferFeeConfig {
+                withdraw_withheld_authority,
+                transfer_fee_basis_points,
+                maximum_fee,
+            } => {
+                msg!("Instruction: InitializeTransferFeeConfig");
+                Self::process_initialize_transfer_fee_config(
+                    accounts,
+                    withdraw_withheld_authority,
+                    transfer_fee_basis_points,
+                    maximum_fee,
+                )
+            }
+            TokenInstruction::TransferCheckedWithFee {
+                amount,
+                decimals,
+                fee,
+            } => {
+                msg!("Instruction: TransferCheckedWithFee");
+                Self::process_transfer(program_id, accounts, amount, Some(decimals), Some(fee))
+            }
+            TokenInstruction::WithdrawWithheldTokensFromMint => {
+                msg!("Instruction: WithdrawWithheldTokensFromMint");
+                Self::process_withdraw_withheld_tokens_from_mint(program_id, accounts)
+            }
+            TokenInstruction::WithdrawWithheldTokensFromAccounts { num_token_accounts } => {
+                msg!("Instruction: WithdrawWithheldTokensFromAccounts");
+                Self::process_withdraw_withheld_tokens_from_accounts(
+                    program_id,
+                    accounts,
+                    num_token_accounts,
+                )
+            }
+            TokenInstruction::HarvestWithheldTokensToMint => {
+                msg!("Instruction: HarvestWithheldTokensToMint");
+                Self::process_harvest_withheld_tokens_to_mint(accounts)
+            }
+            TokenInstruction::EnableRequiredTransferMemos => {
+                msg!("Instruction: EnableRequiredTransferMemos");
+                Self::process_toggle_required_transfer_memos(program_id, accounts, true)
+            }
+            TokenInstruction::DisableRequiredTransferMemos => {
+                msg!("Instruction: DisableRequiredTransferMemos");
+                Self::process_toggle_required_transfer_memos(program_id, accounts, false)
+            }
+            TokenInstruction::InitializeImmutableOwner => {
+                msg!("Instruction: InitializeImmutableOwner");
+                Self::process_initialize_immutable_owner(accounts)
+            }
+            TokenInstruction::InitializeMintCloseAuthority { close_authority } => {
+                msg!("Instruction: InitializeMintCloseAuthority");
+                Self::process_initialize_mint_close_authority(accounts, close_authority)
+            }
+            TokenInstruction::InitializeTokenMetadata {
+                update_authority,
+                name,
+                symbol,
+                uri,
+            } => {
+                msg!("Instruction: InitializeTokenMetadata");
+                Self::process_initialize_token_metadata(
+                    program_id,
+                    accounts,
+                    update_authority,
+                    name,
+                    symbol,
+                    uri,
+                )
+            }
+            TokenInstruction::UpdateTokenMetadata { name, symbol, uri } => {
+                msg!("Instruction: UpdateTokenMetadata");
+                Self::process_update_token_metadata(program_id, accounts, name, symbol, uri)
+            }
+            TokenInstruction::InitializeInterestBearingMint {
+                rate_authority,
+                rate,
+            } => {
+                msg!("Instruction: InitializeInterestBearingMint");
+                Self::process_initialize_interest_bearing_mint(accounts, rate_authority, rate)
+            }
+            TokenInstruction::UpdateInterestRate { rate } => {
+                msg!("Instruction: UpdateInterestRate");
+                Self::process_update_interest_rate(program_id, accounts, rate)
+            }
+            TokenInstruction::EnableCpiGuard => {
+                msg!("Instruction: EnableCpiGuard");
+                Self::process_toggle_cpi_guard(program_id, accounts, true)
+            }
+            TokenInstruction::DisableCpiGuard => {
+                msg!("Instruction: DisableCpiGuard");
+                Self::process_toggle_cpi_guard(program_id, accounts, false)
+            }
+        }
+    }
+
+    /// Checks that the delegation of a token account has not expired
+    fn check_delegation_expiry(account_info: &AccountInfo) -> ProgramResult {
+        if let Some(DelegationExpiry {
+            expiry_slot: COption::Some(expiry_slot),
+        }) = get_extension::<DelegationExpiry>(&account_info.data.borrow())?
+        {
+            if Clock::get()?.slot > expiry_slot {
+                return Err(TokenError::DelegationExpired.into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Gets the permanent delegate of a mint, if any
+    fn get_permanent_delegate(mint_info: &AccountInfo) -> Result<COption<Pubkey>, ProgramError> {
+        Ok(
+            get_extension::<PermanentDelegate>(&mint_info.data.borrow())?
+                .map_or(COption::None, |permanent_delegate| {
+                    permanent_delegate.delegate
+                }),
+        )
+    }
+
+    /// Gets the close authority of a mint, if any
+    fn get_mint_close_authority(mint_info: &AccountInfo) -> Result<COption<Pubkey>, ProgramError> {
+        Ok(
+            get_extension::<MintCloseAuthority>(&mint_info.data.borrow())?
+                .map_or(COption::None, |mint_close_authority| {
+                    mint_close_authority.close_authority
+                }),
+        )
+    }
+
+    /// Gets the transfer fee configuration of a mint
+    fn get_transfer_fee_config(mint_info: &AccountInfo) -> Result<TransferFeeConfig, ProgramError> {
+        let mint_data = mint_info.data.borrow();
+        let _ = Mint::unpack_from_account_data(&mint_data)?;
+        get_extension::<TransferFeeConfig>(&mint_data)?
+            .ok_or_else(|| TokenError::NoTransferFee.into())
+    }
+
+    /// Calculates the fee on a transfer of a mint with the given transfer fee
+    /// configuration, if any
+    fn calculate_transfer_fee(
+        transfer_fee_config: Option<&TransferFeeConfig>,
+        amount: u64,
+    ) -> Result<u64, ProgramError> {
+        match transfer_fee_config {
+            Some(transfer_fee_config) => Ok(transfer_fee_config
+                .calculate_fee(amount)
+                .ok_or(TokenError::Overflow)?),
+            None => Ok(0),
+        }
+    }
+
+    /// Adds a transfer fee to the fees withheld in a destination account
+    fn withhold_transfer_fee(dest_account_info: &AccountInfo, fee: u64) -> ProgramResult {
+        let mut dest_data = dest_account_info.data.borrow_mut();
+        let mut transfer_fee_amount =
+            get_extension::<TransferFeeAmount>(&dest_data)?.unwrap_or_default();
+        transfer_fee_amount.withheld_amount = transfer_fee_amount
+            .withheld_amount
+            .checked_add(fee)
+            .ok_or(TokenError::Overflow)?;
+        set_extension(&mut dest_data, &transfer_fee_amount)
+    }
+
+    /// Clears the fees withheld in token accounts of a mint, returning their
+    /// total
+    fn take_withheld_amounts(
+        mint_info: &AccountInfo,
+        source_account_infos: &[&AccountInfo],
+    ) -> Result<u64, ProgramError> {
+        let mut total_withheld_amount = 0u64;
+        for source_account_info in source_account_infos.iter() {
+            let mut source_data = source_account_info.data.borrow_mut();
+            let source_account = Account::unpack_from_account_data(&source_data)?;
+            if source_account.mint != *mint_info.key {
+                return Err(TokenError::MintMismatch.into());
+            }
+            if let Some(mut transfer_fee_amount) = get_extension::<TransferFeeAmount>(&source_data)?
+            {
+                total_withheld_amount = total_withheld_amount
+                    .checked_add(transfer_fee_amount.withheld_amount)
+                    .ok_or(TokenError::Overflow)?;
+                transfer_fee_amount.withheld_amount = 0;
+                set_extension(&mut source_data, &transfer_fee_amount)?;
+            }
+        }
+        Ok(total_withheld_amount)
+    }
+
+    /// Checks whether a token account requires memos on incoming transfers
+    fn requires_memo(account_info: &AccountInfo) -> Result<bool, ProgramError> {
+        Ok(get_extension::<MemoTransfer>(&account_info.data.borrow())?
+            .map_or(false, |memo_transfer| {
+                memo_transfer.require_incoming_transfer_memos
+            }))
+    }
+
+    /// Checks that the instruction preceding the current one is a memo, using
+    /// the instructions sysvar found among the given accounts
+    fn check_previous_instruction_is_memo(account_infos: &[AccountInfo]) -> ProgramResult {
+        let instructions_info = account_infos
+            .iter()
+            .find(|account_info| instructions::check_id(account_info.key))
+            .ok_or(TokenError::NoMemo)?;
+        let instructions_data = instructions_info.data.borrow();
+        let current_index = instructions::load_current_index(&instructions_data);
+        if current_index == 0 {
+            return Err(TokenError::NoMemo.into());
+        }
+        let previous_instruction =
+            instructions::load_instruction_at(current_index as usize - 1, &instructions_data)
+                .map_err(|_| ProgramError::InvalidAccountData)?;
+        if previous_instruction.program_id != spl_memo::id() {
+            return Err(TokenError::NoMemo.into());
+        }
+        Ok(())
+    }
+
+    fn is_cpi_guarded(account_info: &AccountInfo) -> Result<bool, ProgramError> {
+        Ok(get_extension::<CpiGuard>(&account_info.data.borrow())?
+            .map_or(false, |cpi_guard| cpi_guard.lock_cpi))
+    }
+
+    /// Checks that the current instruction was not invoked by another
+    /// program, using the instructions sysvar found among the given accounts.
+    /// The top-level instruction being executed must belong to this program,
+    /// which never invokes itself.
+    fn check_not_in_cpi(program_id: &Pubkey, account_infos: &[AccountInfo]) -> ProgramResult {
+        let instructions_info = account_infos
+            .iter()
+            .find(|account_info| instructions::check_id(account_info.key))
+            .ok_or(TokenError::CpiGuarded)?;
+        let instructions_data = instructions_info.data.borrow();
+        let current_index = instructions::load_current_index(&instructions_data);
+        let current_instruction =
+            instructions::load_instruction_at(current_index as usize, &instructions_data)
+                .map_err(|_| ProgramError::InvalidAccountData)?;
+        if current_instruction.program_id != *program_id {
+            return Err(TokenError::CpiGuarded.into());
+        }
+        Ok(())
+    }
+
+    /// Validates owner(s) are present
+    pub fn validate_owner(
+        program_id: &Pubkey,
+        expected_owner: &Pubkey,
+        owner_account_info: &AccountInfo,
+        signers: &[AccountInfo],
+    ) -> ProgramResult {
+        if expected_owner != owner_account_info.key {
+            return Err(TokenError::OwnerMismatch.into());
+        }
+        if program_id == owner_account_info.owner
+            && owner_account_info.data_len() == Multisig::get_packed_len()
+        {
+            let multisig = Multisig::unpack(&owner_account_info.data.borrow())?;
+            let mut num_signers = 0;
+            let mut matched = [false; MAX_SIGNERS];
+            for signer in signers.iter() {
+                for (position, key) in multisig.signers[0..multisig.n as usize].iter().enumerate() {
+                    if key == signer.key && !matched[position] {
+                        if !signer.is_signer {
+                            return Err(ProgramError::MissingRequiredSignature);
+                        }
+                        matched[position] = true;
+                        num_signers += 1;
+                    }
+                }
+            }
+            if num_signers < multisig.m {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            return Ok(());
+        } else if !owner_account_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        Ok(())
+    }
+}
+
+impl PrintProgramError for TokenError {
+    fn print<E>(&self)
+    where
+        E: 'static + std::error::Error + DecodeError<E> + PrintProgramError + FromPrimitive,
+    {
+        match self {
+            TokenError::NotRentExempt => msg!("Error: Lamport balance below rent-exempt threshold"),
+            TokenError::InsufficientFunds => msg!("Error: insufficient funds"),
+            TokenError::InvalidMint => msg!("Error: Invalid Mint"),
+            TokenError::MintMismatch => msg!("Error: Account not associated with this Mint"),
+            TokenError::OwnerMismatch => msg!("Error: owner does not match"),
+            TokenError::FixedSupply => msg!("Error: the total supply of this token is fixed"),
+            TokenError::AlreadyInUse => msg!("Error: account or token already in use"),
+            TokenError::InvalidNumberOfProvidedSigners => {
+                msg!("Error: Invalid number of provided signers")
+            }
+            TokenError::InvalidNumberOfRequiredSigners => {
+                msg!("Error: Invalid number of required signers")
+            }
+            TokenError::UninitializedState => msg!("Error: State is uninitialized"),
+            TokenError::NativeNotSupported => {
+                msg!("Error: Instruction does not support native tokens")
+            }
+            TokenError::NonNativeHasBalance => {
+                msg!("Error: Non-native account can only be closed if its balance is zero")
+            }
+            TokenError::InvalidInstruction => msg!("Error: Invalid instruction"),
+            TokenError::InvalidState => msg!("Error: Invalid account state for operation"),
+            TokenError::Overflow => msg!("Error: Operation overflowed"),
+            TokenError::AuthorityTypeNotSupported => {
+                msg!("Error: Account does not support specified authority type")
+            }
+            TokenError::MintCannotFreeze => msg!("Error: This token mint cannot freeze accounts"),
+            TokenError::AccountFrozen => msg!("Error: Account is frozen"),
+            TokenError::MintDecimalsMismatch => {
+                msg!("Error: decimals different from the Mint decimals")
+            }
+            TokenError::NonNativeNotSupported => {
+                msg!("Error: Instruction does not support non-native tokens")
+            }
+            TokenError::MaxSupplyExceeded => {
+                msg!("Error: Minting would exceed the maximum supply of the mint")
+            }
+            TokenError::DelegationExpired => msg!("Error: Delegation has expired"),
+            TokenError::NonTransferable => msg!("Error: Transfers are disabled for this mint"),
+            TokenError::TransferFeeExceedsMaximum => {
+                msg!("Error: Transfer fee exceeds maximum of 10,000 basis points")
+            }
+            TokenError::FeeMismatch => msg!("Error: Calculated fee does not match expected fee"),
+            TokenError::MintRequiredForTransfer => {
+                msg!("Error: Mint required for this transfer, use TransferChecked")
+            }
+            TokenError::NoTransferFee => msg!("Error: Mint has no transfer fee configuration"),
+            TokenError::AccountHasWithheldTransferFees => {
+                msg!("Error: Account has withheld transfer fees")
+            }
+            TokenError::NoMemo => msg!(
+                "Error: No memo in previous instruction; required for recipient to receive a transfer"
+            ),
+            TokenError::ImmutableOwner => msg!("Error: The owner authority cannot be changed"),
+            TokenError::MintHasSupply => msg!("Error: Mint has a nonzero supply"),
+            TokenError::MetadataFieldTooLong => {
+                msg!("Error: Token metadata field exceeds its maximum length")
+            }
+            TokenError::NoTokenMetadata => msg!("Error: Mint has no token metadata"),
+            TokenError::CpiGuarded => {
+                msg!("Error: Account blocks this action when invoked by another program")
+            }
+            TokenError::AccountNotMigratable => {
+                msg!("Error: Account is found by its address and cannot be migrated")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{instruction::*, state::mint_amount_to_ui_amount};
+    use solana_program::{
+        account_info::IntoAccountInfo,
+        clock::Epoch,
+        instruction::{AccountMeta, Instruction},
+        message::Message,
+        sysvar::rent,
+    };
+    use solana_sdk::account::{
+        create_account, create_is_signer_account_infos, Account as SolanaAccount,
+    };
+
+    const CLOCK_SLOT: u64 = 42;
+    const CLOCK_UNIX_TIMESTAMP: i64 = 1_600_000_000;
+
+    struct SyscallStubs {}
+    impl solana_program::program_stubs::SyscallStubs for SyscallStubs {
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe {
+                *(var_addr as *mut Clock) = Clock {
+                    slot: CLOCK_SLOT,
+                    unix_timestamp: CLOCK_UNIX_TIMESTAMP,
+                    ..Clock::default()
+                };
+            }
+            solana_program::entrypoint::SUCCESS
+        }
+
+        fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe {
+                *(var_addr as *mut Rent) = Rent::default();
+            }
+            solana_program::entrypoint::SUCCESS
+        }
+    }
+
+    fn do_process_instruction(
+        instruction: Instruction,
+        accounts: Vec<&mut SolanaAccount>,
+    ) -> ProgramResult {
+        {
+            use std::sync::Once;
+            static ONCE: Once = Once::new();
+
+            ONCE.call_once(|| {
+                solana_program::program_stubs::set_syscall_stubs(Box::new(SyscallStubs {}));
+            });
+        }
+
+        let mut meta = instruction
+            .accounts
+            .iter()
+            .zip(accounts)
+            .map(|(account_meta, account)| (&account_meta.pubkey, account_meta.is_signer, account))
+            .collect::<Vec<_>>();
+
+        let account_infos = create_is_signer_account_infos(&mut meta);
+        Processor::process(&instruction.program_id, &account_infos, &instruction.data)
+    }
+
+    fn do_process_instruction_dups(
+        instruction: Instruction,
+        account_infos: Vec<AccountInfo>,
+    ) -> ProgramResult {
+        Processor::process(&instruction.program_id, &account_infos, &instruction.data)
+    }
+
+    fn return_token_error_as_program_error() -> ProgramError {
+        TokenError::MintMismatch.into()
+    }
+
+    fn rent_sysvar() -> SolanaAccount {
+        create_account(&Rent::default(), 42)
+    }
+
+    fn instructions_sysvar(instructions: &[Instruction], current_index: u16) -> SolanaAccount {
+        let mut data = Message::new(instructions, None).serialize_instructions();
+        data.resize(data.len() + 2, 0);
+        instructions::store_current_index(&mut data, current_index);
+        let mut account = SolanaAccount::new(0, data.len(), &solana_program::sysvar::id());
+        account.data = data;
+        account
+    }
+
+    fn mint_minimum_balance() -> u64 {
+        Rent::default().minimum_balance(Mint::get_packed_len())
+    }
+
+    fn account_minimum_balance() -> u64 {
+        Rent::default().minimum_balance(Account::get_packed_len())
+    }
+
+    fn multisig_minimum_balance() -> u64 {
+        Rent::default().minimum_balance(Multisig::get_packed_len())
+    }
+
+    fn proposal_minimum_balance() -> u64 {
+        Rent::default().minimum_balance(MultisigProposal::get_packed_len())
+    }
+
+    #[test]
+    fn test_print_error() {
+        let error = return_token_error_as_program_error();
+        error.print::<TokenError>();
+    }
+
+    #[test]
+    #[should_panic(expected = "Custom(3)")]
+    fn test_error_unwrap() {
+        Err::<(), ProgramError>(return_token_error_as_program_error()).unwrap();
+    }
+
+    #[test]
+    fn test_unique_account_sizes() {
+        assert_ne!(Mint::get_packed_len(), 0);
+        assert_ne!(Mint::get_packed_len(), Account::get_packed_len());
+        assert_ne!(Mint::get_packed_len(), Multisig::get_packed_len());
+        assert_ne!(Account::get_packed_len(), 0);
+        assert_ne!(Account::get_packed_len(), Multisig::get_packed_len());
+        assert_ne!(Multisig::get_packed_len(), 0);
+        assert_ne!(MultisigProposal::get_packed_len(), Mint::get_packed_len());
+        assert_ne!(
+            MultisigProposal::get_packed_len(),
+            Account::get_packed_len()
+        );
+        assert_ne!(
+            MultisigProposal::get_packed_len(),
+            Multisig::get_packed_len()
+        );
+        assert_ne!(
+            ExtensionType::get_account_len(&[]),
+            Multisig::get_packed_len()
+        );
+        let mut extension_types = vec![ExtensionType::DefaultFrozen];
+        while ExtensionType::get_account_len(&extension_types) < MultisigProposal::get_packed_len()
+        {
+            extension_types.push(ExtensionType::NonTransferable);
+            assert_ne!(
+                ExtensionType::get_account_len(&extension_types),
+                Multisig::get_packed_len()
+            );
+            assert_ne!(
+                ExtensionType::get_account_len(&extension_types),
+                MultisigProposal::get_packed_len()
+            );
+        }
+    }
+
+    #[test]
+    fn test_pack_unpack() {
+        // Mint
+        let check = Mint {
+            mint_authority: COption::Some(Pubkey::new(&[1; 32])),
+            supply: 42,
+            decimals: 7,
+            is_initialized: true,
+            freeze_authority: COption::Some(Pubkey::new(&[2; 32])),
+        };
+        let mut packed = vec![0; Mint::get_packed_len() + 1];
+        assert_eq!(
+            Err(ProgramError::InvalidAccountData),
+            Mint::pack(check, &mut packed)
+        );
+        let mut packed = vec![0; Mint::get_packed_len() - 1];
+        assert_eq!(
+            Err(ProgramError::InvalidAccountData),
+            Mint::pack(check, &mut packed)
+        );
+        let mut packed = vec![0; Mint::get_packed_len()];
+        Mint::pack(check, &mut packed).unwrap();
+        let expect = vec![
+            1, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+            1, 1, 1, 1, 1, 1, 1, 42, 0, 0, 0, 0, 0, 0, 0, 7, 1, 1, 0, 0, 0, 2, 2, 2, 2, 2, 2, 2, 2,
+            2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2,
+        ];
+        assert_eq!(packed, expect);
+        let unpacked = Mint::unpack(&packed).unwrap();
+        assert_eq!(unpacked, check);
+
+        // Account
+        let check = Account {
+            mint: Pubkey::new(&[1; 32]),
+            owner: Pubkey::new(&[2; 32]),
+            amount: 3,
+            delegate: COption::Some(Pubkey::new(&[4; 32])),
+            state: AccountState::Frozen,
+            is_native: COption::Some(5),
+            delegated_amount: 6,
+            close_authority: COption::Some(Pubkey::new(&[7; 32])),
+        };
+        let mut packed = vec![0; Account::get_packed_len() + 1];
+        assert_eq!(
+            Err(ProgramError::InvalidAccountData),
+            Account::pack(check, &mut packed)
+        );
+        let mut packed = vec![0; Account::get_packed_len() - 1];
+        assert_eq!(
+            Err(ProgramError::InvalidAccountData),
+            Account::pack(check, &mut packed)
+        );
+        let mut packed = vec![0; Account::get_packed_len()];
+        Account::pack(check, &mut packed).unwrap();
+        let expect = vec![
+            1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+            1, 1, 1, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2,
+            2, 2, 2, 2, 2, 2, 3, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4,
+            4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 2, 1, 0, 0, 0, 5, 0, 0,
+            0, 0, 0, 0, 0, 6, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7,
+            7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7,
+        ];
+        assert_eq!(packed, expect);
+        let unpacked = Account::unpack(&packed).unwrap();
+        assert_eq!(unpacked, check);
+
+        // Multisig
+        let check = Multisig {
+            m: 1,
+            n: 2,
+            is_initialized: true,
+            signers: [Pubkey::new(&[3; 32]); MAX_SIGNERS],
+        };
+        let mut packed = vec![0; Multisig::get_packed_len() + 1];
+        assert_eq!(
+            Err(ProgramError::InvalidAccountData),
+            Multisig::pack(check, &mut packed)
+        );
+        let mut packed = vec![0; Multisig::get_packed_len() - 1];
+        assert_eq!(
+            Err(ProgramError::InvalidAccountData),
+            Multisig::pack(check, &mut packed)
+        );
+        let mut packed = vec![0; Multisig::get_packed_len()];
+        Multisig::pack(check, &mut packed).unwrap();
+        let expect = vec![
+            1, 2, 1, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
+            3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
+            3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
+            3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
+            3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
+            3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
+            3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
+            3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
+            3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
+            3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
+            3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
+            3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
+            3, 3, 3, 3, 3, 3, 3,
+        ];
+        assert_eq!(packed, expect);
+        let unpacked = Multisig::unpack(&packed).unwrap();
+        assert_eq!(unpacked, check);
+
+        // MultisigProposal
+        let check = MultisigProposal {
+            is_initialized: true,
+            is_executed: false,
+            multisig: Pubkey::new(&[1; 32]),
+            approved: [false; MAX_SIGNERS],
+            num_accounts: MAX_PROPOSAL_ACCOUNTS as u8,
+            accounts: [Pubkey::new(&[2; 32]); MAX_PROPOSAL_ACCOUNTS],
+            data_len: MAX_PROPOSAL_DATA_LEN as u8,
+            data: [3; MAX_PROPOSAL_DATA_LEN],
+        };
+        let mut packed = vec![0; MultisigProposal::get_packed_len()];
+        MultisigProposal::pack(check, &mut packed).unwrap();
+        let unpacked = MultisigProposal::unpack(&packed).unwrap();
+        assert_eq!(unpacked, check);
+        // counts beyond the stored keys and data are rejected
+        let mut packed_accounts = packed.clone();
+        packed_accounts[2 + 32 + MAX_SIGNERS] = MAX_PROPOSAL_ACCOUNTS as u8 + 1;
+        assert_eq!(
+            Err(ProgramError::InvalidAccountData),
+            MultisigProposal::unpack(&packed_accounts)
+        );
+        packed[2 + 32 + MAX_SIGNERS + 1 + 32 * MAX_PROPOSAL_ACCOUNTS] = u8::MAX;
+        assert_eq!(
+            Err(ProgramError::InvalidAccountData),
+            MultisigProposal::unpack(&packed)
+        );
+
+        // MaxSupply
+        let check = MaxSupply { max_supply: 42 };
+        let mut packed = vec![0; MaxSupply::get_packed_len()];
+        MaxSupply::pack(check, &mut packed).unwrap();
+        let expect = vec![42, 0, 0, 0, 0, 0, 0, 0];
+        assert_eq!(packed, expect);
+        let unpacked = MaxSupply::unpack_unchecked(&packed).unwrap();
+        assert_eq!(unpacked, check);
+
+        // DelegationExpiry
+        let check = DelegationExpiry {
+            expiry_slot: COption::Some(42),
+        };
+        let mut packed = vec![0; DelegationExpiry::get_packed_len()];
+        DelegationExpiry::pack(check, &mut packed).unwrap();
+        let expect = vec![1, 0, 0, 0, 42, 0, 0, 0, 0, 0, 0, 0];
+        assert_eq!(packed, expect);
+        let unpacked = DelegationExpiry::unpack_unchecked(&packed).unwrap();
+        assert_eq!(unpacked, check);
+
+        // TransferFeeConfig
+        let check = TransferFeeConfig {
+            withdraw_withheld_authority: COption::Some(Pubkey::new(&[1; 32])),
+            transfer_fee_basis_points: 50,
+            maximum_fee: 2,
+            withheld_amount: 3,
+        };
+        let mut packed = vec![0; TransferFeeConfig::get_packed_len()];
+        TransferFeeConfig::pack(check, &mut packed).unwrap();
+        let mut expect = vec![1, 0, 0, 0];
+        expect.extend_from_slice(&[1; 32]);
+        expect.extend_from_slice(&[50, 0, 2, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(packed, expect);
+        let unpacked = TransferFeeConfig::unpack_unchecked(&packed).unwrap();
+        assert_eq!(unpacked, check);
+        assert_eq!(check.calculate_fee(0), Some(0));
+        assert_eq!(check.calculate_fee(1), Some(1));
+        assert_eq!(check.calculate_fee(200), Some(1));
+        assert_eq!(check.calculate_fee(201), Some(2));
+        assert_eq!(check.calculate_fee(u64::MAX), Some(2));
+
+        // TokenMetadata
+        let check = TokenMetadata {
+            update_authority: COption::None,
+            name: "Token".to_string(),
+            symbol: "TKN".to_string(),
+            uri: "".to_string(),
+        };
+        let mut packed = vec![0; TokenMetadata::get_packed_len()];
+        TokenMetadata::pack(check.clone(), &mut packed).unwrap();
+        let mut expect = vec![0; 36];
+        expect.extend_from_slice(&[5, b'T', b'o', b'k', b'e', b'n']);
+        expect.extend_from_slice(&[0; 27]);
+        expect.extend_from_slice(&[3, b'T', b'K', b'N']);
+        expect.extend_from_slice(&[0; 7]);
+        expect.extend_from_slice(&[0; 201]);
+        assert_eq!(packed, expect);
+        let unpacked = TokenMetadata::unpack_unchecked(&packed).unwrap();
+        assert_eq!(unpacked, check);
+
+        // Extensions
+        let mut packed = vec![
+            0;
+            ExtensionType::get_account_len(&[
+                ExtensionType::MaxSupply,
+                ExtensionType::DefaultFrozen
+            ])
+        ];
+        assert_eq!(get_extension::<MaxSupply>(&packed).unwrap(), None);
+        set_extension(&mut packed, &DefaultFrozen { frozen: true }).unwrap();
+        set_extension(&mut packed, &MaxSupply { max_supply: 1 }).unwrap();
+        set_extension(&mut packed, &MaxSupply { max_supply: 42 }).unwrap();
+        let mut expect = vec![0; Account::get_packed_len()];
+        expect.extend_from_slice(&[1, 4, 0, 1, 0, 1, 1, 0, 8, 0, 42, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(packed, expect);
+        assert!(Mint::is_mint_data(&packed));
+        assert!(!Account::is_account_data(&packed));
+        assert_eq!(
+            get_extension::<MaxSupply>(&packed).unwrap(),
+            Some(MaxSupply { max_supply: 42 })
+        );
+        assert_eq!(
+            get_extension_types(&packed).unwrap(),
+            vec![ExtensionType::DefaultFrozen, ExtensionType::MaxSupply]
+        );
+        assert_eq!(
+            get_extension::<DelegationExpiry>(&packed),
+            Err(ProgramError::InvalidAccountData)
+        );
+        assert_eq!(
+            set_extension(&mut packed, &PermanentDelegate::default()),
+            Err(ProgramError::InvalidAccountData)
+        );
+
+        // MultisigProposal
+        let mut approved = [false; MAX_SIGNERS];
+        approved[1] = true;
+        let mut data = [0; MAX_PROPOSAL_DATA_LEN];
+        data[..2].copy_from_slice(&[4, 5]);
+        let check = MultisigProposal {
+            is_initialized: true,
+            is_executed: false,
+            multisig: Pubkey::new(&[2; 32]),
+            approved,
+            num_accounts: 1,
+            accounts: [Pubkey::new(&[3; 32]); MAX_PROPOSAL_ACCOUNTS],
+            data_len: 2,
+            data,
+        };
+        let mut packed = vec![0; MultisigProposal::get_packed_len() - 1];
+        assert_eq!(
+            Err(ProgramError::InvalidAccountData),
+            MultisigProposal::pack(check, &mut packed)
+        );
+        let mut packed = vec![0; MultisigProposal::get_packed_len()];
+        MultisigProposal::pack(check, &mut packed).unwrap();
+        assert_eq!(&packed[..3], &[1, 0, 2]);
+        assert_eq!(&packed[34..46], &[0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(&packed[302..305], &[2, 4, 5]);
+        let unpacked = MultisigProposal::unpack(&packed).unwrap();
+        assert_eq!(unpacked, check);
+    }
+
+    #[test]
+    fn test_initialize_mint() {
+        let program_id = Pubkey::new_unique();
+        let owner_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account = SolanaAccount::new(42, Mint::get_packed_len(), &program_id);
+        let mint2_key = Pubkey::new_unique();
+        let mut mint2_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let mut rent_sysvar = rent_sysvar();
+
+        // mint is not rent exempt
+        assert_eq!(
+            Err(TokenError::NotRentExempt.into()),
+            do_process_instruction(
+                initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+                vec![&mut mint_account, &mut rent_sysvar]
+            )
+        );
+
+        mint_account.lamports = mint_minimum_balance();
+
+        // create new mint
+        do_process_instruction(
+            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+            vec![&mut mint_account, &mut rent_sysvar],
+        )
+        .unwrap();
+
+        // create twice
+        assert_eq!(
+            Err(TokenError::AlreadyInUse.into()),
+            do_process_instruction(
+                initialize_mint(&program_id, &mint_key, &owner_key, None, 2,).unwrap(),
+                vec![&mut mint_account, &mut rent_sysvar]
+            )
+        );
+
+        // create another mint that can freeze
+        do_process_instruction(
+            initialize_mint(&program_id, &mint2_key, &owner_key, Some(&owner_key), 2).unwrap(),
+            vec![&mut mint2_account, &mut rent_sysvar],
+        )
+        .unwrap();
+        let mint = Mint::unpack_unchecked(&mint2_account.data).unwrap();
+        assert_eq!(mint.freeze_authority, COption::Some(owner_key));
+    }
+
+    #[test]
+    fn test_initialize_mint_account() {
+        let program_id = Pubkey::new_unique();
+        let account_key = Pubkey::new_unique();
+        let mut account_account = SolanaAccount::new(42, Account::get_packed_len(), &program_id);
+        let owner_key = Pubkey::new_unique();
+        let mut owner_account = SolanaAccount::default();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let mut rent_sysvar = rent_sysvar();
+
+        // account is not rent exempt
+        assert_eq!(
+            Err(TokenError::NotRentExempt.into()),
+            do_process_instruction(
+                initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut mint_account,
+                    &mut owner_account,
+                    &mut rent_sysvar
+                ],
+            )
+        );
+
+        account_account.lamports = account_minimum_balance();
+
+        // mint is not valid (not initialized)
+        assert_eq!(
+            Err(TokenError::InvalidMint.into()),
+            do_process_instruction(
+                initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut mint_account,
+                    &mut owner_account,
+                    &mut rent_sysvar
+                ],
+            )
+        );
+
+        // create mint
+        do_process_instruction(
+            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+            vec![&mut mint_account, &mut rent_sysvar],
+        )
+        .unwrap();
+
+        // create account
+        do_process_instruction(
+            initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                &mut account_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+
+        // create twice
+        assert_eq!(
+            Err(TokenError::AlreadyInUse.into()),
+            do_process_instruction(
+                initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut mint_account,
+                    &mut owner_account,
+                    &mut rent_sysvar
+                ],
+            )
+        );
+    }
+
+    #[test]
+    fn test_transfer_dups() {
+        let program_id = Pubkey::new_unique();
+        let account1_key = Pubkey::new_unique();
+        let mut account1_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let mut account1_info: AccountInfo = (&account1_key, true, &mut account1_account).into();
+        let account2_key = Pubkey::new_unique();
+        let mut account2_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let mut account2_info: AccountInfo = (&account2_key, false, &mut account2_account).into();
+        let account3_key = Pubkey::new_unique();
+        let mut account3_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let account3_info: AccountInfo = (&account3_key, false, &mut account3_account).into();
+        let account4_key = Pubkey::new_unique();
+        let mut account4_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let account4_info: AccountInfo = (&account4_key, true, &mut account4_account).into();
+        let multisig_key = Pubkey::new_unique();
+        let mut multisig_account = SolanaAccount::new(
+            multisig_minimum_balance(),
+            Multisig::get_packed_len(),
+            &program_id,
+        );
+        let multisig_info: AccountInfo = (&multisig_key, true, &mut multisig_account).into();
+        let owner_key = Pubkey::new_unique();
+        let mut owner_account = SolanaAccount::default();
+        let owner_info: AccountInfo = (&owner_key, true, &mut owner_account).into();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let mint_info: AccountInfo = (&mint_key, false, &mut mint_account).into();
+        let rent_key = rent::id();
+        let mut rent_sysvar = rent_sysvar();
+        let rent_info: AccountInfo = (&rent_key, false, &mut rent_sysvar).into();
+
+        // create mint
+        do_process_instruction_dups(
+            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+            vec![mint_info.clone(), rent_info.clone()],
+        )
+        .unwrap();
+
+        // create account
+        do_process_instruction_dups(
+            initialize_account(&program_id, &account1_key, &mint_key, &account1_key).unwrap(),
+            vec![
+                account1_info.clone(),
+                mint_info.clone(),
+                account1_info.clone(),
+                rent_info.clone(),
+            ],
+        )
+        .unwrap();
+
+        // create another account
+        do_process_instruction_dups(
+            initialize_account(&program_id, &account2_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                account2_info.clone(),
+                mint_info.clone(),
+                owner_info.clone(),
+                rent_info.clone(),
+            ],
+        )
+        .unwrap();
+
+        // mint to account
+        do_process_instruction_dups(
+            mint_to(&program_id, &mint_key, &account1_key, &owner_key, &[], 1000).unwrap(),
+            vec![mint_info.clone(), account1_info.clone(), owner_info.clone()],
+        )
+        .unwrap();
+
+        // source-owner transfer
+        do_process_instruction_dups(
+            transfer(
+                &program_id,
+                &account1_key,
+                &account2_key,
+                &account1_key,
+                &[],
+                500,
+            )
+            .unwrap(),
+            vec![
+                account1_info.clone(),
+                account2_info.clone(),
+                account1_info.clone(),
+            ],
+        )
+        .unwrap();
+
+        // source-owner TransferChecked
+        do_process_instruction_dups(
+            transfer_checked(
+                &program_id,
+                &account1_key,
+                &mint_key,
+                &account2_key,
+                &account1_key,
+                &[],
+                500,
+                2,
+            )
+            .unwrap(),
+            vec![
+                account1_info.clone(),
+                mint_info.clone(),
+                account2_info.clone(),
+                account1_info.clone(),
+            ],
+        )
+        .unwrap();
+
+        // source-delegate transfer
+        let mut account = Account::unpack_unchecked(&account1_info.data.borrow()).unwrap();
+        account.amount = 1000;
+        account.delegated_amount = 1000;
+        account.delegate = COption::Some(account1_key);
+        account.owner = owner_key;
+        Account::pack(account, &mut account1_info.data.borrow_mut()).unwrap();
+
+        do_process_instruction_dups(
+            transfer(
+                &program_id,
+                &account1_key,
+                &account2_key,
+                &account1_key,
+                &[],
+                500,
+            )
+            .unwrap(),
+            vec![
+                account1_info.clone(),
+                account2_info.clone(),
+                account1_info.clone(),
+            ],
+        )
+        .unwrap();
+
+        // source-delegate TransferChecked
+        do_process_instruction_dups(
+            transfer_checked(
+                &program_id,
+                &account1_key,
+                &mint_key,
+                &account2_key,
+                &account1_key,
+                &[],
+                500,
+                2,
+            )
+            .unwrap(),
+            vec![
+                account1_info.clone(),
+                mint_info.clone(),
+                account2_info.clone(),
+                account1_info.clone(),
+            ],
+        )
+        .unwrap();
+
+        // test destination-owner transfer
+        do_process_instruction_dups(
+            initialize_account(&program_id, &account3_key, &mint_key, &account2_key).unwrap(),
+            vec![
+                account3_info.clone(),
+                mint_info.clone(),
+                account2_info.clone(),
+                rent_info.clone(),
+            ],
+        )
+        .unwrap();
+        do_process_instruction_dups(
+            mint_to(&program_id, &mint_key, &account3_key, &owner_key, &[], 1000).unwrap(),
+            vec![mint_info.clone(), account3_info.clone(), owner_info.clone()],
+        )
+        .unwrap();
+
+        account1_info.is_signer = false;
+        account2_info.is_signer = true;
+        do_process_instruction_dups(
+            transfer(
+                &program_id,
+                &account3_key,
+                &account2_key,
+                &account2_key,
+                &[],
+                500,
+            )
+            .unwrap(),
+            vec![
+                account3_info.clone(),
+                account2_info.clone(),
+                account2_info.clone(),
+            ],
+        )
+        .unwrap();
+
+        // destination-owner TransferChecked
+        do_process_instruction_dups(
+            transfer_checked(
+                &program_id,
+                &account3_key,
+                &mint_key,
+                &account2_key,
+                &account2_key,
+                &[],
+                500,
+                2,
+            )
+            .unwrap(),
+            vec![
+                account3_info.clone(),
+                mint_info.clone(),
+                account2_info.clone(),
+                account2_info.clone(),
+            ],
+        )
+        .unwrap();
+
+        // test source-multisig signer
+        do_process_instruction_dups(
+            initialize_multisig(&program_id, &multisig_key, &[&account4_key], 1).unwrap(),
+            vec![
+                multisig_info.clone(),
+                rent_info.clone(),
+                account4_info.clone(),
+            ],
+        )
+        .unwrap();
+
+        do_process_instruction_dups(
+            initialize_account(&program_id, &account4_key, &mint_key, &multisig_key).unwrap(),
+            vec![
+                account4_info.clone(),
+                mint_info.clone(),
+                multisig_info.clone(),
+                rent_info.clone(),
+            ],
+        )
+        .unwrap();
+
+        do_process_instruction_dups(
+            mint_to(&program_id, &mint_key, &account4_key, &owner_key, &[], 1000).unwrap(),
+            vec![mint_info.clone(), account4_info.clone(), owner_info.clone()],
+        )
+        .unwrap();
+
+        // source-multisig-signer transfer
+        do_process_instruction_dups(
+            transfer(
+                &program_id,
+                &account4_key,
+                &account2_key,
+                &multisig_key,
+                &[&account4_key],
+                500,
+            )
+            .unwrap(),
+            vec![
+                account4_info.clone(),
+                account2_info.clone(),
+                multisig_info.clone(),
+                account4_info.clone(),
+            ],
+        )
+        .unwrap();
+
+        // source-multisig-signer TransferChecked
+        do_process_instruction_dups(
+            transfer_checked(
+                &program_id,
+                &account4_key,
+                &mint_key,
+                &account2_key,
+                &multisig_key,
+                &[&account4_key],
+                500,
+                2,
+            )
+            .unwrap(),
+            vec![
+                account4_info.clone(),
+                mint_info.clone(),
+                account2_info.clone(),
+                multisig_info.clone(),
+                account4_info.clone(),
+            ],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_transfer() {
+        let program_id = Pubkey::new_unique();
+        let account_key = Pubkey::new_unique();
+        let mut account_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let account2_key = Pubkey::new_unique();
+        let mut account2_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let account3_key = Pubkey::new_unique();
+        let mut account3_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let delegate_key = Pubkey::new_unique();
+        let mut delegate_account = SolanaAccount::default();
+        let mismatch_key = Pubkey::new_unique();
+        let mut mismatch_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let owner_key = Pubkey::new_unique();
+        let mut owner_account = SolanaAccount::default();
+        let owner2_key = Pubkey::new_unique();
+        let mut owner2_account = SolanaAccount::default();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let mint2_key = Pubkey::new_unique();
+        let mut rent_sysvar = rent_sysvar();
+
+        // create mint
+        do_process_instruction(
+            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+            vec![&mut mint_account, &mut rent_sysvar],
+        )
+        .unwrap();
+
+        // create account
+        do_process_instruction(
+            initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                &mut account_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+
+        // create another account
+        do_process_instruction(
+            initialize_account(&program_id, &account2_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                &mut account2_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+
+        // create another account
+        do_process_instruction(
+            initialize_account(&program_id, &account3_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                &mut account3_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+
+        // create mismatch account
+        do_process_instruction(
+            initialize_account(&program_id, &mismatch_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                &mut mismatch_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+        let mut account = Account::unpack_unchecked(&mismatch_account.data).unwrap();
+        account.mint = mint2_key;
+        Account::pack(account, &mut mismatch_account.data).unwrap();
+
+        // mint to account
+        do_process_instruction(
+            mint_to(&program_id, &mint_key, &account_key, &owner_key, &[], 1000).unwrap(),
+            vec![&mut mint_account, &mut account_account, &mut owner_account],
+        )
+        .unwrap();
+
+        // missing signer
+        let mut instruction = transfer(
+            &program_id,
+            &account_key,
+            &account2_key,
+            &owner_key,
+            &[],
+            1000,
+        )
+        .unwrap();
+        instruction.accounts[2].is_signer = false;
+        assert_eq!(
+            Err(ProgramError::MissingRequiredSignature),
+            do_process_instruction(
+                instruction,
+                vec![
+                    &mut account_account,
+                    &mut account2_account,
+                    &mut owner_account,
+                ],
+            )
+        );
+
+        // mismatch mint
+        assert_eq!(
+            Err(TokenError::MintMismatch.into()),
+            do_process_instruction(
+                transfer(
+                    &program_id,
+                    &account_key,
+                    &mismatch_key,
+                    &owner_key,
+                    &[],
+                    1000
+                )
+                .unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut mismatch_account,
+                    &mut owner_account,
+                ],
+            )
+        );
+
+        // missing owner
+        assert_eq!(
+            Err(TokenError::OwnerMismatch.into()),
+            do_process_instruction(
+                transfer(
+                    &program_id,
+                    &account_key,
+                    &account2_key,
+                    &owner2_key,
+                    &[],
+                    1000
+                )
+                .unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut account2_account,
+                    &mut owner2_account,
+                ],
+            )
+        );
+
+        // transfer
+        do_process_instruction(
+            transfer(
+                &program_id,
+                &account_key,
+                &account2_key,
+                &owner_key,
+                &[],
+                1000,
+            )
+            .unwrap(),
+            vec![
+                &mut account_account,
+                &mut account2_account,
+                &mut owner_account,
+            ],
+        )
+        .unwrap();
+
+        // insufficient funds
+        assert_eq!(
+            Err(TokenError::InsufficientFunds.into()),
+            do_process_instruction(
+                transfer(&program_id, &account_key, &account2_key, &owner_key, &[], 1).unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut account2_account,
+                    &mut owner_account,
+                ],
+            )
+        );
+
+        // transfer half back
+        do_process_instruction(
+            transfer(
+                &program_id,
+                &account2_key,
+                &account_key,
+                &owner_key,
+                &[],
+                500,
+            )
+            .unwrap(),
+            vec![
+                &mut account2_account,
+                &mut account_account,
+                &mut owner_account,
+            ],
+        )
+        .unwrap();
+
+        // incorrect decimals
+        assert_eq!(
+            Err(TokenError::MintDecimalsMismatch.into()),
+            do_process_instruction(
+                transfer_checked(
+                    &program_id,
+                    &account2_key,
+                    &mint_key,
+                    &account_key,
+                    &owner_key,
+                    &[],
+                    1,
+                    10 // <-- incorrect decimals
+                )
+                .unwrap(),
+                vec![
+                    &mut account2_account,
+                    &mut mint_account,
+                    &mut account_account,
+                    &mut owner_account,
+                ],
+            )
+        );
+
+        // incorrect mint
+        assert_eq!(
+            Err(TokenError::MintMismatch.into()),
+            do_process_instruction(
+                transfer_checked(
+                    &program_id,
+                    &account2_key,
+                    &account3_key, // <-- incorrect mint
+                    &account_key,
+                    &owner_key,
+                    &[],
+                    1,
+                    2
+                )
+                .unwrap(),
+                vec![
+                    &mut account2_account,
+                    &mut account3_account, // <-- incorrect mint
+                    &mut account_account,
+                    &mut owner_account,
+                ],
+            )
+        );
+        // transfer rest with explicit decimals
+        do_process_instruction(
+            transfer_checked(
+                &program_id,
+                &account2_key,
+                &mint_key,
+                &account_key,
+                &owner_key,
+                &[],
+                500,
+                2,
+            )
+            .unwrap(),
+            vec![
+                &mut account2_account,
+                &mut mint_account,
+                &mut account_account,
+                &mut owner_account,
+            ],
+        )
+        .unwrap();
+
+        // insufficient funds
+        assert_eq!(
+            Err(TokenError::InsufficientFunds.into()),
+            do_process_instruction(
+                transfer(&program_id, &account2_key, &account_key, &owner_key, &[], 1).unwrap(),
+                vec![
+                    &mut account2_account,
+                    &mut account_account,
+                    &mut owner_account,
+                ],
+            )
+        );
+
+        // approve delegate
+        do_process_instruction(
+            approve(
+                &program_id,
+                &account_key,
+                &delegate_key,
+                &owner_key,
+                &[],
+                100,
+            )
+            .unwrap(),
+            vec![
+                &mut account_account,
+                &mut delegate_account,
+                &mut owner_account,
+            ],
+        )
+        .unwrap();
+
+        // transfer via delegate
+        do_process_instruction(
+            transfer(
+                &program_id,
+                &account_key,
+                &account2_key,
+                &delegate_key,
+                &[],
+                100,
+            )
+            .unwrap(),
+            vec![
+                &mut account_account,
+                &mut account2_account,
+                &mut delegate_account,
+            ],
+        )
+        .unwrap();
+
+        // insufficient funds approved via delegate
+        assert_eq!(
+            Err(TokenError::OwnerMismatch.into()),
+            do_process_instruction(
+                transfer(
+                    &program_id,
+                    &account_key,
+                    &account2_key,
+                    &delegate_key,
+                    &[],
+                    100
+                )
+                .unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut account2_account,
+                    &mut delegate_account,
+                ],
+            )
+        );
+
+        // transfer rest
+        do_process_instruction(
+            transfer(
+                &program_id,
+                &account_key,
+                &account2_key,
+                &owner_key,
+                &[],
+                900,
+            )
+            .unwrap(),
+            vec![
+                &mut account_account,
+                &mut account2_account,
+                &mut owner_account,
+            ],
+        )
+        .unwrap();
+
+        // approve delegate
+        do_process_instruction(
+            approve(
+                &program_id,
+                &account_key,
+                &delegate_key,
+                &owner_key,
+                &[],
+                100,
+            )
+            .unwrap(),
+            vec![
+                &mut account_account,
+                &mut delegate_account,
+                &mut owner_account,
+            ],
+        )
+        .unwrap();
+
+        // insufficient funds in source account via delegate
+        assert_eq!(
+            Err(TokenError::InsufficientFunds.into()),
+            do_process_instruction(
+                transfer(
+                    &program_id,
+                    &account_key,
+                    &account2_key,
+                    &delegate_key,
+                    &[],
+                    100
+                )
+                .unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut account2_account,
+                    &mut delegate_account,
+                ],
+            )
+        );
+    }
+
+    #[test]
+    fn test_transfer_checked_batch() {
+        let program_id = Pubkey::new_unique();
+        let account_key = Pubkey::new_unique();
+        let mut account_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let account2_key = Pubkey::new_unique();
+        let mut account2_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let account3_key = Pubkey::new_unique();
+        let mut account3_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let mismatch_key = Pubkey::new_unique();
+        let mut mismatch_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let delegate_key = Pubkey::new_unique();
+        let mut delegate_account = SolanaAccount::default();
+        let owner_key = Pubkey::new_unique();
+        let mut owner_account = SolanaAccount::default();
+        let owner2_key = Pubkey::new_unique();
+        let mut owner2_account = SolanaAccount::default();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let mint2_key = Pubkey::new_unique();
+        let mut mint2_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let mut rent_sysvar = rent_sysvar();
+
+        // create mints
+        do_process_instruction(
+            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+            vec![&mut mint_account, &mut rent_sysvar],
+        )
+        .unwrap();
+        do_process_instruction(
+            initialize_mint(&program_id, &mint2_key, &owner_key, None, 2).unwrap(),
+            vec![&mut mint2_account, &mut rent_sysvar],
+        )
+        .unwrap();
+
+        // create accounts
+        do_process_instruction(
+            initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                &mut account_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+        do_process_instruction(
+            initialize_account(&program_id, &account2_key, &mint_key, &owner2_key).unwrap(),
+            vec![
+                &mut account2_account,
+                &mut mint_account,
+                &mut owner2_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+        do_process_instruction(
+            initialize_account(&program_id, &account3_key, &mint_key, &owner2_key).unwrap(),
+            vec![
+                &mut account3_account,
+                &mut mint_account,
+                &mut owner2_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+        do_process_instruction(
+            initialize_account(&program_id, &mismatch_key, &mint2_key, &owner2_key).unwrap(),
+            vec![
+                &mut mismatch_account,
+                &mut mint2_account,
+                &mut owner2_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+
+        // mint to account
+        do_process_instruction(
+            mint_to(&program_id, &mint_key, &account_key, &owner_key, &[], 1000).unwrap(),
+            vec![&mut mint_account, &mut account_account, &mut owner_account],
+        )
+        .unwrap();
+
+        // no destinations
+        assert_eq!(
+            Err(TokenError::InvalidInstruction.into()),
+            transfer_checked_batch(
+                &program_id,
+                &account_key,
+                &mint_key,
+                &[],
+                &owner_key,
+                &[],
+                2,
+            )
+        );
+
+        // missing destination account
+        let mut instruction = transfer_checked_batch(
+            &program_id,
+            &account_key,
+            &mint_key,
+            &[(&account2_key, 100), (&account3_key, 200)],
+            &owner_key,
+            &[],
+            2,
+        )
+        .unwrap();
+        instruction.accounts.pop();
+        assert_eq!(
+            Err(ProgramError::NotEnoughAccountKeys),
+            do_process_instruction(
+                instruction,
+                vec![
+                    &mut account_account,
+                    &mut mint_account,
+                    &mut owner_account,
+                    &mut account2_account,
+                ],
+            )
+        );
+
+        // mismatch decimals
+        assert_eq!(
+            Err(TokenError::MintDecimalsMismatch.into()),
+            do_process_instruction(
+                transfer_checked_batch(
+                    &program_id,
+                    &account_key,
+                    &mint_key,
+                    &[(&account2_key, 100), (&account3_key, 200)],
+                    &owner_key,
+                    &[],
+                    10,
+                )
+                .unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut mint_account,
+                    &mut owner_account,
+                    &mut account2_account,
+                    &mut account3_account,
+                ],
+            )
+        );
+
+        // destination mint mismatch
+        assert_eq!(
+            Err(TokenError::MintMismatch.into()),
+            do_process_instruction(
+                transfer_checked_batch(
+                    &program_id,
+                    &account_key,
+                    &mint_key,
+                    &[(&mismatch_key, 200), (&account2_key, 100)],
+                    &owner_key,
+                    &[],
+                    2,
+                )
+                .unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut mint_account,
+                    &mut owner_account,
+                    &mut mismatch_account,
+                    &mut account2_account,
+                ],
+            )
+        );
+
+        // missing signer
+        let mut instruction = transfer_checked_batch(
+            &program_id,
+            &account_key,
+            &mint_key,
+            &[(&account2_key, 100), (&account3_key, 200)],
+            &owner_key,
+            &[],
+            2,
+        )
+        .unwrap();
+        instruction.accounts[2].is_signer = false;
+        assert_eq!(
+            Err(ProgramError::MissingRequiredSignature),
+            do_process_instruction(
+                instruction,
+                vec![
+                    &mut account_account,
+                    &mut mint_account,
+                    &mut owner_account,
+                    &mut account2_account,
+                    &mut account3_account,
+                ],
+            )
+        );
+
+        // not the owner
+        assert_eq!(
+            Err(TokenError::OwnerMismatch.into()),
+            do_process_instruction(
+                transfer_checked_batch(
+                    &program_id,
+                    &account_key,
+                    &mint_key,
+                    &[(&account2_key, 100), (&account3_key, 200)],
+                    &owner2_key,
+                    &[],
+                    2,
+                )
+                .unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut mint_account,
+                    &mut owner2_account,
+                    &mut account2_account,
+                    &mut account3_account,
+                ],
+            )
+        );
+
+        // insufficient funds across the batch
+        assert_eq!(
+            Err(TokenError::InsufficientFunds.into()),
+            do_process_instruction(
+                transfer_checked_batch(
+                    &program_id,
+                    &account_key,
+                    &mint_key,
+                    &[(&account2_key, 600), (&account3_key, 401)],
+                    &owner_key,
+                    &[],
+                    2,
+                )
+                .unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut mint_account,
+                    &mut owner_account,
+                    &mut account2_account,
+                    &mut account3_account,
+                ],
+            )
+        );
+
+        // transfer to both destinations
+        do_process_instruction(
+            transfer_checked_batch(
+                &program_id,
+                &account_key,
+                &mint_key,
+                &[(&account2_key, 100), (&account3_key, 200)],
+                &owner_key,
+                &[],
+                2,
+            )
+            .unwrap(),
+            vec![
+                &mut account_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut account2_account,
+                &mut account3_account,
+            ],
+        )
+        .unwrap();
+        let account = Account::unpack_unchecked(&account_account.data).unwrap();
+        assert_eq!(account.amount, 700);
+        let account = Account::unpack_unchecked(&account2_account.data).unwrap();
+        assert_eq!(account.amount, 100);
+        let account = Account::unpack_unchecked(&account3_account.data).unwrap();
+        assert_eq!(account.amount, 200);
+
+        // approve delegate
+        do_process_instruction(
+            approve(
+                &program_id,
+                &account_key,
+                &delegate_key,
+                &owner_key,
+                &[],
+                300,
+            )
+            .unwrap(),
+            vec![
+                &mut account_account,
+                &mut delegate_account,
+                &mut owner_account,
+            ],
+        )
+        .unwrap();
+
+        // delegate insufficient funds
+        assert_eq!(
+            Err(TokenError::InsufficientFunds.into()),
+            do_process_instruction(
+                transfer_checked_batch(
+                    &program_id,
+                    &account_key,
+                    &mint_key,
+                    &[(&account2_key, 200), (&account3_key, 101)],
+                    &delegate_key,
+                    &[],
+                    2,
+                )
+                .unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut mint_account,
+                    &mut delegate_account,
+                    &mut account2_account,
+                    &mut account3_account,
+                ],
+            )
+        );
+
+        // delegate transfer
+        do_process_instruction(
+            transfer_checked_batch(
+                &program_id,
+                &account_key,
+                &mint_key,
+                &[(&account2_key, 200), (&account3_key, 50)],
+                &delegate_key,
+                &[],
+                2,
+            )
+            .unwrap(),
+            vec![
+                &mut account_account,
+                &mut mint_account,
+                &mut delegate_account,
+                &mut account2_account,
+                &mut account3_account,
+            ],
+        )
+        .unwrap();
+        let account = Account::unpack_unchecked(&account_account.data).unwrap();
+        assert_eq!(account.amount, 450);
+        assert_eq!(account.delegate, COption::Some(delegate_key));
+        assert_eq!(account.delegated_amount, 50);
+        let account = Account::unpack_unchecked(&account2_account.data).unwrap();
+        assert_eq!(account.amount, 300);
+        let account = Account::unpack_unchecked(&account3_account.data).unwrap();
+        assert_eq!(account.amount, 250);
+    }
+
+    #[test]
+    fn test_self_transfer() {
+        let program_id = Pubkey::new_unique();
+        let account_key = Pubkey::new_unique();
+        let mut account_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let account2_key = Pubkey::new_unique();
+        let mut account2_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let account3_key = Pubkey::new_unique();
+        let mut account3_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let delegate_key = Pubkey::new_unique();
+        let mut delegate_account = SolanaAccount::default();
+        let owner_key = Pubkey::new_unique();
+        let mut owner_account = SolanaAccount::default();
+        let owner2_key = Pubkey::new_unique();
+        let mut owner2_account = SolanaAccount::default();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let mut rent_sysvar = rent_sysvar();
+
+        // create mint
+        do_process_instruction(
+            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+            vec![&mut mint_account, &mut rent_sysvar],
+        )
+        .unwrap();
+
+        // create account
+        do_process_instruction(
+            initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                &mut account_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+
+        // create another account
+        do_process_instruction(
+            initialize_account(&program_id, &account2_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                &mut account2_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+
+        // create another account
+        do_process_instruction(
+            initialize_account(&program_id, &account3_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                &mut account3_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+
+        // mint to account
+        do_process_instruction(
+            mint_to(&program_id, &mint_key, &account_key, &owner_key, &[], 1000).unwrap(),
+            vec![&mut mint_account, &mut account_account, &mut owner_account],
+        )
+        .unwrap();
+
+        let account_info = (&account_key, false, &mut account_account).into_account_info();
+        let account3_info = (&account3_key, false, &mut account3_account).into_account_info();
+        let delegate_info = (&delegate_key, true, &mut delegate_account).into_account_info();
+        let owner_info = (&owner_key, true, &mut owner_account).into_account_info();
+        let owner2_info = (&owner2_key, true, &mut owner2_account).into_account_info();
+        let mint_info = (&mint_key, false, &mut mint_account).into_account_info();
+
+        // transfer
+        let instruction = transfer(
+            &program_id,
+            &account_info.key,
+            &account_info.key,
+            &owner_info.key,
+            &[],
+            1000,
+        )
+        .unwrap();
+        assert_eq!(
+            Ok(()),
+            Processor::process(
+                &instruction.program_id,
+                &[
+                    account_info.clone(),
+                    account_info.clone(),
+                    owner_info.clone(),
+                ],
+                &instruction.data,
+            )
+        );
+        // no balance change...
+        let account = Account::unpack_unchecked(&account_info.try_borrow_data().unwrap()).unwrap();
+        assert_eq!(account.amount, 1000);
+
+        // transfer checked
+        let instruction = transfer_checked(
+            &program_id,
+            &account_info.key,
+            &mint_info.key,
+            &account_info.key,
+            &owner_info.key,
+            &[],
+            1000,
+            2,
+        )
+        .unwrap();
+        assert_eq!(
+            Ok(()),
+            Processor::process(
+                &instruction.program_id,
+                &[
+                    account_info.clone(),
+                    mint_info.clone(),
+                    account_info.clone(),
+                    owner_info.clone(),
+                ],
+                &instruction.data,
+            )
+        );
+        // no balance change...
+        let account = Account::unpack_unchecked(&account_info.try_borrow_data().unwrap()).unwrap();
+        assert_eq!(account.amount, 1000);
+
+        // missing signer
+        let mut owner_no_sign_info = owner_info.clone();
+        let mut instruction = transfer(
+            &program_id,
+            &account_info.key,
+            &account_info.key,
+            &owner_no_sign_info.key,
+            &[],
+            1000,
+        )
+        .unwrap();
+        instruction.accounts[2].is_signer = false;
+        owner_no_sign_info.is_signer = false;
+        assert_eq!(
+            Err(ProgramError::MissingRequiredSignature),
+            Processor::process(
+                &instruction.program_id,
+                &[
+                    account_info.clone(),
+                    account_info.clone(),
+                    owner_no_sign_info.clone(),
+                ],
+                &instruction.data,
+            )
+        );
+
+        // missing signer checked
+        let mut instruction = transfer_checked(
+            &program_id,
+            &account_info.key,
+            &mint_info.key,
+            &account_info.key,
+            &owner_no_sign_info.key,
+            &[],
+            1000,
+            2,
+        )
+        .unwrap();
+        instruction.accounts[3].is_signer = false;
+        assert_eq!(
+            Err(ProgramError::MissingRequiredSignature),
+            Processor::process(
+                &instruction.program_id,
+                &[
+                    account_info.clone(),
+                    mint_info.clone(),
+                    account_info.clone(),
+                    owner_no_sign_info,
+                ],
+                &instruction.data,
+            )
+        );
+
+        // missing owner
+        let instruction = transfer(
+            &program_id,
+            &account_info.key,
+            &account_info.key,
+            &owner2_info.key,
+            &[],
+            1000,
+        )
+        .unwrap();
+        assert_eq!(
+            Err(TokenError::OwnerMismatch.into()),
+            Processor::process(
+                &instruction.program_id,
+                &[
+                    account_info.clone(),
+                    account_info.clone(),
+                    owner2_info.clone(),
+                ],
+                &instruction.data,
+            )
+        );
+
+        // missing owner checked
+        let instruction = transfer_checked(
+            &program_id,
+            &account_info.key,
+            &mint_info.key,
+            &account_info.key,
+            &owner2_info.key,
+            &[],
+            1000,
+            2,
+        )
+        .unwrap();
+        assert_eq!(
+            Err(TokenError::OwnerMismatch.into()),
+            Processor::process(
+                &instruction.program_id,
+                &[
+                    account_info.clone(),
+                    mint_info.clone(),
+                    account_info.clone(),
+                    owner2_info.clone(),
+                ],
+                &instruction.data,
+            )
+        );
+
+        // insufficient funds
+        let instruction = transfer(
+            &program_id,
+            &account_info.key,
+            &account_info.key,
+            &owner_info.key,
+            &[],
+            1001,
+        )
+        .unwrap();
+        assert_eq!(
+            Err(TokenError::InsufficientFunds.into()),
+            Processor::process(
+                &instruction.program_id,
+                &[
+                    account_info.clone(),
+                    account_info.clone(),
+                    owner_info.clone(),
+                ],
+                &instruction.data,
+            )
+        );
+
+        // insufficient funds checked
+        let instruction = transfer_checked(
+            &program_id,
+            &account_info.key,
+            &mint_info.key,
+            &account_info.key,
+            &owner_info.key,
+            &[],
+            1001,
+            2,
+        )
+        .unwrap();
+        assert_eq!(
+            Err(TokenError::InsufficientFunds.into()),
+            Processor::process(
+                &instruction.program_id,
+                &[
+                    account_info.clone(),
+                    mint_info.clone(),
+                    account_info.clone(),
+                    owner_info.clone(),
+                ],
+                &instruction.data,
+            )
+        );
+
+        // incorrect decimals
+        let instruction = transfer_checked(
+            &program_id,
+            &account_info.key,
+            &mint_info.key,
+            &account_info.key,
+            &owner_info.key,
+            &[],
+            1,
+            10, // <-- incorrect decimals
+        )
+        .unwrap();
+        assert_eq!(
+            Err(TokenError::MintDecimalsMismatch.into()),
+            Processor::process(
+                &instruction.program_id,
+                &[
+                    account_info.clone(),
+                    mint_info.clone(),
+                    account_info.clone(),
+                    owner_info.clone(),
+                ],
+                &instruction.data,
+            )
+        );
+
+        // incorrect mint
+        let instruction = transfer_checked(
+            &program_id,
+            &account_info.key,
+            &account3_info.key, // <-- incorrect mint
+            &account_info.key,
+            &owner_info.key,
+            &[],
+            1,
+            2,
+        )
+        .unwrap();
+        assert_eq!(
+            Err(TokenError::MintMismatch.into()),
+            Processor::process(
+                &instruction.program_id,
+                &[
+                    account_info.clone(),
+                    account3_info.clone(), // <-- incorrect mint
+                    account_info.clone(),
+                    owner_info.clone(),
+                ],
+                &instruction.data,
+            )
+        );
+
+        // approve delegate
+        let instruction = approve(
+            &program_id,
+            &account_info.key,
+            &delegate_info.key,
+            &owner_info.key,
+            &[],
+            100,
+        )
+        .unwrap();
+        Processor::process(
+            &instruction.program_id,
+            &[
+                account_info.clone(),
+                delegate_info.clone(),
+                owner_info.clone(),
+            ],
+            &instruction.data,
+        )
+        .unwrap();
+
+        // delegate transfer
+        let instruction = transfer(
+            &program_id,
+            &account_info.key,
+            &account_info.key,
+            &delegate_info.key,
+            &[],
+            100,
+        )
+        .unwrap();
+        assert_eq!(
+            Ok(()),
+            Processor::process(
+                &instruction.program_id,
+                &[
+                    account_info.clone(),
+                    account_info.clone(),
+                    delegate_info.clone(),
+                ],
+                &instruction.data,
+            )
+        );
+        // no balance change...
+        let account = Account::unpack_unchecked(&account_info.try_borrow_data().unwrap()).unwrap();
+        assert_eq!(account.amount, 1000);
+        assert_eq!(account.delegated_amount, 100);
+
+        // delegate transfer checked
+        let instruction = transfer_checked(
+            &program_id,
+            &account_info.key,
+            &mint_info.key,
+            &account_info.key,
+            &delegate_info.key,
+            &[],
+            100,
+            2,
+        )
+        .unwrap();
+        assert_eq!(
+            Ok(()),
+            Processor::process(
+                &instruction.program_id,
+                &[
+                    account_info.clone(),
+                    mint_info.clone(),
+                    account_info.clone(),
+                    delegate_info.clone(),
+                ],
+                &instruction.data,
+            )
+        );
+        // no balance change...
+        let account = Account::unpack_unchecked(&account_info.try_borrow_data().unwrap()).unwrap();
+        assert_eq!(account.amount, 1000);
+        assert_eq!(account.delegated_amount, 100);
+
+        // delegate insufficient funds
+        let instruction = transfer(
+            &program_id,
+            &account_info.key,
+            &account_info.key,
+            &delegate_info.key,
+            &[],
+            101,
+        )
+        .unwrap();
+        assert_eq!(
+            Err(TokenError::InsufficientFunds.into()),
+            Processor::process(
+                &instruction.program_id,
+                &[
+                    account_info.clone(),
+                    account_info.clone(),
+                    delegate_info.clone(),
+                ],
+                &instruction.data,
+            )
+        );
+
+        // delegate insufficient funds checked
+        let instruction = transfer_checked(
+            &program_id,
+            &account_info.key,
+            &mint_info.key,
+            &account_info.key,
+            &delegate_info.key,
+            &[],
+            101,
+            2,
+        )
+        .unwrap();
+        assert_eq!(
+            Err(TokenError::InsufficientFunds.into()),
+            Processor::process(
+                &instruction.program_id,
+                &[
+                    account_info.clone(),
+                    mint_info.clone(),
+                    account_info.clone(),
+                    delegate_info.clone(),
+                ],
+                &instruction.data,
+            )
+        );
+
+        // owner transfer with delegate assigned
+        let instruction = transfer(
+            &program_id,
+            &account_info.key,
+            &account_info.key,
+            &owner_info.key,
+            &[],
+            1000,
+        )
+        .unwrap();
+        assert_eq!(
+            Ok(()),
+            Processor::process(
+                &instruction.program_id,
+                &[
+                    account_info.clone(),
+                    account_info.clone(),
+                    owner_info.clone(),
+                ],
+                &instruction.data,
+            )
+        );
+        // no balance change...
+        let account = Account::unpack_unchecked(&account_info.try_borrow_data().unwrap()).unwrap();
+        assert_eq!(account.amount, 1000);
+
+        // owner transfer with delegate assigned checked
+        let instruction = transfer_checked(
+            &program_id,
+            &account_info.key,
+            &mint_info.key,
+            &account_info.key,
+            &owner_info.key,
+            &[],
+            1000,
+            2,
+        )
+        .unwrap();
+        assert_eq!(
+            Ok(()),
+            Processor::process(
+                &instruction.program_id,
+                &[
+                    account_info.clone(),
+                    mint_info.clone(),
+                    account_info.clone(),
+                    owner_info.clone(),
+                ],
+                &instruction.data,
+            )
+        );
+        // no balance change...
+        let account = Account::unpack_unchecked(&account_info.try_borrow_data().unwrap()).unwrap();
+        assert_eq!(account.amount, 1000);
+    }
+
+    #[test]
+    fn test_mintable_token_with_zero_supply() {
+        let program_id = Pubkey::new_unique();
+        let account_key = Pubkey::new_unique();
+        let mut account_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let owner_key = Pubkey::new_unique();
+        let mut owner_account = SolanaAccount::default();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let mut rent_sysvar = rent_sysvar();
+
+        // create mint-able token with zero supply
+        let decimals = 2;
+        do_process_instruction(
+            initialize_mint(&program_id, &mint_key, &owner_key, None, decimals).unwrap(),
+            vec![&mut mint_account, &mut rent_sysvar],
+        )
+        .unwrap();
+        let mint = Mint::unpack_unchecked(&mint_account.data).unwrap();
+        assert_eq!(
+            mint,
+            Mint {
+                mint_authority: COption::Some(owner_key),
+                supply: 0,
+                decimals,
+                is_initialized: true,
+                freeze_authority: COption::None,
+            }
+        );
+
+        // create account
+        do_process_instruction(
+            initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                &mut account_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+
+        // mint to
+        do_process_instruction(
+            mint_to(&program_id, &mint_key, &account_key, &owner_key, &[], 42).unwrap(),
+            vec![&mut mint_account, &mut account_account, &mut owner_account],
+        )
+        .unwrap();
+        let _ = Mint::unpack(&mint_account.data).unwrap();
+        let account = Account::unpack_unchecked(&account_account.data).unwrap();
+        assert_eq!(account.amount, 42);
+
+        // mint to 2, with incorrect decimals
+        assert_eq!(
+            Err(TokenError::MintDecimalsMismatch.into()),
+            do_process_instruction(
+                mint_to_checked(
+                    &program_id,
+                    &mint_key,
+                    &account_key,
+                    &owner_key,
+                    &[],
+                    42,
+                    decimals + 1
+                )
+                .unwrap(),
+                vec![&mut mint_account, &mut account_account, &mut owner_account],
+            )
+        );
+
+        let _ = Mint::unpack(&mint_account.data).unwrap();
+        let account = Account::unpack_unchecked(&account_account.data).unwrap();
+        assert_eq!(account.amount, 42);
+
+        // mint to 2
+        do_process_instruction(
+            mint_to_checked(
+                &program_id,
+                &mint_key,
+                &account_key,
+                &owner_key,
+                &[],
+                42,
+                decimals,
+            )
+            .unwrap(),
+            vec![&mut mint_account, &mut account_account, &mut owner_account],
+        )
+        .unwrap();
+        let _ = Mint::unpack(&mint_account.data).unwrap();
+        let account = Account::unpack_unchecked(&account_account.data).unwrap();
+        assert_eq!(account.amount, 84);
+    }
+
+    #[test]
+    fn test_approve_dups() {
+        let program_id = Pubkey::new_unique();
+        let account1_key = Pubkey::new_unique();
+        let mut account1_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let account1_info: AccountInfo = (&account1_key, true, &mut account1_account).into();
+        let account2_key = Pubkey::new_unique();
+        let mut account2_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let account2_info: AccountInfo = (&account2_key, false, &mut account2_account).into();
+        let account3_key = Pubkey::new_unique();
+        let mut account3_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let account3_info: AccountInfo = (&account3_key, true, &mut account3_account).into();
+        let multisig_key = Pubkey::new_unique();
+        let mut multisig_account = SolanaAccount::new(
+            multisig_minimum_balance(),
+            Multisig::get_packed_len(),
+            &program_id,
+        );
+        let multisig_info: AccountInfo = (&multisig_key, true, &mut multisig_account).into();
+        let owner_key = Pubkey::new_unique();
+        let mut owner_account = SolanaAccount::default();
+        let owner_info: AccountInfo = (&owner_key, true, &mut owner_account).into();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let mint_info: AccountInfo = (&mint_key, false, &mut mint_account).into();
+        let rent_key = rent::id();
+        let mut rent_sysvar = rent_sysvar();
+        let rent_info: AccountInfo = (&rent_key, false, &mut rent_sysvar).into();
+
+        // create mint
+        do_process_instruction_dups(
+            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+            vec![mint_info.clone(), rent_info.clone()],
+        )
+        .unwrap();
+
+        // create account
+        do_process_instruction_dups(
+            initialize_account(&program_id, &account1_key, &mint_key, &account1_key).unwrap(),
+            vec![
+                account1_info.clone(),
+                mint_info.clone(),
+                account1_info.clone(),
+                rent_info.clone(),
+            ],
+        )
+        .unwrap();
+
+        // create another account
+        do_process_instruction_dups(
+            initialize_account(&program_id, &account2_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                account2_info.clone(),
+                mint_info.clone(),
+                owner_info.clone(),
+                rent_info.clone(),
+            ],
+        )
+        .unwrap();
+
+        // mint to account
+        do_process_instruction_dups(
+            mint_to(&program_id, &mint_key, &account1_key, &owner_key, &[], 1000).unwrap(),
+            vec![mint_info.clone(), account1_info.clone(), owner_info.clone()],
+        )
+        .unwrap();
+
+        // source-owner approve
+        do_process_instruction_dups(
+            approve(
+                &program_id,
+                &account1_key,
+                &account2_key,
+                &account1_key,
+                &[],
+                500,
+            )
+            .unwrap(),
+            vec![
+                account1_info.clone(),
+                account2_info.clone(),
+                account1_info.clone(),
+            ],
+        )
+        .unwrap();
+
+        // source-owner approve_checked
+        do_process_instruction_dups(
+            approve_checked(
+                &program_id,
+                &account1_key,
+                &mint_key,
+                &account2_key,
+                &account1_key,
+                &[],
+                500,
+                2,
+            )
+            .unwrap(),
+            vec![
+                account1_info.clone(),
+                mint_info.clone(),
+                account2_info.clone(),
+                account1_info.clone(),
+            ],
+        )
+        .unwrap();
+
+        // source-owner revoke
+        do_process_instruction_dups(
+            revoke(&program_id, &account1_key, &account1_key, &[]).unwrap(),
+            vec![account1_info.clone(), account1_info.clone()],
+        )
+        .unwrap();
+
+        // test source-multisig signer
+        do_process_instruction_dups(
+            initialize_multisig(&program_id, &multisig_key, &[&account3_key], 1).unwrap(),
+            vec![
+                multisig_info.clone(),
+                rent_info.clone(),
+                account3_info.clone(),
+            ],
+        )
+        .unwrap();
+
+        do_process_instruction_dups(
+            initialize_account(&program_id, &account3_key, &mint_key, &multisig_key).unwrap(),
+            vec![
+                account3_info.clone(),
+                mint_info.clone(),
+                multisig_info.clone(),
+                rent_info.clone(),
+            ],
+        )
+        .unwrap();
+
+        do_process_instruction_dups(
+            mint_to(&program_id, &mint_key, &account3_key, &owner_key, &[], 1000).unwrap(),
+            vec![mint_info.clone(), account3_info.clone(), owner_info.clone()],
+        )
+        .unwrap();
+
+        // source-multisig-signer approve
+        do_process_instruction_dups(
+            approve(
+                &program_id,
+                &account3_key,
+                &account2_key,
+                &multisig_key,
+                &[&account3_key],
+                500,
+            )
+            .unwrap(),
+            vec![
+                account3_info.clone(),
+                account2_info.clone(),
+                multisig_info.clone(),
+                account3_info.clone(),
+            ],
+        )
+        .unwrap();
+
+        // source-multisig-signer approve_checked
+        do_process_instruction_dups(
+            approve_checked(
+                &program_id,
+                &account3_key,
+                &mint_key,
+                &account2_key,
+                &multisig_key,
+                &[&account3_key],
+                500,
+                2,
+            )
+            .unwrap(),
+            vec![
+                account3_info.clone(),
+                mint_info.clone(),
+                account2_info.clone(),
+                multisig_info.clone(),
+                account3_info.clone(),
+            ],
+        )
+        .unwrap();
+
+        // source-owner multisig-signer
+        do_process_instruction_dups(
+            revoke(&program_id, &account3_key, &multisig_key, &[&account3_key]).unwrap(),
+            vec![
+                account3_info.clone(),
+                multisig_info.clone(),
+                account3_info.clone(),
+            ],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_approve() {
+        let program_id = Pubkey::new_unique();
+        let account_key = Pubkey::new_unique();
+        let mut account_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let account2_key = Pubkey::new_unique();
+        let mut account2_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let delegate_key = Pubkey::new_unique();
+        let mut delegate_account = SolanaAccount::default();
+        let owner_key = Pubkey::new_unique();
+        let mut owner_account = SolanaAccount::default();
+        let owner2_key = Pubkey::new_unique();
+        let mut owner2_account = SolanaAccount::default();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let mut rent_sysvar = rent_sysvar();
+
+        // create mint
+        do_process_instruction(
+            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+            vec![&mut mint_account, &mut rent_sysvar],
+        )
+        .unwrap();
+
+        // create account
+        do_process_instruction(
+            initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                &mut account_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+
+        // create another account
+        do_process_instruction(
+            initialize_account(&program_id, &account2_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                &mut account2_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+
+        // mint to account
+        do_process_instruction(
+            mint_to(&program_id, &mint_key, &account_key, &owner_key, &[], 1000).unwrap(),
+            vec![&mut mint_account, &mut account_account, &mut owner_account],
+        )
+        .unwrap();
+
+        // missing signer
+        let mut instruction = approve(
+            &program_id,
+            &account_key,
+            &delegate_key,
+            &owner_key,
+            &[],
+            100,
+        )
+        .unwrap();
+        instruction.accounts[2].is_signer = false;
+        assert_eq!(
+            Err(ProgramError::MissingRequiredSignature),
+            do_process_instruction(
+                instruction,
+                vec![
+                    &mut account_account,
+                    &mut delegate_account,
+                    &mut owner_account,
+                ],
+            )
+        );
+
+        // no owner
+        assert_eq!(
+            Err(TokenError::OwnerMismatch.into()),
+            do_process_instruction(
+                approve(
+                    &program_id,
+                    &account_key,
+                    &delegate_key,
+                    &owner2_key,
+                    &[],
+                    100
+                )
+                .unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut delegate_account,
+                    &mut owner2_account,
+                ],
+            )
+        );
+
+        // approve delegate
+        do_process_instruction(
+            approve(
+                &program_id,
+                &account_key,
+                &delegate_key,
+                &owner_key,
+                &[],
+                100,
+            )
+            .unwrap(),
+            vec![
+                &mut account_account,
+                &mut delegate_account,
+                &mut owner_account,
+            ],
+        )
+        .unwrap();
+
+        // approve delegate 2, with incorrect decimals
+        assert_eq!(
+            Err(TokenError::MintDecimalsMismatch.into()),
+            do_process_instruction(
+                approve_checked(
+                    &program_id,
+                    &account_key,
+                    &mint_key,
+                    &delegate_key,
+                    &owner_key,
+                    &[],
+                    100,
+                    0 // <-- incorrect decimals
+                )
+                .unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut mint_account,
+                    &mut delegate_account,
+                    &mut owner_account,
+                ],
+            )
+        );
+
+        // approve delegate 2, with incorrect mint
+        assert_eq!(
+            Err(TokenError::MintMismatch.into()),
+            do_process_instruction(
+                approve_checked(
+                    &program_id,
+                    &account_key,
+                    &account2_key, // <-- bad mint
+                    &delegate_key,
+                    &owner_key,
+                    &[],
+                    100,
+                    0
+                )
+                .unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut account2_account, // <-- bad mint
+                    &mut delegate_account,
+                    &mut owner_account,
+                ],
+            )
+        );
+
+        // approve delegate 2
+        do_process_instruction(
+            approve_checked(
+                &program_id,
+                &account_key,
+                &mint_key,
+                &delegate_key,
+                &owner_key,
+                &[],
+                100,
+                2,
+            )
+            .unwrap(),
+            vec![
+                &mut account_account,
+                &mut mint_account,
+                &mut delegate_account,
+                &mut owner_account,
+            ],
+        )
+        .unwrap();
+
+        // revoke delegate
+        do_process_instruction(
+            revoke(&program_id, &account_key, &owner_key, &[]).unwrap(),
+            vec![&mut account_account, &mut owner_account],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_approve_with_expiry() {
+        let program_id = Pubkey::new_unique();
+        let account_len = ExtensionType::get_account_len(&[ExtensionType::DelegationExpiry]);
+        let account_key = Pubkey::new_unique();
+        let mut account_account = SolanaAccount::new(
+            Rent::default().minimum_balance(account_len),
+            account_len,
+            &program_id,
+        );
+        let account2_key = Pubkey::new_unique();
+        let mut account2_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let delegate_key = Pubkey::new_unique();
+        let mut delegate_account = SolanaAccount::default();
+        let owner_key = Pubkey::new_unique();
+        let mut owner_account = SolanaAccount::default();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let mut rent_sysvar = rent_sysvar();
+
+        // create mint
+        do_process_instruction(
+            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+            vec![&mut mint_account, &mut rent_sysvar],
+        )
+        .unwrap();
+
+        // create extended account
+        do_process_instruction(
+            initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                &mut account_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+
+        // create base account
+        do_process_instruction(
+            initialize_account(&program_id, &account2_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                &mut account2_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+
+        // mint to account
+        do_process_instruction(
+            mint_to(&program_id, &mint_key, &account_key, &owner_key, &[], 1000).unwrap(),
+            vec![&mut mint_account, &mut account_account, &mut owner_account],
+        )
+        .unwrap();
+
+        // base account cannot hold an expiry
+        assert_eq!(
+            Err(ProgramError::InvalidAccountData),
+            do_process_instruction(
+                approve_checked_with_expiry(
+                    &program_id,
+                    &account2_key,
+                    &mint_key,
+                    &delegate_key,
+                    &owner_key,
+                    &[],
+                    100,
+                    2,
+                    CLOCK_SLOT,
+                )
+                .unwrap(),
+                vec![
+                    &mut account2_account,
+                    &mut mint_account,
+                    &mut delegate_account,
+                    &mut owner_account,
+                ],
+            )
+        );
+
+        // approve delegate until the current slot
+        do_process_instruction(
+            approve_checked_with_expiry(
+                &program_id,
+                &account_key,
+                &mint_key,
+                &delegate_key,
+                &owner_key,
+                &[],
+                100,
+                2,
+                CLOCK_SLOT,
+            )
+            .unwrap(),
+            vec![
+                &mut account_account,
+                &mut mint_account,
+                &mut delegate_account,
+                &mut owner_account,
+            ],
+        )
+        .unwrap();
+        let account = Account::unpack_from_account_data(&account_account.data).unwrap();
+        assert_eq!(account.delegate, COption::Some(delegate_key));
+        assert_eq!(account.delegated_amount, 100);
+        assert_eq!(
+            get_extension::<DelegationExpiry>(&account_account.data).unwrap(),
+            Some(DelegationExpiry {
+                expiry_slot: COption::Some(CLOCK_SLOT)
+            })
+        );
+
+        // delegate may transfer in the expiry slot
+        do_process_instruction(
+            transfer(
+                &program_id,
+                &account_key,
+                &account2_key,
+                &delegate_key,
+                &[],
+                10,
+            )
+            .unwrap(),
+            vec![
+                &mut account_account,
+                &mut account2_account,
+                &mut delegate_account,
+            ],
+        )
+        .unwrap();
+
+        // approve delegate until a past slot
+        do_process_instruction(
+            approve_checked_with_expiry(
+                &program_id,
+                &account_key,
+                &mint_key,
+                &delegate_key,
+                &owner_key,
+                &[],
+                100,
+                2,
+                CLOCK_SLOT - 1,
+            )
+            .unwrap(),
+            vec![
+                &mut account_account,
+                &mut mint_account,
+                &mut delegate_account,
+                &mut owner_account,
+            ],
+        )
+        .unwrap();
+
+        // expired delegate cannot transfer
+        assert_eq!(
+            Err(TokenError::DelegationExpired.into()),
+            do_process_instruction(
+                transfer(
+                    &program_id,
+                    &account_key,
+                    &account2_key,
+                    &delegate_key,
+                    &[],
+                    10,
+                )
+                .unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut account2_account,
+                    &mut delegate_account,
+                ],
+            )
+        );
+
+        // expired delegate cannot burn
+        assert_eq!(
+            Err(TokenError::DelegationExpired.into()),
+            do_process_instruction(
+                burn(&program_id, &account_key, &mint_key, &delegate_key, &[], 10).unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut mint_account,
+                    &mut delegate_account
+                ],
+            )
+        );
+
+        // owner is unaffected by the expiry
+        do_process_instruction(
+            transfer(
+                &program_id,
+                &account_key,
+                &account2_key,
+                &owner_key,
+                &[],
+                10,
+            )
+            .unwrap(),
+            vec![
+                &mut account_account,
+                &mut account2_account,
+                &mut owner_account,
+            ],
+        )
+        .unwrap();
+
+        // a plain approval does not expire
+        do_process_instruction(
+            approve(
+                &program_id,
+                &account_key,
+                &delegate_key,
+                &owner_key,
+                &[],
+                100,
+            )
+            .unwrap(),
+            vec![
+                &mut account_account,
+                &mut delegate_account,
+                &mut owner_account,
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            get_extension::<DelegationExpiry>(&account_account.data).unwrap(),
+            Some(DelegationExpiry {
+                expiry_slot: COption::None
+            })
+        );
+        do_process_instruction(
+            burn(&program_id, &account_key, &mint_key, &delegate_key, &[], 10).unwrap(),
+            vec![
+                &mut account_account,
+                &mut mint_account,
+                &mut delegate_account,
+            ],
+        )
+        .unwrap();
+        let account = Account::unpack_from_account_data(&account_account.data).unwrap();
+        assert_eq!(account.amount, 970);
+        assert_eq!(account.delegated_amount, 90);
     }
 
     #[test]
-    fn test_unique_account_sizes() {
-        assert_ne!(Mint::get_packed_len(), 0);
-        assert_ne!(Mint::get_packed_len(), Account::get_packed_len());
-        assert_ne!(Mint::get_packed_len(), Multisig::get_packed_len());
-        assert_ne!(Account::get_packed_len(), 0);
-        assert_ne!(Account::get_packed_len(), Multisig::get_packed_len());
-        assert_ne!(Multisig::get_packed_len(), 0);
+    fn test_set_authority_dups() {
+        let program_id = Pubkey::new_unique();
+        let account1_key = Pubkey::new_unique();
+        let mut account1_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let account1_info: AccountInfo = (&account1_key, true, &mut account1_account).into();
+        let owner_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let mint_info: AccountInfo = (&mint_key, true, &mut mint_account).into();
+        let rent_key = rent::id();
+        let mut rent_sysvar = rent_sysvar();
+        let rent_info: AccountInfo = (&rent_key, false, &mut rent_sysvar).into();
+
+        // create mint
+        do_process_instruction_dups(
+            initialize_mint(&program_id, &mint_key, &mint_key, Some(&mint_key), 2).unwrap(),
+            vec![mint_info.clone(), rent_info.clone()],
+        )
+        .unwrap();
+
+        // create account
+        do_process_instruction_dups(
+            initialize_account(&program_id, &account1_key, &mint_key, &account1_key).unwrap(),
+            vec![
+                account1_info.clone(),
+                mint_info.clone(),
+                account1_info.clone(),
+                rent_info.clone(),
+            ],
+        )
+        .unwrap();
+
+        // set mint_authority when currently self
+        do_process_instruction_dups(
+            set_authority(
+                &program_id,
+                &mint_key,
+                Some(&owner_key),
+                AuthorityType::MintTokens,
+                &mint_key,
+                &[],
+            )
+            .unwrap(),
+            vec![mint_info.clone(), mint_info.clone()],
+        )
+        .unwrap();
+
+        // set freeze_authority when currently self
+        do_process_instruction_dups(
+            set_authority(
+                &program_id,
+                &mint_key,
+                Some(&owner_key),
+                AuthorityType::FreezeAccount,
+                &mint_key,
+                &[],
+            )
+            .unwrap(),
+            vec![mint_info.clone(), mint_info.clone()],
+        )
+        .unwrap();
+
+        // set account owner when currently self
+        do_process_instruction_dups(
+            set_authority(
+                &program_id,
+                &account1_key,
+                Some(&owner_key),
+                AuthorityType::AccountOwner,
+                &account1_key,
+                &[],
+            )
+            .unwrap(),
+            vec![account1_info.clone(), account1_info.clone()],
+        )
+        .unwrap();
+
+        // set close_authority when currently self
+        let mut account = Account::unpack_unchecked(&account1_info.data.borrow()).unwrap();
+        account.close_authority = COption::Some(account1_key);
+        Account::pack(account, &mut account1_info.data.borrow_mut()).unwrap();
+
+        do_process_instruction_dups(
+            set_authority(
+                &program_id,
+                &account1_key,
+                Some(&owner_key),
+                AuthorityType::CloseAccount,
+                &account1_key,
+                &[],
+            )
+            .unwrap(),
+            vec![account1_info.clone(), account1_info.clone()],
+        )
+        .unwrap();
     }
 
     #[test]
-    fn test_pack_unpack() {
-        // Mint
-        let check = Mint {
-            mint_authority: COption::Some(Pubkey::new(&[1; 32])),
-            supply: 42,
-            decimals: 7,
-            is_initialized: true,
-            freeze_authority: COption::Some(Pubkey::new(&[2; 32])),
-        };
-        let mut packed = vec![0; Mint::get_packed_len() + 1];
+    fn test_set_authority() {
+        let program_id = Pubkey::new_unique();
+        let account_key = Pubkey::new_unique();
+        let mut account_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let account2_key = Pubkey::new_unique();
+        let mut account2_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let owner_key = Pubkey::new_unique();
+        let mut owner_account = SolanaAccount::default();
+        let owner2_key = Pubkey::new_unique();
+        let mut owner2_account = SolanaAccount::default();
+        let owner3_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let mint2_key = Pubkey::new_unique();
+        let mut mint2_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let mut rent_sysvar = rent_sysvar();
+
+        // create new mint with owner
+        do_process_instruction(
+            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+            vec![&mut mint_account, &mut rent_sysvar],
+        )
+        .unwrap();
+
+        // create mint with owner and freeze_authority
+        do_process_instruction(
+            initialize_mint(&program_id, &mint2_key, &owner_key, Some(&owner_key), 2).unwrap(),
+            vec![&mut mint2_account, &mut rent_sysvar],
+        )
+        .unwrap();
+
+        // invalid account
         assert_eq!(
-            Err(ProgramError::InvalidAccountData),
-            Mint::pack(check, &mut packed)
+            Err(ProgramError::UninitializedAccount),
+            do_process_instruction(
+                set_authority(
+                    &program_id,
+                    &account_key,
+                    Some(&owner2_key),
+                    AuthorityType::AccountOwner,
+                    &owner_key,
+                    &[]
+                )
+                .unwrap(),
+                vec![&mut account_account, &mut owner_account],
+            )
         );
-        let mut packed = vec![0; Mint::get_packed_len() - 1];
+
+        // create account
+        do_process_instruction(
+            initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                &mut account_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+
+        // create another account
+        do_process_instruction(
+            initialize_account(&program_id, &account2_key, &mint2_key, &owner_key).unwrap(),
+            vec![
+                &mut account2_account,
+                &mut mint2_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+
+        // missing owner
         assert_eq!(
-            Err(ProgramError::InvalidAccountData),
-            Mint::pack(check, &mut packed)
+            Err(TokenError::OwnerMismatch.into()),
+            do_process_instruction(
+                set_authority(
+                    &program_id,
+                    &account_key,
+                    Some(&owner_key),
+                    AuthorityType::AccountOwner,
+                    &owner2_key,
+                    &[]
+                )
+                .unwrap(),
+                vec![&mut account_account, &mut owner2_account],
+            )
         );
-        let mut packed = vec![0; Mint::get_packed_len()];
-        Mint::pack(check, &mut packed).unwrap();
-        let expect = vec![
-            1, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
-            1, 1, 1, 1, 1, 1, 1, 42, 0, 0, 0, 0, 0, 0, 0, 7, 1, 1, 0, 0, 0, 2, 2, 2, 2, 2, 2, 2, 2,
-            2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2,
-        ];
-        assert_eq!(packed, expect);
-        let unpacked = Mint::unpack(&packed).unwrap();
-        assert_eq!(unpacked, check);
 
-        // Account
-        let check = Account {
-            mint: Pubkey::new(&[1; 32]),
-            owner: Pubkey::new(&[2; 32]),
-            amount: 3,
-            delegate: COption::Some(Pubkey::new(&[4; 32])),
-            state: AccountState::Frozen,
-            is_native: COption::Some(5),
-            delegated_amount: 6,
-            close_authority: COption::Some(Pubkey::new(&[7; 32])),
-        };
-        let mut packed = vec![0; Account::get_packed_len() + 1];
+        // owner did not sign
+        let mut instruction = set_authority(
+            &program_id,
+            &account_key,
+            Some(&owner2_key),
+            AuthorityType::AccountOwner,
+            &owner_key,
+            &[],
+        )
+        .unwrap();
+        instruction.accounts[1].is_signer = false;
         assert_eq!(
-            Err(ProgramError::InvalidAccountData),
-            Account::pack(check, &mut packed)
+            Err(ProgramError::MissingRequiredSignature),
+            do_process_instruction(instruction, vec![&mut account_account, &mut owner_account,],)
         );
-        let mut packed = vec![0; Account::get_packed_len() - 1];
+
+        // wrong authority type
         assert_eq!(
-            Err(ProgramError::InvalidAccountData),
-            Account::pack(check, &mut packed)
+            Err(TokenError::AuthorityTypeNotSupported.into()),
+            do_process_instruction(
+                set_authority(
+                    &program_id,
+                    &account_key,
+                    Some(&owner2_key),
+                    AuthorityType::FreezeAccount,
+                    &owner_key,
+                    &[],
+                )
+                .unwrap(),
+                vec![&mut account_account, &mut owner_account],
+            )
         );
-        let mut packed = vec![0; Account::get_packed_len()];
-        Account::pack(check, &mut packed).unwrap();
-        let expect = vec![
-            1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
-            1, 1, 1, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2,
-            2, 2, 2, 2, 2, 2, 3, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4,
-            4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 2, 1, 0, 0, 0, 5, 0, 0,
-            0, 0, 0, 0, 0, 6, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7,
-            7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7,
-        ];
-        assert_eq!(packed, expect);
-        let unpacked = Account::unpack(&packed).unwrap();
-        assert_eq!(unpacked, check);
 
-        // Multisig
-        let check = Multisig {
-            m: 1,
-            n: 2,
-            is_initialized: true,
-            signers: [Pubkey::new(&[3; 32]); MAX_SIGNERS],
-        };
-        let mut packed = vec![0; Multisig::get_packed_len() + 1];
+        // account owner may not be set to None
+        assert_eq!(
+            Err(TokenError::InvalidInstruction.into()),
+            do_process_instruction(
+                set_authority(
+                    &program_id,
+                    &account_key,
+                    None,
+                    AuthorityType::AccountOwner,
+                    &owner_key,
+                    &[],
+                )
+                .unwrap(),
+                vec![&mut account_account, &mut owner_account],
+            )
+        );
+
+        // set owner
+        do_process_instruction(
+            set_authority(
+                &program_id,
+                &account_key,
+                Some(&owner2_key),
+                AuthorityType::AccountOwner,
+                &owner_key,
+                &[],
+            )
+            .unwrap(),
+            vec![&mut account_account, &mut owner_account],
+        )
+        .unwrap();
+
+        // set close_authority
+        do_process_instruction(
+            set_authority(
+                &program_id,
+                &account_key,
+                Some(&owner2_key),
+                AuthorityType::CloseAccount,
+                &owner2_key,
+                &[],
+            )
+            .unwrap(),
+            vec![&mut account_account, &mut owner2_account],
+        )
+        .unwrap();
+
+        // close_authority may be set to None
+        do_process_instruction(
+            set_authority(
+                &program_id,
+                &account_key,
+                None,
+                AuthorityType::CloseAccount,
+                &owner2_key,
+                &[],
+            )
+            .unwrap(),
+            vec![&mut account_account, &mut owner2_account],
+        )
+        .unwrap();
+
+        // wrong owner
         assert_eq!(
-            Err(ProgramError::InvalidAccountData),
-            Multisig::pack(check, &mut packed)
+            Err(TokenError::OwnerMismatch.into()),
+            do_process_instruction(
+                set_authority(
+                    &program_id,
+                    &mint_key,
+                    Some(&owner3_key),
+                    AuthorityType::MintTokens,
+                    &owner2_key,
+                    &[]
+                )
+                .unwrap(),
+                vec![&mut mint_account, &mut owner2_account],
+            )
         );
-        let mut packed = vec![0; Multisig::get_packed_len() - 1];
+
+        // owner did not sign
+        let mut instruction = set_authority(
+            &program_id,
+            &mint_key,
+            Some(&owner2_key),
+            AuthorityType::MintTokens,
+            &owner_key,
+            &[],
+        )
+        .unwrap();
+        instruction.accounts[1].is_signer = false;
         assert_eq!(
-            Err(ProgramError::InvalidAccountData),
-            Multisig::pack(check, &mut packed)
+            Err(ProgramError::MissingRequiredSignature),
+            do_process_instruction(instruction, vec![&mut mint_account, &mut owner_account],)
         );
-        let mut packed = vec![0; Multisig::get_packed_len()];
-        Multisig::pack(check, &mut packed).unwrap();
-        let expect = vec![
-            1, 2, 1, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
-            3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
-            3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
-            3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
-            3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
-            3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
-            3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
-            3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
-            3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
-            3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
-            3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
-            3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3,
-            3, 3, 3, 3, 3, 3, 3,
-        ];
-        assert_eq!(packed, expect);
-        let unpacked = Multisig::unpack(&packed).unwrap();
-        assert_eq!(unpacked, check);
-    }
-
-    #[test]
-    fn test_initialize_mint() {
-        let program_id = Pubkey::new_unique();
-        let owner_key = Pubkey::new_unique();
-        let mint_key = Pubkey::new_unique();
-        let mut mint_account = SolanaAccount::new(42, Mint::get_packed_len(), &program_id);
-        let mint2_key = Pubkey::new_unique();
-        let mut mint2_account =
-            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
-        let mut rent_sysvar = rent_sysvar();
 
-        // mint is not rent exempt
+        // cannot freeze
         assert_eq!(
-            Err(TokenError::NotRentExempt.into()),
+            Err(TokenError::MintCannotFreeze.into()),
             do_process_instruction(
-                initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
-                vec![&mut mint_account, &mut rent_sysvar]
+                set_authority(
+                    &program_id,
+                    &mint_key,
+                    Some(&owner2_key),
+                    AuthorityType::FreezeAccount,
+                    &owner_key,
+                    &[],
+                )
+                .unwrap(),
+                vec![&mut mint_account, &mut owner_account],
             )
         );
 
-        mint_account.lamports = mint_minimum_balance();
+        // set owner
+        do_process_instruction(
+            set_authority(
+                &program_id,
+                &mint_key,
+                Some(&owner2_key),
+                AuthorityType::MintTokens,
+                &owner_key,
+                &[],
+            )
+            .unwrap(),
+            vec![&mut mint_account, &mut owner_account],
+        )
+        .unwrap();
 
-        // create new mint
+        // set owner to None
         do_process_instruction(
-            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
-            vec![&mut mint_account, &mut rent_sysvar],
+            set_authority(
+                &program_id,
+                &mint_key,
+                None,
+                AuthorityType::MintTokens,
+                &owner2_key,
+                &[],
+            )
+            .unwrap(),
+            vec![&mut mint_account, &mut owner2_account],
         )
         .unwrap();
 
-        // create twice
+        // test unsetting mint_authority is one-way operation
         assert_eq!(
-            Err(TokenError::AlreadyInUse.into()),
+            Err(TokenError::FixedSupply.into()),
             do_process_instruction(
-                initialize_mint(&program_id, &mint_key, &owner_key, None, 2,).unwrap(),
-                vec![&mut mint_account, &mut rent_sysvar]
+                set_authority(
+                    &program_id,
+                    &mint2_key,
+                    Some(&owner2_key),
+                    AuthorityType::MintTokens,
+                    &owner_key,
+                    &[]
+                )
+                .unwrap(),
+                vec![&mut mint_account, &mut owner_account],
             )
         );
 
-        // create another mint that can freeze
+        // set freeze_authority
         do_process_instruction(
-            initialize_mint(&program_id, &mint2_key, &owner_key, Some(&owner_key), 2).unwrap(),
-            vec![&mut mint2_account, &mut rent_sysvar],
+            set_authority(
+                &program_id,
+                &mint2_key,
+                Some(&owner2_key),
+                AuthorityType::FreezeAccount,
+                &owner_key,
+                &[],
+            )
+            .unwrap(),
+            vec![&mut mint2_account, &mut owner_account],
         )
         .unwrap();
-        let mint = Mint::unpack_unchecked(&mint2_account.data).unwrap();
-        assert_eq!(mint.freeze_authority, COption::Some(owner_key));
+
+        // test unsetting freeze_authority is one-way operation
+        do_process_instruction(
+            set_authority(
+                &program_id,
+                &mint2_key,
+                None,
+                AuthorityType::FreezeAccount,
+                &owner2_key,
+                &[],
+            )
+            .unwrap(),
+            vec![&mut mint2_account, &mut owner2_account],
+        )
+        .unwrap();
+
+        assert_eq!(
+            Err(TokenError::MintCannotFreeze.into()),
+            do_process_instruction(
+                set_authority(
+                    &program_id,
+                    &mint2_key,
+                    Some(&owner2_key),
+                    AuthorityType::FreezeAccount,
+                    &owner_key,
+                    &[],
+                )
+                .unwrap(),
+                vec![&mut mint2_account, &mut owner2_account],
+            )
+        );
     }
 
     #[test]
-    fn test_initialize_mint_account() {
+    fn test_mint_to_dups() {
         let program_id = Pubkey::new_unique();
-        let account_key = Pubkey::new_unique();
-        let mut account_account = SolanaAccount::new(42, Account::get_packed_len(), &program_id);
+        let account1_key = Pubkey::new_unique();
+        let mut account1_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let account1_info: AccountInfo = (&account1_key, true, &mut account1_account).into();
         let owner_key = Pubkey::new_unique();
         let mut owner_account = SolanaAccount::default();
+        let owner_info: AccountInfo = (&owner_key, true, &mut owner_account).into();
         let mint_key = Pubkey::new_unique();
         let mut mint_account =
             SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
-        let mut rent_sysvar = rent_sysvar();
-
-        // account is not rent exempt
-        assert_eq!(
-            Err(TokenError::NotRentExempt.into()),
-            do_process_instruction(
-                initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
-                vec![
-                    &mut account_account,
-                    &mut mint_account,
-                    &mut owner_account,
-                    &mut rent_sysvar
-                ],
-            )
-        );
-
-        account_account.lamports = account_minimum_balance();
-
-        // mint is not valid (not initialized)
-        assert_eq!(
-            Err(TokenError::InvalidMint.into()),
-            do_process_instruction(
-                initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
-                vec![
-                    &mut account_account,
-                    &mut mint_account,
-                    &mut owner_account,
-                    &mut rent_sysvar
-                ],
-            )
-        );
+        let mint_info: AccountInfo = (&mint_key, true, &mut mint_account).into();
+        let rent_key = rent::id();
+        let mut rent_sysvar = rent_sysvar();
+        let rent_info: AccountInfo = (&rent_key, false, &mut rent_sysvar).into();
 
         // create mint
-        do_process_instruction(
-            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
-            vec![&mut mint_account, &mut rent_sysvar],
+        do_process_instruction_dups(
+            initialize_mint(&program_id, &mint_key, &mint_key, None, 2).unwrap(),
+            vec![mint_info.clone(), rent_info.clone()],
         )
         .unwrap();
 
         // create account
-        do_process_instruction(
-            initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
+        do_process_instruction_dups(
+            initialize_account(&program_id, &account1_key, &mint_key, &owner_key).unwrap(),
             vec![
-                &mut account_account,
-                &mut mint_account,
-                &mut owner_account,
-                &mut rent_sysvar,
+                account1_info.clone(),
+                mint_info.clone(),
+                owner_info.clone(),
+                rent_info.clone(),
             ],
         )
         .unwrap();
 
-        // create twice
-        assert_eq!(
-            Err(TokenError::AlreadyInUse.into()),
-            do_process_instruction(
-                initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
-                vec![
-                    &mut account_account,
-                    &mut mint_account,
-                    &mut owner_account,
-                    &mut rent_sysvar
-                ],
+        // mint_to when mint_authority is self
+        do_process_instruction_dups(
+            mint_to(&program_id, &mint_key, &account1_key, &mint_key, &[], 42).unwrap(),
+            vec![mint_info.clone(), account1_info.clone(), mint_info.clone()],
+        )
+        .unwrap();
+
+        // mint_to_checked when mint_authority is self
+        do_process_instruction_dups(
+            mint_to_checked(&program_id, &mint_key, &account1_key, &mint_key, &[], 42, 2).unwrap(),
+            vec![mint_info.clone(), account1_info.clone(), mint_info.clone()],
+        )
+        .unwrap();
+
+        // mint_to when mint_authority is account owner
+        let mut mint = Mint::unpack_unchecked(&mint_info.data.borrow()).unwrap();
+        mint.mint_authority = COption::Some(account1_key);
+        Mint::pack(mint, &mut mint_info.data.borrow_mut()).unwrap();
+        do_process_instruction_dups(
+            mint_to(
+                &program_id,
+                &mint_key,
+                &account1_key,
+                &account1_key,
+                &[],
+                42,
             )
-        );
+            .unwrap(),
+            vec![
+                mint_info.clone(),
+                account1_info.clone(),
+                account1_info.clone(),
+            ],
+        )
+        .unwrap();
+
+        // mint_to_checked when mint_authority is account owner
+        do_process_instruction_dups(
+            mint_to(
+                &program_id,
+                &mint_key,
+                &account1_key,
+                &account1_key,
+                &[],
+                42,
+            )
+            .unwrap(),
+            vec![
+                mint_info.clone(),
+                account1_info.clone(),
+                account1_info.clone(),
+            ],
+        )
+        .unwrap();
     }
 
     #[test]
-    fn test_transfer_dups() {
+    fn test_mint_to() {
         let program_id = Pubkey::new_unique();
-        let account1_key = Pubkey::new_unique();
-        let mut account1_account = SolanaAccount::new(
+        let account_key = Pubkey::new_unique();
+        let mut account_account = SolanaAccount::new(
             account_minimum_balance(),
             Account::get_packed_len(),
             &program_id,
         );
-        let mut account1_info: AccountInfo = (&account1_key, true, &mut account1_account).into();
         let account2_key = Pubkey::new_unique();
         let mut account2_account = SolanaAccount::new(
             account_minimum_balance(),
             Account::get_packed_len(),
             &program_id,
         );
-        let mut account2_info: AccountInfo = (&account2_key, false, &mut account2_account).into();
         let account3_key = Pubkey::new_unique();
         let mut account3_account = SolanaAccount::new(
             account_minimum_balance(),
             Account::get_packed_len(),
             &program_id,
         );
-        let account3_info: AccountInfo = (&account3_key, false, &mut account3_account).into();
-        let account4_key = Pubkey::new_unique();
-        let mut account4_account = SolanaAccount::new(
+        let mismatch_key = Pubkey::new_unique();
+        let mut mismatch_account = SolanaAccount::new(
             account_minimum_balance(),
             Account::get_packed_len(),
             &program_id,
         );
-        let account4_info: AccountInfo = (&account4_key, true, &mut account4_account).into();
-        let multisig_key = Pubkey::new_unique();
-        let mut multisig_account = SolanaAccount::new(
-            multisig_minimum_balance(),
-            Multisig::get_packed_len(),
-            &program_id,
-        );
-        let multisig_info: AccountInfo = (&multisig_key, true, &mut multisig_account).into();
         let owner_key = Pubkey::new_unique();
         let mut owner_account = SolanaAccount::default();
-        let owner_info: AccountInfo = (&owner_key, true, &mut owner_account).into();
+        let owner2_key = Pubkey::new_unique();
+        let mut owner2_account = SolanaAccount::default();
         let mint_key = Pubkey::new_unique();
         let mut mint_account =
             SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
-        let mint_info: AccountInfo = (&mint_key, false, &mut mint_account).into();
-        let rent_key = rent::id();
+        let mint2_key = Pubkey::new_unique();
+        let uninitialized_key = Pubkey::new_unique();
+        let mut uninitialized_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
         let mut rent_sysvar = rent_sysvar();
-        let rent_info: AccountInfo = (&rent_key, false, &mut rent_sysvar).into();
 
-        // create mint
-        do_process_instruction_dups(
+        // create new mint with owner
+        do_process_instruction(
             initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
-            vec![mint_info.clone(), rent_info.clone()],
+            vec![&mut mint_account, &mut rent_sysvar],
         )
         .unwrap();
 
         // create account
-        do_process_instruction_dups(
-            initialize_account(&program_id, &account1_key, &mint_key, &account1_key).unwrap(),
+        do_process_instruction(
+            initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
             vec![
-                account1_info.clone(),
-                mint_info.clone(),
-                account1_info.clone(),
-                rent_info.clone(),
+                &mut account_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
             ],
         )
         .unwrap();
 
         // create another account
-        do_process_instruction_dups(
+        do_process_instruction(
             initialize_account(&program_id, &account2_key, &mint_key, &owner_key).unwrap(),
             vec![
-                account2_info.clone(),
-                mint_info.clone(),
-                owner_info.clone(),
-                rent_info.clone(),
+                &mut account2_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
             ],
         )
         .unwrap();
 
-        // mint to account
-        do_process_instruction_dups(
-            mint_to(&program_id, &mint_key, &account1_key, &owner_key, &[], 1000).unwrap(),
-            vec![mint_info.clone(), account1_info.clone(), owner_info.clone()],
+        // create another account
+        do_process_instruction(
+            initialize_account(&program_id, &account3_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                &mut account3_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
         )
         .unwrap();
 
-        // source-owner transfer
-        do_process_instruction_dups(
-            transfer(
-                &program_id,
-                &account1_key,
-                &account2_key,
-                &account1_key,
-                &[],
-                500,
+        // create mismatch account
+        do_process_instruction(
+            initialize_account(&program_id, &mismatch_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                &mut mismatch_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+        let mut account = Account::unpack_unchecked(&mismatch_account.data).unwrap();
+        account.mint = mint2_key;
+        Account::pack(account, &mut mismatch_account.data).unwrap();
+
+        // mint to
+        do_process_instruction(
+            mint_to(&program_id, &mint_key, &account_key, &owner_key, &[], 42).unwrap(),
+            vec![&mut mint_account, &mut account_account, &mut owner_account],
+        )
+        .unwrap();
+
+        let mint = Mint::unpack_unchecked(&mint_account.data).unwrap();
+        assert_eq!(mint.supply, 42);
+        let account = Account::unpack_unchecked(&account_account.data).unwrap();
+        assert_eq!(account.amount, 42);
+
+        // mint to another account to test supply accumulation
+        do_process_instruction(
+            mint_to(&program_id, &mint_key, &account2_key, &owner_key, &[], 42).unwrap(),
+            vec![&mut mint_account, &mut account2_account, &mut owner_account],
+        )
+        .unwrap();
+
+        let mint = Mint::unpack_unchecked(&mint_account.data).unwrap();
+        assert_eq!(mint.supply, 84);
+        let account = Account::unpack_unchecked(&account2_account.data).unwrap();
+        assert_eq!(account.amount, 42);
+
+        // missing signer
+        let mut instruction =
+            mint_to(&program_id, &mint_key, &account2_key, &owner_key, &[], 42).unwrap();
+        instruction.accounts[2].is_signer = false;
+        assert_eq!(
+            Err(ProgramError::MissingRequiredSignature),
+            do_process_instruction(
+                instruction,
+                vec![&mut mint_account, &mut account2_account, &mut owner_account],
+            )
+        );
+
+        // mismatch account
+        assert_eq!(
+            Err(TokenError::MintMismatch.into()),
+            do_process_instruction(
+                mint_to(&program_id, &mint_key, &mismatch_key, &owner_key, &[], 42).unwrap(),
+                vec![&mut mint_account, &mut mismatch_account, &mut owner_account],
+            )
+        );
+
+        // missing owner
+        assert_eq!(
+            Err(TokenError::OwnerMismatch.into()),
+            do_process_instruction(
+                mint_to(&program_id, &mint_key, &account2_key, &owner2_key, &[], 42).unwrap(),
+                vec![
+                    &mut mint_account,
+                    &mut account2_account,
+                    &mut owner2_account,
+                ],
+            )
+        );
+
+        // uninitialized destination account
+        assert_eq!(
+            Err(ProgramError::UninitializedAccount),
+            do_process_instruction(
+                mint_to(
+                    &program_id,
+                    &mint_key,
+                    &uninitialized_key,
+                    &owner_key,
+                    &[],
+                    42
+                )
+                .unwrap(),
+                vec![
+                    &mut mint_account,
+                    &mut uninitialized_account,
+                    &mut owner_account,
+                ],
             )
-            .unwrap(),
-            vec![
-                account1_info.clone(),
-                account2_info.clone(),
-                account1_info.clone(),
-            ],
-        )
-        .unwrap();
+        );
 
-        // source-owner TransferChecked
-        do_process_instruction_dups(
-            transfer_checked(
+        // unset mint_authority and test minting fails
+        do_process_instruction(
+            set_authority(
                 &program_id,
-                &account1_key,
                 &mint_key,
-                &account2_key,
-                &account1_key,
+                None,
+                AuthorityType::MintTokens,
+                &owner_key,
                 &[],
-                500,
-                2,
             )
             .unwrap(),
-            vec![
-                account1_info.clone(),
-                mint_info.clone(),
-                account2_info.clone(),
-                account1_info.clone(),
-            ],
+            vec![&mut mint_account, &mut owner_account],
         )
         .unwrap();
-
-        // source-delegate transfer
-        let mut account = Account::unpack_unchecked(&account1_info.data.borrow()).unwrap();
-        account.amount = 1000;
-        account.delegated_amount = 1000;
-        account.delegate = COption::Some(account1_key);
-        account.owner = owner_key;
-        Account::pack(account, &mut account1_info.data.borrow_mut()).unwrap();
-
-        do_process_instruction_dups(
-            transfer(
-                &program_id,
-                &account1_key,
-                &account2_key,
-                &account1_key,
-                &[],
-                500,
+        assert_eq!(
+            Err(TokenError::FixedSupply.into()),
+            do_process_instruction(
+                mint_to(&program_id, &mint_key, &account2_key, &owner_key, &[], 42).unwrap(),
+                vec![&mut mint_account, &mut account2_account, &mut owner_account],
             )
-            .unwrap(),
-            vec![
-                account1_info.clone(),
-                account2_info.clone(),
-                account1_info.clone(),
-            ],
-        )
-        .unwrap();
+        );
+    }
 
-        // source-delegate TransferChecked
-        do_process_instruction_dups(
-            transfer_checked(
-                &program_id,
-                &account1_key,
-                &mint_key,
-                &account2_key,
-                &account1_key,
-                &[],
-                500,
-                2,
+    #[test]
+    fn test_max_supply() {
+        let program_id = Pubkey::new_unique();
+        let mint_len = ExtensionType::get_account_len(&[ExtensionType::MaxSupply]);
+        let account_key = Pubkey::new_unique();
+        let mut account_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let owner_key = Pubkey::new_unique();
+        let mut owner_account = SolanaAccount::default();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account = SolanaAccount::new(
+            Rent::default().minimum_balance(mint_len),
+            mint_len,
+            &program_id,
+        );
+        let mint2_key = Pubkey::new_unique();
+        let mut mint2_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let mut rent_sysvar = rent_sysvar();
+
+        // base layout cannot hold a maximum supply
+        assert_eq!(
+            Err(ProgramError::InvalidAccountData),
+            do_process_instruction(
+                initialize_max_supply(&program_id, &mint2_key, 100).unwrap(),
+                vec![&mut mint2_account],
             )
-            .unwrap(),
-            vec![
-                account1_info.clone(),
-                mint_info.clone(),
-                account2_info.clone(),
-                account1_info.clone(),
-            ],
-        )
-        .unwrap();
+        );
 
-        // test destination-owner transfer
-        do_process_instruction_dups(
-            initialize_account(&program_id, &account3_key, &mint_key, &account2_key).unwrap(),
-            vec![
-                account3_info.clone(),
-                mint_info.clone(),
-                account2_info.clone(),
-                rent_info.clone(),
-            ],
+        // set maximum supply and create mint
+        do_process_instruction(
+            initialize_max_supply(&program_id, &mint_key, 100).unwrap(),
+            vec![&mut mint_account],
         )
         .unwrap();
-        do_process_instruction_dups(
-            mint_to(&program_id, &mint_key, &account3_key, &owner_key, &[], 1000).unwrap(),
-            vec![mint_info.clone(), account3_info.clone(), owner_info.clone()],
+        do_process_instruction(
+            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+            vec![&mut mint_account, &mut rent_sysvar],
         )
         .unwrap();
+        assert_eq!(
+            get_extension::<MaxSupply>(&mint_account.data).unwrap(),
+            Some(MaxSupply { max_supply: 100 })
+        );
 
-        account1_info.is_signer = false;
-        account2_info.is_signer = true;
-        do_process_instruction_dups(
-            transfer(
-                &program_id,
-                &account3_key,
-                &account2_key,
-                &account2_key,
-                &[],
-                500,
+        // cannot change maximum supply once initialized
+        assert_eq!(
+            Err(TokenError::AlreadyInUse.into()),
+            do_process_instruction(
+                initialize_max_supply(&program_id, &mint_key, 200).unwrap(),
+                vec![&mut mint_account],
             )
-            .unwrap(),
-            vec![
-                account3_info.clone(),
-                account2_info.clone(),
-                account2_info.clone(),
-            ],
-        )
-        .unwrap();
+        );
 
-        // destination-owner TransferChecked
-        do_process_instruction_dups(
-            transfer_checked(
-                &program_id,
-                &account3_key,
-                &mint_key,
-                &account2_key,
-                &account2_key,
-                &[],
-                500,
-                2,
-            )
-            .unwrap(),
+        // create account
+        do_process_instruction(
+            initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
             vec![
-                account3_info.clone(),
-                mint_info.clone(),
-                account2_info.clone(),
-                account2_info.clone(),
+                &mut account_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
             ],
         )
         .unwrap();
 
-        // test source-multisig signer
-        do_process_instruction_dups(
-            initialize_multisig(&program_id, &multisig_key, &[&account4_key], 1).unwrap(),
-            vec![
-                multisig_info.clone(),
-                rent_info.clone(),
-                account4_info.clone(),
-            ],
+        // mint up to the maximum supply
+        do_process_instruction(
+            mint_to(&program_id, &mint_key, &account_key, &owner_key, &[], 60).unwrap(),
+            vec![&mut mint_account, &mut account_account, &mut owner_account],
         )
         .unwrap();
-
-        do_process_instruction_dups(
-            initialize_account(&program_id, &account4_key, &mint_key, &multisig_key).unwrap(),
-            vec![
-                account4_info.clone(),
-                mint_info.clone(),
-                multisig_info.clone(),
-                rent_info.clone(),
-            ],
+        do_process_instruction(
+            mint_to_checked(&program_id, &mint_key, &account_key, &owner_key, &[], 40, 2).unwrap(),
+            vec![&mut mint_account, &mut account_account, &mut owner_account],
         )
         .unwrap();
+        let mint = Mint::unpack_from_account_data(&mint_account.data).unwrap();
+        assert_eq!(mint.supply, 100);
 
-        do_process_instruction_dups(
-            mint_to(&program_id, &mint_key, &account4_key, &owner_key, &[], 1000).unwrap(),
-            vec![mint_info.clone(), account4_info.clone(), owner_info.clone()],
+        // cannot mint past the maximum supply
+        assert_eq!(
+            Err(TokenError::MaxSupplyExceeded.into()),
+            do_process_instruction(
+                mint_to(&program_id, &mint_key, &account_key, &owner_key, &[], 1).unwrap(),
+                vec![&mut mint_account, &mut account_account, &mut owner_account],
+            )
+        );
+
+        // burning frees up supply
+        do_process_instruction(
+            burn(&program_id, &account_key, &mint_key, &owner_key, &[], 10).unwrap(),
+            vec![&mut account_account, &mut mint_account, &mut owner_account],
         )
         .unwrap();
-
-        // source-multisig-signer transfer
-        do_process_instruction_dups(
-            transfer(
-                &program_id,
-                &account4_key,
-                &account2_key,
-                &multisig_key,
-                &[&account4_key],
-                500,
-            )
-            .unwrap(),
-            vec![
-                account4_info.clone(),
-                account2_info.clone(),
-                multisig_info.clone(),
-                account4_info.clone(),
-            ],
+        do_process_instruction(
+            mint_to(&program_id, &mint_key, &account_key, &owner_key, &[], 10).unwrap(),
+            vec![&mut mint_account, &mut account_account, &mut owner_account],
         )
         .unwrap();
+        let mint = Mint::unpack_from_account_data(&mint_account.data).unwrap();
+        assert_eq!(mint.supply, 100);
+        let account = Account::unpack(&account_account.data).unwrap();
+        assert_eq!(account.amount, 100);
 
-        // source-multisig-signer TransferChecked
-        do_process_instruction_dups(
-            transfer_checked(
+        // extended mints support authority changes
+        do_process_instruction(
+            set_authority(
                 &program_id,
-                &account4_key,
                 &mint_key,
-                &account2_key,
-                &multisig_key,
-                &[&account4_key],
-                500,
-                2,
+                None,
+                AuthorityType::MintTokens,
+                &owner_key,
+                &[],
             )
             .unwrap(),
-            vec![
-                account4_info.clone(),
-                mint_info.clone(),
-                account2_info.clone(),
-                multisig_info.clone(),
-                account4_info.clone(),
-            ],
+            vec![&mut mint_account, &mut owner_account],
         )
         .unwrap();
+        let mint = Mint::unpack_from_account_data(&mint_account.data).unwrap();
+        assert_eq!(mint.mint_authority, COption::None);
     }
 
     #[test]
-    fn test_transfer() {
+    fn test_permanent_delegate() {
         let program_id = Pubkey::new_unique();
+        let mint_len = ExtensionType::get_account_len(&[ExtensionType::PermanentDelegate]);
         let account_key = Pubkey::new_unique();
         let mut account_account = SolanaAccount::new(
             account_minimum_balance(),
             Account::get_packed_len(),
             &program_id,
         );
-        let account2_key = Pubkey::new_unique();
-        let mut account2_account = SolanaAccount::new(
-            account_minimum_balance(),
-            Account::get_packed_len(),
-            &program_id,
-        );
-        let account3_key = Pubkey::new_unique();
-        let mut account3_account = SolanaAccount::new(
+        let account2_key = Pubkey::new_unique();
+        let mut account2_account = SolanaAccount::new(
             account_minimum_balance(),
             Account::get_packed_len(),
             &program_id,
         );
+        let owner_key = Pubkey::new_unique();
+        let mut owner_account = SolanaAccount::default();
         let delegate_key = Pubkey::new_unique();
         let mut delegate_account = SolanaAccount::default();
-        let mismatch_key = Pubkey::new_unique();
-        let mut mismatch_account = SolanaAccount::new(
-            account_minimum_balance(),
-            Account::get_packed_len(),
+        let new_delegate_key = Pubkey::new_unique();
+        let mut new_delegate_account = SolanaAccount::default();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account = SolanaAccount::new(
+            Rent::default().minimum_balance(mint_len),
+            mint_len,
             &program_id,
         );
-        let owner_key = Pubkey::new_unique();
-        let mut owner_account = SolanaAccount::default();
-        let owner2_key = Pubkey::new_unique();
-        let mut owner2_account = SolanaAccount::default();
-        let mint_key = Pubkey::new_unique();
-        let mut mint_account =
-            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
-        let mint2_key = Pubkey::new_unique();
         let mut rent_sysvar = rent_sysvar();
 
-        // create mint
+        // set permanent delegate and create mint
+        do_process_instruction(
+            initialize_permanent_delegate(&program_id, &mint_key, &delegate_key).unwrap(),
+            vec![&mut mint_account],
+        )
+        .unwrap();
         do_process_instruction(
             initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
             vec![&mut mint_account, &mut rent_sysvar],
         )
         .unwrap();
 
-        // create account
+        // cannot change permanent delegate with initialize once initialized
+        assert_eq!(
+            Err(TokenError::AlreadyInUse.into()),
+            do_process_instruction(
+                initialize_permanent_delegate(&program_id, &mint_key, &new_delegate_key).unwrap(),
+                vec![&mut mint_account],
+            )
+        );
+
+        // create accounts
         do_process_instruction(
             initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
             vec![
@@ -1470,8 +6347,6 @@ mod tests {
             ],
         )
         .unwrap();
-
-        // create another account
         do_process_instruction(
             initialize_account(&program_id, &account2_key, &mint_key, &owner_key).unwrap(),
             vec![
@@ -1482,86 +6357,39 @@ mod tests {
             ],
         )
         .unwrap();
-
-        // create another account
-        do_process_instruction(
-            initialize_account(&program_id, &account3_key, &mint_key, &owner_key).unwrap(),
-            vec![
-                &mut account3_account,
-                &mut mint_account,
-                &mut owner_account,
-                &mut rent_sysvar,
-            ],
-        )
-        .unwrap();
-
-        // create mismatch account
-        do_process_instruction(
-            initialize_account(&program_id, &mismatch_key, &mint_key, &owner_key).unwrap(),
-            vec![
-                &mut mismatch_account,
-                &mut mint_account,
-                &mut owner_account,
-                &mut rent_sysvar,
-            ],
-        )
-        .unwrap();
-        let mut account = Account::unpack_unchecked(&mismatch_account.data).unwrap();
-        account.mint = mint2_key;
-        Account::pack(account, &mut mismatch_account.data).unwrap();
-
-        // mint to account
         do_process_instruction(
-            mint_to(&program_id, &mint_key, &account_key, &owner_key, &[], 1000).unwrap(),
+            mint_to(&program_id, &mint_key, &account_key, &owner_key, &[], 100).unwrap(),
             vec![&mut mint_account, &mut account_account, &mut owner_account],
         )
         .unwrap();
 
-        // missing signer
-        let mut instruction = transfer(
+        // permanent delegate must sign
+        let mut instruction = transfer_checked(
             &program_id,
             &account_key,
+            &mint_key,
             &account2_key,
-            &owner_key,
+            &delegate_key,
             &[],
-            1000,
+            10,
+            2,
         )
         .unwrap();
-        instruction.accounts[2].is_signer = false;
+        instruction.accounts[3].is_signer = false;
         assert_eq!(
             Err(ProgramError::MissingRequiredSignature),
             do_process_instruction(
                 instruction,
                 vec![
                     &mut account_account,
+                    &mut mint_account,
                     &mut account2_account,
-                    &mut owner_account,
-                ],
-            )
-        );
-
-        // mismatch mint
-        assert_eq!(
-            Err(TokenError::MintMismatch.into()),
-            do_process_instruction(
-                transfer(
-                    &program_id,
-                    &account_key,
-                    &mismatch_key,
-                    &owner_key,
-                    &[],
-                    1000
-                )
-                .unwrap(),
-                vec![
-                    &mut account_account,
-                    &mut mismatch_account,
-                    &mut owner_account,
+                    &mut delegate_account,
                 ],
             )
         );
 
-        // missing owner
+        // permanent delegate cannot use unchecked transfer
         assert_eq!(
             Err(TokenError::OwnerMismatch.into()),
             do_process_instruction(
@@ -1569,274 +6397,142 @@ mod tests {
                     &program_id,
                     &account_key,
                     &account2_key,
-                    &owner2_key,
+                    &delegate_key,
                     &[],
-                    1000
+                    10
                 )
                 .unwrap(),
                 vec![
                     &mut account_account,
                     &mut account2_account,
-                    &mut owner2_account,
-                ],
-            )
-        );
-
-        // transfer
-        do_process_instruction(
-            transfer(
-                &program_id,
-                &account_key,
-                &account2_key,
-                &owner_key,
-                &[],
-                1000,
-            )
-            .unwrap(),
-            vec![
-                &mut account_account,
-                &mut account2_account,
-                &mut owner_account,
-            ],
-        )
-        .unwrap();
-
-        // insufficient funds
-        assert_eq!(
-            Err(TokenError::InsufficientFunds.into()),
-            do_process_instruction(
-                transfer(&program_id, &account_key, &account2_key, &owner_key, &[], 1).unwrap(),
-                vec![
-                    &mut account_account,
-                    &mut account2_account,
-                    &mut owner_account,
-                ],
-            )
-        );
-
-        // transfer half back
-        do_process_instruction(
-            transfer(
-                &program_id,
-                &account2_key,
-                &account_key,
-                &owner_key,
-                &[],
-                500,
-            )
-            .unwrap(),
-            vec![
-                &mut account2_account,
-                &mut account_account,
-                &mut owner_account,
-            ],
-        )
-        .unwrap();
-
-        // incorrect decimals
-        assert_eq!(
-            Err(TokenError::MintDecimalsMismatch.into()),
-            do_process_instruction(
-                transfer_checked(
-                    &program_id,
-                    &account2_key,
-                    &mint_key,
-                    &account_key,
-                    &owner_key,
-                    &[],
-                    1,
-                    10 // <-- incorrect decimals
-                )
-                .unwrap(),
-                vec![
-                    &mut account2_account,
-                    &mut mint_account,
-                    &mut account_account,
-                    &mut owner_account,
+                    &mut delegate_account,
                 ],
             )
         );
 
-        // incorrect mint
-        assert_eq!(
-            Err(TokenError::MintMismatch.into()),
-            do_process_instruction(
-                transfer_checked(
-                    &program_id,
-                    &account2_key,
-                    &account3_key, // <-- incorrect mint
-                    &account_key,
-                    &owner_key,
-                    &[],
-                    1,
-                    2
-                )
-                .unwrap(),
-                vec![
-                    &mut account2_account,
-                    &mut account3_account, // <-- incorrect mint
-                    &mut account_account,
-                    &mut owner_account,
-                ],
-            )
-        );
-        // transfer rest with explicit decimals
+        // permanent delegate transfers without approval
         do_process_instruction(
             transfer_checked(
-                &program_id,
-                &account2_key,
-                &mint_key,
-                &account_key,
-                &owner_key,
-                &[],
-                500,
-                2,
-            )
-            .unwrap(),
-            vec![
-                &mut account2_account,
-                &mut mint_account,
-                &mut account_account,
-                &mut owner_account,
-            ],
-        )
-        .unwrap();
-
-        // insufficient funds
-        assert_eq!(
-            Err(TokenError::InsufficientFunds.into()),
-            do_process_instruction(
-                transfer(&program_id, &account2_key, &account_key, &owner_key, &[], 1).unwrap(),
-                vec![
-                    &mut account2_account,
-                    &mut account_account,
-                    &mut owner_account,
-                ],
-            )
-        );
-
-        // approve delegate
-        do_process_instruction(
-            approve(
                 &program_id,
                 &account_key,
+                &mint_key,
+                &account2_key,
                 &delegate_key,
-                &owner_key,
                 &[],
-                100,
+                10,
+                2,
             )
             .unwrap(),
             vec![
                 &mut account_account,
+                &mut mint_account,
+                &mut account2_account,
                 &mut delegate_account,
-                &mut owner_account,
             ],
         )
         .unwrap();
-
-        // transfer via delegate
-        do_process_instruction(
-            transfer(
-                &program_id,
-                &account_key,
-                &account2_key,
-                &delegate_key,
-                &[],
-                100,
-            )
-            .unwrap(),
+        let account = Account::unpack(&account_account.data).unwrap();
+        assert_eq!(account.amount, 90);
+        let account = Account::unpack(&account2_account.data).unwrap();
+        assert_eq!(account.amount, 10);
+
+        // permanent delegate burns without approval
+        do_process_instruction(
+            burn(&program_id, &account_key, &mint_key, &delegate_key, &[], 20).unwrap(),
             vec![
                 &mut account_account,
-                &mut account2_account,
+                &mut mint_account,
                 &mut delegate_account,
             ],
         )
         .unwrap();
+        let account = Account::unpack(&account_account.data).unwrap();
+        assert_eq!(account.amount, 70);
+        let mint = Mint::unpack_from_account_data(&mint_account.data).unwrap();
+        assert_eq!(mint.supply, 80);
 
-        // insufficient funds approved via delegate
+        // owner cannot change permanent delegate
         assert_eq!(
             Err(TokenError::OwnerMismatch.into()),
             do_process_instruction(
-                transfer(
+                set_authority(
                     &program_id,
-                    &account_key,
-                    &account2_key,
-                    &delegate_key,
+                    &mint_key,
+                    Some(&owner_key),
+                    AuthorityType::PermanentDelegate,
+                    &owner_key,
                     &[],
-                    100
                 )
                 .unwrap(),
-                vec![
-                    &mut account_account,
-                    &mut account2_account,
-                    &mut delegate_account,
-                ],
+                vec![&mut mint_account, &mut owner_account],
             )
         );
 
-        // transfer rest
+        // change permanent delegate
         do_process_instruction(
-            transfer(
+            set_authority(
                 &program_id,
-                &account_key,
-                &account2_key,
-                &owner_key,
+                &mint_key,
+                Some(&new_delegate_key),
+                AuthorityType::PermanentDelegate,
+                &delegate_key,
                 &[],
-                900,
             )
             .unwrap(),
-            vec![
-                &mut account_account,
-                &mut account2_account,
-                &mut owner_account,
-            ],
+            vec![&mut mint_account, &mut delegate_account],
         )
         .unwrap();
 
-        // approve delegate
+        // old permanent delegate can no longer burn
+        assert_eq!(
+            Err(TokenError::OwnerMismatch.into()),
+            do_process_instruction(
+                burn(&program_id, &account_key, &mint_key, &delegate_key, &[], 1).unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut mint_account,
+                    &mut delegate_account
+                ],
+            )
+        );
+
+        // remove permanent delegate
         do_process_instruction(
-            approve(
+            set_authority(
                 &program_id,
-                &account_key,
-                &delegate_key,
-                &owner_key,
+                &mint_key,
+                None,
+                AuthorityType::PermanentDelegate,
+                &new_delegate_key,
                 &[],
-                100,
             )
             .unwrap(),
-            vec![
-                &mut account_account,
-                &mut delegate_account,
-                &mut owner_account,
-            ],
+            vec![&mut mint_account, &mut new_delegate_account],
         )
         .unwrap();
 
-        // insufficient funds in source account via delegate
+        // cannot re-add permanent delegate
         assert_eq!(
-            Err(TokenError::InsufficientFunds.into()),
+            Err(TokenError::AuthorityTypeNotSupported.into()),
             do_process_instruction(
-                transfer(
+                set_authority(
                     &program_id,
-                    &account_key,
-                    &account2_key,
-                    &delegate_key,
+                    &mint_key,
+                    Some(&new_delegate_key),
+                    AuthorityType::PermanentDelegate,
+                    &new_delegate_key,
                     &[],
-                    100
                 )
                 .unwrap(),
-                vec![
-                    &mut account_account,
-                    &mut account2_account,
-                    &mut delegate_account,
-                ],
+                vec![&mut mint_account, &mut new_delegate_account],
             )
         );
     }
 
     #[test]
-    fn test_self_transfer() {
+    fn test_default_frozen() {
         let program_id = Pubkey::new_unique();
+        let mint_len = ExtensionType::get_account_len(&[ExtensionType::DefaultFrozen]);
         let account_key = Pubkey::new_unique();
         let mut account_account = SolanaAccount::new(
             account_minimum_balance(),
@@ -1849,31 +6545,51 @@ mod tests {
             Account::get_packed_len(),
             &program_id,
         );
-        let account3_key = Pubkey::new_unique();
-        let mut account3_account = SolanaAccount::new(
-            account_minimum_balance(),
-            Account::get_packed_len(),
-            &program_id,
-        );
-        let delegate_key = Pubkey::new_unique();
-        let mut delegate_account = SolanaAccount::default();
         let owner_key = Pubkey::new_unique();
         let mut owner_account = SolanaAccount::default();
-        let owner2_key = Pubkey::new_unique();
-        let mut owner2_account = SolanaAccount::default();
+        let freeze_key = Pubkey::new_unique();
+        let mut freeze_account = SolanaAccount::default();
         let mint_key = Pubkey::new_unique();
-        let mut mint_account =
-            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let mut mint_account = SolanaAccount::new(
+            Rent::default().minimum_balance(mint_len),
+            mint_len,
+            &program_id,
+        );
+        let mint2_key = Pubkey::new_unique();
+        let mut mint2_account = SolanaAccount::new(
+            Rent::default().minimum_balance(mint_len),
+            mint_len,
+            &program_id,
+        );
         let mut rent_sysvar = rent_sysvar();
 
-        // create mint
+        // default frozen mint requires a freeze authority
         do_process_instruction(
-            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+            initialize_default_frozen(&program_id, &mint2_key).unwrap(),
+            vec![&mut mint2_account],
+        )
+        .unwrap();
+        assert_eq!(
+            Err(TokenError::MintCannotFreeze.into()),
+            do_process_instruction(
+                initialize_mint(&program_id, &mint2_key, &owner_key, None, 2).unwrap(),
+                vec![&mut mint2_account, &mut rent_sysvar],
+            )
+        );
+
+        // create default frozen mint
+        do_process_instruction(
+            initialize_default_frozen(&program_id, &mint_key).unwrap(),
+            vec![&mut mint_account],
+        )
+        .unwrap();
+        do_process_instruction(
+            initialize_mint(&program_id, &mint_key, &owner_key, Some(&freeze_key), 2).unwrap(),
             vec![&mut mint_account, &mut rent_sysvar],
         )
         .unwrap();
 
-        // create account
+        // new account starts frozen
         do_process_instruction(
             initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
             vec![
@@ -1884,799 +6600,1196 @@ mod tests {
             ],
         )
         .unwrap();
+        let account = Account::unpack(&account_account.data).unwrap();
+        assert_eq!(account.state, AccountState::Frozen);
 
-        // create another account
+        // frozen account cannot receive tokens until thawed
+        assert_eq!(
+            Err(TokenError::AccountFrozen.into()),
+            do_process_instruction(
+                mint_to(&program_id, &mint_key, &account_key, &owner_key, &[], 10).unwrap(),
+                vec![&mut mint_account, &mut account_account, &mut owner_account],
+            )
+        );
         do_process_instruction(
-            initialize_account(&program_id, &account2_key, &mint_key, &owner_key).unwrap(),
-            vec![
-                &mut account2_account,
-                &mut mint_account,
-                &mut owner_account,
-                &mut rent_sysvar,
-            ],
+            thaw_account(&program_id, &account_key, &mint_key, &freeze_key, &[]).unwrap(),
+            vec![&mut account_account, &mut mint_account, &mut freeze_account],
+        )
+        .unwrap();
+        do_process_instruction(
+            mint_to(&program_id, &mint_key, &account_key, &owner_key, &[], 10).unwrap(),
+            vec![&mut mint_account, &mut account_account, &mut owner_account],
         )
         .unwrap();
 
-        // create another account
+        // only the freeze authority can change the default
+        assert_eq!(
+            Err(TokenError::OwnerMismatch.into()),
+            do_process_instruction(
+                set_default_frozen(&program_id, &mint_key, &owner_key, &[], false).unwrap(),
+                vec![&mut mint_account, &mut owner_account],
+            )
+        );
         do_process_instruction(
-            initialize_account(&program_id, &account3_key, &mint_key, &owner_key).unwrap(),
+            set_default_frozen(&program_id, &mint_key, &freeze_key, &[], false).unwrap(),
+            vec![&mut mint_account, &mut freeze_account],
+        )
+        .unwrap();
+        assert_eq!(
+            get_extension::<DefaultFrozen>(&mint_account.data).unwrap(),
+            Some(DefaultFrozen { frozen: false })
+        );
+
+        // new account starts initialized, existing account is unchanged
+        do_process_instruction(
+            initialize_account(&program_id, &account2_key, &mint_key, &owner_key).unwrap(),
             vec![
-                &mut account3_account,
+                &mut account2_account,
                 &mut mint_account,
                 &mut owner_account,
                 &mut rent_sysvar,
             ],
         )
         .unwrap();
+        let account = Account::unpack(&account2_account.data).unwrap();
+        assert_eq!(account.state, AccountState::Initialized);
+        let account = Account::unpack(&account_account.data).unwrap();
+        assert_eq!(account.amount, 10);
+        assert_eq!(account.state, AccountState::Initialized);
 
-        // mint to account
+        // base layout mint cannot start accounts frozen
+        let mut mint3_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let mint3_key = Pubkey::new_unique();
         do_process_instruction(
-            mint_to(&program_id, &mint_key, &account_key, &owner_key, &[], 1000).unwrap(),
-            vec![&mut mint_account, &mut account_account, &mut owner_account],
-        )
-        .unwrap();
-
-        let account_info = (&account_key, false, &mut account_account).into_account_info();
-        let account3_info = (&account3_key, false, &mut account3_account).into_account_info();
-        let delegate_info = (&delegate_key, true, &mut delegate_account).into_account_info();
-        let owner_info = (&owner_key, true, &mut owner_account).into_account_info();
-        let owner2_info = (&owner2_key, true, &mut owner2_account).into_account_info();
-        let mint_info = (&mint_key, false, &mut mint_account).into_account_info();
-
-        // transfer
-        let instruction = transfer(
-            &program_id,
-            &account_info.key,
-            &account_info.key,
-            &owner_info.key,
-            &[],
-            1000,
+            initialize_mint(&program_id, &mint3_key, &owner_key, Some(&freeze_key), 2).unwrap(),
+            vec![&mut mint3_account, &mut rent_sysvar],
         )
         .unwrap();
         assert_eq!(
-            Ok(()),
-            Processor::process(
-                &instruction.program_id,
-                &[
-                    account_info.clone(),
-                    account_info.clone(),
-                    owner_info.clone(),
-                ],
-                &instruction.data,
+            Err(ProgramError::InvalidAccountData),
+            do_process_instruction(
+                set_default_frozen(&program_id, &mint3_key, &freeze_key, &[], true).unwrap(),
+                vec![&mut mint3_account, &mut freeze_account],
             )
         );
-        // no balance change...
-        let account = Account::unpack_unchecked(&account_info.try_borrow_data().unwrap()).unwrap();
-        assert_eq!(account.amount, 1000);
+    }
 
-        // transfer checked
-        let instruction = transfer_checked(
+    #[test]
+    fn test_non_transferable() {
+        let program_id = Pubkey::new_unique();
+        let account_len = ExtensionType::get_account_len(&[ExtensionType::NonTransferableAccount]);
+        let mint_len = ExtensionType::get_account_len(&[ExtensionType::NonTransferable]);
+        let account_key = Pubkey::new_unique();
+        let mut account_account = SolanaAccount::new(
+            Rent::default().minimum_balance(account_len),
+            account_len,
             &program_id,
-            &account_info.key,
-            &mint_info.key,
-            &account_info.key,
-            &owner_info.key,
-            &[],
-            1000,
-            2,
-        )
-        .unwrap();
-        assert_eq!(
-            Ok(()),
-            Processor::process(
-                &instruction.program_id,
-                &[
-                    account_info.clone(),
-                    mint_info.clone(),
-                    account_info.clone(),
-                    owner_info.clone(),
-                ],
-                &instruction.data,
-            )
         );
-        // no balance change...
-        let account = Account::unpack_unchecked(&account_info.try_borrow_data().unwrap()).unwrap();
-        assert_eq!(account.amount, 1000);
-
-        // missing signer
-        let mut owner_no_sign_info = owner_info.clone();
-        let mut instruction = transfer(
+        let account2_key = Pubkey::new_unique();
+        let mut account2_account = SolanaAccount::new(
+            Rent::default().minimum_balance(account_len),
+            account_len,
             &program_id,
-            &account_info.key,
-            &account_info.key,
-            &owner_no_sign_info.key,
-            &[],
-            1000,
+        );
+        let account3_key = Pubkey::new_unique();
+        let mut account3_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let owner_key = Pubkey::new_unique();
+        let mut owner_account = SolanaAccount::default();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account = SolanaAccount::new(
+            Rent::default().minimum_balance(mint_len),
+            mint_len,
+            &program_id,
+        );
+        let mut rent_sysvar = rent_sysvar();
+
+        // create non-transferable mint
+        do_process_instruction(
+            initialize_non_transferable_mint(&program_id, &mint_key).unwrap(),
+            vec![&mut mint_account],
         )
         .unwrap();
-        instruction.accounts[2].is_signer = false;
-        owner_no_sign_info.is_signer = false;
+        do_process_instruction(
+            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+            vec![&mut mint_account, &mut rent_sysvar],
+        )
+        .unwrap();
+
+        // accounts must hold the extended layout
         assert_eq!(
-            Err(ProgramError::MissingRequiredSignature),
-            Processor::process(
-                &instruction.program_id,
-                &[
-                    account_info.clone(),
-                    account_info.clone(),
-                    owner_no_sign_info.clone(),
+            Err(ProgramError::InvalidAccountData),
+            do_process_instruction(
+                initialize_account(&program_id, &account3_key, &mint_key, &owner_key).unwrap(),
+                vec![
+                    &mut account3_account,
+                    &mut mint_account,
+                    &mut owner_account,
+                    &mut rent_sysvar,
                 ],
-                &instruction.data,
             )
         );
 
-        // missing signer checked
-        let mut instruction = transfer_checked(
-            &program_id,
-            &account_info.key,
-            &mint_info.key,
-            &account_info.key,
-            &owner_no_sign_info.key,
-            &[],
-            1000,
-            2,
+        // create accounts
+        do_process_instruction(
+            initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                &mut account_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+        do_process_instruction(
+            initialize_account(&program_id, &account2_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                &mut account2_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
         )
         .unwrap();
-        instruction.accounts[3].is_signer = false;
         assert_eq!(
-            Err(ProgramError::MissingRequiredSignature),
-            Processor::process(
-                &instruction.program_id,
-                &[
-                    account_info.clone(),
-                    mint_info.clone(),
-                    account_info.clone(),
-                    owner_no_sign_info,
-                ],
-                &instruction.data,
-            )
+            get_extension::<NonTransferableAccount>(&account_account.data).unwrap(),
+            Some(NonTransferableAccount)
         );
 
-        // missing owner
-        let instruction = transfer(
-            &program_id,
-            &account_info.key,
-            &account_info.key,
-            &owner2_info.key,
-            &[],
-            1000,
+        // mint to account
+        do_process_instruction(
+            mint_to(&program_id, &mint_key, &account_key, &owner_key, &[], 100).unwrap(),
+            vec![&mut mint_account, &mut account_account, &mut owner_account],
         )
         .unwrap();
+
+        // cannot transfer
         assert_eq!(
-            Err(TokenError::OwnerMismatch.into()),
-            Processor::process(
-                &instruction.program_id,
-                &[
-                    account_info.clone(),
-                    account_info.clone(),
-                    owner2_info.clone(),
+            Err(TokenError::NonTransferable.into()),
+            do_process_instruction(
+                transfer(
+                    &program_id,
+                    &account_key,
+                    &account2_key,
+                    &owner_key,
+                    &[],
+                    10
+                )
+                .unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut account2_account,
+                    &mut owner_account,
                 ],
-                &instruction.data,
             )
         );
-
-        // missing owner checked
-        let instruction = transfer_checked(
-            &program_id,
-            &account_info.key,
-            &mint_info.key,
-            &account_info.key,
-            &owner2_info.key,
-            &[],
-            1000,
-            2,
-        )
-        .unwrap();
         assert_eq!(
-            Err(TokenError::OwnerMismatch.into()),
-            Processor::process(
-                &instruction.program_id,
-                &[
-                    account_info.clone(),
-                    mint_info.clone(),
-                    account_info.clone(),
-                    owner2_info.clone(),
+            Err(TokenError::NonTransferable.into()),
+            do_process_instruction(
+                transfer_checked(
+                    &program_id,
+                    &account_key,
+                    &mint_key,
+                    &account2_key,
+                    &owner_key,
+                    &[],
+                    10,
+                    2,
+                )
+                .unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut mint_account,
+                    &mut account2_account,
+                    &mut owner_account,
+                ],
+            )
+        );
+        assert_eq!(
+            Err(TokenError::NonTransferable.into()),
+            do_process_instruction(
+                transfer_checked_batch(
+                    &program_id,
+                    &account_key,
+                    &mint_key,
+                    &[(&account2_key, 10)],
+                    &owner_key,
+                    &[],
+                    2,
+                )
+                .unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut mint_account,
+                    &mut owner_account,
+                    &mut account2_account,
                 ],
-                &instruction.data,
             )
         );
 
-        // insufficient funds
-        let instruction = transfer(
+        // can burn
+        do_process_instruction(
+            burn(&program_id, &account_key, &mint_key, &owner_key, &[], 100).unwrap(),
+            vec![&mut account_account, &mut mint_account, &mut owner_account],
+        )
+        .unwrap();
+        let account = Account::unpack_from_account_data(&account_account.data).unwrap();
+        assert_eq!(account.amount, 0);
+
+        // can close
+        do_process_instruction(
+            close_account(&program_id, &account_key, &owner_key, &owner_key, &[]).unwrap(),
+            vec![
+                &mut account_account,
+                &mut SolanaAccount::default(),
+                &mut owner_account,
+            ],
+        )
+        .unwrap();
+        assert_eq!(account_account.lamports, 0);
+    }
+
+    #[test]
+    fn test_migrate_account() {
+        let program_id = Pubkey::new_unique();
+        let account_len = ExtensionType::get_account_len(&[ExtensionType::DelegationExpiry]);
+        let account_key = Pubkey::new_unique();
+        let mut account_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
             &program_id,
-            &account_info.key,
-            &account_info.key,
-            &owner_info.key,
-            &[],
-            1001,
+        );
+        let new_account_key = Pubkey::new_unique();
+        let mut new_account_account = SolanaAccount::new(
+            Rent::default().minimum_balance(account_len),
+            account_len,
+            &program_id,
+        );
+        let small_account_key = Pubkey::new_unique();
+        let mut small_account_account = SolanaAccount::new(
+            Rent::default().minimum_balance(account_len - 1),
+            account_len - 1,
+            &program_id,
+        );
+        let owner_key = Pubkey::new_unique();
+        let mut owner_account = SolanaAccount::default();
+        let owner2_key = Pubkey::new_unique();
+        let mut owner2_account = SolanaAccount::default();
+        let delegate_key = Pubkey::new_unique();
+        let mut delegate_account = SolanaAccount::default();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let mut rent_sysvar = rent_sysvar();
+
+        // create mint and account
+        do_process_instruction(
+            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+            vec![&mut mint_account, &mut rent_sysvar],
+        )
+        .unwrap();
+        do_process_instruction(
+            initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                &mut account_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+        do_process_instruction(
+            mint_to(&program_id, &mint_key, &account_key, &owner_key, &[], 1000).unwrap(),
+            vec![&mut mint_account, &mut account_account, &mut owner_account],
         )
         .unwrap();
+
+        // base layout cannot hold a delegation expiry
         assert_eq!(
-            Err(TokenError::InsufficientFunds.into()),
-            Processor::process(
-                &instruction.program_id,
-                &[
-                    account_info.clone(),
-                    account_info.clone(),
-                    owner_info.clone(),
+            Err(ProgramError::InvalidAccountData),
+            do_process_instruction(
+                approve_checked_with_expiry(
+                    &program_id,
+                    &account_key,
+                    &mint_key,
+                    &delegate_key,
+                    &owner_key,
+                    &[],
+                    100,
+                    2,
+                    CLOCK_SLOT,
+                )
+                .unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut mint_account,
+                    &mut delegate_account,
+                    &mut owner_account,
                 ],
-                &instruction.data,
             )
         );
 
-        // insufficient funds checked
-        let instruction = transfer_checked(
-            &program_id,
-            &account_info.key,
-            &mint_info.key,
-            &account_info.key,
-            &owner_info.key,
-            &[],
-            1001,
-            2,
-        )
-        .unwrap();
+        // missing owner
         assert_eq!(
-            Err(TokenError::InsufficientFunds.into()),
-            Processor::process(
-                &instruction.program_id,
-                &[
-                    account_info.clone(),
-                    mint_info.clone(),
-                    account_info.clone(),
-                    owner_info.clone(),
+            Err(TokenError::OwnerMismatch.into()),
+            do_process_instruction(
+                migrate_account(
+                    &program_id,
+                    &account_key,
+                    &new_account_key,
+                    &owner_key,
+                    &owner2_key,
+                    &[],
+                    &[ExtensionType::DelegationExpiry],
+                )
+                .unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut new_account_account,
+                    &mut owner_account,
+                    &mut owner2_account,
                 ],
-                &instruction.data,
             )
         );
 
-        // incorrect decimals
-        let instruction = transfer_checked(
-            &program_id,
-            &account_info.key,
-            &mint_info.key,
-            &account_info.key,
-            &owner_info.key,
-            &[],
-            1,
-            10, // <-- incorrect decimals
-        )
-        .unwrap();
+        // mint extensions cannot be added to an account
         assert_eq!(
-            Err(TokenError::MintDecimalsMismatch.into()),
-            Processor::process(
-                &instruction.program_id,
-                &[
-                    account_info.clone(),
-                    mint_info.clone(),
-                    account_info.clone(),
-                    owner_info.clone(),
+            Err(ProgramError::InvalidArgument),
+            do_process_instruction(
+                migrate_account(
+                    &program_id,
+                    &account_key,
+                    &new_account_key,
+                    &owner_key,
+                    &owner_key,
+                    &[],
+                    &[ExtensionType::MaxSupply],
+                )
+                .unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut new_account_account,
+                    &mut owner_account.clone(),
+                    &mut owner_account,
                 ],
-                &instruction.data,
             )
         );
 
-        // incorrect mint
-        let instruction = transfer_checked(
-            &program_id,
-            &account_info.key,
-            &account3_info.key, // <-- incorrect mint
-            &account_info.key,
-            &owner_info.key,
-            &[],
-            1,
-            2,
-        )
-        .unwrap();
+        // new account too small
         assert_eq!(
-            Err(TokenError::MintMismatch.into()),
-            Processor::process(
-                &instruction.program_id,
-                &[
-                    account_info.clone(),
-                    account3_info.clone(), // <-- incorrect mint
-                    account_info.clone(),
-                    owner_info.clone(),
+            Err(ProgramError::InvalidAccountData),
+            do_process_instruction(
+                migrate_account(
+                    &program_id,
+                    &account_key,
+                    &small_account_key,
+                    &owner_key,
+                    &owner_key,
+                    &[],
+                    &[ExtensionType::DelegationExpiry],
+                )
+                .unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut small_account_account,
+                    &mut owner_account.clone(),
+                    &mut owner_account,
                 ],
-                &instruction.data,
             )
         );
 
-        // approve delegate
-        let instruction = approve(
-            &program_id,
-            &account_info.key,
-            &delegate_info.key,
-            &owner_info.key,
-            &[],
-            100,
-        )
-        .unwrap();
-        Processor::process(
-            &instruction.program_id,
-            &[
-                account_info.clone(),
-                delegate_info.clone(),
-                owner_info.clone(),
-            ],
-            &instruction.data,
-        )
-        .unwrap();
-
-        // delegate transfer
-        let instruction = transfer(
-            &program_id,
-            &account_info.key,
-            &account_info.key,
-            &delegate_info.key,
-            &[],
-            100,
+        // the close authority must agree to closing the old account
+        do_process_instruction(
+            set_authority(
+                &program_id,
+                &account_key,
+                Some(&owner2_key),
+                AuthorityType::CloseAccount,
+                &owner_key,
+                &[],
+            )
+            .unwrap(),
+            vec![&mut account_account, &mut owner_account],
         )
         .unwrap();
         assert_eq!(
-            Ok(()),
-            Processor::process(
-                &instruction.program_id,
-                &[
-                    account_info.clone(),
-                    account_info.clone(),
-                    delegate_info.clone(),
+            Err(TokenError::OwnerMismatch.into()),
+            do_process_instruction(
+                migrate_account(
+                    &program_id,
+                    &account_key,
+                    &new_account_key,
+                    &owner2_key,
+                    &owner_key,
+                    &[],
+                    &[ExtensionType::DelegationExpiry],
+                )
+                .unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut new_account_account,
+                    &mut owner2_account,
+                    &mut owner_account,
                 ],
-                &instruction.data,
             )
         );
-        // no balance change...
-        let account = Account::unpack_unchecked(&account_info.try_borrow_data().unwrap()).unwrap();
-        assert_eq!(account.amount, 1000);
-        assert_eq!(account.delegated_amount, 100);
+        do_process_instruction(
+            set_authority(
+                &program_id,
+                &account_key,
+                None,
+                AuthorityType::CloseAccount,
+                &owner2_key,
+                &[],
+            )
+            .unwrap(),
+            vec![&mut account_account, &mut owner2_account],
+        )
+        .unwrap();
 
-        // delegate transfer checked
-        let instruction = transfer_checked(
-            &program_id,
-            &account_info.key,
-            &mint_info.key,
-            &account_info.key,
-            &delegate_info.key,
-            &[],
-            100,
-            2,
+        // migrate
+        let starting_lamports = owner2_account.lamports;
+        do_process_instruction(
+            migrate_account(
+                &program_id,
+                &account_key,
+                &new_account_key,
+                &owner2_key,
+                &owner_key,
+                &[],
+                &[ExtensionType::DelegationExpiry],
+            )
+            .unwrap(),
+            vec![
+                &mut account_account,
+                &mut new_account_account,
+                &mut owner2_account,
+                &mut owner_account,
+            ],
         )
         .unwrap();
+        assert_eq!(account_account.lamports, 0);
         assert_eq!(
-            Ok(()),
-            Processor::process(
-                &instruction.program_id,
-                &[
-                    account_info.clone(),
-                    mint_info.clone(),
-                    account_info.clone(),
-                    delegate_info.clone(),
-                ],
-                &instruction.data,
-            )
+            owner2_account.lamports,
+            starting_lamports + account_minimum_balance()
         );
-        // no balance change...
-        let account = Account::unpack_unchecked(&account_info.try_borrow_data().unwrap()).unwrap();
+        assert!(account_account.data.iter().all(|byte| *byte == 0));
+        let account = Account::unpack_from_account_data(&new_account_account.data).unwrap();
+        assert_eq!(account.mint, mint_key);
+        assert_eq!(account.owner, owner_key);
         assert_eq!(account.amount, 1000);
-        assert_eq!(account.delegated_amount, 100);
 
-        // delegate insufficient funds
-        let instruction = transfer(
-            &program_id,
-            &account_info.key,
-            &account_info.key,
-            &delegate_info.key,
-            &[],
-            101,
-        )
-        .unwrap();
+        // old account can no longer be used
         assert_eq!(
-            Err(TokenError::InsufficientFunds.into()),
-            Processor::process(
-                &instruction.program_id,
-                &[
-                    account_info.clone(),
-                    account_info.clone(),
-                    delegate_info.clone(),
+            Err(ProgramError::UninitializedAccount),
+            do_process_instruction(
+                transfer(
+                    &program_id,
+                    &account_key,
+                    &new_account_key,
+                    &owner_key,
+                    &[],
+                    1,
+                )
+                .unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut new_account_account,
+                    &mut owner_account,
                 ],
-                &instruction.data,
             )
         );
-
-        // delegate insufficient funds checked
-        let instruction = transfer_checked(
-            &program_id,
-            &account_info.key,
-            &mint_info.key,
-            &account_info.key,
-            &delegate_info.key,
-            &[],
-            101,
-            2,
-        )
-        .unwrap();
         assert_eq!(
-            Err(TokenError::InsufficientFunds.into()),
-            Processor::process(
-                &instruction.program_id,
-                &[
-                    account_info.clone(),
-                    mint_info.clone(),
-                    account_info.clone(),
-                    delegate_info.clone(),
+            Err(ProgramError::UninitializedAccount),
+            do_process_instruction(
+                migrate_account(
+                    &program_id,
+                    &account_key,
+                    &small_account_key,
+                    &owner2_key,
+                    &owner_key,
+                    &[],
+                    &[],
+                )
+                .unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut small_account_account,
+                    &mut owner2_account,
+                    &mut owner_account,
                 ],
-                &instruction.data,
             )
         );
 
-        // owner transfer with delegate assigned
-        let instruction = transfer(
-            &program_id,
-            &account_info.key,
-            &account_info.key,
-            &owner_info.key,
-            &[],
-            1000,
+        // migrate in place within the allocated space, leaving the
+        // destination untouched
+        let starting_lamports = owner_account.lamports;
+        do_process_instruction(
+            migrate_account(
+                &program_id,
+                &new_account_key,
+                &new_account_key,
+                &owner_key,
+                &owner_key,
+                &[],
+                &[ExtensionType::DelegationExpiry],
+            )
+            .unwrap(),
+            vec![
+                &mut new_account_account.clone(),
+                &mut new_account_account,
+                &mut owner_account.clone(),
+                &mut owner_account,
+            ],
         )
         .unwrap();
+        assert_eq!(owner_account.lamports, starting_lamports);
         assert_eq!(
-            Ok(()),
-            Processor::process(
-                &instruction.program_id,
-                &[
-                    account_info.clone(),
-                    account_info.clone(),
-                    owner_info.clone(),
+            Account::unpack_from_account_data(&new_account_account.data)
+                .unwrap()
+                .amount,
+            1000
+        );
+        assert_eq!(
+            Err(ProgramError::InvalidAccountData),
+            do_process_instruction(
+                migrate_account(
+                    &program_id,
+                    &new_account_key,
+                    &new_account_key,
+                    &owner_key,
+                    &owner_key,
+                    &[],
+                    &[ExtensionType::DelegationExpiry, ExtensionType::MemoTransfer],
+                )
+                .unwrap(),
+                vec![
+                    &mut new_account_account.clone(),
+                    &mut new_account_account,
+                    &mut owner_account.clone(),
+                    &mut owner_account,
                 ],
-                &instruction.data,
             )
         );
-        // no balance change...
-        let account = Account::unpack_unchecked(&account_info.try_borrow_data().unwrap()).unwrap();
-        assert_eq!(account.amount, 1000);
 
-        // owner transfer with delegate assigned checked
-        let instruction = transfer_checked(
-            &program_id,
-            &account_info.key,
-            &mint_info.key,
-            &account_info.key,
-            &owner_info.key,
-            &[],
-            1000,
-            2,
+        // new account holds a delegation expiry
+        do_process_instruction(
+            approve_checked_with_expiry(
+                &program_id,
+                &new_account_key,
+                &mint_key,
+                &delegate_key,
+                &owner_key,
+                &[],
+                100,
+                2,
+                CLOCK_SLOT,
+            )
+            .unwrap(),
+            vec![
+                &mut new_account_account,
+                &mut mint_account,
+                &mut delegate_account,
+                &mut owner_account,
+            ],
         )
         .unwrap();
         assert_eq!(
-            Ok(()),
-            Processor::process(
-                &instruction.program_id,
-                &[
-                    account_info.clone(),
-                    mint_info.clone(),
-                    account_info.clone(),
-                    owner_info.clone(),
-                ],
-                &instruction.data,
-            )
+            get_extension::<DelegationExpiry>(&new_account_account.data).unwrap(),
+            Some(DelegationExpiry {
+                expiry_slot: COption::Some(CLOCK_SLOT)
+            })
         );
-        // no balance change...
-        let account = Account::unpack_unchecked(&account_info.try_borrow_data().unwrap()).unwrap();
-        assert_eq!(account.amount, 1000);
     }
 
     #[test]
-    fn test_mintable_token_with_zero_supply() {
+    fn test_migrate_account_found_by_address() {
         let program_id = Pubkey::new_unique();
-        let account_key = Pubkey::new_unique();
-        let mut account_account = SolanaAccount::new(
-            account_minimum_balance(),
-            Account::get_packed_len(),
-            &program_id,
-        );
+        let account_len = ExtensionType::get_account_len(&[ExtensionType::ImmutableOwner]);
         let owner_key = Pubkey::new_unique();
         let mut owner_account = SolanaAccount::default();
         let mint_key = Pubkey::new_unique();
         let mut mint_account =
             SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let (associated_key, _) = Pubkey::find_program_address(
+            &[owner_key.as_ref(), program_id.as_ref(), mint_key.as_ref()],
+            &associated_token_program::id(),
+        );
+        let mut associated_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let reserved_len = ExtensionType::get_account_len(&[
+            ExtensionType::ImmutableOwner,
+            ExtensionType::MemoTransfer,
+            ExtensionType::CpiGuard,
+        ]);
+        let mint2_key = Pubkey::new_unique();
+        let mut mint2_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let (associated2_key, _) = Pubkey::find_program_address(
+            &[owner_key.as_ref(), program_id.as_ref(), mint2_key.as_ref()],
+            &associated_token_program::id(),
+        );
+        let mut associated2_account = SolanaAccount::new(
+            Rent::default().minimum_balance(reserved_len),
+            reserved_len,
+            &program_id,
+        );
+        let immutable_key = Pubkey::new_unique();
+        let mut immutable_account = SolanaAccount::new(
+            Rent::default().minimum_balance(account_len),
+            account_len,
+            &program_id,
+        );
+        let new_account_key = Pubkey::new_unique();
+        let mut new_account_account = SolanaAccount::new(
+            Rent::default().minimum_balance(account_len),
+            account_len,
+            &program_id,
+        );
         let mut rent_sysvar = rent_sysvar();
 
-        // create mint-able token with zero supply
-        let decimals = 2;
         do_process_instruction(
-            initialize_mint(&program_id, &mint_key, &owner_key, None, decimals).unwrap(),
+            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
             vec![&mut mint_account, &mut rent_sysvar],
         )
         .unwrap();
-        let mint = Mint::unpack_unchecked(&mint_account.data).unwrap();
-        assert_eq!(
-            mint,
-            Mint {
-                mint_authority: COption::Some(owner_key),
-                supply: 0,
-                decimals,
-                is_initialized: true,
-                freeze_authority: COption::None,
-            }
-        );
-
-        // create account
         do_process_instruction(
-            initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
+            initialize_account(&program_id, &associated_key, &mint_key, &owner_key).unwrap(),
             vec![
-                &mut account_account,
+                &mut associated_account,
                 &mut mint_account,
                 &mut owner_account,
                 &mut rent_sysvar,
             ],
         )
         .unwrap();
-
-        // mint to
         do_process_instruction(
-            mint_to(&program_id, &mint_key, &account_key, &owner_key, &[], 42).unwrap(),
-            vec![&mut mint_account, &mut account_account, &mut owner_account],
+            initialize_immutable_owner(&program_id, &immutable_key).unwrap(),
+            vec![&mut immutable_account],
+        )
+        .unwrap();
+        do_process_instruction(
+            initialize_account(&program_id, &immutable_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                &mut immutable_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
         )
         .unwrap();
-        let _ = Mint::unpack(&mint_account.data).unwrap();
-        let account = Account::unpack_unchecked(&account_account.data).unwrap();
-        assert_eq!(account.amount, 42);
 
-        // mint to 2, with incorrect decimals
+        // associated token account
         assert_eq!(
-            Err(TokenError::MintDecimalsMismatch.into()),
+            Err(TokenError::AccountNotMigratable.into()),
             do_process_instruction(
-                mint_to_checked(
+                migrate_account(
                     &program_id,
-                    &mint_key,
-                    &account_key,
+                    &associated_key,
+                    &new_account_key,
+                    &owner_key,
                     &owner_key,
                     &[],
-                    42,
-                    decimals + 1
+                    &[],
                 )
                 .unwrap(),
-                vec![&mut mint_account, &mut account_account, &mut owner_account],
+                vec![
+                    &mut associated_account,
+                    &mut new_account_account,
+                    &mut owner_account.clone(),
+                    &mut owner_account,
+                ],
             )
         );
 
-        let _ = Mint::unpack(&mint_account.data).unwrap();
-        let account = Account::unpack_unchecked(&account_account.data).unwrap();
-        assert_eq!(account.amount, 42);
+        // immutable owner
+        assert_eq!(
+            Err(TokenError::AccountNotMigratable.into()),
+            do_process_instruction(
+                migrate_account(
+                    &program_id,
+                    &immutable_key,
+                    &new_account_key,
+                    &owner_key,
+                    &owner_key,
+                    &[],
+                    &[],
+                )
+                .unwrap(),
+                vec![
+                    &mut immutable_account,
+                    &mut new_account_account,
+                    &mut owner_account.clone(),
+                    &mut owner_account,
+                ],
+            )
+        );
 
-        // mint to 2
+        // associated token account allocated with space for optional
+        // extensions migrates in place
         do_process_instruction(
-            mint_to_checked(
+            initialize_mint(&program_id, &mint2_key, &owner_key, None, 2).unwrap(),
+            vec![&mut mint2_account, &mut rent_sysvar],
+        )
+        .unwrap();
+        do_process_instruction(
+            initialize_immutable_owner(&program_id, &associated2_key).unwrap(),
+            vec![&mut associated2_account],
+        )
+        .unwrap();
+        do_process_instruction(
+            initialize_account(&program_id, &associated2_key, &mint2_key, &owner_key).unwrap(),
+            vec![
+                &mut associated2_account,
+                &mut mint2_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+        do_process_instruction(
+            migrate_account(
                 &program_id,
-                &mint_key,
-                &account_key,
+                &associated2_key,
+                &associated2_key,
+                &owner_key,
                 &owner_key,
                 &[],
-                42,
-                decimals,
+                &[ExtensionType::MemoTransfer, ExtensionType::CpiGuard],
             )
             .unwrap(),
-            vec![&mut mint_account, &mut account_account, &mut owner_account],
+            vec![
+                &mut associated2_account.clone(),
+                &mut associated2_account,
+                &mut owner_account.clone(),
+                &mut owner_account,
+            ],
         )
         .unwrap();
-        let _ = Mint::unpack(&mint_account.data).unwrap();
-        let account = Account::unpack_unchecked(&account_account.data).unwrap();
-        assert_eq!(account.amount, 84);
+        do_process_instruction(
+            enable_required_transfer_memos(&program_id, &associated2_key, &owner_key, &[]).unwrap(),
+            vec![&mut associated2_account, &mut owner_account],
+        )
+        .unwrap();
+        do_process_instruction(
+            enable_cpi_guard(&program_id, &associated2_key, &owner_key, &[]).unwrap(),
+            vec![&mut associated2_account, &mut owner_account],
+        )
+        .unwrap();
+        assert_eq!(
+            get_extension_types(&associated2_account.data).unwrap(),
+            vec![
+                ExtensionType::ImmutableOwner,
+                ExtensionType::MemoTransfer,
+                ExtensionType::CpiGuard,
+            ]
+        );
     }
 
     #[test]
-    fn test_approve_dups() {
+    fn test_transfer_fee() {
         let program_id = Pubkey::new_unique();
-        let account1_key = Pubkey::new_unique();
-        let mut account1_account = SolanaAccount::new(
-            account_minimum_balance(),
-            Account::get_packed_len(),
+        let account_len = ExtensionType::get_account_len(&[ExtensionType::TransferFeeAmount]);
+        let mint_len = ExtensionType::get_account_len(&[ExtensionType::TransferFeeConfig]);
+        let account_key = Pubkey::new_unique();
+        let mut account_account = SolanaAccount::new(
+            Rent::default().minimum_balance(account_len),
+            account_len,
             &program_id,
         );
-        let account1_info: AccountInfo = (&account1_key, true, &mut account1_account).into();
         let account2_key = Pubkey::new_unique();
         let mut account2_account = SolanaAccount::new(
-            account_minimum_balance(),
-            Account::get_packed_len(),
+            Rent::default().minimum_balance(account_len),
+            account_len,
             &program_id,
         );
-        let account2_info: AccountInfo = (&account2_key, false, &mut account2_account).into();
         let account3_key = Pubkey::new_unique();
         let mut account3_account = SolanaAccount::new(
-            account_minimum_balance(),
-            Account::get_packed_len(),
+            Rent::default().minimum_balance(account_len),
+            account_len,
             &program_id,
         );
-        let account3_info: AccountInfo = (&account3_key, true, &mut account3_account).into();
-        let multisig_key = Pubkey::new_unique();
-        let mut multisig_account = SolanaAccount::new(
-            multisig_minimum_balance(),
-            Multisig::get_packed_len(),
+        let account4_key = Pubkey::new_unique();
+        let mut account4_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
             &program_id,
         );
-        let multisig_info: AccountInfo = (&multisig_key, true, &mut multisig_account).into();
         let owner_key = Pubkey::new_unique();
         let mut owner_account = SolanaAccount::default();
-        let owner_info: AccountInfo = (&owner_key, true, &mut owner_account).into();
+        let authority_key = Pubkey::new_unique();
+        let mut authority_account = SolanaAccount::default();
         let mint_key = Pubkey::new_unique();
-        let mut mint_account =
-            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
-        let mint_info: AccountInfo = (&mint_key, false, &mut mint_account).into();
-        let rent_key = rent::id();
+        let mut mint_account = SolanaAccount::new(
+            Rent::default().minimum_balance(mint_len),
+            mint_len,
+            &program_id,
+        );
         let mut rent_sysvar = rent_sysvar();
-        let rent_info: AccountInfo = (&rent_key, false, &mut rent_sysvar).into();
 
-        // create mint
-        do_process_instruction_dups(
+        // fee above 100%
+        assert_eq!(
+            Err(TokenError::TransferFeeExceedsMaximum.into()),
+            do_process_instruction(
+                initialize_transfer_fee_config(
+                    &program_id,
+                    &mint_key,
+                    Some(&authority_key),
+                    MAX_FEE_BASIS_POINTS + 1,
+                    5,
+                )
+                .unwrap(),
+                vec![&mut mint_account],
+            )
+        );
+
+        // create mint charging 1%, up to 5 tokens
+        do_process_instruction(
+            initialize_transfer_fee_config(&program_id, &mint_key, Some(&authority_key), 100, 5)
+                .unwrap(),
+            vec![&mut mint_account],
+        )
+        .unwrap();
+        do_process_instruction(
             initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
-            vec![mint_info.clone(), rent_info.clone()],
+            vec![&mut mint_account, &mut rent_sysvar],
+        )
+        .unwrap();
+
+        // create accounts
+        do_process_instruction(
+            initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                &mut account_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
         )
         .unwrap();
-
-        // create account
-        do_process_instruction_dups(
-            initialize_account(&program_id, &account1_key, &mint_key, &account1_key).unwrap(),
+        do_process_instruction(
+            initialize_account(&program_id, &account2_key, &mint_key, &owner_key).unwrap(),
             vec![
-                account1_info.clone(),
-                mint_info.clone(),
-                account1_info.clone(),
-                rent_info.clone(),
+                &mut account2_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
             ],
         )
         .unwrap();
-
-        // create another account
-        do_process_instruction_dups(
-            initialize_account(&program_id, &account2_key, &mint_key, &owner_key).unwrap(),
+        do_process_instruction(
+            initialize_account(&program_id, &account3_key, &mint_key, &owner_key).unwrap(),
             vec![
-                account2_info.clone(),
-                mint_info.clone(),
-                owner_info.clone(),
-                rent_info.clone(),
+                &mut account3_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
             ],
         )
         .unwrap();
 
-        // mint to account
-        do_process_instruction_dups(
-            mint_to(&program_id, &mint_key, &account1_key, &owner_key, &[], 1000).unwrap(),
-            vec![mint_info.clone(), account1_info.clone(), owner_info.clone()],
+        // base layout cannot withhold fees
+        assert_eq!(
+            Err(ProgramError::InvalidAccountData),
+            do_process_instruction(
+                initialize_account(&program_id, &account4_key, &mint_key, &owner_key).unwrap(),
+                vec![
+                    &mut account4_account,
+                    &mut mint_account,
+                    &mut owner_account,
+                    &mut rent_sysvar,
+                ],
+            )
+        );
+
+        do_process_instruction(
+            mint_to(&program_id, &mint_key, &account_key, &owner_key, &[], 1000).unwrap(),
+            vec![&mut mint_account, &mut account_account, &mut owner_account],
         )
         .unwrap();
 
-        // source-owner approve
-        do_process_instruction_dups(
-            approve(
+        // transfer without the mint
+        assert_eq!(
+            Err(TokenError::MintRequiredForTransfer.into()),
+            do_process_instruction(
+                transfer(
+                    &program_id,
+                    &account_key,
+                    &account2_key,
+                    &owner_key,
+                    &[],
+                    100
+                )
+                .unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut account2_account,
+                    &mut owner_account,
+                ],
+            )
+        );
+
+        // unexpected fee
+        assert_eq!(
+            Err(TokenError::FeeMismatch.into()),
+            do_process_instruction(
+                transfer_checked_with_fee(
+                    &program_id,
+                    &account_key,
+                    &mint_key,
+                    &account2_key,
+                    &owner_key,
+                    &[],
+                    100,
+                    2,
+                    0,
+                )
+                .unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut mint_account,
+                    &mut account2_account,
+                    &mut owner_account,
+                ],
+            )
+        );
+
+        // transfer with fee
+        do_process_instruction(
+            transfer_checked_with_fee(
                 &program_id,
-                &account1_key,
+                &account_key,
+                &mint_key,
                 &account2_key,
-                &account1_key,
+                &owner_key,
                 &[],
-                500,
+                100,
+                2,
+                1,
             )
             .unwrap(),
             vec![
-                account1_info.clone(),
-                account2_info.clone(),
-                account1_info.clone(),
+                &mut account_account,
+                &mut mint_account,
+                &mut account2_account,
+                &mut owner_account,
             ],
         )
         .unwrap();
+        let account = Account::unpack_from_account_data(&account_account.data).unwrap();
+        assert_eq!(account.amount, 900);
+        let account = Account::unpack_from_account_data(&account2_account.data).unwrap();
+        assert_eq!(account.amount, 99);
 
-        // source-owner approve_checked
-        do_process_instruction_dups(
-            approve_checked(
+        // fee is capped
+        do_process_instruction(
+            transfer_checked(
                 &program_id,
-                &account1_key,
+                &account_key,
                 &mint_key,
                 &account2_key,
-                &account1_key,
+                &owner_key,
                 &[],
-                500,
+                800,
                 2,
             )
             .unwrap(),
             vec![
-                account1_info.clone(),
-                mint_info.clone(),
-                account2_info.clone(),
-                account1_info.clone(),
+                &mut account_account,
+                &mut mint_account,
+                &mut account2_account,
+                &mut owner_account,
             ],
         )
         .unwrap();
+        let account = Account::unpack_from_account_data(&account_account.data).unwrap();
+        assert_eq!(account.amount, 100);
+        let account = Account::unpack_from_account_data(&account2_account.data).unwrap();
+        assert_eq!(account.amount, 894);
+        assert_eq!(
+            get_extension::<TransferFeeAmount>(&account2_account.data).unwrap(),
+            Some(TransferFeeAmount { withheld_amount: 6 })
+        );
 
-        // source-owner revoke
-        do_process_instruction_dups(
-            revoke(&program_id, &account1_key, &account1_key, &[]).unwrap(),
-            vec![account1_info.clone(), account1_info.clone()],
+        // cannot close an account withholding fees
+        do_process_instruction(
+            burn(&program_id, &account2_key, &mint_key, &owner_key, &[], 894).unwrap(),
+            vec![&mut account2_account, &mut mint_account, &mut owner_account],
         )
         .unwrap();
+        assert_eq!(
+            Err(TokenError::AccountHasWithheldTransferFees.into()),
+            do_process_instruction(
+                close_account(&program_id, &account2_key, &owner_key, &owner_key, &[]).unwrap(),
+                vec![
+                    &mut account2_account,
+                    &mut SolanaAccount::default(),
+                    &mut owner_account,
+                ],
+            )
+        );
 
-        // test source-multisig signer
-        do_process_instruction_dups(
-            initialize_multisig(&program_id, &multisig_key, &[&account3_key], 1).unwrap(),
+        // withdraw from accounts, missing authority
+        assert_eq!(
+            Err(TokenError::OwnerMismatch.into()),
+            do_process_instruction(
+                withdraw_withheld_tokens_from_accounts(
+                    &program_id,
+                    &mint_key,
+                    &account_key,
+                    &owner_key,
+                    &[],
+                    &[&account2_key],
+                )
+                .unwrap(),
+                vec![
+                    &mut mint_account,
+                    &mut account_account,
+                    &mut owner_account,
+                    &mut account2_account,
+                ],
+            )
+        );
+
+        // withdraw from accounts
+        do_process_instruction(
+            withdraw_withheld_tokens_from_accounts(
+                &program_id,
+                &mint_key,
+                &account_key,
+                &authority_key,
+                &[],
+                &[&account2_key],
+            )
+            .unwrap(),
             vec![
-                multisig_info.clone(),
-                rent_info.clone(),
-                account3_info.clone(),
+                &mut mint_account,
+                &mut account_account,
+                &mut authority_account,
+                &mut account2_account,
             ],
         )
         .unwrap();
-
-        do_process_instruction_dups(
-            initialize_account(&program_id, &account3_key, &mint_key, &multisig_key).unwrap(),
+        let account = Account::unpack_from_account_data(&account_account.data).unwrap();
+        assert_eq!(account.amount, 106);
+        assert_eq!(
+            get_extension::<TransferFeeAmount>(&account2_account.data).unwrap(),
+            Some(TransferFeeAmount { withheld_amount: 0 })
+        );
+        do_process_instruction(
+            close_account(&program_id, &account2_key, &owner_key, &owner_key, &[]).unwrap(),
             vec![
-                account3_info.clone(),
-                mint_info.clone(),
-                multisig_info.clone(),
-                rent_info.clone(),
+                &mut account2_account,
+                &mut SolanaAccount::default(),
+                &mut owner_account,
             ],
         )
         .unwrap();
 
-        do_process_instruction_dups(
-            mint_to(&program_id, &mint_key, &account3_key, &owner_key, &[], 1000).unwrap(),
-            vec![mint_info.clone(), account3_info.clone(), owner_info.clone()],
-        )
-        .unwrap();
-
-        // source-multisig-signer approve
-        do_process_instruction_dups(
-            approve(
+        // harvest to mint
+        do_process_instruction(
+            transfer_checked(
                 &program_id,
+                &account_key,
+                &mint_key,
                 &account3_key,
-                &account2_key,
-                &multisig_key,
-                &[&account3_key],
-                500,
+                &owner_key,
+                &[],
+                100,
+                2,
             )
             .unwrap(),
             vec![
-                account3_info.clone(),
-                account2_info.clone(),
-                multisig_info.clone(),
-                account3_info.clone(),
+                &mut account_account,
+                &mut mint_account,
+                &mut account3_account,
+                &mut owner_account,
             ],
         )
         .unwrap();
+        do_process_instruction(
+            harvest_withheld_tokens_to_mint(&program_id, &mint_key, &[&account3_key]).unwrap(),
+            vec![&mut mint_account, &mut account3_account],
+        )
+        .unwrap();
+        assert_eq!(
+            get_extension::<TransferFeeAmount>(&account3_account.data).unwrap(),
+            Some(TransferFeeAmount { withheld_amount: 0 })
+        );
+        let transfer_fee_config = get_extension::<TransferFeeConfig>(&mint_account.data)
+            .unwrap()
+            .unwrap();
+        assert_eq!(transfer_fee_config.withheld_amount, 1);
 
-        // source-multisig-signer approve_checked
-        do_process_instruction_dups(
-            approve_checked(
+        // withdraw from mint
+        do_process_instruction(
+            withdraw_withheld_tokens_from_mint(
                 &program_id,
-                &account3_key,
                 &mint_key,
-                &account2_key,
-                &multisig_key,
-                &[&account3_key],
-                500,
-                2,
+                &account_key,
+                &authority_key,
+                &[],
             )
             .unwrap(),
             vec![
-                account3_info.clone(),
-                mint_info.clone(),
-                account2_info.clone(),
-                multisig_info.clone(),
-                account3_info.clone(),
+                &mut mint_account,
+                &mut account_account,
+                &mut authority_account,
             ],
         )
         .unwrap();
+        let account = Account::unpack_from_account_data(&account_account.data).unwrap();
+        assert_eq!(account.amount, 7);
+        let transfer_fee_config = get_extension::<TransferFeeConfig>(&mint_account.data)
+            .unwrap()
+            .unwrap();
+        assert_eq!(transfer_fee_config.withheld_amount, 0);
 
-        // source-owner multisig-signer
-        do_process_instruction_dups(
-            revoke(&program_id, &account3_key, &multisig_key, &[&account3_key]).unwrap(),
-            vec![
-                account3_info.clone(),
-                multisig_info.clone(),
-                account3_info.clone(),
-            ],
+        // remove withdraw withheld authority
+        do_process_instruction(
+            set_authority(
+                &program_id,
+                &mint_key,
+                None,
+                AuthorityType::WithdrawWithheldTokens,
+                &authority_key,
+                &[],
+            )
+            .unwrap(),
+            vec![&mut mint_account, &mut authority_account],
         )
         .unwrap();
+        assert_eq!(
+            Err(TokenError::AuthorityTypeNotSupported.into()),
+            do_process_instruction(
+                withdraw_withheld_tokens_from_mint(
+                    &program_id,
+                    &mint_key,
+                    &account_key,
+                    &authority_key,
+                    &[],
+                )
+                .unwrap(),
+                vec![
+                    &mut mint_account,
+                    &mut account_account,
+                    &mut authority_account,
+                ],
+            )
+        );
     }
 
     #[test]
-    fn test_approve() {
+    fn test_required_transfer_memos() {
         let program_id = Pubkey::new_unique();
+        let account_len = ExtensionType::get_account_len(&[ExtensionType::MemoTransfer]);
         let account_key = Pubkey::new_unique();
         let mut account_account = SolanaAccount::new(
             account_minimum_balance(),
@@ -2685,12 +7798,10 @@ mod tests {
         );
         let account2_key = Pubkey::new_unique();
         let mut account2_account = SolanaAccount::new(
-            account_minimum_balance(),
-            Account::get_packed_len(),
+            Rent::default().minimum_balance(account_len),
+            account_len,
             &program_id,
         );
-        let delegate_key = Pubkey::new_unique();
-        let mut delegate_account = SolanaAccount::default();
         let owner_key = Pubkey::new_unique();
         let mut owner_account = SolanaAccount::default();
         let owner2_key = Pubkey::new_unique();
@@ -2700,14 +7811,12 @@ mod tests {
             SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
         let mut rent_sysvar = rent_sysvar();
 
-        // create mint
+        // create mint and accounts
         do_process_instruction(
             initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
             vec![&mut mint_account, &mut rent_sysvar],
         )
         .unwrap();
-
-        // create account
         do_process_instruction(
             initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
             vec![
@@ -2718,315 +7827,243 @@ mod tests {
             ],
         )
         .unwrap();
-
-        // create another account
         do_process_instruction(
-            initialize_account(&program_id, &account2_key, &mint_key, &owner_key).unwrap(),
+            initialize_account(&program_id, &account2_key, &mint_key, &owner2_key).unwrap(),
             vec![
                 &mut account2_account,
                 &mut mint_account,
-                &mut owner_account,
+                &mut owner2_account,
                 &mut rent_sysvar,
             ],
         )
         .unwrap();
-
-        // mint to account
         do_process_instruction(
             mint_to(&program_id, &mint_key, &account_key, &owner_key, &[], 1000).unwrap(),
             vec![&mut mint_account, &mut account_account, &mut owner_account],
         )
         .unwrap();
 
-        // missing signer
-        let mut instruction = approve(
+        // base layout cannot require memos
+        assert_eq!(
+            Err(ProgramError::InvalidAccountData),
+            do_process_instruction(
+                enable_required_transfer_memos(&program_id, &account_key, &owner_key, &[]).unwrap(),
+                vec![&mut account_account, &mut owner_account],
+            )
+        );
+
+        // missing owner
+        assert_eq!(
+            Err(TokenError::OwnerMismatch.into()),
+            do_process_instruction(
+                enable_required_transfer_memos(&program_id, &account2_key, &owner_key, &[])
+                    .unwrap(),
+                vec![&mut account2_account, &mut owner_account],
+            )
+        );
+
+        // require memos
+        do_process_instruction(
+            enable_required_transfer_memos(&program_id, &account2_key, &owner2_key, &[]).unwrap(),
+            vec![&mut account2_account, &mut owner2_account],
+        )
+        .unwrap();
+        assert_eq!(
+            get_extension::<MemoTransfer>(&account2_account.data).unwrap(),
+            Some(MemoTransfer {
+                require_incoming_transfer_memos: true
+            })
+        );
+
+        // missing instructions sysvar
+        let transfer_instruction = transfer(
             &program_id,
             &account_key,
-            &delegate_key,
+            &account2_key,
             &owner_key,
             &[],
             100,
         )
         .unwrap();
-        instruction.accounts[2].is_signer = false;
         assert_eq!(
-            Err(ProgramError::MissingRequiredSignature),
+            Err(TokenError::NoMemo.into()),
             do_process_instruction(
-                instruction,
+                transfer_instruction.clone(),
                 vec![
                     &mut account_account,
-                    &mut delegate_account,
+                    &mut account2_account,
                     &mut owner_account,
                 ],
             )
         );
 
-        // no owner
-        assert_eq!(
-            Err(TokenError::OwnerMismatch.into()),
-            do_process_instruction(
-                approve(
-                    &program_id,
-                    &account_key,
-                    &delegate_key,
-                    &owner2_key,
-                    &[],
-                    100
-                )
-                .unwrap(),
-                vec![
-                    &mut account_account,
-                    &mut delegate_account,
-                    &mut owner2_account,
-                ],
-            )
-        );
-
-        // approve delegate
-        do_process_instruction(
-            approve(
-                &program_id,
-                &account_key,
-                &delegate_key,
-                &owner_key,
-                &[],
-                100,
-            )
-            .unwrap(),
-            vec![
-                &mut account_account,
-                &mut delegate_account,
-                &mut owner_account,
-            ],
-        )
-        .unwrap();
-
-        // approve delegate 2, with incorrect decimals
+        // no preceding instruction
+        let mut instruction = transfer_instruction.clone();
+        instruction
+            .accounts
+            .push(AccountMeta::new_readonly(instructions::id(), false));
         assert_eq!(
-            Err(TokenError::MintDecimalsMismatch.into()),
+            Err(TokenError::NoMemo.into()),
             do_process_instruction(
-                approve_checked(
-                    &program_id,
-                    &account_key,
-                    &mint_key,
-                    &delegate_key,
-                    &owner_key,
-                    &[],
-                    100,
-                    0 // <-- incorrect decimals
-                )
-                .unwrap(),
+                instruction.clone(),
                 vec![
                     &mut account_account,
-                    &mut mint_account,
-                    &mut delegate_account,
+                    &mut account2_account,
                     &mut owner_account,
+                    &mut instructions_sysvar(&[transfer_instruction.clone()], 0),
                 ],
             )
         );
 
-        // approve delegate 2, with incorrect mint
+        // preceding instruction is not a memo
+        let mint_to_instruction =
+            mint_to(&program_id, &mint_key, &account_key, &owner_key, &[], 1).unwrap();
         assert_eq!(
-            Err(TokenError::MintMismatch.into()),
+            Err(TokenError::NoMemo.into()),
             do_process_instruction(
-                approve_checked(
-                    &program_id,
-                    &account_key,
-                    &account2_key, // <-- bad mint
-                    &delegate_key,
-                    &owner_key,
-                    &[],
-                    100,
-                    0
-                )
-                .unwrap(),
+                instruction.clone(),
                 vec![
                     &mut account_account,
-                    &mut account2_account, // <-- bad mint
-                    &mut delegate_account,
+                    &mut account2_account,
                     &mut owner_account,
+                    &mut instructions_sysvar(
+                        &[mint_to_instruction, transfer_instruction.clone()],
+                        1
+                    ),
                 ],
             )
         );
 
-        // approve delegate 2
+        // transfer following a memo
         do_process_instruction(
-            approve_checked(
-                &program_id,
-                &account_key,
-                &mint_key,
-                &delegate_key,
-                &owner_key,
-                &[],
-                100,
-                2,
-            )
-            .unwrap(),
+            instruction,
             vec![
                 &mut account_account,
-                &mut mint_account,
-                &mut delegate_account,
+                &mut account2_account,
                 &mut owner_account,
+                &mut instructions_sysvar(
+                    &[
+                        spl_memo::build_memo(b"deposit", &[]),
+                        transfer_instruction.clone(),
+                    ],
+                    1,
+                ),
             ],
         )
         .unwrap();
+        let account = Account::unpack_from_account_data(&account2_account.data).unwrap();
+        assert_eq!(account.amount, 100);
 
-        // revoke delegate
+        // outgoing transfers need no memo
         do_process_instruction(
-            revoke(&program_id, &account_key, &owner_key, &[]).unwrap(),
-            vec![&mut account_account, &mut owner_account],
-        )
-        .unwrap();
-    }
-
-    #[test]
-    fn test_set_authority_dups() {
-        let program_id = Pubkey::new_unique();
-        let account1_key = Pubkey::new_unique();
-        let mut account1_account = SolanaAccount::new(
-            account_minimum_balance(),
-            Account::get_packed_len(),
-            &program_id,
-        );
-        let account1_info: AccountInfo = (&account1_key, true, &mut account1_account).into();
-        let owner_key = Pubkey::new_unique();
-        let mint_key = Pubkey::new_unique();
-        let mut mint_account =
-            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
-        let mint_info: AccountInfo = (&mint_key, true, &mut mint_account).into();
-        let rent_key = rent::id();
-        let mut rent_sysvar = rent_sysvar();
-        let rent_info: AccountInfo = (&rent_key, false, &mut rent_sysvar).into();
-
-        // create mint
-        do_process_instruction_dups(
-            initialize_mint(&program_id, &mint_key, &mint_key, Some(&mint_key), 2).unwrap(),
-            vec![mint_info.clone(), rent_info.clone()],
-        )
-        .unwrap();
-
-        // create account
-        do_process_instruction_dups(
-            initialize_account(&program_id, &account1_key, &mint_key, &account1_key).unwrap(),
-            vec![
-                account1_info.clone(),
-                mint_info.clone(),
-                account1_info.clone(),
-                rent_info.clone(),
-            ],
-        )
-        .unwrap();
-
-        // set mint_authority when currently self
-        do_process_instruction_dups(
-            set_authority(
-                &program_id,
-                &mint_key,
-                Some(&owner_key),
-                AuthorityType::MintTokens,
-                &mint_key,
-                &[],
-            )
-            .unwrap(),
-            vec![mint_info.clone(), mint_info.clone()],
-        )
-        .unwrap();
-
-        // set freeze_authority when currently self
-        do_process_instruction_dups(
-            set_authority(
+            transfer(
                 &program_id,
-                &mint_key,
-                Some(&owner_key),
-                AuthorityType::FreezeAccount,
-                &mint_key,
+                &account2_key,
+                &account_key,
+                &owner2_key,
                 &[],
+                10,
             )
             .unwrap(),
-            vec![mint_info.clone(), mint_info.clone()],
+            vec![
+                &mut account2_account,
+                &mut account_account,
+                &mut owner2_account,
+            ],
         )
         .unwrap();
 
-        // set account owner when currently self
-        do_process_instruction_dups(
-            set_authority(
-                &program_id,
-                &account1_key,
-                Some(&owner_key),
-                AuthorityType::AccountOwner,
-                &account1_key,
-                &[],
-            )
-            .unwrap(),
-            vec![account1_info.clone(), account1_info.clone()],
+        // stop requiring memos
+        do_process_instruction(
+            disable_required_transfer_memos(&program_id, &account2_key, &owner2_key, &[]).unwrap(),
+            vec![&mut account2_account, &mut owner2_account],
         )
         .unwrap();
-
-        // set close_authority when currently self
-        let mut account = Account::unpack_unchecked(&account1_info.data.borrow()).unwrap();
-        account.close_authority = COption::Some(account1_key);
-        Account::pack(account, &mut account1_info.data.borrow_mut()).unwrap();
-
-        do_process_instruction_dups(
-            set_authority(
-                &program_id,
-                &account1_key,
-                Some(&owner_key),
-                AuthorityType::CloseAccount,
-                &account1_key,
-                &[],
-            )
-            .unwrap(),
-            vec![account1_info.clone(), account1_info.clone()],
+        do_process_instruction(
+            transfer_instruction,
+            vec![
+                &mut account_account,
+                &mut account2_account,
+                &mut owner_account,
+            ],
         )
         .unwrap();
+        let account = Account::unpack_from_account_data(&account2_account.data).unwrap();
+        assert_eq!(account.amount, 190);
     }
 
     #[test]
-    fn test_set_authority() {
+    fn test_immutable_owner() {
         let program_id = Pubkey::new_unique();
+        let account_len = ExtensionType::get_account_len(&[ExtensionType::ImmutableOwner]);
         let account_key = Pubkey::new_unique();
         let mut account_account = SolanaAccount::new(
-            account_minimum_balance(),
-            Account::get_packed_len(),
-            &program_id,
-        );
-        let account2_key = Pubkey::new_unique();
-        let mut account2_account = SolanaAccount::new(
-            account_minimum_balance(),
-            Account::get_packed_len(),
+            Rent::default().minimum_balance(account_len),
+            account_len,
             &program_id,
         );
         let owner_key = Pubkey::new_unique();
         let mut owner_account = SolanaAccount::default();
         let owner2_key = Pubkey::new_unique();
-        let mut owner2_account = SolanaAccount::default();
-        let owner3_key = Pubkey::new_unique();
         let mint_key = Pubkey::new_unique();
         let mut mint_account =
             SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
-        let mint2_key = Pubkey::new_unique();
-        let mut mint2_account =
-            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
         let mut rent_sysvar = rent_sysvar();
 
-        // create new mint with owner
+        // create mint
         do_process_instruction(
             initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
             vec![&mut mint_account, &mut rent_sysvar],
         )
         .unwrap();
 
-        // create mint with owner and freeze_authority
+        // base layout has no room for the extension
+        let mut base_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        assert_eq!(
+            Err(ProgramError::InvalidAccountData),
+            do_process_instruction(
+                initialize_immutable_owner(&program_id, &account_key).unwrap(),
+                vec![&mut base_account],
+            )
+        );
+
+        // make the owner immutable, then create the account
         do_process_instruction(
-            initialize_mint(&program_id, &mint2_key, &owner_key, Some(&owner_key), 2).unwrap(),
-            vec![&mut mint2_account, &mut rent_sysvar],
+            initialize_immutable_owner(&program_id, &account_key).unwrap(),
+            vec![&mut account_account],
+        )
+        .unwrap();
+        do_process_instruction(
+            initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                &mut account_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
         )
         .unwrap();
+        assert_eq!(
+            get_extension::<ImmutableOwner>(&account_account.data).unwrap(),
+            Some(ImmutableOwner)
+        );
 
-        // invalid account
+        // cannot be applied to an initialized account
         assert_eq!(
-            Err(ProgramError::UninitializedAccount),
+            Err(TokenError::AlreadyInUse.into()),
+            do_process_instruction(
+                initialize_immutable_owner(&program_id, &account_key).unwrap(),
+                vec![&mut account_account],
+            )
+        );
+
+        // owner cannot be changed
+        assert_eq!(
+            Err(TokenError::ImmutableOwner.into()),
             do_process_instruction(
                 set_authority(
                     &program_id,
@@ -3034,592 +8071,1038 @@ mod tests {
                     Some(&owner2_key),
                     AuthorityType::AccountOwner,
                     &owner_key,
-                    &[]
+                    &[],
                 )
                 .unwrap(),
                 vec![&mut account_account, &mut owner_account],
             )
         );
 
-        // create account
+        // other authorities are unaffected
         do_process_instruction(
-            initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
-            vec![
-                &mut account_account,
-                &mut mint_account,
-                &mut owner_account,
-                &mut rent_sysvar,
-            ],
+            set_authority(
+                &program_id,
+                &account_key,
+                Some(&owner2_key),
+                AuthorityType::CloseAccount,
+                &owner_key,
+                &[],
+            )
+            .unwrap(),
+            vec![&mut account_account, &mut owner_account],
         )
         .unwrap();
+        let account = Account::unpack_from_account_data(&account_account.data).unwrap();
+        assert_eq!(account.owner, owner_key);
+        assert_eq!(account.close_authority, COption::Some(owner2_key));
+    }
 
-        // create another account
+    #[test]
+    fn test_close_mint() {
+        let program_id = Pubkey::new_unique();
+        let mint_len = ExtensionType::get_account_len(&[ExtensionType::MintCloseAuthority]);
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account = SolanaAccount::new(
+            Rent::default().minimum_balance(mint_len),
+            mint_len,
+            &program_id,
+        );
+        let mint2_key = Pubkey::new_unique();
+        let mut mint2_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let account_key = Pubkey::new_unique();
+        let mut account_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let owner_key = Pubkey::new_unique();
+        let mut owner_account = SolanaAccount::default();
+        let authority_key = Pubkey::new_unique();
+        let mut authority_account = SolanaAccount::default();
+        let authority2_key = Pubkey::new_unique();
+        let mut authority2_account = SolanaAccount::default();
+        let dest_key = Pubkey::new_unique();
+        let mut dest_account = SolanaAccount::default();
+        let mut rent_sysvar = rent_sysvar();
+
+        // create mints and account
         do_process_instruction(
-            initialize_account(&program_id, &account2_key, &mint2_key, &owner_key).unwrap(),
+            initialize_mint_close_authority(&program_id, &mint_key, &authority_key).unwrap(),
+            vec![&mut mint_account],
+        )
+        .unwrap();
+        do_process_instruction(
+            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+            vec![&mut mint_account, &mut rent_sysvar],
+        )
+        .unwrap();
+        do_process_instruction(
+            initialize_mint(&program_id, &mint2_key, &owner_key, None, 2).unwrap(),
+            vec![&mut mint2_account, &mut rent_sysvar],
+        )
+        .unwrap();
+        do_process_instruction(
+            initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
             vec![
-                &mut account2_account,
-                &mut mint2_account,
+                &mut account_account,
+                &mut mint_account,
                 &mut owner_account,
                 &mut rent_sysvar,
             ],
         )
         .unwrap();
 
-        // missing owner
+        // cannot be configured once initialized
         assert_eq!(
-            Err(TokenError::OwnerMismatch.into()),
+            Err(TokenError::AlreadyInUse.into()),
             do_process_instruction(
-                set_authority(
-                    &program_id,
-                    &account_key,
-                    Some(&owner_key),
-                    AuthorityType::AccountOwner,
-                    &owner2_key,
-                    &[]
-                )
-                .unwrap(),
-                vec![&mut account_account, &mut owner2_account],
+                initialize_mint_close_authority(&program_id, &mint_key, &authority_key).unwrap(),
+                vec![&mut mint_account],
             )
         );
 
-        // owner did not sign
-        let mut instruction = set_authority(
-            &program_id,
-            &account_key,
-            Some(&owner2_key),
-            AuthorityType::AccountOwner,
-            &owner_key,
-            &[],
-        )
-        .unwrap();
-        instruction.accounts[1].is_signer = false;
+        // mint without a close authority
         assert_eq!(
-            Err(ProgramError::MissingRequiredSignature),
-            do_process_instruction(instruction, vec![&mut account_account, &mut owner_account,],)
+            Err(TokenError::AuthorityTypeNotSupported.into()),
+            do_process_instruction(
+                close_account(&program_id, &mint2_key, &dest_key, &owner_key, &[]).unwrap(),
+                vec![&mut mint2_account, &mut dest_account, &mut owner_account],
+            )
         );
 
-        // wrong authority type
+        // mint with supply
+        do_process_instruction(
+            mint_to(&program_id, &mint_key, &account_key, &owner_key, &[], 42).unwrap(),
+            vec![&mut mint_account, &mut account_account, &mut owner_account],
+        )
+        .unwrap();
         assert_eq!(
-            Err(TokenError::AuthorityTypeNotSupported.into()),
+            Err(TokenError::MintHasSupply.into()),
             do_process_instruction(
-                set_authority(
-                    &program_id,
-                    &account_key,
-                    Some(&owner2_key),
-                    AuthorityType::FreezeAccount,
-                    &owner_key,
-                    &[],
-                )
-                .unwrap(),
-                vec![&mut account_account, &mut owner_account],
+                close_account(&program_id, &mint_key, &dest_key, &authority_key, &[]).unwrap(),
+                vec![&mut mint_account, &mut dest_account, &mut authority_account],
             )
         );
+        do_process_instruction(
+            burn(&program_id, &account_key, &mint_key, &owner_key, &[], 42).unwrap(),
+            vec![&mut account_account, &mut mint_account, &mut owner_account],
+        )
+        .unwrap();
 
-        // account owner may not be set to None
+        // wrong authority
         assert_eq!(
-            Err(TokenError::InvalidInstruction.into()),
+            Err(TokenError::OwnerMismatch.into()),
             do_process_instruction(
-                set_authority(
-                    &program_id,
-                    &account_key,
-                    None,
-                    AuthorityType::AccountOwner,
-                    &owner_key,
-                    &[],
-                )
-                .unwrap(),
-                vec![&mut account_account, &mut owner_account],
+                close_account(&program_id, &mint_key, &dest_key, &owner_key, &[]).unwrap(),
+                vec![&mut mint_account, &mut dest_account, &mut owner_account],
             )
         );
 
-        // set owner
+        // change the close authority
         do_process_instruction(
             set_authority(
                 &program_id,
-                &account_key,
-                Some(&owner2_key),
-                AuthorityType::AccountOwner,
-                &owner_key,
+                &mint_key,
+                Some(&authority2_key),
+                AuthorityType::CloseMint,
+                &authority_key,
                 &[],
             )
             .unwrap(),
-            vec![&mut account_account, &mut owner_account],
+            vec![&mut mint_account, &mut authority_account],
         )
         .unwrap();
+        assert_eq!(
+            get_extension::<MintCloseAuthority>(&mint_account.data).unwrap(),
+            Some(MintCloseAuthority {
+                close_authority: COption::Some(authority2_key),
+            })
+        );
 
-        // set close_authority
+        // close the mint
+        do_process_instruction(
+            close_account(&program_id, &mint_key, &dest_key, &authority2_key, &[]).unwrap(),
+            vec![
+                &mut mint_account,
+                &mut dest_account,
+                &mut authority2_account,
+            ],
+        )
+        .unwrap();
+        assert_eq!(mint_account.lamports, 0);
+        assert_eq!(
+            dest_account.lamports,
+            Rent::default().minimum_balance(mint_len)
+        );
+        assert!(mint_account.data.iter().all(|byte| *byte == 0));
+
+        // once removed, the close authority cannot be added back
+        let mut mint3_account = SolanaAccount::new(
+            Rent::default().minimum_balance(mint_len),
+            mint_len,
+            &program_id,
+        );
+        do_process_instruction(
+            initialize_mint_close_authority(&program_id, &mint_key, &authority_key).unwrap(),
+            vec![&mut mint3_account],
+        )
+        .unwrap();
+        do_process_instruction(
+            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+            vec![&mut mint3_account, &mut rent_sysvar],
+        )
+        .unwrap();
         do_process_instruction(
             set_authority(
                 &program_id,
-                &account_key,
-                Some(&owner2_key),
-                AuthorityType::CloseAccount,
-                &owner2_key,
+                &mint_key,
+                None,
+                AuthorityType::CloseMint,
+                &authority_key,
                 &[],
             )
             .unwrap(),
-            vec![&mut account_account, &mut owner2_account],
+            vec![&mut mint3_account, &mut authority_account],
         )
         .unwrap();
+        assert_eq!(
+            Err(TokenError::AuthorityTypeNotSupported.into()),
+            do_process_instruction(
+                set_authority(
+                    &program_id,
+                    &mint_key,
+                    Some(&authority_key),
+                    AuthorityType::CloseMint,
+                    &authority_key,
+                    &[],
+                )
+                .unwrap(),
+                vec![&mut mint3_account, &mut authority_account],
+            )
+        );
+    }
 
-        // close_authority may be set to None
+    #[test]
+    fn test_interest_bearing_mint() {
+        let program_id = Pubkey::new_unique();
+        let mint_len = ExtensionType::get_account_len(&[ExtensionType::InterestBearingConfig]);
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account = SolanaAccount::new(
+            Rent::default().minimum_balance(mint_len),
+            mint_len,
+            &program_id,
+        );
+        let owner_key = Pubkey::new_unique();
+        let authority_key = Pubkey::new_unique();
+        let mut authority_account = SolanaAccount::default();
+        let authority2_key = Pubkey::new_unique();
+        let mut authority2_account = SolanaAccount::default();
+        let mut rent_sysvar = rent_sysvar();
+
+        // create mint accruing interest
+        do_process_instruction(
+            initialize_interest_bearing_mint(&program_id, &mint_key, Some(&authority_key), 500)
+                .unwrap(),
+            vec![&mut mint_account],
+        )
+        .unwrap();
+        do_process_instruction(
+            initialize_mint(&program_id, &mint_key, &owner_key, None, 6).unwrap(),
+            vec![&mut mint_account, &mut rent_sysvar],
+        )
+        .unwrap();
+        assert_eq!(
+            get_extension::<InterestBearingConfig>(&mint_account.data).unwrap(),
+            Some(InterestBearingConfig {
+                rate_authority: COption::Some(authority_key),
+                initialization_timestamp: CLOCK_UNIX_TIMESTAMP,
+                pre_update_average_rate: 500,
+                last_update_timestamp: CLOCK_UNIX_TIMESTAMP,
+                current_rate: 500,
+            })
+        );
+        assert_eq!(
+            mint_amount_to_ui_amount(&mint_account.data, 1_000_000, CLOCK_UNIX_TIMESTAMP),
+            Ok(1.0)
+        );
+        let one_year = CLOCK_UNIX_TIMESTAMP + (60.0 * 60.0 * 24.0 * 365.24) as i64;
+        let ui_amount = mint_amount_to_ui_amount(&mint_account.data, 1_000_000, one_year).unwrap();
+        assert!((ui_amount - 0.05f64.exp()).abs() < 1e-9);
+
+        // missing rate authority
+        assert_eq!(
+            Err(TokenError::OwnerMismatch.into()),
+            do_process_instruction(
+                update_interest_rate(&program_id, &mint_key, &authority2_key, &[], -100).unwrap(),
+                vec![&mut mint_account, &mut authority2_account],
+            )
+        );
+
+        // update rate
+        do_process_instruction(
+            update_interest_rate(&program_id, &mint_key, &authority_key, &[], -100).unwrap(),
+            vec![&mut mint_account, &mut authority_account],
+        )
+        .unwrap();
+        let interest_bearing_config = get_extension::<InterestBearingConfig>(&mint_account.data)
+            .unwrap()
+            .unwrap();
+        assert_eq!(interest_bearing_config.pre_update_average_rate, 500);
+        assert_eq!(interest_bearing_config.current_rate, -100);
+
+        // change the rate authority
         do_process_instruction(
             set_authority(
                 &program_id,
-                &account_key,
-                None,
-                AuthorityType::CloseAccount,
-                &owner2_key,
+                &mint_key,
+                Some(&authority2_key),
+                AuthorityType::InterestRate,
+                &authority_key,
                 &[],
             )
             .unwrap(),
-            vec![&mut account_account, &mut owner2_account],
+            vec![&mut mint_account, &mut authority_account],
+        )
+        .unwrap();
+        do_process_instruction(
+            update_interest_rate(&program_id, &mint_key, &authority2_key, &[], 0).unwrap(),
+            vec![&mut mint_account, &mut authority2_account],
         )
         .unwrap();
 
-        // wrong owner
+        // interest accrues continuously
+        let seconds_per_year = 31_556_736;
+        let config = InterestBearingConfig {
+            rate_authority: COption::None,
+            initialization_timestamp: 0,
+            pre_update_average_rate: 500,
+            last_update_timestamp: 0,
+            current_rate: 500,
+        };
+        assert_eq!(config.amount_to_ui_amount(1_000_000, 6, 0), 1.0);
+        let ui_amount = config.amount_to_ui_amount(1_000_000, 6, seconds_per_year);
+        assert!((ui_amount - 0.05f64.exp()).abs() < 1e-12);
+
+        // interest accrued before an update is kept
+        let config = InterestBearingConfig {
+            rate_authority: COption::None,
+            initialization_timestamp: 0,
+            pre_update_average_rate: 500,
+            last_update_timestamp: seconds_per_year,
+            current_rate: -500,
+        };
+        let ui_amount = config.amount_to_ui_amount(1_000_000, 6, 2 * seconds_per_year);
+        assert!((ui_amount - 1.0).abs() < 1e-12);
+        assert_eq!(config.time_weighted_average_rate(2 * seconds_per_year), 0);
+        assert_eq!(
+            config.time_weighted_average_rate(4 * seconds_per_year),
+            -250
+        );
+    }
+
+    #[test]
+    fn test_token_metadata_initialized_mint() {
+        let program_id = Pubkey::new_unique();
+        let mint_len = ExtensionType::get_account_len(&[ExtensionType::TokenMetadata]);
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account = SolanaAccount::new(
+            Rent::default().minimum_balance(mint_len),
+            mint_len,
+            &program_id,
+        );
+        let mint2_key = Pubkey::new_unique();
+        let mut mint2_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let owner_key = Pubkey::new_unique();
+        let mut owner_account = SolanaAccount::default();
+        let owner2_key = Pubkey::new_unique();
+        let mut owner2_account = SolanaAccount::default();
+        let authority_key = Pubkey::new_unique();
+        let mut rent_sysvar = rent_sysvar();
+
+        do_process_instruction(
+            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+            vec![&mut mint_account, &mut rent_sysvar],
+        )
+        .unwrap();
+        do_process_instruction(
+            initialize_mint(&program_id, &mint2_key, &owner_key, None, 2).unwrap(),
+            vec![&mut mint2_account, &mut rent_sysvar],
+        )
+        .unwrap();
+
+        // initialized mint needs its mint authority
+        assert_eq!(
+            Err(ProgramError::NotEnoughAccountKeys),
+            do_process_instruction(
+                initialize_token_metadata(
+                    &program_id,
+                    &mint_key,
+                    Some(&authority_key),
+                    "Token".to_string(),
+                    "TKN".to_string(),
+                    "".to_string(),
+                )
+                .unwrap(),
+                vec![&mut mint_account],
+            )
+        );
         assert_eq!(
             Err(TokenError::OwnerMismatch.into()),
             do_process_instruction(
-                set_authority(
+                initialize_token_metadata_with_mint_authority(
                     &program_id,
                     &mint_key,
-                    Some(&owner3_key),
-                    AuthorityType::MintTokens,
                     &owner2_key,
-                    &[]
+                    &[],
+                    Some(&authority_key),
+                    "Token".to_string(),
+                    "TKN".to_string(),
+                    "".to_string(),
                 )
                 .unwrap(),
                 vec![&mut mint_account, &mut owner2_account],
             )
         );
 
-        // owner did not sign
-        let mut instruction = set_authority(
-            &program_id,
-            &mint_key,
-            Some(&owner2_key),
-            AuthorityType::MintTokens,
-            &owner_key,
-            &[],
-        )
-        .unwrap();
-        instruction.accounts[1].is_signer = false;
-        assert_eq!(
-            Err(ProgramError::MissingRequiredSignature),
-            do_process_instruction(instruction, vec![&mut mint_account, &mut owner_account],)
-        );
-
-        // cannot freeze
+        // base layout has no space for metadata
         assert_eq!(
-            Err(TokenError::MintCannotFreeze.into()),
+            Err(ProgramError::InvalidAccountData),
             do_process_instruction(
-                set_authority(
+                initialize_token_metadata_with_mint_authority(
                     &program_id,
-                    &mint_key,
-                    Some(&owner2_key),
-                    AuthorityType::FreezeAccount,
+                    &mint2_key,
                     &owner_key,
                     &[],
+                    Some(&authority_key),
+                    "Token".to_string(),
+                    "TKN".to_string(),
+                    "".to_string(),
                 )
                 .unwrap(),
-                vec![&mut mint_account, &mut owner_account],
+                vec![&mut mint2_account, &mut owner_account],
             )
         );
 
-        // set owner
+        // add metadata
         do_process_instruction(
-            set_authority(
+            initialize_token_metadata_with_mint_authority(
                 &program_id,
                 &mint_key,
-                Some(&owner2_key),
-                AuthorityType::MintTokens,
                 &owner_key,
                 &[],
+                Some(&authority_key),
+                "Token".to_string(),
+                "TKN".to_string(),
+                "".to_string(),
             )
             .unwrap(),
             vec![&mut mint_account, &mut owner_account],
         )
         .unwrap();
+        assert_eq!(
+            get_extension::<TokenMetadata>(&mint_account.data).unwrap(),
+            Some(TokenMetadata {
+                update_authority: COption::Some(authority_key),
+                name: "Token".to_string(),
+                symbol: "TKN".to_string(),
+                uri: "".to_string(),
+            })
+        );
 
-        // set owner to None
-        do_process_instruction(
-            set_authority(
-                &program_id,
-                &mint_key,
-                None,
-                AuthorityType::MintTokens,
-                &owner2_key,
-                &[],
-            )
-            .unwrap(),
-            vec![&mut mint_account, &mut owner2_account],
-        )
-        .unwrap();
-
-        // test unsetting mint_authority is one-way operation
+        // existing metadata can only be changed by its update authority
         assert_eq!(
-            Err(TokenError::FixedSupply.into()),
+            Err(TokenError::AlreadyInUse.into()),
             do_process_instruction(
-                set_authority(
+                initialize_token_metadata_with_mint_authority(
                     &program_id,
-                    &mint2_key,
-                    Some(&owner2_key),
-                    AuthorityType::MintTokens,
+                    &mint_key,
                     &owner_key,
-                    &[]
+                    &[],
+                    None,
+                    "Renamed".to_string(),
+                    "RNM".to_string(),
+                    "".to_string(),
                 )
                 .unwrap(),
                 vec![&mut mint_account, &mut owner_account],
             )
         );
+    }
 
-        // set freeze_authority
+    #[test]
+    fn test_cpi_guard() {
+        let program_id = Pubkey::new_unique();
+        let account_len = ExtensionType::get_account_len(&[ExtensionType::CpiGuard]);
+        let account_key = Pubkey::new_unique();
+        let mut account_account = SolanaAccount::new(
+            Rent::default().minimum_balance(account_len),
+            account_len,
+            &program_id,
+        );
+        let account2_key = Pubkey::new_unique();
+        let mut account2_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let account3_key = Pubkey::new_unique();
+        let mut account3_account = SolanaAccount::new(
+            Rent::default().minimum_balance(account_len),
+            account_len,
+            &program_id,
+        );
+        let owner_key = Pubkey::new_unique();
+        let mut owner_account = SolanaAccount::default();
+        let delegate_key = Pubkey::new_unique();
+        let mut delegate_account = SolanaAccount::default();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let mut rent_sysvar = rent_sysvar();
+
+        // create mint and accounts
         do_process_instruction(
-            set_authority(
+            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+            vec![&mut mint_account, &mut rent_sysvar],
+        )
+        .unwrap();
+        do_process_instruction(
+            initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                &mut account_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+        do_process_instruction(
+            initialize_account(&program_id, &account2_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                &mut account2_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+        do_process_instruction(
+            mint_to(&program_id, &mint_key, &account_key, &owner_key, &[], 1000).unwrap(),
+            vec![&mut mint_account, &mut account_account, &mut owner_account],
+        )
+        .unwrap();
+        do_process_instruction(
+            approve(
                 &program_id,
-                &mint2_key,
-                Some(&owner2_key),
-                AuthorityType::FreezeAccount,
+                &account_key,
+                &delegate_key,
                 &owner_key,
                 &[],
+                100,
             )
             .unwrap(),
-            vec![&mut mint2_account, &mut owner_account],
+            vec![
+                &mut account_account,
+                &mut delegate_account,
+                &mut owner_account,
+            ],
         )
         .unwrap();
 
-        // test unsetting freeze_authority is one-way operation
+        // enable the guard
         do_process_instruction(
-            set_authority(
-                &program_id,
-                &mint2_key,
-                None,
-                AuthorityType::FreezeAccount,
-                &owner2_key,
-                &[],
-            )
-            .unwrap(),
-            vec![&mut mint2_account, &mut owner2_account],
+            enable_cpi_guard(&program_id, &account_key, &owner_key, &[]).unwrap(),
+            vec![&mut account_account, &mut owner_account],
         )
         .unwrap();
+        assert_eq!(
+            get_extension::<CpiGuard>(&account_account.data).unwrap(),
+            Some(CpiGuard { lock_cpi: true })
+        );
 
+        // owner-signed transfer without the instructions sysvar
+        let transfer_instruction = transfer(
+            &program_id,
+            &account_key,
+            &account2_key,
+            &owner_key,
+            &[],
+            10,
+        )
+        .unwrap();
         assert_eq!(
-            Err(TokenError::MintCannotFreeze.into()),
+            Err(TokenError::CpiGuarded.into()),
             do_process_instruction(
-                set_authority(
-                    &program_id,
-                    &mint2_key,
-                    Some(&owner2_key),
-                    AuthorityType::FreezeAccount,
-                    &owner_key,
-                    &[],
-                )
-                .unwrap(),
-                vec![&mut mint2_account, &mut owner2_account],
+                transfer_instruction.clone(),
+                vec![
+                    &mut account_account,
+                    &mut account2_account,
+                    &mut owner_account,
+                ],
             )
         );
-    }
 
-    #[test]
-    fn test_mint_to_dups() {
-        let program_id = Pubkey::new_unique();
-        let account1_key = Pubkey::new_unique();
-        let mut account1_account = SolanaAccount::new(
-            account_minimum_balance(),
-            Account::get_packed_len(),
+        // owner-signed transfer invoked by another program
+        let instruction = transfer_with_instructions_sysvar(
             &program_id,
-        );
-        let account1_info: AccountInfo = (&account1_key, true, &mut account1_account).into();
-        let owner_key = Pubkey::new_unique();
-        let mut owner_account = SolanaAccount::default();
-        let owner_info: AccountInfo = (&owner_key, true, &mut owner_account).into();
-        let mint_key = Pubkey::new_unique();
-        let mut mint_account =
-            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
-        let mint_info: AccountInfo = (&mint_key, true, &mut mint_account).into();
-        let rent_key = rent::id();
-        let mut rent_sysvar = rent_sysvar();
-        let rent_info: AccountInfo = (&rent_key, false, &mut rent_sysvar).into();
-
-        // create mint
-        do_process_instruction_dups(
-            initialize_mint(&program_id, &mint_key, &mint_key, None, 2).unwrap(),
-            vec![mint_info.clone(), rent_info.clone()],
+            &account_key,
+            &account2_key,
+            &owner_key,
+            &[],
+            10,
         )
         .unwrap();
+        let mut caller_instruction = transfer_instruction.clone();
+        caller_instruction.program_id = Pubkey::new_unique();
+        assert_eq!(
+            Err(TokenError::CpiGuarded.into()),
+            do_process_instruction(
+                instruction.clone(),
+                vec![
+                    &mut account_account,
+                    &mut account2_account,
+                    &mut owner_account,
+                    &mut instructions_sysvar(&[caller_instruction.clone()], 0),
+                ],
+            )
+        );
 
-        // create account
-        do_process_instruction_dups(
-            initialize_account(&program_id, &account1_key, &mint_key, &owner_key).unwrap(),
+        // owner-signed transfer invoked directly
+        do_process_instruction(
+            instruction,
             vec![
-                account1_info.clone(),
-                mint_info.clone(),
-                owner_info.clone(),
-                rent_info.clone(),
+                &mut account_account,
+                &mut account2_account,
+                &mut owner_account,
+                &mut instructions_sysvar(&[transfer_instruction.clone()], 0),
             ],
         )
         .unwrap();
 
-        // mint_to when mint_authority is self
-        do_process_instruction_dups(
-            mint_to(&program_id, &mint_key, &account1_key, &mint_key, &[], 42).unwrap(),
-            vec![mint_info.clone(), account1_info.clone(), mint_info.clone()],
-        )
-        .unwrap();
-
-        // mint_to_checked when mint_authority is self
-        do_process_instruction_dups(
-            mint_to_checked(&program_id, &mint_key, &account1_key, &mint_key, &[], 42, 2).unwrap(),
-            vec![mint_info.clone(), account1_info.clone(), mint_info.clone()],
+        // batched transfers likewise
+        assert_eq!(
+            Err(TokenError::CpiGuarded.into()),
+            do_process_instruction(
+                transfer_checked_batch(
+                    &program_id,
+                    &account_key,
+                    &mint_key,
+                    &[(&account2_key, 10)],
+                    &owner_key,
+                    &[],
+                    2,
+                )
+                .unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut mint_account,
+                    &mut owner_account,
+                    &mut account2_account,
+                ],
+            )
+        );
+        let instruction = transfer_checked_batch_with_instructions_sysvar(
+            &program_id,
+            &account_key,
+            &mint_key,
+            &[(&account2_key, 10)],
+            &owner_key,
+            &[],
+            2,
         )
         .unwrap();
-
-        // mint_to when mint_authority is account owner
-        let mut mint = Mint::unpack_unchecked(&mint_info.data.borrow()).unwrap();
-        mint.mint_authority = COption::Some(account1_key);
-        Mint::pack(mint, &mut mint_info.data.borrow_mut()).unwrap();
-        do_process_instruction_dups(
-            mint_to(
-                &program_id,
-                &mint_key,
-                &account1_key,
-                &account1_key,
-                &[],
-                42,
-            )
-            .unwrap(),
+        do_process_instruction(
+            instruction.clone(),
             vec![
-                mint_info.clone(),
-                account1_info.clone(),
-                account1_info.clone(),
+                &mut account_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut account2_account,
+                &mut instructions_sysvar(&[instruction], 0),
             ],
         )
         .unwrap();
-
-        // mint_to_checked when mint_authority is account owner
-        do_process_instruction_dups(
-            mint_to(
+        // delegate-signed transfers are not guarded
+        do_process_instruction(
+            transfer(
                 &program_id,
-                &mint_key,
-                &account1_key,
-                &account1_key,
+                &account_key,
+                &account2_key,
+                &delegate_key,
                 &[],
-                42,
+                10,
             )
             .unwrap(),
             vec![
-                mint_info.clone(),
-                account1_info.clone(),
-                account1_info.clone(),
+                &mut account_account,
+                &mut account2_account,
+                &mut delegate_account,
             ],
         )
         .unwrap();
-    }
 
-    #[test]
-    fn test_mint_to() {
-        let program_id = Pubkey::new_unique();
-        let account_key = Pubkey::new_unique();
-        let mut account_account = SolanaAccount::new(
-            account_minimum_balance(),
-            Account::get_packed_len(),
-            &program_id,
-        );
-        let account2_key = Pubkey::new_unique();
-        let mut account2_account = SolanaAccount::new(
-            account_minimum_balance(),
-            Account::get_packed_len(),
-            &program_id,
-        );
-        let account3_key = Pubkey::new_unique();
-        let mut account3_account = SolanaAccount::new(
-            account_minimum_balance(),
-            Account::get_packed_len(),
-            &program_id,
+        // approve and set authority are guarded
+        assert_eq!(
+            Err(TokenError::CpiGuarded.into()),
+            do_process_instruction(
+                approve(
+                    &program_id,
+                    &account_key,
+                    &delegate_key,
+                    &owner_key,
+                    &[],
+                    100,
+                )
+                .unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut delegate_account,
+                    &mut owner_account,
+                ],
+            )
         );
-        let mismatch_key = Pubkey::new_unique();
-        let mut mismatch_account = SolanaAccount::new(
-            account_minimum_balance(),
-            Account::get_packed_len(),
-            &program_id,
+        assert_eq!(
+            Err(TokenError::CpiGuarded.into()),
+            do_process_instruction(
+                set_authority(
+                    &program_id,
+                    &account_key,
+                    Some(&delegate_key),
+                    AuthorityType::CloseAccount,
+                    &owner_key,
+                    &[],
+                )
+                .unwrap(),
+                vec![&mut account_account, &mut owner_account],
+            )
         );
-        let owner_key = Pubkey::new_unique();
-        let mut owner_account = SolanaAccount::default();
-        let owner2_key = Pubkey::new_unique();
-        let mut owner2_account = SolanaAccount::default();
-        let mint_key = Pubkey::new_unique();
-        let mut mint_account =
-            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
-        let mint2_key = Pubkey::new_unique();
-        let uninitialized_key = Pubkey::new_unique();
-        let mut uninitialized_account = SolanaAccount::new(
-            account_minimum_balance(),
-            Account::get_packed_len(),
-            &program_id,
+        assert_eq!(
+            Err(TokenError::CpiGuarded.into()),
+            do_process_instruction(
+                migrate_account(
+                    &program_id,
+                    &account_key,
+                    &account3_key,
+                    &owner_key,
+                    &owner_key,
+                    &[],
+                    &[],
+                )
+                .unwrap(),
+                vec![
+                    &mut account_account,
+                    &mut account3_account,
+                    &mut owner_account.clone(),
+                    &mut owner_account,
+                ],
+            )
         );
-        let mut rent_sysvar = rent_sysvar();
 
-        // create new mint with owner
-        do_process_instruction(
-            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
-            vec![&mut mint_account, &mut rent_sysvar],
+        // and succeed when invoked directly
+        let instruction = approve_with_instructions_sysvar(
+            &program_id,
+            &account_key,
+            &delegate_key,
+            &owner_key,
+            &[],
+            100,
         )
         .unwrap();
-
-        // create account
         do_process_instruction(
-            initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
+            instruction.clone(),
             vec![
                 &mut account_account,
-                &mut mint_account,
+                &mut delegate_account,
                 &mut owner_account,
-                &mut rent_sysvar,
+                &mut instructions_sysvar(&[instruction], 0),
             ],
         )
         .unwrap();
-
-        // create another account
+        let instruction = set_authority_with_instructions_sysvar(
+            &program_id,
+            &account_key,
+            Some(&delegate_key),
+            AuthorityType::CloseAccount,
+            &owner_key,
+            &[],
+        )
+        .unwrap();
         do_process_instruction(
-            initialize_account(&program_id, &account2_key, &mint_key, &owner_key).unwrap(),
+            instruction.clone(),
             vec![
-                &mut account2_account,
-                &mut mint_account,
+                &mut account_account,
                 &mut owner_account,
-                &mut rent_sysvar,
+                &mut instructions_sysvar(&[instruction], 0),
             ],
         )
         .unwrap();
+        let account = Account::unpack_from_account_data(&account_account.data).unwrap();
+        assert_eq!(account.delegate, COption::Some(delegate_key));
+        assert_eq!(account.close_authority, COption::Some(delegate_key));
 
-        // create another account
+        // disabling the guard is itself guarded
+        let disable_instruction =
+            disable_cpi_guard(&program_id, &account_key, &owner_key, &[]).unwrap();
+        let mut caller_instruction = disable_instruction.clone();
+        caller_instruction.program_id = Pubkey::new_unique();
+        assert_eq!(
+            Err(TokenError::CpiGuarded.into()),
+            do_process_instruction(
+                disable_instruction.clone(),
+                vec![
+                    &mut account_account,
+                    &mut owner_account,
+                    &mut instructions_sysvar(&[caller_instruction], 0),
+                ],
+            )
+        );
         do_process_instruction(
-            initialize_account(&program_id, &account3_key, &mint_key, &owner_key).unwrap(),
+            disable_instruction.clone(),
             vec![
-                &mut account3_account,
-                &mut mint_account,
+                &mut account_account,
                 &mut owner_account,
-                &mut rent_sysvar,
+                &mut instructions_sysvar(&[disable_instruction], 0),
             ],
         )
         .unwrap();
-
-        // create mismatch account
         do_process_instruction(
-            initialize_account(&program_id, &mismatch_key, &mint_key, &owner_key).unwrap(),
+            transfer_instruction,
             vec![
-                &mut mismatch_account,
-                &mut mint_account,
+                &mut account_account,
+                &mut account2_account,
                 &mut owner_account,
-                &mut rent_sysvar,
             ],
         )
         .unwrap();
-        let mut account = Account::unpack_unchecked(&mismatch_account.data).unwrap();
-        account.mint = mint2_key;
-        Account::pack(account, &mut mismatch_account.data).unwrap();
+        let account = Account::unpack_from_account_data(&account_account.data).unwrap();
+        assert_eq!(account.amount, 960);
 
-        // mint to
+        // moving a guarded account needs the instructions sysvar too
         do_process_instruction(
-            mint_to(&program_id, &mint_key, &account_key, &owner_key, &[], 42).unwrap(),
-            vec![&mut mint_account, &mut account_account, &mut owner_account],
+            enable_cpi_guard(&program_id, &account_key, &owner_key, &[]).unwrap(),
+            vec![&mut account_account, &mut owner_account],
+        )
+        .unwrap();
+        let instruction = set_authority_with_instructions_sysvar(
+            &program_id,
+            &account_key,
+            None,
+            AuthorityType::CloseAccount,
+            &delegate_key,
+            &[],
+        )
+        .unwrap();
+        do_process_instruction(
+            instruction.clone(),
+            vec![
+                &mut account_account,
+                &mut delegate_account,
+                &mut instructions_sysvar(&[instruction], 0),
+            ],
+        )
+        .unwrap();
+        let instruction = migrate_account_with_instructions_sysvar(
+            &program_id,
+            &account_key,
+            &account3_key,
+            &owner_key,
+            &owner_key,
+            &[],
+            &[],
         )
         .unwrap();
-
-        let mint = Mint::unpack_unchecked(&mint_account.data).unwrap();
-        assert_eq!(mint.supply, 42);
-        let account = Account::unpack_unchecked(&account_account.data).unwrap();
-        assert_eq!(account.amount, 42);
-
-        // mint to another account to test supply accumulation
         do_process_instruction(
-            mint_to(&program_id, &mint_key, &account2_key, &owner_key, &[], 42).unwrap(),
-            vec![&mut mint_account, &mut account2_account, &mut owner_account],
+            instruction.clone(),
+            vec![
+                &mut account_account,
+                &mut account3_account,
+                &mut owner_account.clone(),
+                &mut owner_account,
+                &mut instructions_sysvar(&[instruction], 0),
+            ],
         )
         .unwrap();
+        let account = Account::unpack_from_account_data(&account3_account.data).unwrap();
+        assert_eq!(account.amount, 960);
+    }
 
-        let mint = Mint::unpack_unchecked(&mint_account.data).unwrap();
-        assert_eq!(mint.supply, 84);
-        let account = Account::unpack_unchecked(&account2_account.data).unwrap();
-        assert_eq!(account.amount, 42);
+    #[test]
+    fn test_token_metadata() {
+        let program_id = Pubkey::new_unique();
+        let mint_len = ExtensionType::get_account_len(&[ExtensionType::TokenMetadata]);
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account = SolanaAccount::new(
+            Rent::default().minimum_balance(mint_len),
+            mint_len,
+            &program_id,
+        );
+        let owner_key = Pubkey::new_unique();
+        let authority_key = Pubkey::new_unique();
+        let mut authority_account = SolanaAccount::default();
+        let authority2_key = Pubkey::new_unique();
+        let mut authority2_account = SolanaAccount::default();
+        let mut rent_sysvar = rent_sysvar();
 
-        // missing signer
-        let mut instruction =
-            mint_to(&program_id, &mint_key, &account2_key, &owner_key, &[], 42).unwrap();
-        instruction.accounts[2].is_signer = false;
+        // fields too long
         assert_eq!(
-            Err(ProgramError::MissingRequiredSignature),
-            do_process_instruction(
-                instruction,
-                vec![&mut mint_account, &mut account2_account, &mut owner_account],
+            Err(ProgramError::InvalidArgument),
+            initialize_token_metadata(
+                &program_id,
+                &mint_key,
+                Some(&authority_key),
+                "Token".to_string(),
+                "SYMBOLTOOLONG".to_string(),
+                "".to_string(),
             )
         );
-
-        // mismatch account
         assert_eq!(
-            Err(TokenError::MintMismatch.into()),
-            do_process_instruction(
-                mint_to(&program_id, &mint_key, &mismatch_key, &owner_key, &[], 42).unwrap(),
-                vec![&mut mint_account, &mut mismatch_account, &mut owner_account],
+            Err(ProgramError::InvalidArgument),
+            update_token_metadata(
+                &program_id,
+                &mint_key,
+                &authority_key,
+                &[],
+                "Token".to_string(),
+                "TKN".to_string(),
+                "x".repeat(300),
             )
         );
-
-        // missing owner
+        let instruction = Instruction {
+            program_id,
+            accounts: vec![AccountMeta::new(mint_key, false)],
+            data: TokenInstruction::InitializeTokenMetadata {
+                update_authority: COption::Some(authority_key),
+                name: "Token".to_string(),
+                symbol: "SYMBOLTOOLONG".to_string(),
+                uri: "".to_string(),
+            }
+            .pack(),
+        };
         assert_eq!(
-            Err(TokenError::OwnerMismatch.into()),
-            do_process_instruction(
-                mint_to(&program_id, &mint_key, &account2_key, &owner2_key, &[], 42).unwrap(),
-                vec![
-                    &mut mint_account,
-                    &mut account2_account,
-                    &mut owner2_account,
-                ],
+            Err(TokenError::MetadataFieldTooLong.into()),
+            do_process_instruction(instruction, vec![&mut mint_account])
+        );
+
+        // create mint with metadata
+        do_process_instruction(
+            initialize_token_metadata(
+                &program_id,
+                &mint_key,
+                Some(&authority_key),
+                "Token".to_string(),
+                "TKN".to_string(),
+                "https://example.com/token.json".to_string(),
             )
+            .unwrap(),
+            vec![&mut mint_account],
+        )
+        .unwrap();
+        do_process_instruction(
+            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+            vec![&mut mint_account, &mut rent_sysvar],
+        )
+        .unwrap();
+        assert_eq!(
+            get_extension::<TokenMetadata>(&mint_account.data).unwrap(),
+            Some(TokenMetadata {
+                update_authority: COption::Some(authority_key),
+                name: "Token".to_string(),
+                symbol: "TKN".to_string(),
+                uri: "https://example.com/token.json".to_string(),
+            })
         );
 
-        // uninitialized destination account
+        // missing update authority
         assert_eq!(
-            Err(ProgramError::UninitializedAccount),
+            Err(TokenError::OwnerMismatch.into()),
             do_process_instruction(
-                mint_to(
+                update_token_metadata(
                     &program_id,
                     &mint_key,
-                    &uninitialized_key,
-                    &owner_key,
+                    &authority2_key,
                     &[],
-                    42
+                    "Renamed".to_string(),
+                    "RNM".to_string(),
+                    "".to_string(),
                 )
                 .unwrap(),
-                vec![
-                    &mut mint_account,
-                    &mut uninitialized_account,
-                    &mut owner_account,
-                ],
+                vec![&mut mint_account, &mut authority2_account],
             )
         );
 
-        // unset mint_authority and test minting fails
+        // update metadata
+        do_process_instruction(
+            update_token_metadata(
+                &program_id,
+                &mint_key,
+                &authority_key,
+                &[],
+                "Renamed".to_string(),
+                "RNM".to_string(),
+                "".to_string(),
+            )
+            .unwrap(),
+            vec![&mut mint_account, &mut authority_account],
+        )
+        .unwrap();
+
+        // change the update authority
+        do_process_instruction(
+            set_authority(
+                &program_id,
+                &mint_key,
+                Some(&authority2_key),
+                AuthorityType::MetadataUpdate,
+                &authority_key,
+                &[],
+            )
+            .unwrap(),
+            vec![&mut mint_account, &mut authority_account],
+        )
+        .unwrap();
+        assert_eq!(
+            get_extension::<TokenMetadata>(&mint_account.data).unwrap(),
+            Some(TokenMetadata {
+                update_authority: COption::Some(authority2_key),
+                name: "Renamed".to_string(),
+                symbol: "RNM".to_string(),
+                uri: "".to_string(),
+            })
+        );
+
+        // remove the update authority
         do_process_instruction(
             set_authority(
                 &program_id,
                 &mint_key,
                 None,
-                AuthorityType::MintTokens,
-                &owner_key,
+                AuthorityType::MetadataUpdate,
+                &authority2_key,
                 &[],
             )
             .unwrap(),
-            vec![&mut mint_account, &mut owner_account],
+            vec![&mut mint_account, &mut authority2_account],
         )
         .unwrap();
         assert_eq!(
-            Err(TokenError::FixedSupply.into()),
+            Err(TokenError::AuthorityTypeNotSupported.into()),
             do_process_instruction(
-                mint_to(&program_id, &mint_key, &account2_key, &owner_key, &[], 42).unwrap(),
-                vec![&mut mint_account, &mut account2_account, &mut owner_account],
+                update_token_metadata(
+                    &program_id,
+                    &mint_key,
+                    &authority2_key,
+                    &[],
+                    "Token".to_string(),
+                    "TKN".to_string(),
+                    "".to_string(),
+                )
+                .unwrap(),
+                vec![&mut mint_account, &mut authority2_account],
             )
         );
     }
-
     #[test]
     fn test_burn_dups() {
         let program_id = Pubkey::new_unique();
@@ -4305,188 +9788,664 @@ mod tests {
         )
         .unwrap();
 
-        // mint to
+        // mint to
+        let account_info_iter = &mut signer_accounts.iter_mut();
+        do_process_instruction(
+            mint_to(
+                &program_id,
+                &mint_key,
+                &account2_key,
+                &multisig_key,
+                &[&signer_keys[0]],
+                42,
+            )
+            .unwrap(),
+            vec![
+                &mut mint_account,
+                &mut account2_account,
+                &mut multisig_account,
+                &mut account_info_iter.next().unwrap(),
+            ],
+        )
+        .unwrap();
+
+        // burn
+        let account_info_iter = &mut signer_accounts.iter_mut();
+        do_process_instruction(
+            burn(
+                &program_id,
+                &account_key,
+                &mint_key,
+                &multisig_key,
+                &[&signer_keys[0]],
+                42,
+            )
+            .unwrap(),
+            vec![
+                &mut account,
+                &mut mint_account,
+                &mut multisig_account,
+                &mut account_info_iter.next().unwrap(),
+            ],
+        )
+        .unwrap();
+
+        // burn via delegate
+        let account_info_iter = &mut signer_accounts.iter_mut();
+        do_process_instruction(
+            burn(
+                &program_id,
+                &account_key,
+                &mint_key,
+                &multisig_delegate_key,
+                &signer_key_refs,
+                42,
+            )
+            .unwrap(),
+            vec![
+                &mut account,
+                &mut mint_account,
+                &mut multisig_delegate_account,
+                &mut account_info_iter.next().unwrap(),
+                &mut account_info_iter.next().unwrap(),
+                &mut account_info_iter.next().unwrap(),
+                &mut account_info_iter.next().unwrap(),
+                &mut account_info_iter.next().unwrap(),
+                &mut account_info_iter.next().unwrap(),
+                &mut account_info_iter.next().unwrap(),
+                &mut account_info_iter.next().unwrap(),
+                &mut account_info_iter.next().unwrap(),
+                &mut account_info_iter.next().unwrap(),
+                &mut account_info_iter.next().unwrap(),
+            ],
+        )
+        .unwrap();
+
+        // freeze account
+        let account3_key = Pubkey::new_unique();
+        let mut account3_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let mint2_key = Pubkey::new_unique();
+        let mut mint2_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        do_process_instruction(
+            initialize_mint(
+                &program_id,
+                &mint2_key,
+                &multisig_key,
+                Some(&multisig_key),
+                2,
+            )
+            .unwrap(),
+            vec![&mut mint2_account, &mut rent_sysvar],
+        )
+        .unwrap();
+        do_process_instruction(
+            initialize_account(&program_id, &account3_key, &mint2_key, &owner_key).unwrap(),
+            vec![
+                &mut account3_account,
+                &mut mint2_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+        let account_info_iter = &mut signer_accounts.iter_mut();
+        do_process_instruction(
+            mint_to(
+                &program_id,
+                &mint2_key,
+                &account3_key,
+                &multisig_key,
+                &[&signer_keys[0]],
+                1000,
+            )
+            .unwrap(),
+            vec![
+                &mut mint2_account,
+                &mut account3_account,
+                &mut multisig_account,
+                &mut account_info_iter.next().unwrap(),
+            ],
+        )
+        .unwrap();
+        let account_info_iter = &mut signer_accounts.iter_mut();
+        do_process_instruction(
+            freeze_account(
+                &program_id,
+                &account3_key,
+                &mint2_key,
+                &multisig_key,
+                &[&signer_keys[0]],
+            )
+            .unwrap(),
+            vec![
+                &mut account3_account,
+                &mut mint2_account,
+                &mut multisig_account,
+                &mut account_info_iter.next().unwrap(),
+            ],
+        )
+        .unwrap();
+
+        // do SetAuthority on mint
         let account_info_iter = &mut signer_accounts.iter_mut();
         do_process_instruction(
-            mint_to(
+            set_authority(
                 &program_id,
                 &mint_key,
-                &account2_key,
+                Some(&owner_key),
+                AuthorityType::MintTokens,
                 &multisig_key,
                 &[&signer_keys[0]],
-                42,
             )
             .unwrap(),
             vec![
                 &mut mint_account,
-                &mut account2_account,
                 &mut multisig_account,
                 &mut account_info_iter.next().unwrap(),
             ],
         )
         .unwrap();
 
-        // burn
+        // do SetAuthority on account
         let account_info_iter = &mut signer_accounts.iter_mut();
         do_process_instruction(
-            burn(
+            set_authority(
                 &program_id,
                 &account_key,
-                &mint_key,
+                Some(&owner_key),
+                AuthorityType::AccountOwner,
                 &multisig_key,
                 &[&signer_keys[0]],
-                42,
             )
             .unwrap(),
             vec![
                 &mut account,
-                &mut mint_account,
                 &mut multisig_account,
                 &mut account_info_iter.next().unwrap(),
             ],
         )
         .unwrap();
+    }
 
-        // burn via delegate
-        let account_info_iter = &mut signer_accounts.iter_mut();
+    #[test]
+    fn test_multisig_proposal() {
+        let program_id = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let account_key = Pubkey::new_unique();
+        let mut account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let owner_key = Pubkey::new_unique();
+        let mut owner_account = SolanaAccount::default();
+        let multisig_key = Pubkey::new_unique();
+        let mut multisig_account = SolanaAccount::new(
+            multisig_minimum_balance(),
+            Multisig::get_packed_len(),
+            &program_id,
+        );
+        let proposal_key = Pubkey::new_unique();
+        let mut proposal_account = SolanaAccount::new(
+            proposal_minimum_balance(),
+            MultisigProposal::get_packed_len(),
+            &program_id,
+        );
+        let signer_keys = vec![
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let signer_key_refs: Vec<&Pubkey> = signer_keys.iter().collect();
+        let mut signer0_account = SolanaAccount::default();
+        let mut signer1_account = SolanaAccount::default();
+        let mut signer2_account = SolanaAccount::default();
+        let outsider_key = Pubkey::new_unique();
+        let mut outsider_account = SolanaAccount::default();
+        let mut rent_sysvar = rent_sysvar();
+
+        // 2 of 3 multisig
         do_process_instruction(
-            burn(
-                &program_id,
-                &account_key,
-                &mint_key,
-                &multisig_delegate_key,
-                &signer_key_refs,
-                42,
-            )
-            .unwrap(),
+            initialize_multisig(&program_id, &multisig_key, &signer_key_refs, 2).unwrap(),
+            vec![
+                &mut multisig_account,
+                &mut rent_sysvar,
+                &mut signer0_account,
+                &mut signer1_account,
+                &mut signer2_account,
+            ],
+        )
+        .unwrap();
+
+        // create new mint with multisig mint authority
+        do_process_instruction(
+            initialize_mint(&program_id, &mint_key, &multisig_key, None, 2).unwrap(),
+            vec![&mut mint_account, &mut rent_sysvar],
+        )
+        .unwrap();
+
+        // create account
+        do_process_instruction(
+            initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
             vec![
                 &mut account,
                 &mut mint_account,
-                &mut multisig_delegate_account,
-                &mut account_info_iter.next().unwrap(),
-                &mut account_info_iter.next().unwrap(),
-                &mut account_info_iter.next().unwrap(),
-                &mut account_info_iter.next().unwrap(),
-                &mut account_info_iter.next().unwrap(),
-                &mut account_info_iter.next().unwrap(),
-                &mut account_info_iter.next().unwrap(),
-                &mut account_info_iter.next().unwrap(),
-                &mut account_info_iter.next().unwrap(),
-                &mut account_info_iter.next().unwrap(),
-                &mut account_info_iter.next().unwrap(),
+                &mut owner_account,
+                &mut rent_sysvar,
             ],
         )
         .unwrap();
 
-        // freeze account
-        let account3_key = Pubkey::new_unique();
-        let mut account3_account = SolanaAccount::new(
-            account_minimum_balance(),
-            Account::get_packed_len(),
+        let proposed =
+            mint_to(&program_id, &mint_key, &account_key, &multisig_key, &[], 42).unwrap();
+
+        // proposer is not a multisig signer
+        let mut multisig_authority_account = multisig_account.clone();
+        assert_eq!(
+            Err(TokenError::OwnerMismatch.into()),
+            do_process_instruction(
+                create_multisig_proposal(
+                    &program_id,
+                    &proposal_key,
+                    &multisig_key,
+                    &outsider_key,
+                    &proposed,
+                )
+                .unwrap(),
+                vec![
+                    &mut proposal_account,
+                    &mut multisig_account,
+                    &mut outsider_account,
+                    &mut mint_account,
+                    &mut account,
+                    &mut multisig_authority_account,
+                ],
+            )
+        );
+
+        // proposals cannot be proposed
+        let nested =
+            approve_multisig_proposal(&program_id, &proposal_key, &multisig_key, &multisig_key)
+                .unwrap();
+        let mut instruction = create_multisig_proposal(
             &program_id,
+            &proposal_key,
+            &multisig_key,
+            &signer_keys[0],
+            &proposed,
+        )
+        .unwrap();
+        instruction.data = TokenInstruction::CreateMultisigProposal {
+            instruction_data: nested.data,
+        }
+        .pack();
+        assert_eq!(
+            Err(TokenError::InvalidInstruction.into()),
+            do_process_instruction(
+                instruction,
+                vec![
+                    &mut proposal_account,
+                    &mut multisig_account,
+                    &mut signer0_account,
+                    &mut mint_account,
+                    &mut account,
+                    &mut multisig_authority_account,
+                ],
+            )
         );
-        let mint2_key = Pubkey::new_unique();
-        let mut mint2_account =
-            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+
+        // create proposal
         do_process_instruction(
-            initialize_mint(
+            create_multisig_proposal(
                 &program_id,
-                &mint2_key,
+                &proposal_key,
                 &multisig_key,
-                Some(&multisig_key),
-                2,
+                &signer_keys[0],
+                &proposed,
             )
             .unwrap(),
-            vec![&mut mint2_account, &mut rent_sysvar],
+            vec![
+                &mut proposal_account,
+                &mut multisig_account,
+                &mut signer0_account,
+                &mut mint_account,
+                &mut account,
+                &mut multisig_authority_account,
+            ],
         )
         .unwrap();
+        let proposal = MultisigProposal::unpack(&proposal_account.data).unwrap();
+        assert!(!proposal.is_executed);
+        assert_eq!(proposal.multisig, multisig_key);
+        assert_eq!(proposal.num_approvals(3), 1);
+        assert_eq!(proposal.num_accounts, 3);
+        assert_eq!(
+            &proposal.accounts[..3],
+            &[mint_key, account_key, multisig_key]
+        );
+        assert_eq!(
+            &proposal.data[..proposal.data_len as usize],
+            &proposed.data[..]
+        );
+
+        // proposal already created
+        assert_eq!(
+            Err(TokenError::AlreadyInUse.into()),
+            do_process_instruction(
+                create_multisig_proposal(
+                    &program_id,
+                    &proposal_key,
+                    &multisig_key,
+                    &signer_keys[1],
+                    &proposed,
+                )
+                .unwrap(),
+                vec![
+                    &mut proposal_account,
+                    &mut multisig_account,
+                    &mut signer1_account,
+                    &mut mint_account,
+                    &mut account,
+                    &mut multisig_authority_account,
+                ],
+            )
+        );
+
+        // not enough approvals
+        assert_eq!(
+            Err(ProgramError::MissingRequiredSignature),
+            do_process_instruction(
+                execute_multisig_proposal(
+                    &program_id,
+                    &proposal_key,
+                    &multisig_key,
+                    &proposed,
+                    &[&signer_keys[0]],
+                )
+                .unwrap(),
+                vec![
+                    &mut proposal_account,
+                    &mut multisig_account,
+                    &mut mint_account,
+                    &mut account,
+                    &mut multisig_authority_account,
+                    &mut signer0_account,
+                ],
+            )
+        );
+
+        // outsider cannot approve
+        assert_eq!(
+            Err(TokenError::OwnerMismatch.into()),
+            do_process_instruction(
+                approve_multisig_proposal(&program_id, &proposal_key, &multisig_key, &outsider_key)
+                    .unwrap(),
+                vec![
+                    &mut proposal_account,
+                    &mut multisig_account,
+                    &mut outsider_account,
+                ],
+            )
+        );
+
+        // approve
         do_process_instruction(
-            initialize_account(&program_id, &account3_key, &mint2_key, &owner_key).unwrap(),
+            approve_multisig_proposal(&program_id, &proposal_key, &multisig_key, &signer_keys[2])
+                .unwrap(),
             vec![
-                &mut account3_account,
-                &mut mint2_account,
-                &mut owner_account,
-                &mut rent_sysvar,
+                &mut proposal_account,
+                &mut multisig_account,
+                &mut signer2_account,
             ],
         )
         .unwrap();
-        let account_info_iter = &mut signer_accounts.iter_mut();
+        let proposal = MultisigProposal::unpack(&proposal_account.data).unwrap();
+        assert_eq!(proposal.num_approvals(3), 2);
+
+        // approver that did not approve
+        assert_eq!(
+            Err(ProgramError::MissingRequiredSignature),
+            do_process_instruction(
+                execute_multisig_proposal(
+                    &program_id,
+                    &proposal_key,
+                    &multisig_key,
+                    &proposed,
+                    &[&signer_keys[0], &signer_keys[1]],
+                )
+                .unwrap(),
+                vec![
+                    &mut proposal_account,
+                    &mut multisig_account,
+                    &mut mint_account,
+                    &mut account,
+                    &mut multisig_authority_account,
+                    &mut signer0_account,
+                    &mut signer1_account,
+                ],
+            )
+        );
+
+        // wrong inner account
+        let mut instruction = execute_multisig_proposal(
+            &program_id,
+            &proposal_key,
+            &multisig_key,
+            &proposed,
+            &[&signer_keys[0], &signer_keys[2]],
+        )
+        .unwrap();
+        instruction.accounts[3].pubkey = owner_key;
+        assert_eq!(
+            Err(ProgramError::InvalidArgument),
+            do_process_instruction(
+                instruction,
+                vec![
+                    &mut proposal_account,
+                    &mut multisig_account,
+                    &mut mint_account,
+                    &mut owner_account,
+                    &mut multisig_authority_account,
+                    &mut signer0_account,
+                    &mut signer2_account,
+                ],
+            )
+        );
+
+        // execute
         do_process_instruction(
-            mint_to(
+            execute_multisig_proposal(
                 &program_id,
-                &mint2_key,
-                &account3_key,
+                &proposal_key,
                 &multisig_key,
-                &[&signer_keys[0]],
-                1000,
+                &proposed,
+                &[&signer_keys[0], &signer_keys[2]],
             )
             .unwrap(),
             vec![
-                &mut mint2_account,
-                &mut account3_account,
+                &mut proposal_account,
+                &mut multisig_account,
+                &mut mint_account,
+                &mut account,
+                &mut multisig_authority_account,
+                &mut signer0_account,
+                &mut signer2_account,
+            ],
+        )
+        .unwrap();
+        let mint = Mint::unpack(&mint_account.data).unwrap();
+        assert_eq!(mint.supply, 42);
+        let account = Account::unpack(&account.data).unwrap();
+        assert_eq!(account.amount, 42);
+        let proposal = MultisigProposal::unpack(&proposal_account.data).unwrap();
+        assert!(proposal.is_executed);
+
+        // cannot execute twice
+        assert_eq!(
+            Err(TokenError::InvalidState.into()),
+            do_process_instruction(
+                execute_multisig_proposal(
+                    &program_id,
+                    &proposal_key,
+                    &multisig_key,
+                    &proposed,
+                    &[&signer_keys[0], &signer_keys[2]],
+                )
+                .unwrap(),
+                vec![
+                    &mut proposal_account,
+                    &mut multisig_account,
+                    &mut mint_account,
+                    &mut owner_account,
+                    &mut multisig_authority_account,
+                    &mut signer0_account,
+                    &mut signer2_account,
+                ],
+            )
+        );
+    }
+
+    #[test]
+    fn test_multisig_proposal_member_authority() {
+        let program_id = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let account_key = Pubkey::new_unique();
+        let mut account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let multisig_key = Pubkey::new_unique();
+        let mut multisig_account = SolanaAccount::new(
+            multisig_minimum_balance(),
+            Multisig::get_packed_len(),
+            &program_id,
+        );
+        let proposal_key = Pubkey::new_unique();
+        let mut proposal_account = SolanaAccount::new(
+            proposal_minimum_balance(),
+            MultisigProposal::get_packed_len(),
+            &program_id,
+        );
+        let signer_keys = vec![
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let signer_key_refs: Vec<&Pubkey> = signer_keys.iter().collect();
+        let mut signer0_account = SolanaAccount::default();
+        let mut signer1_account = SolanaAccount::default();
+        let mut signer2_account = SolanaAccount::default();
+        let mut authority_account = SolanaAccount::default();
+        let outsider_key = Pubkey::new_unique();
+        let mut rent_sysvar = rent_sysvar();
+
+        // 2 of 3 multisig
+        do_process_instruction(
+            initialize_multisig(&program_id, &multisig_key, &signer_key_refs, 2).unwrap(),
+            vec![
                 &mut multisig_account,
-                &mut account_info_iter.next().unwrap(),
+                &mut rent_sysvar,
+                &mut signer0_account,
+                &mut signer1_account,
+                &mut signer2_account,
             ],
         )
         .unwrap();
-        let account_info_iter = &mut signer_accounts.iter_mut();
+
+        // create new mint
         do_process_instruction(
-            freeze_account(
-                &program_id,
-                &account3_key,
-                &mint2_key,
-                &multisig_key,
-                &[&signer_keys[0]],
-            )
-            .unwrap(),
-            vec![
-                &mut account3_account,
-                &mut mint2_account,
-                &mut multisig_account,
-                &mut account_info_iter.next().unwrap(),
-            ],
+            initialize_mint(&program_id, &mint_key, &multisig_key, None, 2).unwrap(),
+            vec![&mut mint_account, &mut rent_sysvar],
         )
         .unwrap();
 
-        // do SetAuthority on mint
-        let account_info_iter = &mut signer_accounts.iter_mut();
+        // create account owned by one of the multisig signers
         do_process_instruction(
-            set_authority(
-                &program_id,
-                &mint_key,
-                Some(&owner_key),
-                AuthorityType::MintTokens,
-                &multisig_key,
-                &[&signer_keys[0]],
-            )
-            .unwrap(),
+            initialize_account(&program_id, &account_key, &mint_key, &signer_keys[0]).unwrap(),
             vec![
+                &mut account,
                 &mut mint_account,
-                &mut multisig_account,
-                &mut account_info_iter.next().unwrap(),
+                &mut authority_account,
+                &mut rent_sysvar,
             ],
         )
         .unwrap();
 
-        // do SetAuthority on account
-        let account_info_iter = &mut signer_accounts.iter_mut();
+        // propose to hand the signer's own account to an outsider
+        let proposed = set_authority(
+            &program_id,
+            &account_key,
+            Some(&outsider_key),
+            AuthorityType::AccountOwner,
+            &signer_keys[0],
+            &[],
+        )
+        .unwrap();
         do_process_instruction(
-            set_authority(
+            create_multisig_proposal(
                 &program_id,
-                &account_key,
-                Some(&owner_key),
-                AuthorityType::AccountOwner,
+                &proposal_key,
                 &multisig_key,
-                &[&signer_keys[0]],
+                &signer_keys[0],
+                &proposed,
             )
             .unwrap(),
             vec![
+                &mut proposal_account,
+                &mut multisig_account,
+                &mut signer0_account,
                 &mut account,
+                &mut authority_account,
+            ],
+        )
+        .unwrap();
+        do_process_instruction(
+            approve_multisig_proposal(&program_id, &proposal_key, &multisig_key, &signer_keys[2])
+                .unwrap(),
+            vec![
+                &mut proposal_account,
                 &mut multisig_account,
-                &mut account_info_iter.next().unwrap(),
+                &mut signer2_account,
             ],
         )
         .unwrap();
+
+        // approvals do not stand in for the signer's own signature
+        assert_eq!(
+            Err(TokenError::OwnerMismatch.into()),
+            do_process_instruction(
+                execute_multisig_proposal(
+                    &program_id,
+                    &proposal_key,
+                    &multisig_key,
+                    &proposed,
+                    &[&signer_keys[0], &signer_keys[2]],
+                )
+                .unwrap(),
+                vec![
+                    &mut proposal_account,
+                    &mut multisig_account,
+                    &mut account,
+                    &mut authority_account,
+                    &mut signer0_account,
+                    &mut signer2_account,
+                ],
+            )
+        );
+        let account = Account::unpack(&account.data).unwrap();
+        assert_eq!(account.owner, signer_keys[0]);
+        let proposal = MultisigProposal::unpack(&proposal_account.data).unwrap();
+        assert!(!proposal.is_executed);
     }
 
     #[test]
@@ -5728,4 +11687,247 @@ mod tests {
 
         assert_eq!(account_account, account2_account);
     }
+
+    #[test]
+    fn test_initialize_account3() {
+        let program_id = Pubkey::new_unique();
+        let account_key = Pubkey::new_unique();
+        let mut account_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let mut account2_account = SolanaAccount::new(
+            account_minimum_balance(),
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let mut account3_account = SolanaAccount::new(
+            account_minimum_balance() - 1,
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let owner_key = Pubkey::new_unique();
+        let mut owner_account = SolanaAccount::default();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let mut rent_sysvar = rent_sysvar();
+
+        // create mint
+        do_process_instruction(
+            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+            vec![&mut mint_account, &mut rent_sysvar],
+        )
+        .unwrap();
+
+        do_process_instruction(
+            initialize_account(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
+            vec![
+                &mut account_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+
+        do_process_instruction(
+            initialize_account3(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
+            vec![&mut account2_account, &mut mint_account],
+        )
+        .unwrap();
+
+        assert_eq!(account_account, account2_account);
+
+        // account is not rent exempt
+        assert_eq!(
+            Err(TokenError::NotRentExempt.into()),
+            do_process_instruction(
+                initialize_account3(&program_id, &account_key, &mint_key, &owner_key).unwrap(),
+                vec![&mut account3_account, &mut mint_account],
+            )
+        );
+    }
+
+    #[test]
+    fn test_initialize_mint2() {
+        let program_id = Pubkey::new_unique();
+        let owner_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account = SolanaAccount::new(42, Mint::get_packed_len(), &program_id);
+        let mint2_key = Pubkey::new_unique();
+        let mut mint2_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let mut mint3_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let mut rent_sysvar = rent_sysvar();
+
+        // mint is not rent exempt
+        assert_eq!(
+            Err(TokenError::NotRentExempt.into()),
+            do_process_instruction(
+                initialize_mint2(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+                vec![&mut mint_account]
+            )
+        );
+
+        mint_account.lamports = mint_minimum_balance();
+
+        // create new mint
+        do_process_instruction(
+            initialize_mint2(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+            vec![&mut mint_account],
+        )
+        .unwrap();
+
+        // create twice
+        assert_eq!(
+            Err(TokenError::AlreadyInUse.into()),
+            do_process_instruction(
+                initialize_mint2(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+                vec![&mut mint_account]
+            )
+        );
+
+        // create another mint that can freeze
+        do_process_instruction(
+            initialize_mint2(&program_id, &mint2_key, &owner_key, Some(&owner_key), 2).unwrap(),
+            vec![&mut mint2_account],
+        )
+        .unwrap();
+        let mint = Mint::unpack_unchecked(&mint2_account.data).unwrap();
+        assert_eq!(mint.freeze_authority, COption::Some(owner_key));
+
+        // same result as InitializeMint with the Rent sysvar
+        do_process_instruction(
+            initialize_mint(&program_id, &mint2_key, &owner_key, Some(&owner_key), 2).unwrap(),
+            vec![&mut mint3_account, &mut rent_sysvar],
+        )
+        .unwrap();
+        assert_eq!(mint2_account, mint3_account);
+    }
+
+    #[test]
+    fn test_sync_native() {
+        let program_id = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let mut mint_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        let native_account_key = Pubkey::new_unique();
+        let lamports = 40;
+        let mut native_account = SolanaAccount::new(
+            account_minimum_balance() + lamports,
+            Account::get_packed_len(),
+            &program_id,
+        );
+        let non_native_account_key = Pubkey::new_unique();
+        let mut non_native_account = SolanaAccount::new(
+            account_minimum_balance() + 50,
+            Account::get_packed_len(),
+            &program_id,
+        );
+
+        let owner_key = Pubkey::new_unique();
+        let mut owner_account = SolanaAccount::default();
+        let mut rent_sysvar = rent_sysvar();
+
+        // initialize non-native mint
+        do_process_instruction(
+            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+            vec![&mut mint_account, &mut rent_sysvar],
+        )
+        .unwrap();
+
+        // initialize non-native account
+        do_process_instruction(
+            initialize_account(&program_id, &non_native_account_key, &mint_key, &owner_key)
+                .unwrap(),
+            vec![
+                &mut non_native_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+
+        let account = Account::unpack_unchecked(&non_native_account.data).unwrap();
+        assert!(!account.is_native());
+        assert_eq!(account.amount, 0);
+
+        // fail sync non-native
+        assert_eq!(
+            Err(TokenError::NonNativeNotSupported.into()),
+            do_process_instruction(
+                sync_native(&program_id, &non_native_account_key).unwrap(),
+                vec![&mut non_native_account],
+            )
+        );
+
+        // fail sync uninitialized
+        assert_eq!(
+            Err(ProgramError::UninitializedAccount),
+            do_process_instruction(
+                sync_native(&program_id, &native_account_key).unwrap(),
+                vec![&mut native_account],
+            )
+        );
+
+        // wrap native account
+        do_process_instruction(
+            initialize_account(
+                &program_id,
+                &native_account_key,
+                &crate::native_mint::id(),
+                &owner_key,
+            )
+            .unwrap(),
+            vec![
+                &mut native_account,
+                &mut mint_account,
+                &mut owner_account,
+                &mut rent_sysvar,
+            ],
+        )
+        .unwrap();
+
+        let account = Account::unpack_unchecked(&native_account.data).unwrap();
+        assert!(account.is_native());
+        assert_eq!(account.amount, lamports);
+
+        // sync, no change
+        do_process_instruction(
+            sync_native(&program_id, &native_account_key).unwrap(),
+            vec![&mut native_account],
+        )
+        .unwrap();
+        let account = Account::unpack_unchecked(&native_account.data).unwrap();
+        assert_eq!(account.amount, lamports);
+
+        // transfer sol
+        let new_lamports = lamports + 50;
+        native_account.lamports = account_minimum_balance() + new_lamports;
+
+        // success sync
+        do_process_instruction(
+            sync_native(&program_id, &native_account_key).unwrap(),
+            vec![&mut native_account],
+        )
+        .unwrap();
+        let account = Account::unpack_unchecked(&native_account.data).unwrap();
+        assert_eq!(account.amount, new_lamports);
+
+        // reduce sol
+        native_account.lamports -= 1;
+
+        // fail sync
+        assert_eq!(
+            Err(TokenError::InvalidState.into()),
+            do_process_instruction(
+                sync_native(&program_id, &native_account_key).unwrap(),
+                vec![&mut native_account],
+            )
+        );
+    }
 }
@@ -1,9 +1,11 @@
 //! State transition types
 
-use crate::instruction::MAX_SIGNERS;
+use crate::instruction::{MAX_PROPOSAL_ACCOUNTS, MAX_PROPOSAL_DATA_LEN, MAX_SIGNERS};
 use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
 use num_enum::TryFromPrimitive;
 use solana_program::{
+    clock::UnixTimestamp,
+    entrypoint::ProgramResult,
     program_error::ProgramError,
     program_option::COption,
     program_pack::{IsInitialized, Pack, Sealed},
@@ -27,6 +29,37 @@ pub struct Mint {
     /// Optional authority to freeze token accounts.
     pub freeze_authority: COption<Pubkey>,
 }
+impl Mint {
+    /// Checks if the data is that of a mint, holding either the base layout or
+    /// the base layout followed by extensions
+    pub fn is_mint_data(data: &[u8]) -> bool {
+        data.len() == Self::LEN || has_account_type(data, AccountType::Mint)
+    }
+    /// Unpacks the base mint from the data of a mint, checking that it is
+    /// initialized
+    pub fn unpack_from_account_data(data: &[u8]) -> Result<Self, ProgramError> {
+        Self::unpack(Self::base_data(data)?)
+    }
+    /// Unpacks the base mint from the data of a mint, without checking that it
+    /// is initialized
+    pub fn unpack_unchecked_from_account_data(data: &[u8]) -> Result<Self, ProgramError> {
+        Self::unpack_unchecked(Self::base_data(data)?)
+    }
+    /// Packs the base mint into the data of a mint
+    pub fn pack_into_account_data(src: Self, data: &mut [u8]) -> ProgramResult {
+        if !Self::is_mint_data(data) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Self::pack(src, &mut data[..Self::LEN])
+    }
+    fn base_data(data: &[u8]) -> Result<&[u8], ProgramError> {
+        if Self::is_mint_data(data) {
+            Ok(&data[..Self::LEN])
+        } else {
+            Err(ProgramError::InvalidAccountData)
+        }
+    }
+}
 impl Sealed for Mint {}
 impl IsInitialized for Mint {
     fn is_initialized(&self) -> bool {
@@ -113,6 +146,35 @@ impl Account {
     pub fn is_native(&self) -> bool {
         self.is_native.is_some()
     }
+    /// Checks if the data is that of a token account, holding either the base
+    /// layout or the base layout followed by extensions
+    pub fn is_account_data(data: &[u8]) -> bool {
+        data.len() == Self::LEN || has_account_type(data, AccountType::Account)
+    }
+    /// Unpacks the base account from the data of a token account, checking
+    /// that it is initialized
+    pub fn unpack_from_account_data(data: &[u8]) -> Result<Self, ProgramError> {
+        Self::unpack(Self::base_data(data)?)
+    }
+    /// Unpacks the base account from the data of a token account, without
+    /// checking that it is initialized
+    pub fn unpack_unchecked_from_account_data(data: &[u8]) -> Result<Self, ProgramError> {
+        Self::unpack_unchecked(Self::base_data(data)?)
+    }
+    /// Packs the base account into the data of a token account
+    pub fn pack_into_account_data(src: Self, data: &mut [u8]) -> ProgramResult {
+        if !Self::is_account_data(data) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Self::pack(src, &mut data[..Self::LEN])
+    }
+    fn base_data(data: &[u8]) -> Result<&[u8], ProgramError> {
+        if Self::is_account_data(data) {
+            Ok(&data[..Self::LEN])
+        } else {
+            Err(ProgramError::InvalidAccountData)
+        }
+    }
 }
 impl Sealed for Account {}
 impl IsInitialized for Account {
@@ -245,7 +307,900 @@ impl Pack for Multisig {
     }
 }
 
+/// Multisignature proposal data.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MultisigProposal {
+    /// Is `true` if this structure has been initialized
+    pub is_initialized: bool,
+    /// Is `true` once the proposed instruction has been executed
+    pub is_executed: bool,
+    /// The multisignature account whose signers vote on the proposal
+    pub multisig: Pubkey,
+    /// Approvals of the multisignature's signers, by signer position
+    pub approved: [bool; MAX_SIGNERS],
+    /// Number of valid account keys
+    pub num_accounts: u8,
+    /// Account keys of the proposed instruction
+    pub accounts: [Pubkey; MAX_PROPOSAL_ACCOUNTS],
+    /// Number of valid instruction data bytes
+    pub data_len: u8,
+    /// Instruction data of the proposed instruction
+    pub data: [u8; MAX_PROPOSAL_DATA_LEN],
+}
+impl MultisigProposal {
+    /// Number of approvals recorded for the first `n` signer positions
+    pub fn num_approvals(&self, n: u8) -> u8 {
+        self.approved[0..n as usize]
+            .iter()
+            .filter(|approved| **approved)
+            .count() as u8
+    }
+}
+impl Sealed for MultisigProposal {}
+impl IsInitialized for MultisigProposal {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl Pack for MultisigProposal {
+    const LEN: usize = 367;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 367];
+        #[allow(clippy::ptr_offset_with_cast)]
+        let (
+            is_initialized,
+            is_executed,
+            multisig,
+            approved,
+            num_accounts,
+            accounts_flat,
+            data_len,
+            data,
+        ) = array_refs![
+            src,
+            1,
+            1,
+            32,
+            MAX_SIGNERS,
+            1,
+            32 * MAX_PROPOSAL_ACCOUNTS,
+            1,
+            MAX_PROPOSAL_DATA_LEN
+        ];
+        if num_accounts[0] as usize > MAX_PROPOSAL_ACCOUNTS
+            || data_len[0] as usize > MAX_PROPOSAL_DATA_LEN
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let mut result = MultisigProposal {
+            is_initialized: unpack_bool(is_initialized)?,
+            is_executed: unpack_bool(is_executed)?,
+            multisig: Pubkey::new_from_array(*multisig),
+            approved: [false; MAX_SIGNERS],
+            num_accounts: num_accounts[0],
+            accounts: [Pubkey::new_from_array([0u8; 32]); MAX_PROPOSAL_ACCOUNTS],
+            data_len: data_len[0],
+            data: *data,
+        };
+        for (src, dst) in approved.iter().zip(result.approved.iter_mut()) {
+            *dst = unpack_bool(&[*src])?;
+        }
+        for (src, dst) in accounts_flat.chunks(32).zip(result.accounts.iter_mut()) {
+            *dst = Pubkey::new(src);
+        }
+        Ok(result)
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 367];
+        #[allow(clippy::ptr_offset_with_cast)]
+        let (
+            is_initialized,
+            is_executed,
+            multisig,
+            approved,
+            num_accounts,
+            accounts_flat,
+            data_len,
+            data,
+        ) = mut_array_refs![
+            dst,
+            1,
+            1,
+            32,
+            MAX_SIGNERS,
+            1,
+            32 * MAX_PROPOSAL_ACCOUNTS,
+            1,
+            MAX_PROPOSAL_DATA_LEN
+        ];
+        *is_initialized = [self.is_initialized as u8];
+        *is_executed = [self.is_executed as u8];
+        multisig.copy_from_slice(self.multisig.as_ref());
+        for (src, dst) in self.approved.iter().zip(approved.iter_mut()) {
+            *dst = *src as u8;
+        }
+        *num_accounts = [self.num_accounts];
+        for (i, src) in self.accounts.iter().enumerate() {
+            let dst_array = array_mut_ref![accounts_flat, 32 * i, 32];
+            dst_array.copy_from_slice(src.as_ref());
+        }
+        *data_len = [self.data_len];
+        *data = self.data;
+    }
+}
+
+/// Type of a mint or token account holding extensions, stored just after the
+/// base layout.  Mints are padded to the length of the base token account
+/// layout so that the account type is found at the same offset for both.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
+pub enum AccountType {
+    /// Not yet initialized
+    Uninitialized,
+    /// Mint
+    Mint,
+    /// Token account
+    Account,
+}
+
+/// Type of an extension held by a mint or token account.
+///
+/// Extensions are stored after the account type as entries of a 2-byte
+/// little-endian type, a 2-byte little-endian length and the packed
+/// extension.  The unused space following the last entry is zeroed, which
+/// reads as an `Uninitialized` type.
+#[repr(u16)]
+#[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
+pub enum ExtensionType {
+    /// Marks the unused space following the last extension
+    Uninitialized,
+    /// Maximum supply of a mint
+    MaxSupply,
+    /// Expiry of a token account's delegation
+    DelegationExpiry,
+    /// Permanent delegate of a mint
+    PermanentDelegate,
+    /// Whether new accounts of a mint start frozen
+    DefaultFrozen,
+    /// Marks a mint as non-transferable
+    NonTransferable,
+    /// Marks a token account as belonging to a non-transferable mint
+    NonTransferableAccount,
+    /// Transfer fee configuration of a mint
+    TransferFeeConfig,
+    /// Transfer fees withheld in a token account
+    TransferFeeAmount,
+    /// Whether a token account requires memos on incoming transfers
+    MemoTransfer,
+    /// Marks a token account whose owner cannot be changed
+    ImmutableOwner,
+    /// Authority that may close a mint
+    MintCloseAuthority,
+    /// Human-readable metadata of a mint
+    TokenMetadata,
+    /// Interest rate configuration of a mint
+    InterestBearingConfig,
+    /// Whether a token account blocks privileged actions invoked by other
+    /// programs
+    CpiGuard,
+}
+impl ExtensionType {
+    /// Gets the type of the accounts that may hold the extension
+    pub fn get_account_type(&self) -> AccountType {
+        match self {
+            ExtensionType::Uninitialized => AccountType::Uninitialized,
+            ExtensionType::MaxSupply
+            | ExtensionType::PermanentDelegate
+            | ExtensionType::DefaultFrozen
+            | ExtensionType::NonTransferable
+            | ExtensionType::TransferFeeConfig
+            | ExtensionType::MintCloseAuthority
+            | ExtensionType::TokenMetadata
+            | ExtensionType::InterestBearingConfig => AccountType::Mint,
+            ExtensionType::DelegationExpiry
+            | ExtensionType::NonTransferableAccount
+            | ExtensionType::TransferFeeAmount
+            | ExtensionType::MemoTransfer
+            | ExtensionType::ImmutableOwner
+            | ExtensionType::CpiGuard => AccountType::Account,
+        }
+    }
+    /// Gets the length of the packed extension
+    pub fn get_type_len(&self) -> usize {
+        match self {
+            ExtensionType::Uninitialized => 0,
+            ExtensionType::MaxSupply => MaxSupply::LEN,
+            ExtensionType::DelegationExpiry => DelegationExpiry::LEN,
+            ExtensionType::PermanentDelegate => PermanentDelegate::LEN,
+            ExtensionType::DefaultFrozen => DefaultFrozen::LEN,
+            ExtensionType::NonTransferable => NonTransferable::LEN,
+            ExtensionType::NonTransferableAccount => NonTransferableAccount::LEN,
+            ExtensionType::TransferFeeConfig => TransferFeeConfig::LEN,
+            ExtensionType::TransferFeeAmount => TransferFeeAmount::LEN,
+            ExtensionType::MemoTransfer => MemoTransfer::LEN,
+            ExtensionType::ImmutableOwner => ImmutableOwner::LEN,
+            ExtensionType::MintCloseAuthority => MintCloseAuthority::LEN,
+            ExtensionType::TokenMetadata => TokenMetadata::LEN,
+            ExtensionType::InterestBearingConfig => InterestBearingConfig::LEN,
+            ExtensionType::CpiGuard => CpiGuard::LEN,
+        }
+    }
+    /// Gets the length of the data of a mint or token account holding the
+    /// given extensions
+    pub fn get_account_len(extension_types: &[ExtensionType]) -> usize {
+        let len = extension_types
+            .iter()
+            .fold(EXTENSIONS_START_INDEX, |len, extension_type| {
+                len + TLV_HEADER_LEN + extension_type.get_type_len()
+            });
+        // Extended accounts must never be mistaken for multisignatures or
+        // multisignature proposals, neither of which hold an account type
+        if len == Multisig::LEN || len == MultisigProposal::LEN {
+            len + TLV_HEADER_LEN
+        } else {
+            len
+        }
+    }
+    /// Gets the extensions that new token accounts of a mint holding the
+    /// given extensions must have space for
+    pub fn get_required_init_account_extensions(
+        mint_extension_types: &[ExtensionType],
+    ) -> Vec<ExtensionType> {
+        mint_extension_types
+            .iter()
+            .filter_map(|extension_type| match extension_type {
+                ExtensionType::NonTransferable => Some(ExtensionType::NonTransferableAccount),
+                ExtensionType::TransferFeeConfig => Some(ExtensionType::TransferFeeAmount),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Extension held by a mint or token account
+pub trait Extension: Pack {
+    /// The type of the extension
+    const TYPE: ExtensionType;
+}
+
+/// Maximum supply of a mint.  No tokens may be minted that would bring the
+/// total supply above this amount.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MaxSupply {
+    /// The maximum supply
+    pub max_supply: u64,
+}
+impl Extension for MaxSupply {
+    const TYPE: ExtensionType = ExtensionType::MaxSupply;
+}
+impl Sealed for MaxSupply {}
+impl Pack for MaxSupply {
+    const LEN: usize = 8;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let max_supply = array_ref![src, 0, 8];
+        Ok(MaxSupply {
+            max_supply: u64::from_le_bytes(*max_supply),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let max_supply_dst = array_mut_ref![dst, 0, 8];
+        *max_supply_dst = self.max_supply.to_le_bytes();
+    }
+}
+
+/// Expiry of a token account's delegation.  The delegate may no longer
+/// transfer or burn tokens once the current slot is past the expiry slot.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DelegationExpiry {
+    /// The last slot in which the delegate may use its approval, if any
+    pub expiry_slot: COption<u64>,
+}
+impl Extension for DelegationExpiry {
+    const TYPE: ExtensionType = ExtensionType::DelegationExpiry;
+}
+impl Sealed for DelegationExpiry {}
+impl Pack for DelegationExpiry {
+    const LEN: usize = 12;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let expiry_slot = array_ref![src, 0, 12];
+        Ok(DelegationExpiry {
+            expiry_slot: unpack_coption_u64(expiry_slot)?,
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let expiry_slot_dst = array_mut_ref![dst, 0, 12];
+        pack_coption_u64(&self.expiry_slot, expiry_slot_dst);
+    }
+}
+
+/// Permanent delegate of a mint, which may transfer or burn tokens from any
+/// account of the mint.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PermanentDelegate {
+    /// The permanent delegate, or `None` once it has been removed
+    pub delegate: COption<Pubkey>,
+}
+impl Extension for PermanentDelegate {
+    const TYPE: ExtensionType = ExtensionType::PermanentDelegate;
+}
+impl Sealed for PermanentDelegate {}
+impl Pack for PermanentDelegate {
+    const LEN: usize = 36;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let delegate = array_ref![src, 0, 36];
+        Ok(PermanentDelegate {
+            delegate: unpack_coption_key(delegate)?,
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let delegate_dst = array_mut_ref![dst, 0, 36];
+        pack_coption_key(&self.delegate, delegate_dst);
+    }
+}
+
+/// Whether new accounts of a mint start frozen, until thawed by the mint's
+/// freeze authority.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DefaultFrozen {
+    /// Is `true` if new accounts start frozen
+    pub frozen: bool,
+}
+impl Extension for DefaultFrozen {
+    const TYPE: ExtensionType = ExtensionType::DefaultFrozen;
+}
+impl Sealed for DefaultFrozen {}
+impl Pack for DefaultFrozen {
+    const LEN: usize = 1;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let frozen = array_ref![src, 0, 1];
+        Ok(DefaultFrozen {
+            frozen: unpack_bool(frozen)?,
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let frozen_dst = array_mut_ref![dst, 0, 1];
+        *frozen_dst = [self.frozen as u8];
+    }
+}
+
+/// Marks a mint whose tokens cannot be transferred once minted.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct NonTransferable;
+impl Extension for NonTransferable {
+    const TYPE: ExtensionType = ExtensionType::NonTransferable;
+}
+impl Sealed for NonTransferable {}
+impl Pack for NonTransferable {
+    const LEN: usize = 0;
+    fn unpack_from_slice(_src: &[u8]) -> Result<Self, ProgramError> {
+        Ok(NonTransferable)
+    }
+    fn pack_into_slice(&self, _dst: &mut [u8]) {}
+}
+
+/// Marks a token account of a non-transferable mint.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct NonTransferableAccount;
+impl Extension for NonTransferableAccount {
+    const TYPE: ExtensionType = ExtensionType::NonTransferableAccount;
+}
+impl Sealed for NonTransferableAccount {}
+impl Pack for NonTransferableAccount {
+    const LEN: usize = 0;
+    fn unpack_from_slice(_src: &[u8]) -> Result<Self, ProgramError> {
+        Ok(NonTransferableAccount)
+    }
+    fn pack_into_slice(&self, _dst: &mut [u8]) {}
+}
+
+/// Maximum transfer fee, in basis points of the transferred amount
+pub const MAX_FEE_BASIS_POINTS: u16 = 10_000;
+
+/// Transfer fee configuration of a mint.  A fee is charged on every transfer
+/// and withheld in the destination account, from which it is harvested to
+/// the mint or withdrawn by the withdraw withheld authority.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TransferFeeConfig {
+    /// Optional authority that may withdraw withheld fees
+    pub withdraw_withheld_authority: COption<Pubkey>,
+    /// Fee charged on transfers, in basis points of the transferred amount
+    pub transfer_fee_basis_points: u16,
+    /// Maximum fee charged on a single transfer
+    pub maximum_fee: u64,
+    /// Fees harvested from the mint's accounts and not yet withdrawn
+    pub withheld_amount: u64,
+}
+impl TransferFeeConfig {
+    /// Calculates the fee charged on a transfer of the given amount, rounded
+    /// up and capped at the maximum fee
+    pub fn calculate_fee(&self, amount: u64) -> Option<u64> {
+        let numerator = (amount as u128).checked_mul(self.transfer_fee_basis_points as u128)?;
+        let fee = numerator
+            .checked_add(MAX_FEE_BASIS_POINTS as u128 - 1)?
+            .checked_div(MAX_FEE_BASIS_POINTS as u128)?;
+        Some(std::cmp::min(fee, self.maximum_fee as u128) as u64)
+    }
+}
+impl Extension for TransferFeeConfig {
+    const TYPE: ExtensionType = ExtensionType::TransferFeeConfig;
+}
+impl Sealed for TransferFeeConfig {}
+impl Pack for TransferFeeConfig {
+    const LEN: usize = 54;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 54];
+        let (withdraw_withheld_authority, transfer_fee_basis_points, maximum_fee, withheld_amount) =
+            array_refs![src, 36, 2, 8, 8];
+        Ok(TransferFeeConfig {
+            withdraw_withheld_authority: unpack_coption_key(withdraw_withheld_authority)?,
+            transfer_fee_basis_points: u16::from_le_bytes(*transfer_fee_basis_points),
+            maximum_fee: u64::from_le_bytes(*maximum_fee),
+            withheld_amount: u64::from_le_bytes(*withheld_amount),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 54];
+        let (
+            withdraw_withheld_authority_dst,
+            transfer_fee_basis_points_dst,
+            maximum_fee_dst,
+            withheld_amount_dst,
+        ) = mut_array_refs![dst, 36, 2, 8, 8];
+        pack_coption_key(
+            &self.withdraw_withheld_authority,
+            withdraw_withheld_authority_dst,
+        );
+        *transfer_fee_basis_points_dst = self.transfer_fee_basis_points.to_le_bytes();
+        *maximum_fee_dst = self.maximum_fee.to_le_bytes();
+        *withheld_amount_dst = self.withheld_amount.to_le_bytes();
+    }
+}
+
+/// Transfer fees withheld in a token account of a mint charging them.  The
+/// withheld tokens are not part of the account's balance.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TransferFeeAmount {
+    /// Fees withheld from transfers into the account
+    pub withheld_amount: u64,
+}
+impl Extension for TransferFeeAmount {
+    const TYPE: ExtensionType = ExtensionType::TransferFeeAmount;
+}
+impl Sealed for TransferFeeAmount {}
+impl Pack for TransferFeeAmount {
+    const LEN: usize = 8;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let withheld_amount = array_ref![src, 0, 8];
+        Ok(TransferFeeAmount {
+            withheld_amount: u64::from_le_bytes(*withheld_amount),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let withheld_amount_dst = array_mut_ref![dst, 0, 8];
+        *withheld_amount_dst = self.withheld_amount.to_le_bytes();
+    }
+}
+
+/// Whether a token account requires incoming transfers to be preceded by a
+/// memo instruction in the same transaction.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MemoTransfer {
+    /// Is `true` if incoming transfers require a memo
+    pub require_incoming_transfer_memos: bool,
+}
+impl Extension for MemoTransfer {
+    const TYPE: ExtensionType = ExtensionType::MemoTransfer;
+}
+impl Sealed for MemoTransfer {}
+impl Pack for MemoTransfer {
+    const LEN: usize = 1;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let require_incoming_transfer_memos = array_ref![src, 0, 1];
+        Ok(MemoTransfer {
+            require_incoming_transfer_memos: unpack_bool(require_incoming_transfer_memos)?,
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let require_incoming_transfer_memos_dst = array_mut_ref![dst, 0, 1];
+        *require_incoming_transfer_memos_dst = [self.require_incoming_transfer_memos as u8];
+    }
+}
+
+/// Marks a token account whose owner cannot be changed with `SetAuthority`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ImmutableOwner;
+impl Extension for ImmutableOwner {
+    const TYPE: ExtensionType = ExtensionType::ImmutableOwner;
+}
+impl Sealed for ImmutableOwner {}
+impl Pack for ImmutableOwner {
+    const LEN: usize = 0;
+    fn unpack_from_slice(_src: &[u8]) -> Result<Self, ProgramError> {
+        Ok(ImmutableOwner)
+    }
+    fn pack_into_slice(&self, _dst: &mut [u8]) {}
+}
+
+/// Authority of a mint that may close it once its supply is zero.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MintCloseAuthority {
+    /// The close authority, or `None` once it has been removed
+    pub close_authority: COption<Pubkey>,
+}
+impl Extension for MintCloseAuthority {
+    const TYPE: ExtensionType = ExtensionType::MintCloseAuthority;
+}
+impl Sealed for MintCloseAuthority {}
+impl Pack for MintCloseAuthority {
+    const LEN: usize = 36;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let close_authority = array_ref![src, 0, 36];
+        Ok(MintCloseAuthority {
+            close_authority: unpack_coption_key(close_authority)?,
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let close_authority_dst = array_mut_ref![dst, 0, 36];
+        pack_coption_key(&self.close_authority, close_authority_dst);
+    }
+}
+
+/// Maximum length in bytes of the name of a token
+pub const MAX_METADATA_NAME_LEN: usize = 32;
+/// Maximum length in bytes of the symbol of a token
+pub const MAX_METADATA_SYMBOL_LEN: usize = 10;
+/// Maximum length in bytes of the URI of a token's off-chain metadata
+pub const MAX_METADATA_URI_LEN: usize = 200;
+
+/// Human-readable metadata of a mint.  Each field is stored as a 1-byte
+/// length followed by its UTF-8 bytes, zero-padded to the field's maximum
+/// length.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TokenMetadata {
+    /// Authority that may update the metadata, or `None` once it has been
+    /// removed
+    pub update_authority: COption<Pubkey>,
+    /// Name of the token
+    pub name: String,
+    /// Symbol of the token
+    pub symbol: String,
+    /// URI of the token's off-chain metadata
+    pub uri: String,
+}
+impl TokenMetadata {
+    /// Checks that each field fits within its maximum length
+    pub fn has_valid_lengths(name: &str, symbol: &str, uri: &str) -> bool {
+        name.len() <= MAX_METADATA_NAME_LEN
+            && symbol.len() <= MAX_METADATA_SYMBOL_LEN
+            && uri.len() <= MAX_METADATA_URI_LEN
+    }
+}
+impl Extension for TokenMetadata {
+    const TYPE: ExtensionType = ExtensionType::TokenMetadata;
+}
+impl Sealed for TokenMetadata {}
+impl Pack for TokenMetadata {
+    const LEN: usize = 281;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 281];
+        let (update_authority, name, symbol, uri) = array_refs![src, 36, 33, 11, 201];
+        Ok(TokenMetadata {
+            update_authority: unpack_coption_key(update_authority)?,
+            name: unpack_metadata_string(name)?,
+            symbol: unpack_metadata_string(symbol)?,
+            uri: unpack_metadata_string(uri)?,
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        // Instructions reject longer fields, which could not be stored
+        debug_assert!(Self::has_valid_lengths(&self.name, &self.symbol, &self.uri));
+        let dst = array_mut_ref![dst, 0, 281];
+        let (update_authority_dst, name_dst, symbol_dst, uri_dst) =
+            mut_array_refs![dst, 36, 33, 11, 201];
+        pack_coption_key(&self.update_authority, update_authority_dst);
+        pack_metadata_string(&self.name, name_dst);
+        pack_metadata_string(&self.symbol, symbol_dst);
+        pack_metadata_string(&self.uri, uri_dst);
+    }
+}
+
+/// Whether a token account blocks approvals, authority changes, closing and
+/// owner-signed transfers invoked by other programs.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CpiGuard {
+    /// Is `true` if privileged actions may not be invoked by other programs
+    pub lock_cpi: bool,
+}
+impl Extension for CpiGuard {
+    const TYPE: ExtensionType = ExtensionType::CpiGuard;
+}
+impl Sealed for CpiGuard {}
+impl Pack for CpiGuard {
+    const LEN: usize = 1;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let lock_cpi = array_ref![src, 0, 1];
+        Ok(CpiGuard {
+            lock_cpi: unpack_bool(lock_cpi)?,
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let lock_cpi_dst = array_mut_ref![dst, 0, 1];
+        *lock_cpi_dst = [self.lock_cpi as u8];
+    }
+}
+
+/// Number of seconds in a year, used to accrue interest
+const SECONDS_PER_YEAR: f64 = 60.0 * 60.0 * 24.0 * 365.24;
+/// Number of basis points in one
+const ONE_IN_BASIS_POINTS: f64 = 10_000.0;
+
+/// Interest rate configuration of a mint.  Interest accrues on the UI amounts
+/// of the mint's tokens, compounded continuously, while the raw amounts held
+/// by accounts never change.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct InterestBearingConfig {
+    /// Authority that may update the rate, or `None` once it has been removed
+    pub rate_authority: COption<Pubkey>,
+    /// Timestamp at which the mint was initialized
+    pub initialization_timestamp: UnixTimestamp,
+    /// Average rate, in basis points per year, from initialization until the
+    /// last update
+    pub pre_update_average_rate: i16,
+    /// Timestamp of the last rate update
+    pub last_update_timestamp: UnixTimestamp,
+    /// Current rate, in basis points per year
+    pub current_rate: i16,
+}
+impl InterestBearingConfig {
+    fn pre_update_exp(&self) -> f64 {
+        let pre_update_time = self
+            .last_update_timestamp
+            .saturating_sub(self.initialization_timestamp) as f64;
+        (self.pre_update_average_rate as f64 * pre_update_time
+            / SECONDS_PER_YEAR
+            / ONE_IN_BASIS_POINTS)
+            .exp()
+    }
+    fn post_update_exp(&self, unix_timestamp: UnixTimestamp) -> f64 {
+        let post_update_time = unix_timestamp.saturating_sub(self.last_update_timestamp) as f64;
+        (self.current_rate as f64 * post_update_time / SECONDS_PER_YEAR / ONE_IN_BASIS_POINTS).exp()
+    }
+    /// Convert a raw amount to its UI representation, including the interest
+    /// accrued until the given timestamp
+    pub fn amount_to_ui_amount(
+        &self,
+        amount: u64,
+        decimals: u8,
+        unix_timestamp: UnixTimestamp,
+    ) -> f64 {
+        crate::amount_to_ui_amount(amount, decimals)
+            * self.pre_update_exp()
+            * self.post_update_exp(unix_timestamp)
+    }
+    /// Gets the average rate from initialization until the given timestamp,
+    /// weighted by the time each rate was in effect
+    pub fn time_weighted_average_rate(&self, unix_timestamp: UnixTimestamp) -> i16 {
+        let total_time = unix_timestamp.saturating_sub(self.initialization_timestamp);
+        if total_time <= 0 {
+            return self.current_rate;
+        }
+        let pre_update_time = self
+            .last_update_timestamp
+            .saturating_sub(self.initialization_timestamp);
+        let post_update_time = unix_timestamp.saturating_sub(self.last_update_timestamp);
+        let weighted_rates = self.pre_update_average_rate as i128 * pre_update_time as i128
+            + self.current_rate as i128 * post_update_time as i128;
+        (weighted_rates / total_time as i128) as i16
+    }
+}
+impl Extension for InterestBearingConfig {
+    const TYPE: ExtensionType = ExtensionType::InterestBearingConfig;
+}
+impl Sealed for InterestBearingConfig {}
+impl Pack for InterestBearingConfig {
+    const LEN: usize = 56;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, 56];
+        let (
+            rate_authority,
+            initialization_timestamp,
+            pre_update_average_rate,
+            last_update_timestamp,
+            current_rate,
+        ) = array_refs![src, 36, 8, 2, 8, 2];
+        Ok(InterestBearingConfig {
+            rate_authority: unpack_coption_key(rate_authority)?,
+            initialization_timestamp: i64::from_le_bytes(*initialization_timestamp),
+            pre_update_average_rate: i16::from_le_bytes(*pre_update_average_rate),
+            last_update_timestamp: i64::from_le_bytes(*last_update_timestamp),
+            current_rate: i16::from_le_bytes(*current_rate),
+        })
+    }
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, 56];
+        let (
+            rate_authority_dst,
+            initialization_timestamp_dst,
+            pre_update_average_rate_dst,
+            last_update_timestamp_dst,
+            current_rate_dst,
+        ) = mut_array_refs![dst, 36, 8, 2, 8, 2];
+        pack_coption_key(&self.rate_authority, rate_authority_dst);
+        *initialization_timestamp_dst = self.initialization_timestamp.to_le_bytes();
+        *pre_update_average_rate_dst = self.pre_update_average_rate.to_le_bytes();
+        *last_update_timestamp_dst = self.last_update_timestamp.to_le_bytes();
+        *current_rate_dst = self.current_rate.to_le_bytes();
+    }
+}
+
+/// Offset of the account type in the data of extended mints and accounts
+const ACCOUNT_TYPE_INDEX: usize = Account::LEN;
+/// Offset of the first extension in the data of extended mints and accounts
+const EXTENSIONS_START_INDEX: usize = ACCOUNT_TYPE_INDEX + 1;
+/// Length of the type and length preceding each packed extension
+const TLV_HEADER_LEN: usize = 4;
+
+/// Gets the extension of the given type held by a mint or token account, or
+/// `None` if the account holds only the base layout or lacks the extension
+pub fn get_extension<E: Extension>(data: &[u8]) -> Result<Option<E>, ProgramError> {
+    if !is_extended_data_len(data.len()) {
+        return Ok(None);
+    }
+    check_account_type(data, E::TYPE.get_account_type())?;
+    let tlv_data = &data[EXTENSIONS_START_INDEX..];
+    let (entries, _) = get_extension_entries(tlv_data)?;
+    match entries
+        .iter()
+        .find(|(extension_type, _, _)| *extension_type == E::TYPE)
+    {
+        Some(&(_, start, end)) => E::unpack_unchecked(&tlv_data[start..end]).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Sets the extension of the given type held by a mint or token account,
+/// replacing the existing extension or adding it in the unused space
+/// following the last extension.  Fails if the account holds only the base
+/// layout or lacks the space for the extension.
+pub fn set_extension<E: Extension>(data: &mut [u8], extension: &E) -> ProgramResult {
+    if !is_extended_data_len(data.len()) {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    init_account_type(data, E::TYPE.get_account_type())?;
+    let tlv_data = &mut data[EXTENSIONS_START_INDEX..];
+    let (entries, unused_start) = get_extension_entries(tlv_data)?;
+    let (start, end) = match entries
+        .iter()
+        .find(|(extension_type, _, _)| *extension_type == E::TYPE)
+    {
+        Some(&(_, start, end)) => (start, end),
+        None => {
+            let start = unused_start + TLV_HEADER_LEN;
+            let end = start + E::LEN;
+            if end > tlv_data.len() {
+                return Err(ProgramError::InvalidAccountData);
+            }
+            tlv_data[unused_start..unused_start + 2]
+                .copy_from_slice(&(E::TYPE as u16).to_le_bytes());
+            tlv_data[unused_start + 2..start].copy_from_slice(&(E::LEN as u16).to_le_bytes());
+            (start, end)
+        }
+    };
+    if end - start != E::LEN {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    extension.pack_into_slice(&mut tlv_data[start..end]);
+    Ok(())
+}
+
+/// Gets the types of the extensions held by a mint or token account
+pub fn get_extension_types(data: &[u8]) -> Result<Vec<ExtensionType>, ProgramError> {
+    if !is_extended_data_len(data.len()) {
+        return Ok(vec![]);
+    }
+    let (entries, _) = get_extension_entries(&data[EXTENSIONS_START_INDEX..])?;
+    Ok(entries
+        .into_iter()
+        .map(|(extension_type, _, _)| extension_type)
+        .collect())
+}
+
+/// Converts a raw amount of a mint's tokens to its UI representation from the
+/// mint's account data, including any interest accrued until the given
+/// timestamp
+pub fn mint_amount_to_ui_amount(
+    mint_data: &[u8],
+    amount: u64,
+    unix_timestamp: UnixTimestamp,
+) -> Result<f64, ProgramError> {
+    let mint = Mint::unpack_from_account_data(mint_data)?;
+    Ok(match get_extension::<InterestBearingConfig>(mint_data)? {
+        Some(interest_bearing_config) => {
+            interest_bearing_config.amount_to_ui_amount(amount, mint.decimals, unix_timestamp)
+        }
+        None => crate::amount_to_ui_amount(amount, mint.decimals),
+    })
+}
+
+/// Sets the account type of an extended mint or token account, checking that
+/// it is not already set to another type.  Accounts holding only the base
+/// layout are left untouched.
+pub fn init_account_type(data: &mut [u8], account_type: AccountType) -> ProgramResult {
+    if !is_extended_data_len(data.len()) {
+        return Ok(());
+    }
+    check_account_type(data, account_type)?;
+    data[ACCOUNT_TYPE_INDEX] = account_type as u8;
+    Ok(())
+}
+
+fn is_extended_data_len(len: usize) -> bool {
+    len >= EXTENSIONS_START_INDEX && len != Multisig::LEN && len != MultisigProposal::LEN
+}
+
+fn has_account_type(data: &[u8], account_type: AccountType) -> bool {
+    is_extended_data_len(data.len()) && check_account_type(data, account_type).is_ok()
+}
+
+fn check_account_type(data: &[u8], account_type: AccountType) -> ProgramResult {
+    let current = data[ACCOUNT_TYPE_INDEX];
+    if current == AccountType::Uninitialized as u8 || current == account_type as u8 {
+        Ok(())
+    } else {
+        Err(ProgramError::InvalidAccountData)
+    }
+}
+
+/// Parses the extension entries, returning the type and the start and end
+/// offsets of each packed extension, followed by the offset of the unused
+/// space
+#[allow(clippy::type_complexity)]
+fn get_extension_entries(
+    tlv_data: &[u8],
+) -> Result<(Vec<(ExtensionType, usize, usize)>, usize), ProgramError> {
+    let mut entries = vec![];
+    let mut start = 0;
+    while start + TLV_HEADER_LEN <= tlv_data.len() {
+        let extension_type = u16::from_le_bytes([tlv_data[start], tlv_data[start + 1]]);
+        let extension_type = ExtensionType::try_from_primitive(extension_type)
+            .or(Err(ProgramError::InvalidAccountData))?;
+        if extension_type == ExtensionType::Uninitialized {
+            break;
+        }
+        let len = u16::from_le_bytes([tlv_data[start + 2], tlv_data[start + 3]]) as usize;
+        let value_start = start + TLV_HEADER_LEN;
+        let value_end = value_start + len;
+        if value_end > tlv_data.len() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        entries.push((extension_type, value_start, value_end));
+        start = value_end;
+    }
+    Ok((entries, start))
+}
+
 // Helpers
+fn unpack_bool(src: &[u8; 1]) -> Result<bool, ProgramError> {
+    match src {
+        [0] => Ok(false),
+        [1] => Ok(true),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
 fn pack_coption_key(src: &COption<Pubkey>, dst: &mut [u8; 36]) {
     let (tag, body) = mut_array_refs![dst, 4, 32];
     match src {
@@ -258,6 +1213,22 @@ fn pack_coption_key(src: &COption<Pubkey>, dst: &mut [u8; 36]) {
         }
     }
 }
+fn pack_metadata_string(src: &str, dst: &mut [u8]) {
+    let (len, body) = dst.split_at_mut(1);
+    let src_len = src.len().min(body.len());
+    len[0] = src_len as u8;
+    body[..src_len].copy_from_slice(&src.as_bytes()[..src_len]);
+    body[src_len..].iter_mut().for_each(|byte| *byte = 0);
+}
+
+fn unpack_metadata_string(src: &[u8]) -> Result<String, ProgramError> {
+    let (len, body) = src.split_at(1);
+    let bytes = body
+        .get(..len[0] as usize)
+        .ok_or(ProgramError::InvalidAccountData)?;
+    String::from_utf8(bytes.to_vec()).map_err(|_| ProgramError::InvalidAccountData)
+}
+
 fn unpack_coption_key(src: &[u8; 36]) -> Result<COption<Pubkey>, ProgramError> {
     let (tag, body) = array_refs![src, 4, 32];
     match *tag {
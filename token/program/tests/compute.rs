@@ -0,0 +1,127 @@
+// Mark this test as BPF-only, so the budget covers the cost of logging events on chain
+#![cfg(feature = "test-bpf")]
+
+use solana_program::{program_option::COption, program_pack::Pack, pubkey::Pubkey};
+use solana_program_test::*;
+use solana_sdk::{
+    account::Account as SolanaAccount,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use spl_token::{
+    instruction::{transfer_checked, transfer_checked_batch},
+    processor::Processor,
+    state::{Account, AccountState, Mint},
+};
+
+const DECIMALS: u8 = 2;
+const NUM_DESTINATIONS: usize = 8;
+
+fn token_account(data: Vec<u8>) -> SolanaAccount {
+    SolanaAccount {
+        lamports: 1_000_000_000,
+        data,
+        owner: spl_token::id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+fn program_test(mint: &Pubkey, owner: &Pubkey, accounts: &[Pubkey]) -> ProgramTest {
+    let mut pc = ProgramTest::new("spl_token", spl_token::id(), processor!(Processor::process));
+
+    let mut data = vec![0; Mint::LEN];
+    Mint {
+        mint_authority: COption::None,
+        supply: 1_000_000,
+        decimals: DECIMALS,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    }
+    .pack_into_slice(&mut data);
+    pc.add_account(*mint, token_account(data));
+
+    // The first account holds the whole supply, the others start out empty
+    for (i, account) in accounts.iter().enumerate() {
+        let mut data = vec![0; Account::LEN];
+        Account {
+            mint: *mint,
+            owner: *owner,
+            amount: if i == 0 { 1_000_000 } else { 0 },
+            state: AccountState::Initialized,
+            ..Account::default()
+        }
+        .pack_into_slice(&mut data);
+        pc.add_account(*account, token_account(data));
+    }
+
+    // Dial down the BPF compute budget to detect if the program, or the events it logs, get
+    // bloated in the future
+    pc.set_bpf_compute_max_units(100_000);
+
+    pc
+}
+
+#[tokio::test]
+async fn test_transfer_checked() {
+    let mint = Pubkey::new_unique();
+    let owner = Keypair::new();
+    let accounts = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let (mut banks_client, payer, recent_blockhash) =
+        program_test(&mint, &owner.pubkey(), &accounts)
+            .start()
+            .await;
+
+    let mut transaction = Transaction::new_with_payer(
+        &[transfer_checked(
+            &spl_token::id(),
+            &accounts[0],
+            &mint,
+            &accounts[1],
+            &owner.pubkey(),
+            &[],
+            100,
+            DECIMALS,
+        )
+        .unwrap()],
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, &owner], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_transfer_checked_batch() {
+    let mint = Pubkey::new_unique();
+    let owner = Keypair::new();
+    let accounts: Vec<Pubkey> = (0..=NUM_DESTINATIONS)
+        .map(|_| Pubkey::new_unique())
+        .collect();
+    let (mut banks_client, payer, recent_blockhash) =
+        program_test(&mint, &owner.pubkey(), &accounts)
+            .start()
+            .await;
+
+    let destinations: Vec<(&Pubkey, u64)> =
+        accounts[1..].iter().map(|account| (account, 100)).collect();
+    let mut transaction = Transaction::new_with_payer(
+        &[transfer_checked_batch(
+            &spl_token::id(),
+            &accounts[0],
+            &mint,
+            &destinations,
+            &owner.pubkey(),
+            &[],
+            DECIMALS,
+        )
+        .unwrap()],
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[&payer, &owner], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    for account in &accounts[1..] {
+        let account = banks_client.get_account(*account).await.unwrap().unwrap();
+        assert_eq!(Account::unpack(&account.data).unwrap().amount, 100);
+    }
+}
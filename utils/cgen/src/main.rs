@@ -9,6 +9,22 @@ fn token<P: AsRef<Path>>(crate_dir: P) {
 
     let config = cbindgen::Config {
         header: Some("/* Autogenerated SPL Token program C Bindings */".to_string()),
+        // Some instructions carry variable-length data in Rust-owned buffers,
+        // which C programs cannot use: they pack those instructions by hand.
+        // Sized opaque definitions keep the instruction type complete.
+        after_includes: Some(
+            [
+                "",
+                "/**",
+                " * Rust-owned buffer of variable-length instruction data, opaque to C",
+                " */",
+                "struct Token_String { uintptr_t _private[3]; };",
+                "struct Token_Vec_u8 { uintptr_t _private[3]; };",
+                "struct Token_Vec_u64 { uintptr_t _private[3]; };",
+                "struct Token_Vec_ExtensionType { uintptr_t _private[3]; };",
+            ]
+            .join("\n"),
+        ),
         language: cbindgen::Language::C,
         line_length: 80,
         style: cbindgen::Style::Both,